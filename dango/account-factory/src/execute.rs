@@ -1,7 +1,7 @@
 use {
     crate::{
         ACCOUNTS, ACCOUNTS_BY_USER, CODE_HASHES, DEPOSITS, KEYS, KEYS_BY_USER, NEXT_ACCOUNT_INDEX,
-        USERS_BY_KEY,
+        NEXT_SESSION_ID, SESSION_KEYS, USERS_BY_KEY,
     },
     anyhow::{bail, ensure},
     dango_types::{
@@ -10,7 +10,7 @@ use {
             Account, AccountParams, AccountType, ExecuteMsg, InstantiateMsg, NewUserSalt, Salt,
             Username,
         },
-        auth::Key,
+        auth::{Key, Session},
         config::IBC_TRANSFER_KEY,
     },
     grug::{
@@ -115,6 +115,12 @@ pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
         } => register_user(ctx, username, key, key_hash),
         ExecuteMsg::RegisterAccount { params } => register_account(ctx, params),
         ExecuteMsg::ConfigureSafe { updates } => configure_safe(ctx, updates),
+        ExecuteMsg::RegisterSessionKey {
+            key,
+            key_hash,
+            session,
+        } => register_session_key(ctx, key, key_hash, session),
+        ExecuteMsg::RevokeSessionKey { key_hash } => revoke_session_key(ctx, key_hash),
     }
 }
 
@@ -329,3 +335,67 @@ fn configure_safe(ctx: MutableCtx, updates: multi::ParamUpdates) -> anyhow::Resu
 
     Ok(Response::new())
 }
+
+fn register_session_key(
+    ctx: MutableCtx,
+    key: Key,
+    key_hash: Hash160,
+    session: Session,
+) -> anyhow::Result<Response> {
+    let username = single_signature_owner(ctx.storage, ctx.sender)?;
+
+    ensure!(
+        session.expiry > ctx.block.timestamp,
+        "session key expiry must be in the future"
+    );
+
+    // The key must not already be associated with the user, either as a
+    // session key or not. Otherwise, a primary key could be downgraded, or an
+    // existing session extended without being revoked first.
+    ensure!(
+        !KEYS_BY_USER.has(ctx.storage, (&username, key_hash)),
+        "key hash {key_hash} is already associated with user `{username}`"
+    );
+
+    // Save the key.
+    // If the key exists (already used by another account), they must match.
+    KEYS.may_update(ctx.storage, key_hash, |maybe_key| {
+        if let Some(existing_key) = maybe_key {
+            ensure!(key == existing_key, "reusing an existing key but mismatch");
+        }
+        Ok(key)
+    })?;
+
+    KEYS_BY_USER.insert(ctx.storage, (&username, key_hash))?;
+    USERS_BY_KEY.insert(ctx.storage, (key_hash, &username))?;
+
+    let (session_id, _) = NEXT_SESSION_ID.increment(ctx.storage)?;
+
+    SESSION_KEYS.save(ctx.storage, (&username, key_hash), &(session_id, session))?;
+
+    Ok(Response::new())
+}
+
+fn revoke_session_key(ctx: MutableCtx, key_hash: Hash160) -> anyhow::Result<Response> {
+    let username = single_signature_owner(ctx.storage, ctx.sender)?;
+
+    ensure!(
+        SESSION_KEYS.has(ctx.storage, (&username, key_hash)),
+        "key hash {key_hash} isn't a session key of user `{username}`"
+    );
+
+    KEYS_BY_USER.remove(ctx.storage, (&username, key_hash));
+    USERS_BY_KEY.remove(ctx.storage, (key_hash, &username));
+    SESSION_KEYS.remove(ctx.storage, (&username, key_hash));
+
+    Ok(Response::new())
+}
+
+// Find the owner of a spot or margin account. Session keys are managed by
+// sending a transaction from such an account.
+fn single_signature_owner(storage: &dyn Storage, address: Addr) -> anyhow::Result<Username> {
+    match ACCOUNTS.load(storage, address)?.params {
+        AccountParams::Spot(params) | AccountParams::Margin(params) => Ok(params.owner),
+        AccountParams::Safe(_) => bail!("a Safe account can't manage session keys"),
+    }
+}
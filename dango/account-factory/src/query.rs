@@ -1,11 +1,11 @@
 use {
     crate::{
         ACCOUNTS, ACCOUNTS_BY_USER, CODE_HASHES, DEPOSITS, KEYS, KEYS_BY_USER, NEXT_ACCOUNT_INDEX,
-        USERS_BY_KEY,
+        SESSION_KEYS, USERS_BY_KEY,
     },
     dango_types::{
         account_factory::{Account, AccountIndex, AccountType, QueryMsg, User, Username},
-        auth::Key,
+        auth::{Key, Session},
     },
     grug::{
         Addr, Bound, Coins, Hash160, Hash256, ImmutableCtx, Json, JsonSerExt, Order, StdResult,
@@ -71,6 +71,14 @@ pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> anyhow::Result<Json> {
             let res = query_users_by_key(ctx.storage, hash)?;
             res.to_json_value()
         },
+        QueryMsg::SessionKey { username, key_hash } => {
+            let res = query_session_key(ctx.storage, &username, key_hash)?;
+            res.to_json_value()
+        },
+        QueryMsg::SessionKeysByUser { username } => {
+            let res = query_session_keys_by_user(ctx.storage, &username)?;
+            res.to_json_value()
+        },
     }
     .map_err(Into::into)
 }
@@ -194,6 +202,27 @@ fn query_users_by_key(storage: &dyn Storage, hash: Hash160) -> StdResult<BTreeSe
         .collect()
 }
 
+fn query_session_key(
+    storage: &dyn Storage,
+    username: &Username,
+    key_hash: Hash160,
+) -> StdResult<Option<Session>> {
+    Ok(SESSION_KEYS
+        .may_load(storage, (username, key_hash))?
+        .map(|(_, session)| session))
+}
+
+fn query_session_keys_by_user(
+    storage: &dyn Storage,
+    username: &Username,
+) -> StdResult<BTreeMap<Hash160, Session>> {
+    SESSION_KEYS
+        .prefix(username)
+        .range(storage, None, None, Order::Ascending)
+        .map(|res| res.map(|(key_hash, (_, session))| (key_hash, session)))
+        .collect()
+}
+
 // ----------------------------------- tests -----------------------------------
 
 #[cfg(test)]
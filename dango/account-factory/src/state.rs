@@ -1,7 +1,7 @@
 use {
     dango_types::{
         account_factory::{Account, AccountIndex, AccountType, Username},
-        auth::Key,
+        auth::{Key, Session},
     },
     grug::{Addr, Coins, Counter, Hash160, Hash256, Map, Set},
 };
@@ -18,6 +18,18 @@ pub const KEYS_BY_USER: Set<(&Username, Hash160)> = Set::new("key__user");
 
 pub const USERS_BY_KEY: Set<(Hash160, &Username)> = Set::new("user__key");
 
+pub const NEXT_SESSION_ID: Counter<u32> = Counter::new("session_id", 0, 1);
+
+/// Scopes of session keys, indexed by the username and the key hash, along
+/// with an ID unique to each registration.
+///
+/// A session key is also saved in `KEYS`, `KEYS_BY_USER`, and `USERS_BY_KEY`
+/// just like any other key. An entry in this map is what tells it apart.
+///
+/// Accounts track the spending of session keys by this ID, so that a key that
+/// is revoked and registered again starts with nothing spent.
+pub const SESSION_KEYS: Map<(&Username, Hash160), (u32, Session)> = Map::new("session_key");
+
 pub const ACCOUNTS: Map<Addr, Account> = Map::new("account");
 
 pub const ACCOUNTS_BY_USER: Set<(&Username, Addr)> = Set::new("account__user");
@@ -1,5 +1,5 @@
 use {
-    dango_auth::NEXT_SEQUENCE,
+    dango_auth::{query_grant, query_grants, NEXT_SEQUENCE},
     dango_types::account::single::QueryMsg,
     grug::{ImmutableCtx, Json, JsonSerExt, StdResult, Storage},
 };
@@ -8,6 +8,10 @@ use {
 pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
     match msg {
         QueryMsg::Sequence {} => query_sequence(ctx.storage)?.to_json_value(),
+        QueryMsg::Grant { grantee } => query_grant(ctx.storage, grantee)?.to_json_value(),
+        QueryMsg::Grants { start_after, limit } => {
+            query_grants(ctx.storage, start_after, limit)?.to_json_value()
+        },
     }
 }
 
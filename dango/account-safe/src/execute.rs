@@ -1,10 +1,13 @@
 use {
     crate::{NEXT_PROPOSAL_ID, PROPOSALS, VOTES},
     anyhow::{bail, ensure},
-    dango_auth::authenticate_tx,
+    dango_auth::{authenticate_tx, create_grant, revoke_grant},
     dango_types::{
         account::{
-            multi::{ExecuteMsg, Proposal, ProposalId, Status, Vote},
+            multi::{
+                ExecuteMsg, Proposal, ProposalCreated, ProposalExecuted, ProposalId, ProposalVoted,
+                Status, Vote,
+            },
             InstantiateMsg,
         },
         account_factory::{QueryAccountRequest, Username},
@@ -38,17 +41,29 @@ pub fn authenticate(ctx: AuthCtx, tx: Tx) -> anyhow::Result<AuthResponse> {
     // execute itself. Everything else needs to be done through proposals.
     // Additionally, if the action is proposing or voting, the proposer/voter's
     // username must match the transaction signer's username.
-    for msg in &tx.msgs {
-        match msg {
-            Message::Execute(MsgExecute { contract, msg, .. }) if contract == ctx.contract => {
-                if let ExecuteMsg::Vote { voter, .. } = msg.clone().deserialize_json()? {
-                    ensure!(
-                        voter == metadata.username,
-                        "can't vote with a different username"
-                    );
-                }
-            },
-            _ => bail!("a Safe account can only execute itself"),
+    //
+    // The exception is transactions sent by a grantee, in which case the
+    // messages are checked against the grant instead. Since grants can only be
+    // created through proposals, this doesn't bypass the members' approval.
+    if metadata.grantee.is_none() {
+        for msg in &tx.msgs {
+            match msg {
+                Message::Execute(MsgExecute { contract, msg, .. }) if contract == ctx.contract => {
+                    match msg.clone().deserialize_json()? {
+                        ExecuteMsg::Vote { voter, .. } => {
+                            ensure!(
+                                voter == metadata.username,
+                                "can't vote with a different username"
+                            );
+                        },
+                        ExecuteMsg::Grant { .. } | ExecuteMsg::Revoke { .. } => {
+                            bail!("grants can only be managed through proposals");
+                        },
+                        _ => (),
+                    }
+                },
+                _ => bail!("a Safe account can only execute itself"),
+            }
         }
     }
 
@@ -77,6 +92,8 @@ pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
             execute,
         } => do_vote(ctx, proposal_id, voter, vote, execute),
         ExecuteMsg::Execute { proposal_id } => execute_proposal(ctx, proposal_id),
+        ExecuteMsg::Grant { grantee, grant } => create_grant(ctx, grantee, grant),
+        ExecuteMsg::Revoke { grantee } => revoke_grant(ctx, grantee),
     }
 }
 
@@ -105,7 +122,7 @@ fn propose(
         .as_safe();
 
     let proposal = Proposal {
-        title,
+        title: title.clone(),
         description,
         messages,
         status: Status::Voting {
@@ -123,7 +140,7 @@ fn propose(
     // Save the proposal.
     PROPOSALS.save(ctx.storage, proposal_id, &proposal)?;
 
-    Ok(Response::new())
+    Ok(Response::new().add_event(&ProposalCreated { proposal_id, title })?)
 }
 
 fn do_vote(
@@ -220,7 +237,22 @@ fn do_vote(
     // Save the updated proposal.
     PROPOSALS.save(ctx.storage, proposal_id, &proposal)?;
 
-    Ok(Response::new().add_messages(msgs))
+    let executed = matches!(proposal.status, Status::Executed);
+
+    let mut response = Response::new()
+        .add_messages(msgs)
+        .add_event(&ProposalVoted {
+            proposal_id,
+            voter,
+            vote,
+            status: proposal.status,
+        })?;
+
+    if executed {
+        response = response.add_event(&ProposalExecuted { proposal_id })?;
+    }
+
+    Ok(response)
 }
 
 fn execute_proposal(ctx: MutableCtx, proposal_id: ProposalId) -> anyhow::Result<Response> {
@@ -238,7 +270,9 @@ fn execute_proposal(ctx: MutableCtx, proposal_id: ProposalId) -> anyhow::Result<
     // Save the updated proposal.
     PROPOSALS.save(ctx.storage, proposal_id, &proposal)?;
 
-    Ok(Response::new().add_messages(msgs))
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_event(&ProposalExecuted { proposal_id })?)
 }
 
 // ----------------------------------- tests -----------------------------------
@@ -251,6 +285,7 @@ mod tests {
         dango_types::{
             account::multi::{self, Params},
             account_factory::{self, Account, AccountParams},
+            auth::{Authorization, Grant},
             config::ACCOUNT_FACTORY_KEY,
         },
         grug::{
@@ -330,6 +365,8 @@ mod tests {
                     // reach the signature verification step.
                     key_hash: Hash::ZERO,
                     sequence: 0,
+                    gas_price: None,
+                    grantee: None,
                 }
                 .to_json_value()
                 .unwrap(),
@@ -349,9 +386,11 @@ mod tests {
                 gas_limit: 1_000_000,
                 msgs: vec![Message::transfer(Addr::mock(123), Coins::new()).unwrap()],
                 data: Metadata {
-                    username: member1,
+                    username: member1.clone(),
                     key_hash: Hash::ZERO,
                     sequence: 0,
+                    gas_price: None,
+                    grantee: None,
                 }
                 .to_json_value()
                 .unwrap(),
@@ -384,6 +423,8 @@ mod tests {
                     username: member3,
                     key_hash: Hash::ZERO,
                     sequence: 0,
+                    gas_price: None,
+                    grantee: None,
                 }
                 .to_json_value()
                 .unwrap(),
@@ -394,6 +435,42 @@ mod tests {
                 .to_string()
                 .contains("can't vote with a different username")));
         }
+
+        // A member sends a tx to grant someone permission without going through
+        // a proposal. Should fail.
+        {
+            let res = authenticate(ctx.as_auth(), Tx {
+                sender: SAFE,
+                gas_limit: 1_000_000,
+                msgs: vec![Message::execute(
+                    SAFE,
+                    &multi::ExecuteMsg::Grant {
+                        grantee: Addr::mock(123),
+                        grant: Grant {
+                            authorizations: vec![Authorization::Transfer],
+                            spend_limit: None,
+                            expiration: None,
+                        },
+                    },
+                    Coins::new(),
+                )
+                .unwrap()],
+                data: Metadata {
+                    username: member1,
+                    key_hash: Hash::ZERO,
+                    sequence: 0,
+                    gas_price: None,
+                    grantee: None,
+                }
+                .to_json_value()
+                .unwrap(),
+                credential: Json::Null,
+            });
+
+            assert!(res.is_err_and(|err| err
+                .to_string()
+                .contains("grants can only be managed through proposals")));
+        }
     }
 
     #[test]
@@ -784,7 +861,11 @@ mod tests {
         Status::Passed {
             execute_after: Timestamp::from_seconds(100),
         },
-        GenericResult::Ok(Response::new());
+        GenericResult::Ok(
+            Response::new()
+                .add_event(&ProposalExecuted { proposal_id: 123 })
+                .unwrap()
+        );
         "proposal passed and timelock elapsed"
     )]
     #[test_case(
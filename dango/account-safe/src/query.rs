@@ -1,6 +1,6 @@
 use {
     crate::{PROPOSALS, VOTES},
-    dango_auth::NEXT_SEQUENCE,
+    dango_auth::{query_grant, query_grants, NEXT_SEQUENCE},
     dango_types::{
         account::multi::{Proposal, ProposalId, QueryMsg, Status, Vote},
         account_factory::Username,
@@ -37,6 +37,14 @@ pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
             let res = query_votes(ctx.storage, proposal_id)?;
             res.to_json_value()
         },
+        QueryMsg::Grant { grantee } => {
+            let res = query_grant(ctx.storage, grantee)?;
+            res.to_json_value()
+        },
+        QueryMsg::Grants { start_after, limit } => {
+            let res = query_grants(ctx.storage, start_after, limit)?;
+            res.to_json_value()
+        },
     }
 }
 
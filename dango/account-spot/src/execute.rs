@@ -1,7 +1,10 @@
 use {
     anyhow::ensure,
-    dango_auth::authenticate_tx,
-    dango_types::{account::InstantiateMsg, config::ACCOUNT_FACTORY_KEY},
+    dango_auth::{authenticate_tx, create_grant, revoke_grant},
+    dango_types::{
+        account::{single::ExecuteMsg, InstantiateMsg},
+        config::ACCOUNT_FACTORY_KEY,
+    },
     grug::{Addr, AuthCtx, AuthResponse, MutableCtx, Response, StdResult, Tx},
 };
 
@@ -30,3 +33,11 @@ pub fn receive(_ctx: MutableCtx) -> StdResult<Response> {
     // Do nothing, accept all transfers.
     Ok(Response::new())
 }
+
+#[cfg_attr(not(feature = "library"), grug::export)]
+pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
+    match msg {
+        ExecuteMsg::Grant { grantee, grant } => create_grant(ctx, grantee, grant),
+        ExecuteMsg::Revoke { grantee } => revoke_grant(ctx, grantee),
+    }
+}
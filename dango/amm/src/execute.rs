@@ -1,15 +1,17 @@
 use {
-    crate::{perform_swap, PoolExt, PoolInit, CONFIG, NEXT_POOL_ID, POOLS},
+    crate::{
+        perform_swap, perform_swap_exact_output, PoolExt, PoolInit, CONFIG, NEXT_POOL_ID, POOLS,
+    },
     anyhow::{anyhow, ensure},
     dango_types::{
         amm::{
-            ConcentratedPool, ExecuteMsg, InstantiateMsg, Pool, PoolId, PoolParams, XykPool,
-            MINIMUM_LIQUIDITY, NAMESPACE, SUBNAMESPACE,
+            ConcentratedPool, ExecuteMsg, InstantiateMsg, Pool, PoolId, PoolParams, Swapped,
+            XykPool, MINIMUM_LIQUIDITY, NAMESPACE, SUBNAMESPACE,
         },
         bank, taxman,
     },
     grug::{
-        Coins, Denom, Inner, IsZero, Message, MutableCtx, Number, Part, Response, StdResult,
+        Coin, Coins, Denom, Inner, IsZero, Message, MutableCtx, Number, Part, Response, StdResult,
         Uint128, UniqueVec,
     },
 };
@@ -29,6 +31,11 @@ pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
             route,
             minimum_output,
         } => swap(ctx, route, minimum_output),
+        ExecuteMsg::SwapExactOutput {
+            route,
+            output,
+            maximum_input,
+        } => swap_exact_output(ctx, route, output, maximum_input),
         ExecuteMsg::ProvideLiquidity {
             pool_id,
             minimum_output,
@@ -126,7 +133,7 @@ fn swap(
         .collect::<StdResult<Vec<_>>>()?;
 
     // Perform the swap in each pool.
-    let outcome = perform_swap(&amm_cfg, input, pools.iter_mut())?;
+    let outcome = perform_swap(&amm_cfg, input.clone(), pools.iter_mut())?;
 
     if let Some(minimum_output) = minimum_output {
         ensure!(
@@ -142,6 +149,15 @@ fn swap(
         POOLS.save(ctx.storage, *pool_id, &pool)?;
     }
 
+    let event = Swapped {
+        trader: ctx.sender,
+        route,
+        input,
+        output: outcome.output.clone(),
+        protocol_fee: outcome.protocol_fee.clone(),
+        liquidity_fees: outcome.liquidity_fees,
+    };
+
     // Transfer the post-fee output, if non-zero, to the trader.
     let output_msg = if outcome.output.is_non_zero() {
         Some(Message::transfer(ctx.sender, outcome.output)?)
@@ -164,7 +180,103 @@ fn swap(
 
     Ok(Response::new()
         .may_add_message(output_msg)
-        .may_add_message(fee_msg))
+        .may_add_message(fee_msg)
+        .add_event(&event)?)
+}
+
+fn swap_exact_output(
+    ctx: MutableCtx,
+    route: UniqueVec<PoolId>,
+    output: Coin,
+    maximum_input: Option<Uint128>,
+) -> anyhow::Result<Response> {
+    let amm_cfg = CONFIG.load(ctx.storage)?;
+    let funds = ctx.funds.into_one_coin()?;
+    let mut pools = route
+        .inner()
+        .iter()
+        .map(|&pool_id| POOLS.load(ctx.storage, pool_id))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    // Compute the input required by each pool.
+    let outcome = perform_swap_exact_output(&amm_cfg, output, pools.iter_mut())?;
+
+    ensure!(
+        outcome.input.denom == funds.denom,
+        "invalid input denom! expecting: {}, got: {}",
+        outcome.input.denom,
+        funds.denom
+    );
+
+    ensure!(
+        outcome.input.amount <= funds.amount,
+        "insufficient funds for swap: {} < {}",
+        funds.amount,
+        outcome.input.amount
+    );
+
+    if let Some(maximum_input) = maximum_input {
+        ensure!(
+            outcome.input.amount <= maximum_input,
+            "swap input exceeds maximum: {} > {}",
+            outcome.input.amount,
+            maximum_input
+        );
+    }
+
+    // Save the updated pool states.
+    for (pool_id, pool) in route.inner().iter().zip(pools) {
+        POOLS.save(ctx.storage, *pool_id, &pool)?;
+    }
+
+    let refund = Coin {
+        denom: funds.denom,
+        amount: funds.amount.checked_sub(outcome.input.amount)?,
+    };
+
+    let event = Swapped {
+        trader: ctx.sender,
+        route,
+        input: outcome.input,
+        output: outcome.output.clone(),
+        protocol_fee: outcome.protocol_fee.clone(),
+        liquidity_fees: outcome.liquidity_fees,
+    };
+
+    // Transfer the output and the unused input, if non-zero, to the trader.
+    let mut transfers = Coins::new();
+
+    if outcome.output.is_non_zero() {
+        transfers.insert(outcome.output)?;
+    }
+
+    if refund.is_non_zero() {
+        transfers.insert(refund)?;
+    }
+
+    let transfer_msg = if !transfers.is_empty() {
+        Some(Message::transfer(ctx.sender, transfers)?)
+    } else {
+        None
+    };
+
+    // Transfer the protocol fee, if non-zero, to taxman.
+    let fee_msg = if outcome.protocol_fee.is_non_zero() {
+        let cfg = ctx.querier.query_config()?;
+
+        Some(Message::execute(
+            cfg.taxman,
+            &taxman::ExecuteMsg::Pay { payer: ctx.sender },
+            outcome.protocol_fee,
+        )?)
+    } else {
+        None
+    };
+
+    Ok(Response::new()
+        .may_add_message(transfer_msg)
+        .may_add_message(fee_msg)
+        .add_event(&event)?)
 }
 
 fn provide_liquidity(
@@ -1,7 +1,8 @@
 mod execute;
 mod pools;
 mod query;
+mod route;
 mod state;
 mod swap;
 
-pub use {execute::*, pools::*, query::*, state::*, swap::*};
+pub use {execute::*, pools::*, query::*, route::*, state::*, swap::*};
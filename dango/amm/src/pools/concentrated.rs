@@ -1,28 +1,409 @@
 use {
+    crate::{PoolExt, PoolInit},
+    anyhow::{bail, ensure},
     dango_types::amm::{ConcentratedParams, ConcentratedPool},
-    grug::{Coin, CoinPair, Uint128},
+    grug::{
+        Coin, CoinPair, Inner, IsZero, MultiplyFraction, NextNumber, Number, NumberConst,
+        PrevNumber, Udec128, Uint128, Uint256,
+    },
 };
 
-use super::{PoolExt, PoolInit};
+/// The maximum amplification coefficient a pool can have. Same as Curve's.
+const MAX_AMPLIFICATION: u64 = 1_000_000;
+
+/// The maximum number of iterations when solving the invariant with Newton's
+/// method. Same as Curve's.
+const MAX_ITERATIONS: usize = 255;
+
+/// The number of assets in a pool.
+const N: Uint256 = Uint256::new_from_u128(2);
 
 impl PoolInit for ConcentratedPool {
     type Params = ConcentratedParams;
 
-    fn initialize(_liquidity: CoinPair, _params: ConcentratedParams) -> anyhow::Result<Self> {
-        todo!()
+    fn initialize(liquidity: CoinPair, params: ConcentratedParams) -> anyhow::Result<Self> {
+        ensure!(
+            params.amplification.into_inner() <= MAX_AMPLIFICATION,
+            "amplification can't be greater than {}, got: {}",
+            MAX_AMPLIFICATION,
+            params.amplification
+        );
+
+        ensure!(
+            liquidity.first().amount.is_non_zero() && liquidity.second().amount.is_non_zero(),
+            "initial liquidity must be non-zero for both assets"
+        );
+
+        // The liquidity is concentrated around the price implied by the ratio
+        // of the initial liquidity.
+        let price_scale =
+            Udec128::checked_from_ratio(*liquidity.first().amount, *liquidity.second().amount)?;
+
+        let mut pool = Self {
+            params,
+            liquidity,
+            shares: Uint128::ZERO,
+            price_scale,
+        };
+
+        // The initial shares is the invariant, which is denominated in units
+        // of the first asset.
+        let (x, y) = scaled_liquidity(&pool)?;
+
+        pool.shares =
+            compute_d(pool.params.amplification.into_inner(), x, y)?.checked_into_prev()?;
+
+        Ok(pool)
     }
 }
 
 impl PoolExt for ConcentratedPool {
-    fn swap(&mut self, _input: Coin) -> anyhow::Result<(Coin, Coin)> {
-        todo!()
+    fn swap(&mut self, input: Coin) -> anyhow::Result<(Coin, Coin)> {
+        let offer_is_first = if input.denom == *self.liquidity.first().denom {
+            true
+        } else if input.denom == *self.liquidity.second().denom {
+            false
+        } else {
+            bail!(
+                "invalid input denom! must be {}|{}, got: {}",
+                self.liquidity.first().denom,
+                self.liquidity.second().denom,
+                input.denom
+            );
+        };
+
+        let amplification = self.params.amplification.into_inner();
+        let (x, y) = scaled_liquidity(self)?;
+        let d = compute_d(amplification, x, y)?;
+
+        let (offer_before, ask_before, input_scaled) = if offer_is_first {
+            (x, y, input.amount)
+        } else {
+            (y, x, input.amount.checked_mul_dec_floor(self.price_scale)?)
+        };
+
+        // Compute the ask asset's balance after the swap, such that the
+        // invariant is preserved.
+        let offer_after = offer_before.checked_add(input_scaled.into_next())?;
+        let ask_after = compute_y(amplification, offer_after, d)?;
+
+        // Compute swap output. Round it down by one unit in favor of the pool,
+        // as the invariant is only solved to within one unit of precision.
+        let output_scaled = ask_before
+            .checked_sub(ask_after)?
+            .saturating_sub(Uint256::ONE)
+            .checked_into_prev()?;
+
+        let mut output = if offer_is_first {
+            output_scaled.checked_div_dec_floor(self.price_scale)?
+        } else {
+            output_scaled
+        };
+
+        // Compute liquidity fee. (Note: use ceil rounding.)
+        let liquidity_fee = output.checked_mul_dec_ceil(*self.params.liquidity_fee_rate.inner())?;
+
+        // Deduct liquidity fee from the output.
+        output.checked_sub_assign(liquidity_fee)?;
+
+        // Update pool state.
+        let (offer, ask) = if offer_is_first {
+            self.liquidity.as_mut()
+        } else {
+            self.liquidity.as_mut_rev()
+        };
+
+        offer.amount.checked_add_assign(input.amount)?;
+        ask.amount.checked_sub_assign(output)?;
+
+        Ok((
+            Coin {
+                denom: ask.denom.clone(),
+                amount: output,
+            },
+            Coin {
+                denom: ask.denom.clone(),
+                amount: liquidity_fee,
+            },
+        ))
+    }
+
+    fn swap_exact_output(&mut self, output: Coin) -> anyhow::Result<(Coin, Coin)> {
+        let offer_is_first = if output.denom == *self.liquidity.second().denom {
+            true
+        } else if output.denom == *self.liquidity.first().denom {
+            false
+        } else {
+            bail!(
+                "invalid output denom! must be {}|{}, got: {}",
+                self.liquidity.first().denom,
+                self.liquidity.second().denom,
+                output.denom
+            );
+        };
+
+        let amplification = self.params.amplification.into_inner();
+        let (x, y) = scaled_liquidity(self)?;
+        let d = compute_d(amplification, x, y)?;
+
+        let (offer_before, ask_before) = if offer_is_first {
+            (x, y)
+        } else {
+            (y, x)
+        };
+
+        // Compute the output before liquidity fee, such that the output after
+        // deducting the fee is no less than the requested amount.
+        let one_minus_fee_rate =
+            Udec128::ONE.checked_sub(*self.params.liquidity_fee_rate.inner())?;
+        let output_before_fee = output.amount.checked_div_dec_ceil(one_minus_fee_rate)?;
+
+        let output_scaled = if offer_is_first {
+            output_before_fee.checked_mul_dec_ceil(self.price_scale)?
+        } else {
+            output_before_fee
+        };
+
+        ensure!(
+            output_scaled.into_next().checked_add(Uint256::ONE)? < ask_before,
+            "insufficient liquidity! requested: {}, available: {}",
+            output_before_fee,
+            if offer_is_first {
+                self.liquidity.second().amount
+            } else {
+                self.liquidity.first().amount
+            }
+        );
+
+        // Compute the ask asset's balance after the swap. Deduct one more unit,
+        // mirroring the rounding in `swap`.
+        let ask_after = ask_before
+            .checked_sub(output_scaled.into_next())?
+            .checked_sub(Uint256::ONE)?;
+
+        // Compute the offer asset's balance after the swap, such that the
+        // invariant is preserved. Round it up by one unit in favor of the pool,
+        // as the invariant is only solved to within one unit of precision.
+        let offer_after = compute_y(amplification, ask_after, d)?.checked_add(Uint256::ONE)?;
+
+        let input_scaled = offer_after.checked_sub(offer_before)?.checked_into_prev()?;
+
+        let input = if offer_is_first {
+            input_scaled
+        } else {
+            input_scaled.checked_div_dec_ceil(self.price_scale)?
+        };
+
+        let (offer_denom, ask_denom) = if offer_is_first {
+            (self.liquidity.first().denom, self.liquidity.second().denom)
+        } else {
+            (self.liquidity.second().denom, self.liquidity.first().denom)
+        };
+
+        let input = Coin {
+            denom: offer_denom.clone(),
+            amount: input,
+        };
+
+        let liquidity_fee = Coin {
+            denom: ask_denom.clone(),
+            amount: output_before_fee.checked_sub(output.amount)?,
+        };
+
+        // Newton's method only approximates the solution, so make sure that
+        // swapping the computed input does yield the requested output.
+        let (simulated_output, _) = self.clone().swap(input.clone())?;
+
+        ensure!(
+            simulated_output.amount >= output.amount,
+            "failed to compute swap input! input: {}, output: {} < {}",
+            input,
+            simulated_output.amount,
+            output.amount
+        );
+
+        // Update pool state.
+        let (offer, ask) = if offer_is_first {
+            self.liquidity.as_mut()
+        } else {
+            self.liquidity.as_mut_rev()
+        };
+
+        offer.amount.checked_add_assign(input.amount)?;
+        ask.amount.checked_sub_assign(output.amount)?;
+
+        Ok((input, liquidity_fee))
+    }
+
+    // Shares are minted proportionally to the increase in the invariant.
+    // Similar to Curve, a fee is charged on the portion of the deposit that is
+    // imbalanced relative to the pool's current liquidity.
+    fn provide_liquidity(&mut self, deposit: CoinPair) -> anyhow::Result<Uint128> {
+        let amplification = self.params.amplification.into_inner();
+
+        let (x_before, y_before) = scaled_liquidity(self)?;
+        let d_before = compute_d(amplification, x_before, y_before)?;
+
+        self.liquidity.merge(deposit)?;
+
+        let (x_after, y_after) = scaled_liquidity(self)?;
+        let d_after = compute_d(amplification, x_after, y_after)?;
+
+        ensure!(
+            d_after > d_before,
+            "liquidity provision must increase the pool's invariant"
+        );
+
+        let fee_rate = self
+            .params
+            .liquidity_fee_rate
+            .inner()
+            .checked_mul(Udec128::new_percent(50))?;
+
+        let x_less_fee = deduct_imbalance_fee(x_before, x_after, d_before, d_after, fee_rate)?;
+        let y_less_fee = deduct_imbalance_fee(y_before, y_after, d_before, d_after, fee_rate)?;
+        let d_less_fee = compute_d(amplification, x_less_fee, y_less_fee)?;
+
+        let shares_to_mint = self
+            .shares
+            .into_next()
+            .checked_mul(d_less_fee.checked_sub(d_before)?)?
+            .checked_div(d_before)?
+            .checked_into_prev()?;
+
+        self.shares.checked_add_assign(shares_to_mint)?;
+
+        Ok(shares_to_mint)
+    }
+
+    fn withdraw_liquidity(&mut self, shares_to_burn: Uint128) -> anyhow::Result<CoinPair> {
+        let shares_before = self.shares;
+
+        self.shares = shares_before.checked_sub(shares_to_burn)?;
+
+        Ok(self.liquidity.split(shares_to_burn, shares_before)?)
     }
+}
+
+/// Return the pool's liquidity, with the second asset converted to units of
+/// the first asset using the price scale.
+fn scaled_liquidity(pool: &ConcentratedPool) -> anyhow::Result<(Uint256, Uint256)> {
+    let x = pool.liquidity.first().amount.into_next();
+    let y = pool
+        .liquidity
+        .second()
+        .amount
+        .checked_mul_dec_floor(pool.price_scale)?
+        .into_next();
 
-    fn provide_liquidity(&mut self, _deposit: CoinPair) -> anyhow::Result<Uint128> {
-        todo!()
+    Ok((x, y))
+}
+
+/// Solve the StableSwap invariant `D` given the balances of the two assets,
+/// using Newton's method:
+///
+/// ```plain
+/// A * n^n * (x + y) + D = A * n^n * D + D^(n + 1) / (n^n * x * y)
+/// ```
+///
+/// Following Curve's implementation, `Ann = A * n` is used in place of
+/// `A * n^n`, which amounts to a different scaling of the amplification.
+fn compute_d(amplification: u64, x: Uint256, y: Uint256) -> anyhow::Result<Uint256> {
+    let sum = x.checked_add(y)?;
+
+    if sum.is_zero() {
+        return Ok(Uint256::ZERO);
     }
 
-    fn withdraw_liquidity(&mut self, _shares_to_burn: Uint128) -> anyhow::Result<CoinPair> {
-        todo!()
+    let ann = Uint256::new_from_u128(amplification as u128).checked_mul(N)?;
+    let mut d = sum;
+
+    for _ in 0..MAX_ITERATIONS {
+        // d_p = D^3 / (n^n * x * y)
+        let d_p = d
+            .checked_mul(d)?
+            .checked_div(x.checked_mul(N)?)?
+            .checked_mul(d)?
+            .checked_div(y.checked_mul(N)?)?;
+
+        let d_prev = d;
+
+        // D = (Ann * S + n * d_p) * D / ((Ann - 1) * D + (n + 1) * d_p)
+        d = ann
+            .checked_mul(sum)?
+            .checked_add(d_p.checked_mul(N)?)?
+            .checked_mul(d)?
+            .checked_div(
+                ann.checked_sub(Uint256::ONE)?
+                    .checked_mul(d)?
+                    .checked_add(d_p.checked_mul(N.checked_add(Uint256::ONE)?)?)?,
+            )?;
+
+        if abs_diff(d, d_prev) <= Uint256::ONE {
+            return Ok(d);
+        }
+    }
+
+    bail!("failed to solve the pool's invariant: no convergence");
+}
+
+/// Given the invariant `D` and the balance `x` of one asset, solve for the
+/// balance `y` of the other asset using Newton's method.
+fn compute_y(amplification: u64, x: Uint256, d: Uint256) -> anyhow::Result<Uint256> {
+    let ann = Uint256::new_from_u128(amplification as u128).checked_mul(N)?;
+
+    // c = D^3 / (n^n * x * Ann)
+    let c = d
+        .checked_mul(d)?
+        .checked_div(x.checked_mul(N)?)?
+        .checked_mul(d)?
+        .checked_div(ann.checked_mul(N)?)?;
+
+    // b = x + D / Ann
+    let b = x.checked_add(d.checked_div(ann)?)?;
+
+    let mut y = d;
+
+    for _ in 0..MAX_ITERATIONS {
+        let y_prev = y;
+
+        // y = (y^2 + c) / (2 * y + b - D)
+        y = y
+            .checked_mul(y)?
+            .checked_add(c)?
+            .checked_div(y.checked_mul(N)?.checked_add(b)?.checked_sub(d)?)?;
+
+        if abs_diff(y, y_prev) <= Uint256::ONE {
+            return Ok(y);
+        }
+    }
+
+    bail!("failed to solve the pool's balance: no convergence");
+}
+
+/// Given an asset's balances before and after a liquidity provision, deduct a
+/// fee proportional to how much the balance after deviates from the "ideal"
+/// balance, that is, the balance if the deposit were perfectly balanced.
+fn deduct_imbalance_fee(
+    before: Uint256,
+    after: Uint256,
+    d_before: Uint256,
+    d_after: Uint256,
+    fee_rate: Udec128,
+) -> anyhow::Result<Uint256> {
+    let ideal = d_after.checked_mul(before)?.checked_div(d_before)?;
+    let fee = abs_diff(ideal, after)
+        .checked_into_prev()?
+        .checked_mul_dec_ceil(fee_rate)?
+        .into_next();
+
+    Ok(after.checked_sub(fee)?)
+}
+
+fn abs_diff(a: Uint256, b: Uint256) -> Uint256 {
+    if a > b {
+        a - b
+    } else {
+        b - a
     }
 }
@@ -21,6 +21,14 @@ pub trait PoolExt {
     /// We just output it in events for data logging purpose.
     fn swap(&mut self, input: Coin) -> anyhow::Result<(Coin, Coin)>;
 
+    /// Perform a swap operation that yields exactly the given output, after
+    /// the liquidity fee is deducted.
+    ///
+    /// Returns:
+    /// 1. swap input required, rounded up in favor of the pool;
+    /// 2. liquidity fee charged.
+    fn swap_exact_output(&mut self, output: Coin) -> anyhow::Result<(Coin, Coin)>;
+
     /// Provide liquidity to the pool.
     /// Returns the amount of liquidity tokens to be minted.
     fn provide_liquidity(&mut self, deposit: CoinPair) -> anyhow::Result<Uint128>;
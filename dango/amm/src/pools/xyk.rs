@@ -1,10 +1,10 @@
 use {
     crate::{PoolExt, PoolInit},
-    anyhow::bail,
+    anyhow::{bail, ensure},
     dango_types::amm::{XykParams, XykPool},
     grug::{
-        Coin, CoinPair, Inner, MultiplyFraction, MultiplyRatio, NextNumber, Number, PrevNumber,
-        Uint128,
+        Coin, CoinPair, Inner, MultiplyFraction, MultiplyRatio, NextNumber, Number, NumberConst,
+        PrevNumber, Udec128, Uint128,
     },
 };
 
@@ -75,6 +75,62 @@ impl PoolExt for XykPool {
         ))
     }
 
+    fn swap_exact_output(&mut self, output: Coin) -> anyhow::Result<(Coin, Coin)> {
+        let (offer, ask) = if output.denom == *self.liquidity.second().denom {
+            self.liquidity.as_mut()
+        } else if output.denom == *self.liquidity.first().denom {
+            self.liquidity.as_mut_rev()
+        } else {
+            bail!(
+                "invalid output denom! must be {}|{}, got: {}",
+                self.liquidity.first().denom,
+                self.liquidity.second().denom,
+                output.denom
+            );
+        };
+
+        // Compute the output before liquidity fee, such that the output after
+        // deducting the fee is no less than the requested amount.
+        //
+        // output_before_fee * (1 - fee_rate) >= output
+        let one_minus_fee_rate =
+            Udec128::ONE.checked_sub(*self.params.liquidity_fee_rate.inner())?;
+        let output_before_fee = output.amount.checked_div_dec_ceil(one_minus_fee_rate)?;
+
+        ensure!(
+            output_before_fee < *ask.amount,
+            "insufficient liquidity! requested: {}, available: {}",
+            output_before_fee,
+            ask.amount
+        );
+
+        // Compute swap input. (Note: use ceil rounding.)
+        //
+        // ask_pool * offer_pool = (ask_pool - output_before_fee) * (offer_pool + input)
+        // input = ask_pool * offer_pool / (ask_pool - output_before_fee) - offer_pool
+        let input = offer
+            .amount
+            .checked_multiply_ratio_ceil(*ask.amount, ask.amount.checked_sub(output_before_fee)?)?
+            .checked_sub(*offer.amount)?;
+
+        let liquidity_fee = output_before_fee.checked_sub(output.amount)?;
+
+        // Update pool state.
+        offer.amount.checked_add_assign(input)?;
+        ask.amount.checked_sub_assign(output.amount)?;
+
+        Ok((
+            Coin {
+                denom: offer.denom.clone(),
+                amount: input,
+            },
+            Coin {
+                denom: ask.denom.clone(),
+                amount: liquidity_fee,
+            },
+        ))
+    }
+
     // See `liquidity-providion.md` in docs for the math used here.
     fn provide_liquidity(&mut self, deposit: CoinPair) -> anyhow::Result<Uint128> {
         let pool1 = self.liquidity.first().amount.into_next();
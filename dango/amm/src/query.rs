@@ -1,7 +1,9 @@
 use {
-    crate::{perform_swap, CONFIG, POOLS},
-    dango_types::amm::{Config, Pool, PoolId, QueryMsg, SwapOutcome},
-    grug::{Bound, Coin, ImmutableCtx, Json, JsonSerExt, Order, StdResult, Storage, UniqueVec},
+    crate::{find_best_route, perform_swap, perform_swap_exact_output, CONFIG, POOLS},
+    dango_types::amm::{Config, Pool, PoolId, QueryMsg, RouteOutcome, SwapOutcome},
+    grug::{
+        Bound, Coin, Denom, ImmutableCtx, Json, JsonSerExt, Order, StdResult, Storage, UniqueVec,
+    },
     std::collections::BTreeMap,
 };
 
@@ -26,6 +28,18 @@ pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> anyhow::Result<Json> {
             let res = query_simulte(ctx.storage, input, route)?;
             res.to_json_value()
         },
+        QueryMsg::SimulateExactOutput { output, route } => {
+            let res = query_simulate_exact_output(ctx.storage, output, route)?;
+            res.to_json_value()
+        },
+        QueryMsg::BestRoute {
+            input,
+            target_denom,
+            max_hops,
+        } => {
+            let res = query_best_route(ctx.storage, input, target_denom, max_hops)?;
+            res.to_json_value()
+        },
     }
     .map_err(Into::into)
 }
@@ -65,3 +79,31 @@ fn query_simulte(
 
     perform_swap(&cfg, input, pools.iter_mut())
 }
+
+fn query_simulate_exact_output(
+    storage: &dyn Storage,
+    output: Coin,
+    route: UniqueVec<PoolId>,
+) -> anyhow::Result<SwapOutcome> {
+    let cfg = CONFIG.load(storage)?;
+    let mut pools = route
+        .into_iter()
+        .map(|pool_id| POOLS.load(storage, pool_id))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    perform_swap_exact_output(&cfg, output, pools.iter_mut())
+}
+
+fn query_best_route(
+    storage: &dyn Storage,
+    input: Coin,
+    target_denom: Denom,
+    max_hops: u32,
+) -> anyhow::Result<RouteOutcome> {
+    let cfg = CONFIG.load(storage)?;
+    let pools = POOLS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+
+    find_best_route(&cfg, &pools, input, target_denom, max_hops)
+}
@@ -0,0 +1,165 @@
+use {
+    crate::{perform_swap, PoolExt},
+    anyhow::{anyhow, ensure},
+    dango_types::amm::{Config, Pool, PoolId, RouteOutcome},
+    grug::{Coin, Denom, UniqueVec},
+    std::collections::BTreeMap,
+};
+
+/// The maximum number of hops the `BestRoute` query can search for.
+///
+/// The number of routes, and thus of swaps simulated, grows exponentially with
+/// the number of hops, so we cap it to keep the query's gas cost reasonable.
+pub const MAX_HOPS: u32 = 4;
+
+/// Find the route that yields the most output when swapping `input` into
+/// `target_denom`, going through at most `max_hops` pools.
+///
+/// The search advances one hop at a time, and tries every route that doesn't
+/// visit the same denom twice. Keeping only the best route to each denom after
+/// each hop isn't enough: the best route to a denom may have already visited a
+/// denom that the way onward to the target needs, whereas a worse route may
+/// not have. Swaps that fail to simulate (e.g. due to insufficient liquidity)
+/// are skipped. If two routes yield the same output, the shorter one is
+/// preferred.
+pub fn find_best_route(
+    cfg: &Config,
+    pools: &BTreeMap<PoolId, Pool>,
+    input: Coin,
+    target_denom: Denom,
+    max_hops: u32,
+) -> anyhow::Result<RouteOutcome> {
+    ensure!(
+        input.denom != target_denom,
+        "input and target denoms must be different, got: {}",
+        target_denom
+    );
+
+    ensure!(
+        max_hops > 0 && max_hops <= MAX_HOPS,
+        "max hops must be between 1 and {}, got: {}",
+        MAX_HOPS,
+        max_hops
+    );
+
+    // Build the pool graph: for each denom, the pools that trade it.
+    let mut pools_by_denom = BTreeMap::<&Denom, Vec<PoolId>>::new();
+    for (pool_id, pool) in pools {
+        let (denom1, denom2) = pool_denoms(pool);
+        pools_by_denom.entry(denom1).or_default().push(*pool_id);
+        pools_by_denom.entry(denom2).or_default().push(*pool_id);
+    }
+
+    // The routes of the current number of hops that may be continued.
+    let mut frontier = vec![Candidate {
+        route: Vec::new(),
+        denoms: vec![&input.denom],
+        output: input.clone(),
+    }];
+    let mut best: Option<Candidate> = None;
+
+    for _ in 0..max_hops {
+        let mut next = Vec::new();
+
+        for candidate in &frontier {
+            let denom = &candidate.output.denom;
+            let Some(pool_ids) = pools_by_denom.get(denom) else {
+                continue;
+            };
+
+            for pool_id in pool_ids {
+                let pool = &pools[pool_id];
+                let (denom1, denom2) = pool_denoms(pool);
+                let next_denom = if denom == denom1 {
+                    denom2
+                } else {
+                    denom1
+                };
+
+                // Don't go back to a denom that's already in the route. This
+                // also ensures the same pool isn't used twice.
+                if candidate.denoms.contains(&next_denom) {
+                    continue;
+                }
+
+                let Ok(output) = simulate_swap(pool, candidate.output.clone()) else {
+                    continue;
+                };
+
+                let candidate = Candidate {
+                    route: [candidate.route.as_slice(), &[*pool_id]].concat(),
+                    denoms: [candidate.denoms.as_slice(), &[next_denom]].concat(),
+                    output,
+                };
+
+                // Routes that reach the target denom end here. A route only
+                // replaces the best if its output is strictly more, so that
+                // shorter routes, which are found first, are preferred.
+                if *next_denom == target_denom {
+                    if best
+                        .as_ref()
+                        .map_or(true, |best| candidate.output.amount > best.output.amount)
+                    {
+                        best = Some(candidate);
+                    }
+                } else {
+                    next.push(candidate);
+                }
+            }
+        }
+
+        frontier = next;
+    }
+
+    let Some(Candidate { route, .. }) = best else {
+        return Err(anyhow!(
+            "no swap route found from {} to {} within {} hops",
+            input.denom,
+            target_denom,
+            max_hops
+        ));
+    };
+
+    // Simulate the route as a whole, which also deducts the protocol fee.
+    let mut route_pools = route
+        .iter()
+        .map(|pool_id| pools[pool_id].clone())
+        .collect::<Vec<_>>();
+
+    let outcome = perform_swap(cfg, input, route_pools.iter_mut())?;
+
+    Ok(RouteOutcome {
+        // The route can't contain duplicate pools, because the search never
+        // visits the same denom twice.
+        route: UniqueVec::new_unchecked(route),
+        outcome,
+    })
+}
+
+/// A route being searched, and the output of swapping through it, before the
+/// protocol fee is deducted.
+struct Candidate<'a> {
+    route: Vec<PoolId>,
+    denoms: Vec<&'a Denom>,
+    output: Coin,
+}
+
+/// Simulate swapping through a single pool, without deducting the protocol
+/// fee, which is only charged once at the end of the route.
+fn simulate_swap(pool: &Pool, input: Coin) -> anyhow::Result<Coin> {
+    let (output, _) = match pool.clone() {
+        Pool::Xyk(mut xyk) => xyk.swap(input)?,
+        Pool::Concentrated(mut concentrated) => concentrated.swap(input)?,
+    };
+
+    Ok(output)
+}
+
+fn pool_denoms(pool: &Pool) -> (&Denom, &Denom) {
+    let (coin1, coin2) = match pool {
+        Pool::Xyk(xyk) => xyk.liquidity.as_ref(),
+        Pool::Concentrated(concentrated) => concentrated.liquidity.as_ref(),
+    };
+
+    (coin1.denom, coin2.denom)
+}
@@ -1,7 +1,7 @@
 use {
     crate::PoolExt,
     dango_types::amm::{Config, Pool, SwapOutcome},
-    grug::{Coin, Coins, Inner, MultiplyFraction, Number},
+    grug::{Coin, Coins, Inner, MultiplyFraction, Number, NumberConst, Udec128},
 };
 
 // Note: this function assumes the swap route doesn't contain any loop, meaning
@@ -14,6 +14,9 @@ where
 {
     let mut liquidity_fees = Coins::new();
 
+    // Record the swap input before it's consumed by the pools.
+    let swap_input = input.clone();
+
     // Iterate through the pools and perform swaps.
     for pool in pools {
         let (output, liquidity_fee) = match pool {
@@ -40,6 +43,7 @@ where
     output.amount = output.amount.checked_sub(protocol_fee)?;
 
     Ok(SwapOutcome {
+        input: swap_input,
         protocol_fee: Coin {
             denom: output.denom.clone(),
             amount: protocol_fee,
@@ -48,3 +52,56 @@ where
         liquidity_fees,
     })
 }
+
+// Same as `perform_swap`, but computes the input needed to obtain exactly the
+// given output, after both the liquidity and protocol fees are deducted.
+//
+// The pools are iterated in reverse order: the input required by the last pool
+// is the output the second last pool needs to produce, and so on.
+pub fn perform_swap_exact_output<'a, I>(
+    cfg: &Config,
+    output: Coin,
+    pools: I,
+) -> anyhow::Result<SwapOutcome>
+where
+    I: DoubleEndedIterator<Item = &'a mut Pool>,
+{
+    let mut liquidity_fees = Coins::new();
+
+    // Compute the output before protocol fee, such that the output after
+    // deducting the fee is no less than the requested amount.
+    // (Note: use ceil rounding.)
+    let one_minus_fee_rate = Udec128::ONE.checked_sub(*cfg.protocol_fee_rate.inner())?;
+    let output_before_fee = output.amount.checked_div_dec_ceil(one_minus_fee_rate)?;
+
+    let protocol_fee = Coin {
+        denom: output.denom.clone(),
+        amount: output_before_fee.checked_sub(output.amount)?,
+    };
+
+    let mut required = Coin {
+        denom: output.denom.clone(),
+        amount: output_before_fee,
+    };
+
+    // Iterate through the pools in reverse and compute the required inputs.
+    for pool in pools.rev() {
+        let (input, liquidity_fee) = match pool {
+            Pool::Xyk(xyk) => xyk.swap_exact_output(required)?,
+            Pool::Concentrated(concentrated) => concentrated.swap_exact_output(required)?,
+        };
+
+        // The input of this pool is the output of the previous pool.
+        required = input;
+
+        // Track the liquidity fees charged.
+        liquidity_fees.insert(liquidity_fee)?;
+    }
+
+    Ok(SwapOutcome {
+        input: required,
+        output,
+        protocol_fee,
+        liquidity_fees,
+    })
+}
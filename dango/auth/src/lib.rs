@@ -1,24 +1,48 @@
 use {
-    alloy_dyn_abi::{Eip712Domain, TypedData},
+    alloy_dyn_abi::{DynSolType, Eip712Domain, TypedData},
     alloy_primitives::U160,
     anyhow::{anyhow, bail, ensure},
     base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine},
-    dango_account_factory::{ACCOUNTS_BY_USER, KEYS, KEYS_BY_USER},
+    dango_account_factory::{ACCOUNTS_BY_USER, KEYS, KEYS_BY_USER, SESSION_KEYS},
     dango_types::{
-        auth::{ClientData, Credential, Key, Metadata, SignDoc},
+        auth::{
+            ClientData, Credential, Grant, GrantCreated, GrantRevoked, Key, Metadata, Session,
+            SignDoc,
+        },
         config::ACCOUNT_FACTORY_KEY,
+        taxman,
     },
     grug::{
-        json, Addr, AuthCtx, AuthMode, BorshDeExt, Counter, Inner, JsonDeExt, JsonSerExt, Query, Tx,
+        json, Addr, AuthCtx, AuthMode, BorshDeExt, Bound, Coin, Coins, Counter, Hash160, Inner,
+        IsZero, JsonDeExt, JsonSerExt, Map, Message, MsgExecute, MsgTransfer, MultiplyFraction,
+        MutableCtx, Order, QuerierWrapper, Query, Response, StdResult, Storage, Timestamp, Tx,
+        Udec128, Uint128,
     },
+    std::collections::BTreeMap,
 };
 
+const DEFAULT_PAGE_LIMIT: u32 = 30;
+
 /// Expected sequence number of the next transaction this account sends.
 ///
 /// All three account types (spot, margin, Safe) stores their sequences in this
 /// same storage slot.
 pub const NEXT_SEQUENCE: Counter<u32> = Counter::new("sequence", 0, 1);
 
+/// Grants given by this account, indexed by grantees.
+///
+/// Similar to the sequence, all three account types store their grants here.
+pub const GRANTS: Map<Addr, Grant> = Map::new("grant");
+
+/// Tokens spent so far by each session key, indexed by key hashes, along with
+/// the ID of the session during which they were spent.
+///
+/// A session key's spend limit applies to each account separately, so the
+/// spending is tracked here, not in the account factory. If the ID doesn't
+/// match the key's current session, i.e. the key has been revoked and
+/// registered again, the spending is reset.
+pub const SESSION_SPENDS: Map<Hash160, (u32, Coins)> = Map::new("session_spend");
+
 /// Authenticate a transaction.
 ///
 /// This logic is shared across all three account types.
@@ -47,33 +71,43 @@ pub fn authenticate_tx(
     // Increment the sequence.
     let (sequence, _) = NEXT_SEQUENCE.increment(ctx.storage)?;
 
-    // Query the account factory. We need to do three things:
-    // - ensure the `tx.sender` is associated with the username;
+    // If the transaction is sent under a grant, the signer acts through the
+    // grantee account, so it's the grantee that must be associated with the
+    // username, not the sender.
+    let signer_account = metadata.grantee.unwrap_or(tx.sender);
+
+    // Query the account factory. We need to do four things:
+    // - ensure the signer account is associated with the username;
     // - ensure the `key_hash` is associated wit the username;
-    // - query the key by key hash.
+    // - query the key by key hash;
+    // - query the session scope, in case the key is a session key.
     //
     // We use Wasm raw queries instead of smart queries to optimize on gas.
     // We also user the multi query to reduce the number of FFI calls.
-    let key = {
-        let [res1, res2, res3] = ctx.querier.query_multi([
+    let (key, maybe_session) = {
+        let [res1, res2, res3, res4] = ctx.querier.query_multi([
             Query::wasm_raw(
                 factory,
-                ACCOUNTS_BY_USER.path((&metadata.username, tx.sender)),
+                ACCOUNTS_BY_USER.path((&metadata.username, signer_account)),
             ),
             Query::wasm_raw(
                 factory,
                 KEYS_BY_USER.path((&metadata.username, metadata.key_hash)),
             ),
             Query::wasm_raw(factory, KEYS.path(metadata.key_hash)),
+            Query::wasm_raw(
+                factory,
+                SESSION_KEYS.path((&metadata.username, metadata.key_hash)),
+            ),
         ])?;
 
-        // If the sender account is associated with the username, then an entry
+        // If the signer account is associated with the username, then an entry
         // must exist in the `ACCOUNTS_BY_USER` set, and the value should be
         // empty because we Borsh for encoding.
         ensure!(
             res1.as_wasm_raw().is_some_and(|bytes| bytes.is_empty()),
             "account {} isn't associated with user `{}`",
-            tx.sender,
+            signer_account,
             metadata.username,
         );
 
@@ -87,9 +121,19 @@ pub fn authenticate_tx(
         );
 
         // Deserialize the key from Borsh bytes.
-        res3.as_wasm_raw()
+        let key = res3
+            .as_wasm_raw()
             .ok_or_else(|| anyhow!("key hash {} not found", metadata.key_hash))?
-            .deserialize_borsh()?
+            .deserialize_borsh()?;
+
+        // If the key is a session key, deserialize its session ID and scope as
+        // well.
+        let maybe_session = res4
+            .as_wasm_raw()
+            .map(|bytes| bytes.deserialize_borsh::<(u32, Session)>())
+            .transpose()?;
+
+        (key, maybe_session)
     };
 
     // Verify sequence.
@@ -119,6 +163,45 @@ pub fn authenticate_tx(
         AuthMode::Simulate => (),
     }
 
+    // The taxman withholds the fee from the sender, which is the granter
+    // account for a grant, so the fee must count against the spend limits of
+    // grants and session keys. Otherwise, the grantee or the session key could
+    // drain the account by choosing a high gas price or gas limit.
+    let max_fee = if metadata.grantee.is_some() || maybe_session.is_some() {
+        query_max_fee(&ctx.querier, ctx.mode, tx.gas_limit, metadata.gas_price)?
+    } else {
+        None
+    };
+
+    // If the transaction is sent under a grant, ensure the grant permits the
+    // messages, and deduct the tokens spent from the spend limit.
+    if let Some(grantee) = metadata.grantee {
+        consume_grant(
+            ctx.storage,
+            ctx.block.timestamp,
+            ctx.contract,
+            grantee,
+            &tx.msgs,
+            max_fee.as_ref(),
+        )?;
+    }
+
+    // If the transaction is signed by a session key, ensure it's within the
+    // session's scope, and record the tokens spent.
+    if let Some((session_id, session)) = maybe_session {
+        consume_session(
+            ctx.storage,
+            ctx.block.timestamp,
+            factory,
+            tx.sender,
+            metadata.key_hash,
+            session_id,
+            &session,
+            &tx.msgs,
+            max_fee.as_ref(),
+        )?;
+    }
+
     // Verify signature.
     match ctx.mode {
         AuthMode::Check | AuthMode::Finalize => match (key, tx.credential.deserialize_json()?) {
@@ -130,6 +213,50 @@ pub fn authenticate_tx(
                 // Recreate the EIP-712 data originally used for signing.
                 // Verify that the critical values in the transaction such as
                 // the message and the verifying contract (sender).
+                let mut message = json!({
+                    "chainId": ctx.chain_id,
+                    "sequence": metadata.sequence,
+                    "messages": tx.msgs,
+                });
+
+                // The gas price and the grantee are only part of the signed
+                // data if provided. In that case, they must be declared in the
+                // types, otherwise they're left out of the signing hash, and
+                // anyone relaying the transaction could change them.
+                let optional_fields = [
+                    (
+                        "gas price",
+                        "gasPrice",
+                        metadata.gas_price.map(|p| p.to_json_value()).transpose()?,
+                    ),
+                    (
+                        "grantee",
+                        "grantee",
+                        metadata.grantee.map(|g| g.to_json_value()).transpose()?,
+                    ),
+                ];
+
+                if optional_fields.iter().any(|(_, _, value)| value.is_some()) {
+                    let DynSolType::CustomStruct { prop_names, .. } =
+                        resolver.resolve("Message")?
+                    else {
+                        bail!("EIP-712 primary type `Message` isn't a struct");
+                    };
+
+                    for (field, prop_name, value) in optional_fields {
+                        let Some(value) = value else {
+                            continue;
+                        };
+
+                        ensure!(
+                            prop_names.iter().any(|name| name == prop_name),
+                            "{field} isn't signed: `{prop_name}` is missing from EIP-712 type `Message`"
+                        );
+
+                        message[prop_name] = value;
+                    }
+                }
+
                 let typed_data = TypedData {
                     resolver,
                     domain: Eip712Domain {
@@ -140,11 +267,7 @@ pub fn authenticate_tx(
                         ..Default::default()
                     },
                     primary_type: "Message".to_string(),
-                    message: json!({
-                        "chainId": ctx.chain_id,
-                        "sequence": metadata.sequence,
-                        "messages": tx.msgs,
-                    }),
+                    message,
                 };
 
                 // EIP-712 hash used in the signature.
@@ -166,6 +289,8 @@ pub fn authenticate_tx(
                             messages: tx.msgs,
                             chain_id: ctx.chain_id,
                             sequence: metadata.sequence,
+                            gas_price: metadata.gas_price,
+                            grantee: metadata.grantee,
                         }
                         .to_json_vec()?,
                     );
@@ -202,12 +327,34 @@ pub fn authenticate_tx(
                         messages: tx.msgs,
                         chain_id: ctx.chain_id,
                         sequence: metadata.sequence,
+                        gas_price: metadata.gas_price,
+                        grantee: metadata.grantee,
                     }
                     .to_json_vec()?,
                 );
 
                 ctx.api.secp256k1_verify(&sign_bytes, &sig, &pk)?;
             },
+            (Key::Ed25519(pk), Credential::Ed25519(sig)) => {
+                let sign_bytes = ctx.api.sha2_256(
+                    &SignDoc {
+                        sender: tx.sender,
+                        messages: tx.msgs,
+                        chain_id: ctx.chain_id,
+                        sequence: metadata.sequence,
+                        gas_price: metadata.gas_price,
+                        grantee: metadata.grantee,
+                    }
+                    .to_json_vec()?,
+                );
+
+                // Solana-style wallets sign the message as is ("pure" Ed25519),
+                // whereas `ed25519_verify` expects the message to be prehashed
+                // (Ed25519ph). `ed25519_batch_verify` works with raw messages,
+                // so we use it here with a batch of one.
+                ctx.api
+                    .ed25519_batch_verify(&[&sign_bytes], &[sig.as_ref()], &[pk.as_ref()])?;
+            },
             _ => bail!("key and credential types don't match!"),
         },
         // No need to verify signature in simulation mode.
@@ -217,6 +364,243 @@ pub fn authenticate_tx(
     Ok(())
 }
 
+/// Compute the maximum fee the taxman may withhold from the sender for this
+/// transaction, i.e. the gas limit times the gas price, in the fee denom.
+///
+/// Returns `None` if no fee is charged, which is the case in simulation mode.
+fn query_max_fee(
+    querier: &QuerierWrapper,
+    mode: AuthMode,
+    gas_limit: u64,
+    gas_price: Option<Udec128>,
+) -> anyhow::Result<Option<Coin>> {
+    // The taxman doesn't charge any fee during simulation, in which case the
+    // gas limit is the node's query gas limit, not an actual limit requested.
+    if mode == AuthMode::Simulate {
+        return Ok(None);
+    }
+
+    let cfg = querier.query_config()?;
+    let fee_cfg = querier.query_wasm_smart(cfg.taxman, taxman::QueryConfigRequest {})?;
+
+    // If the gas price isn't provided, the taxman charges the base fee. If it's
+    // provided but lower than the base fee, the taxman rejects the tx anyway.
+    let gas_price = match gas_price {
+        Some(gas_price) => gas_price,
+        None => querier.query_wasm_smart(cfg.taxman, taxman::QueryBaseFeeRequest {})?,
+    };
+
+    // Same as in the taxman, ceil the amount.
+    let amount = Uint128::new(gas_limit as u128).checked_mul_dec_ceil(gas_price)?;
+
+    if amount.is_zero() {
+        return Ok(None);
+    }
+
+    Ok(Some(Coin {
+        denom: fee_cfg.fee_denom,
+        amount,
+    }))
+}
+
+fn consume_grant(
+    storage: &mut dyn Storage,
+    current_time: Timestamp,
+    account: Addr,
+    grantee: Addr,
+    msgs: &[Message],
+    max_fee: Option<&Coin>,
+) -> anyhow::Result<()> {
+    let mut grant = GRANTS
+        .may_load(storage, grantee)?
+        .ok_or_else(|| anyhow!("no grant found for grantee {grantee}"))?;
+
+    ensure!(
+        !grant.is_expired(current_time),
+        "grant for grantee {grantee} has expired"
+    );
+
+    // The maximum fee counts against the spend limit. Any unused fee refunded
+    // by the taxman isn't added back to it.
+    if let (Some(spend_limit), Some(fee)) = (&mut grant.spend_limit, max_fee) {
+        deduct_spend_limit(spend_limit, fee.clone())?;
+    }
+
+    for msg in msgs {
+        let spent = match msg {
+            Message::Transfer(MsgTransfer { coins, .. }) => coins,
+            Message::Execute(MsgExecute {
+                contract, funds, ..
+            }) => {
+                // A grantee may never execute the account itself. Otherwise, it
+                // would be able to alter its own grant.
+                ensure!(
+                    *contract != account,
+                    "grantee can't execute the granter account"
+                );
+
+                funds
+            },
+            _ => bail!("grantee can only send transfer or execute messages"),
+        };
+
+        ensure!(
+            grant.permits(msg),
+            "message isn't permitted by the grant for grantee {grantee}"
+        );
+
+        if let Some(spend_limit) = &mut grant.spend_limit {
+            for coin in spent.clone() {
+                deduct_spend_limit(spend_limit, coin)?;
+            }
+        }
+    }
+
+    GRANTS.save(storage, grantee, &grant)?;
+
+    Ok(())
+}
+
+fn deduct_spend_limit(spend_limit: &mut Coins, coin: Coin) -> anyhow::Result<()> {
+    let remaining = spend_limit.amount_of(&coin.denom);
+
+    ensure!(
+        remaining >= coin.amount,
+        "spend limit exceeded for denom `{}`: remaining {}, requested {}",
+        coin.denom,
+        remaining,
+        coin.amount
+    );
+
+    spend_limit.deduct(coin)?;
+
+    Ok(())
+}
+
+fn consume_session(
+    storage: &mut dyn Storage,
+    current_time: Timestamp,
+    factory: Addr,
+    account: Addr,
+    key_hash: Hash160,
+    session_id: u32,
+    session: &Session,
+    msgs: &[Message],
+    max_fee: Option<&Coin>,
+) -> anyhow::Result<()> {
+    ensure!(
+        !session.is_expired(current_time),
+        "session key {key_hash} has expired"
+    );
+
+    let mut spent = SESSION_SPENDS
+        .may_load(storage, key_hash)?
+        .filter(|(id, _)| *id == session_id)
+        .map(|(_, spent)| spent)
+        .unwrap_or_default();
+
+    // Same as with grants, the maximum fee counts as spent.
+    if let Some(fee) = max_fee {
+        spent.insert(fee.clone())?;
+    }
+
+    for msg in msgs {
+        let (target, coins) = match msg {
+            Message::Transfer(MsgTransfer { to, coins }) => (to, coins),
+            Message::Execute(MsgExecute {
+                contract, funds, ..
+            }) => (contract, funds),
+            _ => bail!("session key can only send transfer or execute messages"),
+        };
+
+        // A session key may never interact with the account factory or the
+        // account itself. Otherwise, it would be able to register more keys,
+        // or create grants, escaping the session's scope.
+        ensure!(
+            *target != factory && *target != account,
+            "session key can't interact with the account factory or the account itself"
+        );
+
+        ensure!(
+            session.allowed_contracts.contains(target),
+            "contract {target} isn't allowed by session key {key_hash}"
+        );
+
+        for coin in coins.clone() {
+            spent.insert(coin)?;
+        }
+    }
+
+    for coin in spent.clone() {
+        let max = session.max_spend.amount_of(&coin.denom);
+
+        ensure!(
+            coin.amount <= max,
+            "max spend exceeded for denom `{}`: max {}, spent {}",
+            coin.denom,
+            max,
+            coin.amount
+        );
+    }
+
+    SESSION_SPENDS.save(storage, key_hash, &(session_id, spent))?;
+
+    Ok(())
+}
+
+/// Authorize a grantee to send transactions on behalf of the account.
+///
+/// This logic is shared across all three account types.
+pub fn create_grant(ctx: MutableCtx, grantee: Addr, grant: Grant) -> anyhow::Result<Response> {
+    ensure!(
+        ctx.sender == ctx.contract,
+        "only the account itself can create grants"
+    );
+
+    ensure!(grantee != ctx.contract, "an account can't grant itself");
+
+    GRANTS.save(ctx.storage, grantee, &grant)?;
+
+    Ok(Response::new().add_event(&GrantCreated { grantee, grant })?)
+}
+
+/// Revoke the grant given to a grantee.
+///
+/// This logic is shared across all three account types.
+pub fn revoke_grant(ctx: MutableCtx, grantee: Addr) -> anyhow::Result<Response> {
+    ensure!(
+        ctx.sender == ctx.contract,
+        "only the account itself can revoke grants"
+    );
+
+    ensure!(
+        GRANTS.has(ctx.storage, grantee),
+        "no grant found for grantee {grantee}"
+    );
+
+    GRANTS.remove(ctx.storage, grantee);
+
+    Ok(Response::new().add_event(&GrantRevoked { grantee })?)
+}
+
+pub fn query_grant(storage: &dyn Storage, grantee: Addr) -> StdResult<Option<Grant>> {
+    GRANTS.may_load(storage, grantee)
+}
+
+pub fn query_grants(
+    storage: &dyn Storage,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<BTreeMap<Addr, Grant>> {
+    let start = start_after.map(Bound::Exclusive);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize;
+
+    GRANTS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
 // ----------------------------------- tests -----------------------------------
 
 #[cfg(test)]
@@ -1,10 +1,10 @@
 use {
-    crate::{BALANCES, NAMESPACE_OWNERS, SUPPLIES},
+    crate::{BALANCES, NAMESPACE_OWNERS, SUPPLIES, WITHHELD},
     anyhow::{bail, ensure},
     dango_types::bank::{ExecuteMsg, InstantiateMsg},
     grug::{
-        Addr, BankMsg, Denom, IsZero, MutableCtx, Number, NumberConst, Part, Response, StdResult,
-        Storage, SudoCtx, Uint128,
+        Addr, BankMsg, Denom, IsZero, MutableCtx, Number, NumberConst, Order, Part, Response,
+        StdResult, Storage, SudoCtx, Uint128,
     },
     std::collections::HashMap,
 };
@@ -55,6 +55,19 @@ pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
             denom,
             amount,
         } => force_transfer(ctx, from, to, denom, amount),
+        ExecuteMsg::Withhold {
+            from,
+            to,
+            denom,
+            amount,
+        } => withhold(ctx, from, to, denom, amount),
+        ExecuteMsg::Release {
+            from,
+            to,
+            denom,
+            amount,
+        } => release(ctx, from, to, denom, amount),
+        ExecuteMsg::Settle {} => settle(ctx),
     }
 }
 
@@ -129,18 +142,70 @@ fn force_transfer(
     denom: Denom,
     amount: Uint128,
 ) -> anyhow::Result<Response> {
+    ensure_taxman(&ctx)?;
+
+    decrease_balance(ctx.storage, &from, &denom, amount)?;
+    increase_balance(ctx.storage, &to, &denom, amount)?;
+
+    Ok(Response::new())
+}
+
+fn withhold(
+    ctx: MutableCtx,
+    from: Addr,
+    to: Addr,
+    denom: Denom,
+    amount: Uint128,
+) -> anyhow::Result<Response> {
+    ensure_taxman(&ctx)?;
+
+    decrease_balance(ctx.storage, &from, &denom, amount)?;
+    increase_withheld(ctx.storage, &from, &to, &denom, amount)?;
+
+    Ok(Response::new())
+}
+
+fn release(
+    ctx: MutableCtx,
+    from: Addr,
+    to: Addr,
+    denom: Denom,
+    amount: Uint128,
+) -> anyhow::Result<Response> {
+    ensure_taxman(&ctx)?;
+
+    decrease_withheld(ctx.storage, &from, &to, &denom, amount)?;
+    increase_balance(ctx.storage, &from, &denom, amount)?;
+
+    Ok(Response::new())
+}
+
+fn settle(ctx: MutableCtx) -> anyhow::Result<Response> {
+    ensure_taxman(&ctx)?;
+
+    let withheld = WITHHELD
+        .range(ctx.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for ((_from, to, denom), amount) in withheld {
+        increase_balance(ctx.storage, &to, &denom, amount)?;
+    }
+
+    WITHHELD.clear(ctx.storage, None, None);
+
+    Ok(Response::new())
+}
+
+fn ensure_taxman(ctx: &MutableCtx) -> anyhow::Result<()> {
     let cfg = ctx.querier.query_config()?;
 
-    // Only taxman can force transfer.
+    // Only taxman can force transfer, or withhold coins.
     ensure!(
         ctx.sender == cfg.taxman,
         "you don't have the right, O you don't have the right"
     );
 
-    decrease_balance(ctx.storage, &from, &denom, amount)?;
-    increase_balance(ctx.storage, &to, &denom, amount)?;
-
-    Ok(Response::new())
+    Ok(())
 }
 
 #[cfg_attr(not(feature = "library"), grug::export)]
@@ -202,6 +267,42 @@ fn increase_balance(
     })
 }
 
+fn increase_withheld(
+    storage: &mut dyn Storage,
+    from: &Addr,
+    to: &Addr,
+    denom: &Denom,
+    amount: Uint128,
+) -> StdResult<Option<Uint128>> {
+    WITHHELD.may_modify(storage, (from, to, denom), |maybe_amount| {
+        let withheld = maybe_amount.unwrap_or(Uint128::ZERO).checked_add(amount)?;
+        // Only write to storage if the amount is non-zero.
+        if withheld.is_zero() {
+            Ok(None)
+        } else {
+            Ok(Some(withheld))
+        }
+    })
+}
+
+fn decrease_withheld(
+    storage: &mut dyn Storage,
+    from: &Addr,
+    to: &Addr,
+    denom: &Denom,
+    amount: Uint128,
+) -> StdResult<Option<Uint128>> {
+    WITHHELD.may_modify(storage, (from, to, denom), |maybe_amount| {
+        let withheld = maybe_amount.unwrap_or(Uint128::ZERO).checked_sub(amount)?;
+        // If the amount is reduced to zero, delete it, to save disk space.
+        if withheld.is_zero() {
+            Ok(None)
+        } else {
+            Ok(Some(withheld))
+        }
+    })
+}
+
 fn decrease_balance(
     storage: &mut dyn Storage,
     address: &Addr,
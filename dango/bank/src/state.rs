@@ -5,3 +5,7 @@ pub const SUPPLIES: Map<&Denom, Uint128> = Map::new("supply");
 pub const BALANCES: Map<(&Addr, &Denom), Uint128> = Map::new("balance");
 
 pub const NAMESPACE_OWNERS: Map<&Part, Addr> = Map::new("namespace_owner");
+
+/// Coins withheld from accounts, pending being credited to their receivers:
+/// (from, to, denom) => amount
+pub const WITHHELD: Map<(&Addr, &Addr, &Denom), Uint128> = Map::new("withheld");
@@ -66,6 +66,8 @@ fn main() {
         Udec128::new_percent(25),                 // 0.25 uusdc per gas unit
         Some(Uint128::new(10_000_000)),           // 10 USDC
         Duration::from_seconds(7 * 24 * 60 * 60), // 1 week
+        100_000_000,                              // 100M gas per block
+        None,                                     // no state deposit
     )
     .unwrap();
 
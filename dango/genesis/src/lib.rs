@@ -6,13 +6,13 @@ use {
         bank,
         config::{ACCOUNT_FACTORY_KEY, IBC_TRANSFER_KEY},
         ibc_transfer,
-        lending::{self, MarketUpdates},
+        lending::{self, InterestRateModel, MarketUpdates, OptimalUtilization},
         taxman, token_factory,
     },
     grug::{
         btree_map, btree_set, Addr, Binary, Coin, Coins, Config, Denom, Duration, GenesisState,
-        Hash160, Hash256, HashExt, JsonSerExt, Message, NonZero, Permission, Permissions,
-        StdResult, Udec128, Uint128, GENESIS_SENDER,
+        Hash160, Hash256, HashExt, JsonSerExt, Message, NonZero, NumberConst, Permission,
+        Permissions, StateDepositParams, StdResult, Udec128, Uint128, GENESIS_SENDER,
     },
     serde::Serialize,
     std::{collections::BTreeMap, error::Error, fs, io, path::Path},
@@ -87,6 +87,8 @@ pub fn build_genesis<T, D>(
     fee_rate: Udec128,
     token_creation_fee: Option<Uint128>,
     max_orphan_age: Duration,
+    max_block_gas: u64,
+    state_deposit_per_byte: Option<Uint128>,
 ) -> anyhow::Result<(GenesisState, Contracts, Addresses)>
 where
     T: Into<Binary>,
@@ -195,7 +197,14 @@ where
         lending_code_hash,
         &lending::InstantiateMsg {
             markets: btree_map! {
-                fee_denom.clone() => MarketUpdates {},
+                fee_denom.clone() => MarketUpdates {
+                    interest_rate_model: Some(InterestRateModel {
+                        base_rate: Udec128::ZERO,
+                        optimal_utilization: OptimalUtilization::new_unchecked(Udec128::new_percent(80)),
+                        first_slope: Udec128::new_percent(4),
+                        second_slope: Udec128::new_percent(75),
+                    }),
+                },
             },
         },
         "dango/lending",
@@ -243,8 +252,9 @@ where
         taxman_code_hash,
         &taxman::InstantiateMsg {
             config: taxman::Config {
-                fee_denom,
+                fee_denom: fee_denom.clone(),
                 fee_rate,
+                fee_market: None,
             },
         },
         "dango/taxman",
@@ -270,7 +280,8 @@ where
         owner: addresses.get(owner).cloned().unwrap(),
         bank,
         taxman,
-        cronjobs: BTreeMap::new(),
+        // The taxman adjusts the base fee at the beginning of every block.
+        cronjobs: btree_map! { taxman => Duration::from_seconds(0) },
         permissions,
         max_orphan_age,
     };
@@ -284,6 +295,12 @@ where
         config,
         msgs,
         app_configs,
+        max_block_gas: Some(max_block_gas),
+        // State deposit is paid in the fee denom.
+        state_deposit: state_deposit_per_byte.map(|amount_per_byte| StateDepositParams {
+            denom: fee_denom,
+            amount_per_byte,
+        }),
     };
 
     Ok((genesis_state, contracts, addresses))
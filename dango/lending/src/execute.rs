@@ -1,5 +1,5 @@
 use {
-    crate::{DEBTS, MARKETS},
+    crate::{MarketExt, DEBTS, MARKETS},
     anyhow::{anyhow, bail, ensure, Ok},
     dango_account_factory::ACCOUNTS,
     dango_types::{
@@ -8,14 +8,17 @@ use {
         config::ACCOUNT_FACTORY_KEY,
         lending::{ExecuteMsg, InstantiateMsg, Market, MarketUpdates, NAMESPACE, SUBNAMESPACE},
     },
-    grug::{Addr, BorshDeExt, Coin, Coins, Denom, Message, MutableCtx, Response},
+    grug::{
+        Addr, BorshDeExt, Coin, Coins, Denom, IsZero, Message, MutableCtx, NumberConst, Response,
+        Storage, Timestamp, Udec128, Uint128,
+    },
     std::collections::BTreeMap,
 };
 
 #[cfg_attr(not(feature = "library"), grug::export)]
 pub fn instantiate(ctx: MutableCtx, msg: InstantiateMsg) -> anyhow::Result<Response> {
-    for (denom, _updates) in msg.markets {
-        MARKETS.save(ctx.storage, &denom, &Market {})?;
+    for (denom, updates) in msg.markets {
+        apply_market_updates(ctx.storage, ctx.block.timestamp, &denom, updates)?;
     }
 
     Ok(Response::new())
@@ -28,6 +31,7 @@ pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
         ExecuteMsg::Deposit {} => deposit(ctx),
         ExecuteMsg::Withdraw {} => withdraw(ctx),
         ExecuteMsg::Borrow(coins) => borrow(ctx, coins),
+        ExecuteMsg::Repay {} => repay(ctx),
     }
 }
 
@@ -41,27 +45,64 @@ fn update_markets(
         "Only the owner can whitelist denoms"
     );
 
-    for (denom, _updates) in updates {
-        MARKETS.save(ctx.storage, &denom, &Market {})?;
+    for (denom, updates) in updates {
+        apply_market_updates(ctx.storage, ctx.block.timestamp, &denom, updates)?;
     }
 
     Ok(Response::new())
 }
 
+fn apply_market_updates(
+    storage: &mut dyn Storage,
+    now: Timestamp,
+    denom: &Denom,
+    updates: MarketUpdates,
+) -> anyhow::Result<()> {
+    let market = match MARKETS.may_load(storage, denom)? {
+        // Accrue interest under the current interest rate model before
+        // applying the updates.
+        Some(mut market) => {
+            market.accrue_interest(now)?;
+
+            if let Some(interest_rate_model) = updates.interest_rate_model {
+                market.interest_rate_model = interest_rate_model;
+            }
+
+            market
+        },
+        None => Market {
+            interest_rate_model: updates.interest_rate_model.ok_or_else(|| {
+                anyhow!("interest rate model must be provided when creating market for {denom}")
+            })?,
+            total_supplied: Uint128::ZERO,
+            total_lp_supply: Uint128::ZERO,
+            total_borrowed_scaled: Uint128::ZERO,
+            borrow_index: Udec128::ONE,
+            last_update_time: now,
+        },
+    };
+
+    MARKETS.save(storage, denom, &market)?;
+
+    Ok(())
+}
+
 pub fn deposit(ctx: MutableCtx) -> anyhow::Result<Response> {
     let cfg = ctx.querier.query_config()?;
 
     let mut msgs = vec![];
 
     for coin in ctx.funds {
-        ensure!(MARKETS.has(ctx.storage, &coin.denom), "Invalid denom");
+        let Some(mut market) = MARKETS.may_load(ctx.storage, &coin.denom)? else {
+            bail!("Invalid denom");
+        };
+
+        market.accrue_interest(ctx.block.timestamp)?;
 
+        let amount = market.deposit(coin.amount)?;
         let denom = coin.denom.prepend(&[&NAMESPACE, &SUBNAMESPACE])?;
 
-        // TODO:
-        // 1. compute LP token mint amount
-        // 2. update `Market`
-        let amount = coin.amount;
+        MARKETS.save(ctx.storage, &coin.denom, &market)?;
 
         msgs.push(Message::execute(
             cfg.bank,
@@ -88,10 +129,13 @@ pub fn withdraw(ctx: MutableCtx) -> anyhow::Result<Response> {
             bail!("not a lending pool token: {}", coin.denom)
         };
 
-        // TODO:
-        // 1. compute withdraw amount
-        // 2. update `Market`
-        let underlying_amount = coin.amount;
+        let mut market = MARKETS.load(ctx.storage, &underlying_denom)?;
+
+        market.accrue_interest(ctx.block.timestamp)?;
+
+        let underlying_amount = market.withdraw(coin.amount)?;
+
+        MARKETS.save(ctx.storage, &underlying_denom, &market)?;
 
         // Burn the LP tokens
         msgs.push(Message::execute(
@@ -130,23 +174,78 @@ pub fn borrow(ctx: MutableCtx, coins: Coins) -> anyhow::Result<Response> {
         "Only margin accounts can borrow and repay"
     );
 
-    // Ensure the coins are whitelisted
+    let mut debts = DEBTS.may_load(ctx.storage, ctx.sender)?.unwrap_or_default();
+
     for coin in &coins {
-        ensure!(
-            MARKETS.has(ctx.storage, coin.denom),
-            "Invalid denom. Only whitelisted denoms can be borrowed."
-        );
-    }
+        // Ensure the coins are whitelisted
+        let Some(mut market) = MARKETS.may_load(ctx.storage, coin.denom)? else {
+            bail!("Invalid denom. Only whitelisted denoms can be borrowed.");
+        };
 
-    // Update the sender's liabilities
-    DEBTS.may_update(ctx.storage, ctx.sender, |maybe_debts| {
-        let mut debts = maybe_debts.unwrap_or_default();
+        market.accrue_interest(ctx.block.timestamp)?;
 
-        debts.insert_many(coins.clone())?;
+        let amount_scaled = market.borrow(*coin.amount)?;
 
-        Ok(debts)
-    })?;
+        MARKETS.save(ctx.storage, coin.denom, &market)?;
+
+        // Update the sender's liabilities
+        debts.insert(Coin::new(coin.denom.clone(), amount_scaled)?)?;
+    }
+
+    DEBTS.save(ctx.storage, ctx.sender, &debts)?;
 
     // Transfer the coins to the caller
     Ok(Response::new().add_message(Message::transfer(ctx.sender, coins)?))
 }
+
+pub fn repay(ctx: MutableCtx) -> anyhow::Result<Response> {
+    let Some(mut debts) = DEBTS.may_load(ctx.storage, ctx.sender)? else {
+        bail!("account {} has no debt", ctx.sender);
+    };
+
+    let mut refunds = Coins::new();
+
+    for coin in ctx.funds {
+        let debt_scaled = debts.amount_of(&coin.denom);
+
+        ensure!(
+            debt_scaled.is_non_zero(),
+            "account {} has no debt in {}",
+            ctx.sender,
+            coin.denom
+        );
+
+        let mut market = MARKETS.load(ctx.storage, &coin.denom)?;
+
+        market.accrue_interest(ctx.block.timestamp)?;
+
+        // Refund the amount in excess of the debt.
+        let debt = market.debt_amount(debt_scaled)?;
+
+        if coin.amount > debt {
+            refunds.insert(Coin::new(coin.denom.clone(), coin.amount - debt)?)?;
+        }
+
+        let debt_scaled_after = market.repay(coin.amount, debt_scaled)?;
+
+        MARKETS.save(ctx.storage, &coin.denom, &market)?;
+
+        debts.take(coin.denom.clone());
+        debts.insert(Coin::new(coin.denom, debt_scaled_after)?)?;
+    }
+
+    if debts.is_empty() {
+        DEBTS.remove(ctx.storage, ctx.sender);
+    } else {
+        DEBTS.save(ctx.storage, ctx.sender, &debts)?;
+    }
+
+    // Refund the excess, if any, to the borrower.
+    let refund_msg = if !refunds.is_empty() {
+        Some(Message::transfer(ctx.sender, refunds)?)
+    } else {
+        None
+    };
+
+    Ok(Response::new().may_add_message(refund_msg))
+}
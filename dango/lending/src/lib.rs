@@ -1,5 +1,6 @@
 mod execute;
+mod market;
 mod query;
 mod state;
 
-pub use {execute::*, query::*, state::*};
+pub use {execute::*, market::*, query::*, state::*};
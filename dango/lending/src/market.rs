@@ -0,0 +1,216 @@
+use {
+    anyhow::ensure,
+    dango_types::lending::{InterestRateModel, Market},
+    grug::{
+        Inner, IsZero, MultiplyFraction, MultiplyRatio, Number, NumberConst, Timestamp, Udec128,
+        Uint128,
+    },
+};
+
+/// Number of seconds in a year, used to convert annualized rates to the
+/// actual interest accrued over a period of time.
+pub const SECONDS_PER_YEAR: u128 = 365 * 24 * 60 * 60;
+
+pub trait InterestRateModelExt {
+    /// Compute the annualized borrow rate at the given utilization.
+    fn borrow_rate(&self, utilization: Udec128) -> anyhow::Result<Udec128>;
+}
+
+impl InterestRateModelExt for InterestRateModel {
+    fn borrow_rate(&self, utilization: Udec128) -> anyhow::Result<Udec128> {
+        let optimal = *self.optimal_utilization.inner();
+
+        if utilization <= optimal {
+            let variable = self
+                .first_slope
+                .checked_mul(utilization)?
+                .checked_div(optimal)?;
+
+            return Ok(self.base_rate.checked_add(variable)?);
+        }
+
+        let variable = self.second_slope.checked_mul(
+            utilization
+                .checked_sub(optimal)?
+                .checked_div(Udec128::ONE.checked_sub(optimal)?)?,
+        )?;
+
+        Ok(self
+            .base_rate
+            .checked_add(self.first_slope)?
+            .checked_add(variable)?)
+    }
+}
+
+pub trait MarketExt {
+    /// Return the amount of the underlying asset borrowed, including accrued
+    /// interest.
+    fn total_borrowed(&self) -> anyhow::Result<Uint128>;
+
+    /// Return the portion of the supplied liquidity that is borrowed, capped
+    /// at 100%.
+    fn utilization(&self) -> anyhow::Result<Udec128>;
+
+    /// Return the amount of the underlying asset that each LP token can be
+    /// redeemed for.
+    fn lp_exchange_rate(&self) -> anyhow::Result<Udec128>;
+
+    /// Accrue interest on borrowed amounts from the last update time up to
+    /// `now`. The interest is added to the amount owed to depositors.
+    ///
+    /// This must be called before any interaction with the market, so that
+    /// the interaction happens at up-to-date exchange rate and borrow index.
+    fn accrue_interest(&mut self, now: Timestamp) -> anyhow::Result<()>;
+
+    /// Deposit the underlying asset into the market.
+    /// Returns the amount of LP tokens to be minted.
+    fn deposit(&mut self, amount: Uint128) -> anyhow::Result<Uint128>;
+
+    /// Redeem LP tokens.
+    /// Returns the amount of the underlying asset to be withdrawn.
+    fn withdraw(&mut self, lp_amount: Uint128) -> anyhow::Result<Uint128>;
+
+    /// Borrow the underlying asset from the market.
+    /// Returns the scaled amount to be added to the borrower's debt.
+    fn borrow(&mut self, amount: Uint128) -> anyhow::Result<Uint128>;
+
+    /// Repay the underlying asset to the market, given the borrower's scaled
+    /// debt. If the amount covers the full debt, the debt is cleared; it's up
+    /// to the caller to refund the excess.
+    /// Returns the borrower's scaled debt after the repayment.
+    fn repay(&mut self, amount: Uint128, debt_scaled: Uint128) -> anyhow::Result<Uint128>;
+
+    /// Convert a scaled debt to the actual amount, including accrued interest.
+    fn debt_amount(&self, debt_scaled: Uint128) -> anyhow::Result<Uint128>;
+}
+
+impl MarketExt for Market {
+    fn total_borrowed(&self) -> anyhow::Result<Uint128> {
+        self.debt_amount(self.total_borrowed_scaled)
+    }
+
+    fn utilization(&self) -> anyhow::Result<Udec128> {
+        if self.total_supplied.is_zero() {
+            return Ok(Udec128::ZERO);
+        }
+
+        let utilization = Udec128::checked_from_ratio(self.total_borrowed()?, self.total_supplied)?;
+
+        Ok(utilization.min(Udec128::ONE))
+    }
+
+    fn lp_exchange_rate(&self) -> anyhow::Result<Udec128> {
+        if self.total_lp_supply.is_zero() {
+            return Ok(Udec128::ONE);
+        }
+
+        Ok(Udec128::checked_from_ratio(
+            self.total_supplied,
+            self.total_lp_supply,
+        )?)
+    }
+
+    fn accrue_interest(&mut self, now: Timestamp) -> anyhow::Result<()> {
+        if now <= self.last_update_time {
+            return Ok(());
+        }
+
+        let elapsed = (now - self.last_update_time).into_seconds();
+        let total_borrowed_before = self.total_borrowed()?;
+
+        // Compute the interest rate over the elapsed period. Interest is
+        // compounded each time it's accrued.
+        let rate = self
+            .interest_rate_model
+            .borrow_rate(self.utilization()?)?
+            .checked_mul(Udec128::checked_from_ratio(elapsed, SECONDS_PER_YEAR)?)?;
+
+        self.borrow_index = self
+            .borrow_index
+            .checked_mul(Udec128::ONE.checked_add(rate)?)?;
+        self.last_update_time = now;
+
+        // The interest paid by borrowers is owed to depositors.
+        let interest = self.total_borrowed()?.checked_sub(total_borrowed_before)?;
+
+        self.total_supplied.checked_add_assign(interest)?;
+
+        Ok(())
+    }
+
+    fn deposit(&mut self, amount: Uint128) -> anyhow::Result<Uint128> {
+        // The first deposit mints LP tokens 1:1. Subsequent deposits mint at
+        // the current exchange rate, rounded down in favor of the market.
+        let lp_amount = if self.total_lp_supply.is_zero() {
+            amount
+        } else {
+            amount.checked_multiply_ratio_floor(self.total_lp_supply, self.total_supplied)?
+        };
+
+        ensure!(
+            lp_amount.is_non_zero(),
+            "deposit amount is too small to mint any LP token"
+        );
+
+        self.total_supplied.checked_add_assign(amount)?;
+        self.total_lp_supply.checked_add_assign(lp_amount)?;
+
+        Ok(lp_amount)
+    }
+
+    fn withdraw(&mut self, lp_amount: Uint128) -> anyhow::Result<Uint128> {
+        let available = self.total_supplied.saturating_sub(self.total_borrowed()?);
+        let amount =
+            lp_amount.checked_multiply_ratio_floor(self.total_supplied, self.total_lp_supply)?;
+
+        ensure!(
+            amount <= available,
+            "insufficient liquidity! available: {}, requested: {}",
+            available,
+            amount
+        );
+
+        self.total_supplied.checked_sub_assign(amount)?;
+        self.total_lp_supply.checked_sub_assign(lp_amount)?;
+
+        Ok(amount)
+    }
+
+    fn borrow(&mut self, amount: Uint128) -> anyhow::Result<Uint128> {
+        let available = self.total_supplied.saturating_sub(self.total_borrowed()?);
+
+        ensure!(
+            amount <= available,
+            "insufficient liquidity! available: {}, requested: {}",
+            available,
+            amount
+        );
+
+        // Round up in favor of the market.
+        let amount_scaled = amount.checked_div_dec_ceil(self.borrow_index)?;
+
+        self.total_borrowed_scaled
+            .checked_add_assign(amount_scaled)?;
+
+        Ok(amount_scaled)
+    }
+
+    fn repay(&mut self, amount: Uint128, debt_scaled: Uint128) -> anyhow::Result<Uint128> {
+        // If the full debt is repaid, clear the scaled debt entirely, so that
+        // no dust remains due to rounding. Otherwise, round down in favor of
+        // the market.
+        let amount_scaled = if amount >= self.debt_amount(debt_scaled)? {
+            debt_scaled
+        } else {
+            amount.checked_div_dec_floor(self.borrow_index)?
+        };
+
+        self.total_borrowed_scaled = self.total_borrowed_scaled.saturating_sub(amount_scaled);
+
+        Ok(debt_scaled.checked_sub(amount_scaled)?)
+    }
+
+    fn debt_amount(&self, debt_scaled: Uint128) -> anyhow::Result<Uint128> {
+        Ok(debt_scaled.checked_mul_dec_ceil(self.borrow_index)?)
+    }
+}
@@ -1,66 +1,107 @@
 use {
-    crate::{DEBTS, MARKETS},
+    crate::{MarketExt, DEBTS, MARKETS},
     dango_types::lending::{Market, QueryMsg},
-    grug::{Addr, Bound, Coins, Denom, ImmutableCtx, Json, JsonSerExt, Order, StdResult, Storage},
+    grug::{
+        Addr, Bound, Coin, Coins, Denom, ImmutableCtx, Json, JsonSerExt, Order, Storage, Timestamp,
+    },
     std::collections::BTreeMap,
 };
 
 const DEFAULT_PAGE_LIMIT: u32 = 30;
 
 #[cfg_attr(not(feature = "library"), grug::export)]
-pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
+pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> anyhow::Result<Json> {
     match msg {
         QueryMsg::Market { denom } => {
-            let res = query_market(ctx.storage, denom)?;
+            let res = query_market(ctx.storage, ctx.block.timestamp, denom)?;
             res.to_json_value()
         },
         QueryMsg::Markets { start_after, limit } => {
-            let res = query_markets(ctx.storage, start_after, limit)?;
+            let res = query_markets(ctx.storage, ctx.block.timestamp, start_after, limit)?;
             res.to_json_value()
         },
         QueryMsg::Debt { account } => {
-            let res = query_debt(ctx.storage, account)?;
+            let res = query_debt(ctx.storage, ctx.block.timestamp, account)?;
             res.to_json_value()
         },
         QueryMsg::Debts { start_after, limit } => {
-            let res = query_debts(ctx.storage, start_after, limit)?;
+            let res = query_debts(ctx.storage, ctx.block.timestamp, start_after, limit)?;
             res.to_json_value()
         },
     }
+    .map_err(Into::into)
 }
 
-pub fn query_market(storage: &dyn Storage, denom: Denom) -> StdResult<Market> {
-    MARKETS.load(storage, &denom)
+pub fn query_market(storage: &dyn Storage, now: Timestamp, denom: Denom) -> anyhow::Result<Market> {
+    let mut market = MARKETS.load(storage, &denom)?;
+
+    market.accrue_interest(now)?;
+
+    Ok(market)
 }
 
 pub fn query_markets(
     storage: &dyn Storage,
+    now: Timestamp,
     start_after: Option<Denom>,
     limit: Option<u32>,
-) -> StdResult<BTreeMap<Denom, Market>> {
+) -> anyhow::Result<BTreeMap<Denom, Market>> {
     let start = start_after.as_ref().map(Bound::Exclusive);
     let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
 
     MARKETS
         .range(storage, start, None, Order::Ascending)
         .take(limit as usize)
+        .map(|res| {
+            let (denom, mut market) = res?;
+
+            market.accrue_interest(now)?;
+
+            Ok((denom, market))
+        })
         .collect()
 }
 
-pub fn query_debt(storage: &dyn Storage, account: Addr) -> StdResult<Coins> {
-    DEBTS.load(storage, account)
+pub fn query_debt(storage: &dyn Storage, now: Timestamp, account: Addr) -> anyhow::Result<Coins> {
+    let debts_scaled = DEBTS.load(storage, account)?;
+
+    debt_amounts(storage, now, debts_scaled)
 }
 
 pub fn query_debts(
     storage: &dyn Storage,
+    now: Timestamp,
     start_after: Option<Addr>,
     limit: Option<u32>,
-) -> StdResult<BTreeMap<Addr, Coins>> {
+) -> anyhow::Result<BTreeMap<Addr, Coins>> {
     let start = start_after.map(Bound::Exclusive);
     let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
 
     DEBTS
         .range(storage, start, None, Order::Ascending)
         .take(limit as usize)
+        .map(|res| {
+            let (account, debts_scaled) = res?;
+
+            Ok((account, debt_amounts(storage, now, debts_scaled)?))
+        })
         .collect()
 }
+
+/// Convert scaled debts to the actual amounts, including interest accrued up
+/// to `now`.
+fn debt_amounts(
+    storage: &dyn Storage,
+    now: Timestamp,
+    debts_scaled: Coins,
+) -> anyhow::Result<Coins> {
+    let mut debts = Coins::new();
+
+    for coin in debts_scaled {
+        let market = query_market(storage, now, coin.denom.clone())?;
+
+        debts.insert(Coin::new(coin.denom, market.debt_amount(coin.amount)?)?)?;
+    }
+
+    Ok(debts)
+}
@@ -5,4 +5,6 @@ use {
 
 pub const MARKETS: Map<&Denom, Market> = Map::new("market");
 
+/// Debts of margin accounts, scaled down by the respective market's borrow
+/// index. Multiply by the borrow index to get the amount including interest.
 pub const DEBTS: Map<Addr, Coins> = Map::new("debt");
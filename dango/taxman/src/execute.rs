@@ -1,5 +1,5 @@
 use {
-    crate::{CONFIG, WITHHELD_FEE},
+    crate::{BASE_FEE, CONFIG, GAS_USED, WITHHELD_FEE},
     anyhow::ensure,
     dango_types::{
         bank,
@@ -7,14 +7,18 @@ use {
         taxman::{Config, ExecuteMsg, InstantiateMsg},
     },
     grug::{
-        Addr, AuthCtx, AuthMode, Coins, IsZero, Message, MultiplyFraction, MutableCtx, Number,
-        NumberConst, Response, StdResult, Tx, TxOutcome, Uint128,
+        Addr, AuthCtx, AuthMode, Coins, IsZero, JsonDeExt, Message, MultiplyFraction, MutableCtx,
+        Number, NumberConst, Order, Response, StdResult, Storage, SudoCtx, Tx, TxOutcome, Udec128,
+        Uint128,
     },
 };
 
 #[cfg_attr(not(feature = "library"), grug::export)]
-pub fn instantiate(ctx: MutableCtx, msg: InstantiateMsg) -> StdResult<Response> {
+pub fn instantiate(ctx: MutableCtx, msg: InstantiateMsg) -> anyhow::Result<Response> {
+    validate_config(&msg.config)?;
+
     CONFIG.save(ctx.storage, &msg.config)?;
+    BASE_FEE.save(ctx.storage, &msg.config.fee_rate)?;
 
     Ok(Response::new())
 }
@@ -36,22 +40,128 @@ fn configure(ctx: MutableCtx, new_cfg: Config) -> anyhow::Result<Response> {
         "you don't have the right, O you don't have the right"
     );
 
+    validate_config(&new_cfg)?;
+
+    // Without a fee market, the base fee is simply the fee rate.
+    // With a fee market, keep the current base fee, but not lower than the new
+    // fee rate.
+    BASE_FEE.update(ctx.storage, |base_fee| -> StdResult<_> {
+        if new_cfg.fee_market.is_some() {
+            Ok(base_fee.max(new_cfg.fee_rate))
+        } else {
+            Ok(new_cfg.fee_rate)
+        }
+    })?;
+
     CONFIG.save(ctx.storage, &new_cfg)?;
 
     Ok(Response::new())
 }
 
+fn validate_config(cfg: &Config) -> anyhow::Result<()> {
+    if let Some(fee_market) = &cfg.fee_market {
+        ensure!(
+            fee_market.target_gas_per_block > 0,
+            "target gas per block must be non-zero"
+        );
+
+        ensure!(
+            fee_market.max_change_rate <= Udec128::ONE,
+            "max change rate can't be greater than one: {}",
+            fee_market.max_change_rate
+        );
+    }
+
+    Ok(())
+}
+
 fn pay(_ctx: MutableCtx, _payer: Addr) -> anyhow::Result<Response> {
     // For now, nothing to do.
     // In the future, we will implement affiliate fees.
     Ok(Response::new())
 }
 
-// TODO: exempt the account factory from paying fee.
+/// Credit the fees withheld in the previous block to their receivers, and
+/// adjust the base fee based on how much gas was consumed in the previous
+/// block, following [EIP-1559](https://eips.ethereum.org/EIPS/eip-1559).
+///
+/// Configured to run at the beginning of every block, before any transaction.
+#[cfg_attr(not(feature = "library"), grug::export)]
+pub fn cron_execute(ctx: SudoCtx) -> StdResult<Response> {
+    let fee_cfg = CONFIG.load(ctx.storage)?;
+    let gas_used = take_gas_used(ctx.storage)?;
+    let cfg = ctx.querier.query_config()?;
+
+    let settle_msg = Message::execute(cfg.bank, &bank::ExecuteMsg::Settle {}, Coins::new())?;
+
+    let Some(fee_market) = fee_cfg.fee_market else {
+        return Ok(Response::new().add_message(settle_msg));
+    };
+
+    let base_fee = BASE_FEE.load(ctx.storage)?;
+    let target = fee_market.target_gas_per_block;
+
+    // The base fee changes by `max_change_rate` times the relative difference
+    // between the gas used and the target. The difference is capped at the
+    // target, so that a block can't move the base fee by more than
+    // `max_change_rate`.
+    let diff = gas_used.abs_diff(target).min(target);
+    let change = base_fee
+        .checked_mul(fee_market.max_change_rate)?
+        .checked_mul(Udec128::checked_from_ratio(diff as u128, target as u128)?)?;
+
+    let new_base_fee = if gas_used > target {
+        base_fee.checked_add(change)?
+    } else {
+        base_fee.saturating_sub(change)
+    };
+
+    // The base fee can't go below the fee rate.
+    BASE_FEE.save(ctx.storage, &new_base_fee.max(fee_cfg.fee_rate))?;
+
+    Ok(Response::new().add_message(settle_msg))
+}
+
+fn take_gas_used(storage: &mut dyn Storage) -> StdResult<u64> {
+    let gas_used = GAS_USED
+        .values(storage, None, None, Order::Ascending)
+        .try_fold(0_u64, |total, gas_used| -> StdResult<_> {
+            Ok(total.saturating_add(gas_used?))
+        })?;
+
+    GAS_USED.clear(storage, None, None);
+
+    Ok(gas_used)
+}
+
 #[cfg_attr(not(feature = "library"), grug::export)]
-pub fn withhold_fee(ctx: AuthCtx, tx: Tx) -> StdResult<Response> {
+pub fn withhold_fee(ctx: AuthCtx, tx: Tx) -> anyhow::Result<Response> {
     let fee_cfg = CONFIG.load(ctx.storage)?;
-    let account_factory: Addr = ctx.querier.query_app_config("account_factory")?;
+    let base_fee = BASE_FEE.load(ctx.storage)?;
+    let account_factory: Addr = ctx.querier.query_app_config(ACCOUNT_FACTORY_KEY)?;
+
+    // Find the gas price the sender is willing to pay, from the `gas_price`
+    // field of the transaction's data. If not provided, the sender pays the
+    // base fee. The rest of the data is up to the sender's account, so we
+    // don't assume any particular format for it.
+    let gas_price = tx
+        .data
+        .get("gas_price")
+        .map(|gas_price| gas_price.clone().deserialize_json::<Option<Udec128>>())
+        .transpose()?
+        .flatten();
+
+    let gas_price = match gas_price {
+        Some(gas_price) => {
+            ensure!(
+                gas_price >= base_fee,
+                "gas price is lower than base fee: {gas_price} < {base_fee}"
+            );
+
+            gas_price
+        },
+        None => base_fee,
+    };
 
     // Compute the maximum amount of fee this transaction may incur.
     // Note that we ceil this amount, instead of flooring.
@@ -66,11 +176,12 @@ pub fn withhold_fee(ctx: AuthCtx, tx: Tx) -> StdResult<Response> {
     let withhold_amount = if ctx.mode == AuthMode::Simulate || tx.sender == account_factory {
         Uint128::ZERO
     } else {
-        Uint128::new(tx.gas_limit as u128).checked_mul_dec_ceil(fee_cfg.fee_rate)?
+        Uint128::new(tx.gas_limit as u128).checked_mul_dec_ceil(gas_price)?
     };
 
-    // If the withhold amount is non-zero, we force transfer this amount from
-    // the sender to taxman.
+    // If the withhold amount is non-zero, we have the bank withhold this
+    // amount from the sender for the taxman. It's credited to the taxman at the
+    // beginning of the next block.
     //
     // If the sender doesn't have enough fund to cover the maximum amount of fee
     // the tx may incur, this submessage fails, causing the tx to be rejected
@@ -82,7 +193,7 @@ pub fn withhold_fee(ctx: AuthCtx, tx: Tx) -> StdResult<Response> {
 
         Some(Message::execute(
             cfg.bank,
-            &bank::ExecuteMsg::ForceTransfer {
+            &bank::ExecuteMsg::Withhold {
                 from: tx.sender,
                 to: ctx.contract,
                 denom: fee_cfg.fee_denom.clone(),
@@ -95,49 +206,91 @@ pub fn withhold_fee(ctx: AuthCtx, tx: Tx) -> StdResult<Response> {
     };
 
     // Save the withheld fee in storage, which we will use in `finalize_fee`.
-    WITHHELD_FEE.save(ctx.storage, &(fee_cfg, withhold_amount))?;
+    WITHHELD_FEE.save(
+        ctx.storage,
+        tx.sender,
+        &(fee_cfg, base_fee, gas_price, withhold_amount),
+    )?;
 
     Ok(Response::new().may_add_message(withhold_msg))
 }
 
 #[cfg_attr(not(feature = "library"), grug::export)]
 pub fn finalize_fee(ctx: AuthCtx, tx: Tx, outcome: TxOutcome) -> StdResult<Response> {
-    let (fee_cfg, withheld_amount) = WITHHELD_FEE.take(ctx.storage)?;
+    let (fee_cfg, base_fee, gas_price, withheld_amount) =
+        WITHHELD_FEE.take(ctx.storage, tx.sender)?;
     let account_factory: Addr = ctx.querier.query_app_config(ACCOUNT_FACTORY_KEY)?;
 
+    // Record the gas consumed, which is used to adjust the base fee at the
+    // beginning of the next block. Transactions exempt from fees count too, as
+    // they take up block space all the same.
+    if ctx.mode == AuthMode::Finalize && fee_cfg.fee_market.is_some() {
+        GAS_USED.may_update(ctx.storage, tx.sender, |gas_used| -> StdResult<_> {
+            Ok(gas_used.unwrap_or(0).saturating_add(outcome.gas_used))
+        })?;
+    }
+
     // Compute how much fee to charge the sender, based on the actual amount of
-    // gas consumed.
+    // gas consumed, and how much of it goes to the tip recipient.
     //
     // Again, during simulation, or any tx sent by the account factory, is
     // exempt from gas fees.
-    let charge_amount = if ctx.mode == AuthMode::Simulate || tx.sender == account_factory {
-        Uint128::ZERO
-    } else {
-        Uint128::new(outcome.gas_used as u128).checked_mul_dec_ceil(fee_cfg.fee_rate)?
-    };
+    let (charge_amount, tip_amount) =
+        if ctx.mode == AuthMode::Simulate || tx.sender == account_factory {
+            (Uint128::ZERO, Uint128::ZERO)
+        } else {
+            let gas_used = Uint128::new(outcome.gas_used as u128);
+            let charge_amount = gas_used.checked_mul_dec_ceil(gas_price)?;
+            let base_amount = gas_used.checked_mul_dec_ceil(base_fee)?;
+
+            (charge_amount, charge_amount.saturating_sub(base_amount))
+        };
 
     // If we have withheld more funds than the actual charge amount, we need to
     // refund the difference.
     let refund_amount = withheld_amount.saturating_sub(charge_amount);
 
-    // Use ForceTransfer instead of Transfer so that we don't need to invoke the
-    // sender's `receive` method (unnecessary).
-    let refund_msg = if refund_amount.is_non_zero() {
-        let cfg = ctx.querier.query_config()?;
+    // The refund is released back to the sender. The tip is withheld for the
+    // tip recipient instead of the taxman. Neither the sender's nor the tip
+    // recipient's `receive` method is invoked (unnecessary).
+    let mut bank_msgs = Vec::with_capacity(3);
 
-        Some(Message::execute(
-            cfg.bank,
-            &bank::ExecuteMsg::ForceTransfer {
-                from: ctx.contract,
-                to: tx.sender,
-                denom: fee_cfg.fee_denom,
-                amount: refund_amount,
-            },
-            Coins::new(),
-        )?)
-    } else {
-        None
-    };
+    if refund_amount.is_non_zero() {
+        bank_msgs.push(bank::ExecuteMsg::Release {
+            from: tx.sender,
+            to: ctx.contract,
+            denom: fee_cfg.fee_denom.clone(),
+            amount: refund_amount,
+        });
+    }
+
+    if let Some(fee_market) = fee_cfg.fee_market {
+        if tip_amount.is_non_zero() {
+            bank_msgs.push(bank::ExecuteMsg::Release {
+                from: tx.sender,
+                to: ctx.contract,
+                denom: fee_cfg.fee_denom.clone(),
+                amount: tip_amount,
+            });
+            bank_msgs.push(bank::ExecuteMsg::Withhold {
+                from: tx.sender,
+                to: fee_market.tip_recipient,
+                denom: fee_cfg.fee_denom.clone(),
+                amount: tip_amount,
+            });
+        }
+    }
+
+    if bank_msgs.is_empty() {
+        return Ok(Response::new());
+    }
+
+    let cfg = ctx.querier.query_config()?;
+
+    let msgs = bank_msgs
+        .into_iter()
+        .map(|msg| Message::execute(cfg.bank, &msg, Coins::new()))
+        .collect::<StdResult<Vec<_>>>()?;
 
-    Ok(Response::new().may_add_message(refund_msg))
+    Ok(Response::new().add_messages(msgs))
 }
@@ -1,16 +1,21 @@
 use {
-    crate::CONFIG,
+    crate::{BASE_FEE, CONFIG},
     dango_types::taxman::{Config, QueryMsg},
-    grug::{ImmutableCtx, Json, JsonSerExt, StdResult},
+    grug::{ImmutableCtx, Json, JsonSerExt, StdResult, Udec128},
 };
 
 #[cfg_attr(not(feature = "library"), grug::export)]
 pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
     match msg {
         QueryMsg::Config {} => query_config(ctx)?.to_json_value(),
+        QueryMsg::BaseFee {} => query_base_fee(ctx)?.to_json_value(),
     }
 }
 
 fn query_config(ctx: ImmutableCtx) -> StdResult<Config> {
     CONFIG.load(ctx.storage)
 }
+
+fn query_base_fee(ctx: ImmutableCtx) -> StdResult<Udec128> {
+    BASE_FEE.load(ctx.storage)
+}
@@ -1,8 +1,26 @@
 use {
     dango_types::taxman::Config,
-    grug::{Item, Uint128},
+    grug::{Addr, Item, Map, Udec128, Uint128},
 };
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
-pub const WITHHELD_FEE: Item<(Config, Uint128)> = Item::new("withheld_fee");
+/// The minimum gas price a transaction must offer in order to be accepted.
+///
+/// Equals `fee_rate` in the config if the fee market is disabled. Otherwise,
+/// adjusted at the beginning of each block in `cron_execute`.
+pub const BASE_FEE: Item<Udec128> = Item::new("base_fee");
+
+/// Gas consumed in the current block, summed by transaction sender.
+///
+/// Indexed by sender instead of summed into a single value, so that the
+/// transactions of different senders don't write to the same storage slot.
+///
+/// For the same reason, the fees aren't credited to the taxman in every
+/// transaction, but withheld by the bank, and settled at the beginning of the
+/// next block in `cron_execute`.
+pub const GAS_USED: Map<Addr, u64> = Map::new("gas_used");
+
+/// The fee config, base fee, and gas price at the time the fee was withheld,
+/// and the amount withheld, indexed by transaction sender.
+pub const WITHHELD_FEE: Map<Addr, (Config, Udec128, Udec128, Uint128)> = Map::new("withheld_fee");
@@ -8,11 +8,11 @@ use {
     },
     grug::{
         Addr, Addressable, Coins, Defined, Hash160, Hash256, HashExt, Json, JsonSerExt,
-        MaybeDefined, Message, ResultExt, Signer, StdResult, TestSuite, Tx, Undefined,
+        MaybeDefined, Message, ResultExt, Signer, StdResult, TestSuite, Tx, Udec128, Undefined,
     },
     k256::{
         ecdsa::{signature::Signer as SignerTrait, Signature, SigningKey},
-        elliptic_curve::rand_core::OsRng,
+        elliptic_curve::rand_core::{OsRng, RngCore},
     },
     std::{collections::BTreeMap, str::FromStr},
 };
@@ -24,13 +24,20 @@ pub struct Accounts {
 
 // ------------------------------- test account --------------------------------
 
+/// Private key of a test account.
+#[derive(Debug, Clone)]
+enum PrivateKey {
+    Secp256k1(SigningKey),
+    Ed25519(ed25519_dalek::SigningKey),
+}
+
 #[derive(Debug)]
 pub struct TestAccount<T: MaybeDefined<Addr> = Defined<Addr>> {
     pub username: Username,
     pub key: Key,
     pub key_hash: Hash160,
     pub sequence: u32,
-    sk: SigningKey,
+    sk: PrivateKey,
     address: T,
 }
 
@@ -46,16 +53,30 @@ impl TestAccount<Undefined<Addr>> {
             .try_into()
             .unwrap();
 
-        let username = Username::from_str(username).unwrap();
-        let key = Key::Secp256k1(pk);
-        let key_hash = pk.hash160();
+        Self {
+            username: Username::from_str(username).unwrap(),
+            key: Key::Secp256k1(pk),
+            key_hash: pk.hash160(),
+            sequence: 0,
+            sk: PrivateKey::Secp256k1(sk),
+            address: Undefined::new(),
+        }
+    }
+
+    pub fn new_random_ed25519(username: &str) -> Self {
+        // Generate a random Ed25519 key pair.
+        let mut sk_bytes = [0; 32];
+        OsRng.fill_bytes(&mut sk_bytes);
+
+        let sk = ed25519_dalek::SigningKey::from_bytes(&sk_bytes);
+        let pk = sk.verifying_key().to_bytes();
 
         Self {
-            username,
-            key,
-            key_hash,
+            username: Username::from_str(username).unwrap(),
+            key: Key::Ed25519(pk.into()),
+            key_hash: pk.hash160(),
             sequence: 0,
-            sk,
+            sk: PrivateKey::Ed25519(sk),
             address: Undefined::new(),
         }
     }
@@ -113,27 +134,68 @@ where
         msgs: Vec<Message>,
         chain_id: &str,
         sequence: u32,
+    ) -> StdResult<(Metadata, Credential)> {
+        self.sign_transaction_with_gas_price(sender, msgs, chain_id, sequence, None)
+    }
+
+    pub fn sign_transaction_with_gas_price(
+        &self,
+        sender: Addr,
+        msgs: Vec<Message>,
+        chain_id: &str,
+        sequence: u32,
+        gas_price: Option<Udec128>,
+    ) -> StdResult<(Metadata, Credential)> {
+        self.sign_transaction_with_grantee(sender, msgs, chain_id, sequence, gas_price, None)
+    }
+
+    /// Sign a transaction to be sent under a grant, acting through the given
+    /// grantee account.
+    pub fn sign_transaction_with_grantee(
+        &self,
+        sender: Addr,
+        msgs: Vec<Message>,
+        chain_id: &str,
+        sequence: u32,
+        gas_price: Option<Udec128>,
+        grantee: Option<Addr>,
     ) -> StdResult<(Metadata, Credential)> {
         let sign_bytes = SignDoc {
             sender,
             messages: msgs.clone(),
             chain_id: chain_id.to_string(),
             sequence,
+            gas_price,
+            grantee,
         }
         .to_json_vec()?;
 
-        // This hashes `sign_doc_raw` with SHA2-256. If we eventually choose to
-        // use another hash, it's necessary to update this.
-        let signature: Signature = self.sk.sign(&sign_bytes);
+        let credential = match &self.sk {
+            PrivateKey::Secp256k1(sk) => {
+                // This hashes `sign_doc_raw` with SHA2-256. If we eventually
+                // choose to use another hash, it's necessary to update this.
+                let signature: Signature = sk.sign(&sign_bytes);
+
+                Credential::Secp256k1(signature.to_bytes().to_vec().try_into()?)
+            },
+            PrivateKey::Ed25519(sk) => {
+                // Ed25519 signs the SHA2-256 hash of `sign_doc_raw` as a raw
+                // message. `hash256` is SHA2-256 for now; if we eventually
+                // choose to use another hash, it's necessary to update this.
+                let signature = ed25519_dalek::Signer::sign(sk, &sign_bytes.hash256());
+
+                Credential::Ed25519(signature.to_bytes().into())
+            },
+        };
 
         let data = Metadata {
             username: self.username.clone(),
             key_hash: self.key_hash,
             sequence,
+            gas_price,
+            grantee,
         };
 
-        let credential = Credential::Secp256k1(signature.to_bytes().to_vec().try_into()?);
-
         Ok((data, credential))
     }
 }
@@ -322,3 +384,71 @@ impl<'a> Signer for Safe<'a> {
         })
     }
 }
+
+// --------------------------------- grantee -----------------------------------
+
+/// Sends transactions on behalf of a granter account, signed by a test account
+/// that has been given a grant by the granter.
+pub struct Grantee<'a> {
+    granter: Addr,
+    signer: &'a TestAccount,
+    sequence: u32,
+    gas_price: Option<Udec128>,
+}
+
+impl<'a> Grantee<'a> {
+    pub fn new(granter: Addr, signer: &'a TestAccount) -> Self {
+        Self {
+            granter,
+            signer,
+            sequence: 0,
+            gas_price: None,
+        }
+    }
+
+    /// Set the granter account's sequence number.
+    ///
+    /// The sequence is shared between the granter and all its grantees, so it
+    /// can't be tracked here internally.
+    pub fn with_sequence(&mut self, sequence: u32) -> &mut Self {
+        self.sequence = sequence;
+        self
+    }
+
+    pub fn with_gas_price(&mut self, gas_price: Udec128) -> &mut Self {
+        self.gas_price = Some(gas_price);
+        self
+    }
+}
+
+impl<'a> Addressable for Grantee<'a> {
+    fn address(&self) -> Addr {
+        self.granter
+    }
+}
+
+impl<'a> Signer for Grantee<'a> {
+    fn sign_transaction(
+        &mut self,
+        msgs: Vec<Message>,
+        chain_id: &str,
+        gas_limit: u64,
+    ) -> StdResult<Tx> {
+        let (data, credential) = self.signer.sign_transaction_with_grantee(
+            self.granter,
+            msgs.clone(),
+            chain_id,
+            self.sequence,
+            self.gas_price,
+            Some(self.signer.address()),
+        )?;
+
+        Ok(Tx {
+            sender: self.granter,
+            gas_limit,
+            msgs,
+            data: data.to_json_value()?,
+            credential: credential.to_json_value()?,
+        })
+    }
+}
@@ -10,7 +10,7 @@ use {
     grug_db_disk::{DiskDb, TempDataDir},
     grug_db_memory::MemDb,
     grug_vm_rust::RustVm,
-    grug_vm_wasm::WasmVm,
+    grug_vm_wasm::{WasmVm, WasmVmConfig},
     std::{env, path::PathBuf},
 };
 
@@ -26,8 +26,10 @@ where
     VM: Vm + Clone,
     AppError: From<DB::Error> + From<VM::Error>,
 {
+    // The relayer uses an Ed25519 key, so that genesis is exercised with more
+    // than one key type.
     let owner = TestAccount::new_random("owner");
-    let relayer = TestAccount::new_random("relayer");
+    let relayer = TestAccount::new_random_ed25519("relayer");
 
     let (genesis_state, contracts, addresses) = build_genesis(
         codes.clone(),
@@ -54,6 +56,8 @@ where
         Udec128::ZERO,
         Some(Uint128::new(10_000_000)),
         Duration::from_seconds(7 * 24 * 60 * 60),
+        u64::MAX,
+        None,
     )
     .unwrap();
 
@@ -82,6 +86,12 @@ where
 
 /// Set up a `TestSuite` with `MemDb`, `RustVm`, and `ContractWrapper` codes.
 pub fn setup_test() -> (TestSuite, Accounts, Codes<ContractWrapper>, Contracts) {
+    setup_test_with_db(MemDb::new())
+}
+
+/// Same as `setup_test`, but with the given `MemDb`, which the caller may keep
+/// a handle of, e.g. to fork other test suites from its state.
+pub fn setup_test_with_db(db: MemDb) -> (TestSuite, Accounts, Codes<ContractWrapper>, Contracts) {
     let account_factory = ContractBuilder::new(Box::new(dango_account_factory::instantiate))
         .with_execute(Box::new(dango_account_factory::execute))
         .with_query(Box::new(dango_account_factory::query))
@@ -91,6 +101,7 @@ pub fn setup_test() -> (TestSuite, Accounts, Codes<ContractWrapper>, Contracts)
     let account_margin = ContractBuilder::new(Box::new(dango_account_margin::instantiate))
         .with_authenticate(Box::new(dango_account_margin::authenticate))
         .with_receive(Box::new(dango_account_margin::receive))
+        .with_execute(Box::new(dango_account_margin::execute))
         .with_query(Box::new(dango_account_margin::query))
         .build();
 
@@ -104,6 +115,7 @@ pub fn setup_test() -> (TestSuite, Accounts, Codes<ContractWrapper>, Contracts)
     let account_spot = ContractBuilder::new(Box::new(dango_account_spot::instantiate))
         .with_authenticate(Box::new(dango_account_spot::authenticate))
         .with_receive(Box::new(dango_account_spot::receive))
+        .with_execute(Box::new(dango_account_spot::execute))
         .with_query(Box::new(dango_account_spot::query))
         .build();
 
@@ -132,6 +144,7 @@ pub fn setup_test() -> (TestSuite, Accounts, Codes<ContractWrapper>, Contracts)
         .with_query(Box::new(dango_taxman::query))
         .with_withhold_fee(Box::new(dango_taxman::withhold_fee))
         .with_finalize_fee(Box::new(dango_taxman::finalize_fee))
+        .with_cron_execute(Box::new(dango_taxman::cron_execute))
         .build();
 
     let token_factory = ContractBuilder::new(Box::new(dango_token_factory::instantiate))
@@ -152,7 +165,7 @@ pub fn setup_test() -> (TestSuite, Accounts, Codes<ContractWrapper>, Contracts)
         token_factory,
     };
 
-    setup_suite_with_db_and_vm(MemDb::new(), RustVm::new(), codes)
+    setup_suite_with_db_and_vm(db, RustVm::new(), codes)
 }
 
 /// Set up a `TestSuite` with `DiskDb`, `WasmVm`, and `Vec<u8>` codes.
@@ -171,7 +184,7 @@ pub fn setup_benchmark(
         .unwrap();
 
     let db = DiskDb::open(dir).unwrap();
-    let vm = WasmVm::new(wasm_cache_size);
+    let vm = WasmVm::new(wasm_cache_size, WasmVmConfig::default()).unwrap();
 
     setup_suite_with_db_and_vm(db, vm, codes)
 }
@@ -1,11 +1,13 @@
 use {
     dango_testing::setup_test,
     dango_types::amm::{
-        self, ExecuteMsg, FeeRate, Pool, PoolParams, QueryPoolRequest, QueryPoolsRequest,
-        XykParams, XykPool, MINIMUM_LIQUIDITY,
+        self, ConcentratedParams, ExecuteMsg, FeeRate, Pool, PoolParams, QueryBestRouteRequest,
+        QueryPoolRequest, QueryPoolsRequest, QuerySimulateExactOutputRequest, QuerySimulateRequest,
+        Swapped, XykParams, XykPool, MINIMUM_LIQUIDITY,
     },
     grug::{
-        btree_map, Coin, CoinPair, Coins, Denom, Message, ResultExt, Udec128, Uint128, UniqueVec,
+        btree_map, Addressable, Coin, CoinPair, Coins, Denom, Event, Message, MultiplyFraction,
+        NonZero, NumberConst, ResultExt, Udec128, Uint128, UniqueVec,
     },
     std::{str::FromStr, sync::LazyLock},
 };
@@ -272,7 +274,7 @@ fn amm() {
     // --------------------------------- Swap ----------------------------------
 
     // Swap USDC for OSMO.
-    suite
+    let success = suite
         .execute(
             &mut accounts.owner,
             contracts.amm,
@@ -315,6 +317,32 @@ fn amm() {
         .query_balance(&contracts.taxman, OSMO.clone())
         .should_succeed_and_equal(Uint128::new(1_150_340));
 
+    // The AMM should have emitted a swap event.
+    let swapped = success
+        .events
+        .iter()
+        .find_map(|event| match event {
+            Event::Execute(execute) if execute.contract == contracts.amm => {
+                execute.guest.contract_events[0]
+                    .decode::<Swapped>()
+                    .unwrap()
+            },
+            _ => None,
+        })
+        .unwrap();
+
+    assert_eq!(swapped, Swapped {
+        trader: accounts.owner.address(),
+        route: UniqueVec::new_unchecked(vec![2, 1]),
+        input: Coin::new(USDC.clone(), Uint128::new(100_000_000)).unwrap(),
+        output: Coin::new(OSMO.clone(), Uint128::new(1_149_188_712)).unwrap(),
+        protocol_fee: Coin::new(OSMO.clone(), Uint128::new(1_150_340)).unwrap(),
+        liquidity_fees: Coins::new_unchecked(btree_map! {
+            ATOM.clone() => Uint128::new(258_177),
+            OSMO.clone() => Uint128::new(2_305_289),
+        }),
+    });
+
     // The pool states should have been updated.
     //
     // Pool 1
@@ -424,3 +452,595 @@ fn amm() {
             shares: Uint128::new(1_311_793_604_756),
         }));
 }
+
+#[test]
+fn concentrated() {
+    let (mut suite, mut accounts, _, contracts) = setup_test();
+
+    let liquidity_fee_rate = FeeRate::new_unchecked(Udec128::new_bps(5));
+
+    // ----------------------------- Pool creation -----------------------------
+
+    // Create two ATOM-OSMO pools with the same liquidity, one concentrated and
+    // one xyk, so that their swap outputs can be compared. The liquidity is
+    // chosen such that the price scale isn't one.
+    suite
+        .send_messages(&mut accounts.relayer, vec![
+            // pool 1: concentrated
+            Message::execute(
+                contracts.amm,
+                &amm::ExecuteMsg::CreatePool(PoolParams::Concentracted(ConcentratedParams {
+                    amplification: NonZero::new_unchecked(100),
+                    liquidity_fee_rate: liquidity_fee_rate.clone(),
+                })),
+                Coins::new_unchecked(btree_map! {
+                    ATOM.clone() => Uint128::new(100_000_000_000),
+                    OSMO.clone() => Uint128::new(200_000_000_000),
+                    // pool creation fee
+                    USDC.clone() => Uint128::new(10_000_000),
+                }),
+            )
+            .unwrap(),
+            // pool 2: xyk
+            Message::execute(
+                contracts.amm,
+                &amm::ExecuteMsg::CreatePool(PoolParams::Xyk(XykParams {
+                    liquidity_fee_rate: liquidity_fee_rate.clone(),
+                })),
+                Coins::new_unchecked(btree_map! {
+                    ATOM.clone() => Uint128::new(100_000_000_000),
+                    OSMO.clone() => Uint128::new(200_000_000_000),
+                    // pool creation fee
+                    USDC.clone() => Uint128::new(10_000_000),
+                }),
+            )
+            .unwrap(),
+        ])
+        .should_succeed();
+
+    // The price scale is the ratio of the initial liquidity, and the initial
+    // shares is the invariant, which for a balanced pool is the sum of the
+    // scaled balances: 100,000,000,000 + 200,000,000,000 * 0.5.
+    let pool = match suite
+        .query_wasm_smart(contracts.amm, QueryPoolRequest { pool_id: 1 })
+        .should_succeed()
+    {
+        Pool::Concentrated(pool) => pool,
+        _ => panic!("expecting a concentrated pool"),
+    };
+
+    assert_eq!(pool.price_scale, Udec128::new_percent(50));
+    assert_eq!(pool.shares, Uint128::new(200_000_000_000));
+
+    // --------------------------------- Swap ----------------------------------
+
+    // Simulate swapping ATOM for OSMO in both pools.
+    let input = Coin::new(ATOM.clone(), Uint128::new(10_000_000_000)).unwrap();
+
+    let concentrated_outcome = suite
+        .query_wasm_smart(contracts.amm, QuerySimulateRequest {
+            input: input.clone(),
+            route: UniqueVec::new_unchecked(vec![1]),
+        })
+        .should_succeed();
+
+    let xyk_outcome = suite
+        .query_wasm_smart(contracts.amm, QuerySimulateRequest {
+            input: input.clone(),
+            route: UniqueVec::new_unchecked(vec![2]),
+        })
+        .should_succeed();
+
+    // Near the price scale, the concentrated pool should give an output close
+    // to the input converted at the price scale (20,000,000,000 uosmo), and
+    // considerably better than the xyk pool.
+    assert!(concentrated_outcome.output.amount < Uint128::new(20_000_000_000));
+    assert!(concentrated_outcome.output.amount > Uint128::new(19_900_000_000));
+    assert!(concentrated_outcome.output.amount > xyk_outcome.output.amount);
+
+    // Perform the swap. The actual output should match the simulation.
+    let osmo_before = suite
+        .query_balance(&accounts.relayer, OSMO.clone())
+        .should_succeed();
+
+    suite
+        .execute(
+            &mut accounts.relayer,
+            contracts.amm,
+            &ExecuteMsg::Swap {
+                route: UniqueVec::new_unchecked(vec![1]),
+                minimum_output: None,
+            },
+            input.clone(),
+        )
+        .should_succeed();
+
+    let osmo_received = suite
+        .query_balance(&accounts.relayer, OSMO.clone())
+        .should_succeed()
+        - osmo_before;
+
+    assert_eq!(osmo_received, concentrated_outcome.output.amount);
+
+    // Swap the OSMO back to ATOM. Due to fees, the trader should receive less
+    // ATOM than they started with.
+    let atom_before = suite
+        .query_balance(&accounts.relayer, ATOM.clone())
+        .should_succeed();
+
+    suite
+        .execute(
+            &mut accounts.relayer,
+            contracts.amm,
+            &ExecuteMsg::Swap {
+                route: UniqueVec::new_unchecked(vec![1]),
+                minimum_output: None,
+            },
+            Coin::new(OSMO.clone(), osmo_received).unwrap(),
+        )
+        .should_succeed();
+
+    let atom_received = suite
+        .query_balance(&accounts.relayer, ATOM.clone())
+        .should_succeed()
+        - atom_before;
+
+    assert!(atom_received < input.amount);
+
+    // ------------------- Liquidity provision and withdrawal -------------------
+
+    // Provide one-sided liquidity, then immediately withdraw it. The liquidity
+    // provider shouldn't get back more than they deposited.
+    let atom_before = suite
+        .query_balance(&accounts.relayer, ATOM.clone())
+        .should_succeed();
+    let osmo_before = suite
+        .query_balance(&accounts.relayer, OSMO.clone())
+        .should_succeed();
+    let lp_before = suite
+        .query_balance(&accounts.relayer, LP_1.clone())
+        .should_succeed();
+
+    suite
+        .execute(
+            &mut accounts.relayer,
+            contracts.amm,
+            &ExecuteMsg::ProvideLiquidity {
+                pool_id: 1,
+                minimum_output: None,
+            },
+            Coin::new(ATOM.clone(), Uint128::new(10_000_000_000)).unwrap(),
+        )
+        .should_succeed();
+
+    let lp_minted = suite
+        .query_balance(&accounts.relayer, LP_1.clone())
+        .should_succeed()
+        - lp_before;
+
+    suite
+        .execute(
+            &mut accounts.relayer,
+            contracts.amm,
+            &ExecuteMsg::WithdrawLiquidity { pool_id: 1 },
+            Coin::new(LP_1.clone(), lp_minted).unwrap(),
+        )
+        .should_succeed();
+
+    let atom_after = suite
+        .query_balance(&accounts.relayer, ATOM.clone())
+        .should_succeed();
+    let osmo_after = suite
+        .query_balance(&accounts.relayer, OSMO.clone())
+        .should_succeed();
+
+    // Value both assets in ATOM at the price scale.
+    let value_before = atom_before + osmo_before.checked_mul_dec_floor(pool.price_scale).unwrap();
+    let value_after = atom_after + osmo_after.checked_mul_dec_floor(pool.price_scale).unwrap();
+
+    assert!(value_after < value_before);
+
+    // The pool's liquidity should be backed by the AMM contract's balances.
+    let pool = match suite
+        .query_wasm_smart(contracts.amm, QueryPoolRequest { pool_id: 1 })
+        .should_succeed()
+    {
+        Pool::Concentrated(pool) => pool,
+        _ => panic!("expecting a concentrated pool"),
+    };
+
+    // Pool 2 (xyk) has been untouched since creation.
+    suite
+        .query_balances(&contracts.amm)
+        .should_succeed_and_equal(Coins::new_unchecked(btree_map! {
+            ATOM.clone() => *pool.liquidity.first().amount + Uint128::new(100_000_000_000),
+            OSMO.clone() => *pool.liquidity.second().amount + Uint128::new(200_000_000_000),
+            LP_1.clone() => MINIMUM_LIQUIDITY,
+            LP_2.clone() => MINIMUM_LIQUIDITY,
+        }));
+}
+
+#[test]
+fn best_route() {
+    let (mut suite, mut accounts, _, contracts) = setup_test();
+
+    let liquidity_fee_rate = FeeRate::new_unchecked(Udec128::new_bps(20));
+
+    // Create four pools: ATOM-OSMO, ATOM-USDC, OSMO-USDC, and another ATOM-OSMO.
+    // The first two have deep liquidity, while the last two are shallow, so
+    // swapping USDC to OSMO through ATOM should give a better output than
+    // directly, and should go through the first ATOM-OSMO pool.
+    suite
+        .send_messages(&mut accounts.relayer, vec![
+            // pool 1: ATOM-OSMO
+            Message::execute(
+                contracts.amm,
+                &amm::ExecuteMsg::CreatePool(PoolParams::Xyk(XykParams {
+                    liquidity_fee_rate: liquidity_fee_rate.clone(),
+                })),
+                Coins::new_unchecked(btree_map! {
+                    ATOM.clone() => Uint128::new(657_761_324_779),
+                    OSMO.clone() => Uint128::new(5_886_161_498_040),
+                    // pool creation fee
+                    USDC.clone() => Uint128::new(10_000_000),
+                }),
+            )
+            .unwrap(),
+            // pool 2: ATOM-USDC
+            Message::execute(
+                contracts.amm,
+                &amm::ExecuteMsg::CreatePool(PoolParams::Xyk(XykParams {
+                    liquidity_fee_rate: liquidity_fee_rate.clone(),
+                })),
+                Coins::new_unchecked(btree_map! {
+                    ATOM.clone() => Uint128::new(224_078_907_873),
+                    // liquidity + pool creation fee
+                    USDC.clone() => Uint128::new(173_573_581_955),
+                }),
+            )
+            .unwrap(),
+            // pool 3: OSMO-USDC
+            Message::execute(
+                contracts.amm,
+                &amm::ExecuteMsg::CreatePool(PoolParams::Xyk(XykParams {
+                    liquidity_fee_rate: liquidity_fee_rate.clone(),
+                })),
+                Coins::new_unchecked(btree_map! {
+                    OSMO.clone() => Uint128::new(1_000_000_000),
+                    // liquidity + pool creation fee
+                    USDC.clone() => Uint128::new(1_010_000_000),
+                }),
+            )
+            .unwrap(),
+            // pool 4: ATOM-OSMO
+            Message::execute(
+                contracts.amm,
+                &amm::ExecuteMsg::CreatePool(PoolParams::Xyk(XykParams {
+                    liquidity_fee_rate: liquidity_fee_rate.clone(),
+                })),
+                Coins::new_unchecked(btree_map! {
+                    ATOM.clone() => Uint128::new(100_000_000),
+                    OSMO.clone() => Uint128::new(900_000_000),
+                    // pool creation fee
+                    USDC.clone() => Uint128::new(10_000_000),
+                }),
+            )
+            .unwrap(),
+        ])
+        .should_succeed();
+
+    let input = Coin::new(USDC.clone(), Uint128::new(100_000_000)).unwrap();
+
+    // With up to two hops, the best route goes through ATOM. Its outcome should
+    // match simulating the route directly.
+    let best = suite
+        .query_wasm_smart(contracts.amm, QueryBestRouteRequest {
+            input: input.clone(),
+            target_denom: OSMO.clone(),
+            max_hops: 2,
+        })
+        .should_succeed();
+
+    assert_eq!(best.route, UniqueVec::new_unchecked(vec![2, 1]));
+
+    suite
+        .query_wasm_smart(contracts.amm, QuerySimulateRequest {
+            input: input.clone(),
+            route: UniqueVec::new_unchecked(vec![2, 1]),
+        })
+        .should_succeed_and_equal(best.outcome.clone());
+
+    // With only one hop, the direct pool is the only option.
+    let direct = suite
+        .query_wasm_smart(contracts.amm, QueryBestRouteRequest {
+            input: input.clone(),
+            target_denom: OSMO.clone(),
+            max_hops: 1,
+        })
+        .should_succeed();
+
+    assert_eq!(direct.route, UniqueVec::new_unchecked(vec![3]));
+    assert!(direct.outcome.output.amount < best.outcome.output.amount);
+
+    // There's no pool for this denom, so no route can be found.
+    suite
+        .query_wasm_smart(contracts.amm, QueryBestRouteRequest {
+            input,
+            target_denom: LP_1.clone(),
+            max_hops: 2,
+        })
+        .should_fail_with_error("no swap route found");
+}
+
+#[test]
+fn best_route_is_not_greedy() {
+    let (mut suite, mut accounts, _, contracts) = setup_test();
+
+    let liquidity_fee_rate = FeeRate::new_unchecked(Udec128::new_bps(20));
+
+    let create_pool = |liquidity| {
+        Message::execute(
+            contracts.amm,
+            &amm::ExecuteMsg::CreatePool(PoolParams::Xyk(XykParams {
+                liquidity_fee_rate: liquidity_fee_rate.clone(),
+            })),
+            Coins::new_unchecked(liquidity),
+        )
+        .unwrap()
+    };
+
+    // Create five pools, using the first pool's liquidity token as a fourth
+    // denom:
+    //
+    // 1. OSMO-ATOM, at 1 OSMO = 10 ATOM
+    // 2. USDC-OSMO, at 1 USDC = 1 OSMO
+    // 3. USDC-LP_1, at 1 USDC = 1 LP_1
+    // 4. LP_1-ATOM, at 1 LP_1 = 5 ATOM
+    // 5. ATOM-OSMO, at 1 ATOM = 1 OSMO
+    //
+    // The best route from USDC to ATOM is 2-1, so a search that only keeps the
+    // best route to each denom drops 3-4. But from there, 2-1 can't continue
+    // to OSMO, as it has already visited OSMO, whereas 3-4-5 can, and yields
+    // five times as much OSMO as the direct pool.
+    suite
+        .send_messages(&mut accounts.relayer, vec![
+            create_pool(btree_map! {
+                OSMO.clone() => Uint128::new(100_000_000_000),
+                ATOM.clone() => Uint128::new(1_000_000_000_000),
+                // pool creation fee
+                USDC.clone() => Uint128::new(10_000_000),
+            }),
+            create_pool(btree_map! {
+                // liquidity + pool creation fee
+                USDC.clone() => Uint128::new(100_010_000_000),
+                OSMO.clone() => Uint128::new(100_000_000_000),
+            }),
+            create_pool(btree_map! {
+                // liquidity + pool creation fee
+                USDC.clone() => Uint128::new(100_010_000_000),
+                LP_1.clone() => Uint128::new(100_000_000_000),
+            }),
+            create_pool(btree_map! {
+                LP_1.clone() => Uint128::new(100_000_000_000),
+                ATOM.clone() => Uint128::new(500_000_000_000),
+                // pool creation fee
+                USDC.clone() => Uint128::new(10_000_000),
+            }),
+            create_pool(btree_map! {
+                ATOM.clone() => Uint128::new(1_000_000_000_000),
+                OSMO.clone() => Uint128::new(1_000_000_000_000),
+                // pool creation fee
+                USDC.clone() => Uint128::new(10_000_000),
+            }),
+        ])
+        .should_succeed();
+
+    let input = Coin::new(USDC.clone(), Uint128::new(1_000_000)).unwrap();
+
+    let best = suite
+        .query_wasm_smart(contracts.amm, QueryBestRouteRequest {
+            input: input.clone(),
+            target_denom: OSMO.clone(),
+            max_hops: 3,
+        })
+        .should_succeed();
+
+    assert_eq!(best.route, UniqueVec::new_unchecked(vec![3, 4, 5]));
+
+    let direct = suite
+        .query_wasm_smart(contracts.amm, QuerySimulateRequest {
+            input,
+            route: UniqueVec::new_unchecked(vec![2]),
+        })
+        .should_succeed();
+
+    assert!(best.outcome.output.amount > direct.output.amount);
+}
+
+#[test]
+fn exact_output() {
+    let (mut suite, mut accounts, _, contracts) = setup_test();
+
+    let liquidity_fee_rate = FeeRate::new_unchecked(Udec128::new_bps(20));
+
+    // Create two xyk pools, ATOM-OSMO and ATOM-USDC, and a concentrated
+    // ATOM-OSMO pool.
+    suite
+        .send_messages(&mut accounts.relayer, vec![
+            // pool 1: ATOM-OSMO
+            Message::execute(
+                contracts.amm,
+                &amm::ExecuteMsg::CreatePool(PoolParams::Xyk(XykParams {
+                    liquidity_fee_rate: liquidity_fee_rate.clone(),
+                })),
+                Coins::new_unchecked(btree_map! {
+                    ATOM.clone() => Uint128::new(657_761_324_779),
+                    OSMO.clone() => Uint128::new(5_886_161_498_040),
+                    // pool creation fee
+                    USDC.clone() => Uint128::new(10_000_000),
+                }),
+            )
+            .unwrap(),
+            // pool 2: ATOM-USDC
+            Message::execute(
+                contracts.amm,
+                &amm::ExecuteMsg::CreatePool(PoolParams::Xyk(XykParams {
+                    liquidity_fee_rate: liquidity_fee_rate.clone(),
+                })),
+                Coins::new_unchecked(btree_map! {
+                    ATOM.clone() => Uint128::new(224_078_907_873),
+                    // liquidity + pool creation fee
+                    USDC.clone() => Uint128::new(173_573_581_955),
+                }),
+            )
+            .unwrap(),
+            // pool 3: ATOM-OSMO, concentrated
+            Message::execute(
+                contracts.amm,
+                &amm::ExecuteMsg::CreatePool(PoolParams::Concentracted(ConcentratedParams {
+                    amplification: NonZero::new_unchecked(100),
+                    liquidity_fee_rate: liquidity_fee_rate.clone(),
+                })),
+                Coins::new_unchecked(btree_map! {
+                    ATOM.clone() => Uint128::new(100_000_000_000),
+                    OSMO.clone() => Uint128::new(200_000_000_000),
+                    // pool creation fee
+                    USDC.clone() => Uint128::new(10_000_000),
+                }),
+            )
+            .unwrap(),
+        ])
+        .should_succeed();
+
+    // ------------------------------- Xyk pools -------------------------------
+
+    let output = Coin::new(OSMO.clone(), Uint128::new(1_000_000_000)).unwrap();
+
+    // Simulate swapping USDC for exactly 1,000,000,000 uosmo.
+    let outcome = suite
+        .query_wasm_smart(contracts.amm, QuerySimulateExactOutputRequest {
+            output: output.clone(),
+            route: UniqueVec::new_unchecked(vec![2, 1]),
+        })
+        .should_succeed();
+
+    assert_eq!(outcome.input.denom, USDC.clone());
+    assert_eq!(outcome.output, output);
+
+    // Since inputs are rounded up, swapping the required input forward should
+    // yield at least the requested output, but not much more.
+    let forward = suite
+        .query_wasm_smart(contracts.amm, QuerySimulateRequest {
+            input: outcome.input.clone(),
+            route: UniqueVec::new_unchecked(vec![2, 1]),
+        })
+        .should_succeed();
+
+    assert!(forward.output.amount >= output.amount);
+    assert!(forward.output.amount - output.amount < Uint128::new(100));
+
+    // Attempt to swap with a maximum input lower than required. Should fail.
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.amm,
+            &ExecuteMsg::SwapExactOutput {
+                route: UniqueVec::new_unchecked(vec![2, 1]),
+                output: output.clone(),
+                maximum_input: Some(outcome.input.amount - Uint128::ONE),
+            },
+            Coin::new(USDC.clone(), Uint128::new(200_000_000)).unwrap(),
+        )
+        .should_fail_with_error("swap input exceeds maximum");
+
+    // Attempt to swap without sending enough funds. Should fail.
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.amm,
+            &ExecuteMsg::SwapExactOutput {
+                route: UniqueVec::new_unchecked(vec![2, 1]),
+                output: output.clone(),
+                maximum_input: None,
+            },
+            Coin::new(USDC.clone(), outcome.input.amount - Uint128::ONE).unwrap(),
+        )
+        .should_fail_with_error("insufficient funds for swap");
+
+    // Perform the swap, sending more USDC than needed. The trader should
+    // receive exactly the requested output, and be refunded the unused input.
+    let usdc_before = suite
+        .query_balance(&accounts.owner, USDC.clone())
+        .should_succeed();
+
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.amm,
+            &ExecuteMsg::SwapExactOutput {
+                route: UniqueVec::new_unchecked(vec![2, 1]),
+                output: output.clone(),
+                maximum_input: Some(outcome.input.amount),
+            },
+            Coin::new(USDC.clone(), Uint128::new(200_000_000)).unwrap(),
+        )
+        .should_succeed();
+
+    suite
+        .query_balance(&accounts.owner, OSMO.clone())
+        .should_succeed_and_equal(output.amount);
+
+    suite
+        .query_balance(&accounts.owner, USDC.clone())
+        .should_succeed_and_equal(usdc_before - outcome.input.amount);
+
+    // Check that taxman has received the protocol fee.
+    suite
+        .query_balance(&contracts.taxman, OSMO.clone())
+        .should_succeed_and_equal(outcome.protocol_fee.amount);
+
+    // --------------------------- Concentrated pool ---------------------------
+
+    let output = Coin::new(OSMO.clone(), Uint128::new(10_000_000_000)).unwrap();
+
+    let outcome = suite
+        .query_wasm_smart(contracts.amm, QuerySimulateExactOutputRequest {
+            output: output.clone(),
+            route: UniqueVec::new_unchecked(vec![3]),
+        })
+        .should_succeed();
+
+    // Near the price scale, the input should be close to the output converted
+    // at the price scale (5,000,000,000 uatom), plus fees.
+    assert_eq!(outcome.input.denom, ATOM.clone());
+    assert!(outcome.input.amount > Uint128::new(5_000_000_000));
+    assert!(outcome.input.amount < Uint128::new(5_050_000_000));
+
+    let atom_before = suite
+        .query_balance(&accounts.relayer, ATOM.clone())
+        .should_succeed();
+    let osmo_before = suite
+        .query_balance(&accounts.relayer, OSMO.clone())
+        .should_succeed();
+
+    suite
+        .execute(
+            &mut accounts.relayer,
+            contracts.amm,
+            &ExecuteMsg::SwapExactOutput {
+                route: UniqueVec::new_unchecked(vec![3]),
+                output: output.clone(),
+                maximum_input: None,
+            },
+            Coin::new(ATOM.clone(), Uint128::new(6_000_000_000)).unwrap(),
+        )
+        .should_succeed();
+
+    suite
+        .query_balance(&accounts.relayer, OSMO.clone())
+        .should_succeed_and_equal(osmo_before + output.amount);
+
+    suite
+        .query_balance(&accounts.relayer, ATOM.clone())
+        .should_succeed_and_equal(atom_before - outcome.input.amount);
+}
@@ -0,0 +1,291 @@
+use {
+    dango_testing::{setup_test, Grantee},
+    dango_types::{
+        account::single::{self, QueryGrantRequest, QuerySequenceRequest},
+        account_factory::AccountParams,
+        auth::{Authorization, Grant, Metadata},
+        taxman,
+    },
+    grug::{
+        Addressable, Coins, Duration, HashExt, JsonDeExt, JsonSerExt, Message, NumberConst,
+        ResultExt, Timestamp, Udec128, Uint128,
+    },
+};
+
+#[test]
+fn authz() {
+    let (mut suite, mut accounts, _, contracts) = setup_test();
+
+    let owner = accounts.owner.address();
+    let relayer = accounts.relayer.address();
+    // Grantee sends tokens to itself.
+    let recipient = relayer;
+
+    // Owner authorizes relayer to make transfers and execute the AMM on its
+    // behalf, spending no more than 100 uusdc, until timestamp 10.
+    suite
+        .execute(
+            &mut accounts.owner,
+            owner,
+            &single::ExecuteMsg::Grant {
+                grantee: relayer,
+                grant: Grant {
+                    authorizations: vec![Authorization::Transfer, Authorization::Execute {
+                        contract: contracts.amm,
+                    }],
+                    spend_limit: Some(Coins::one("uusdc", 100).unwrap()),
+                    expiration: Some(Timestamp::from_seconds(10)),
+                },
+            },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    // Someone other than the account itself attempts to create a grant.
+    // Should fail.
+    suite
+        .execute(
+            &mut accounts.relayer,
+            owner,
+            &single::ExecuteMsg::Grant {
+                grantee: relayer,
+                grant: Grant {
+                    authorizations: vec![Authorization::Transfer],
+                    spend_limit: None,
+                    expiration: None,
+                },
+            },
+            Coins::new(),
+        )
+        .should_fail_with_error("only the account itself can create grants");
+
+    let mut grantee = Grantee::new(owner, &accounts.relayer);
+
+    // Relayer sends 60 uusdc from the owner's account. Should succeed.
+    suite
+        .transfer(
+            grantee.with_sequence(1),
+            recipient,
+            Coins::one("uusdc", 60).unwrap(),
+        )
+        .should_succeed();
+
+    suite
+        .query_balance(&owner, "uusdc")
+        .should_succeed_and_equal(Uint128::new(100_000_000_000 - 60));
+
+    // The spend limit should have been reduced.
+    suite
+        .query_wasm_smart(owner, QueryGrantRequest { grantee: relayer })
+        .should_succeed_and(|grant| {
+            grant
+                .as_ref()
+                .is_some_and(|grant| grant.spend_limit == Some(Coins::one("uusdc", 40).unwrap()))
+        });
+
+    // Relayer attempts to send another 60 uusdc, exceeding the spend limit.
+    // Should fail.
+    suite
+        .transfer(
+            grantee.with_sequence(2),
+            recipient,
+            Coins::one("uusdc", 60).unwrap(),
+        )
+        .should_fail_with_error("spend limit exceeded for denom `uusdc`");
+
+    // Relayer attempts to execute a contract that isn't authorized.
+    // Should fail.
+    suite
+        .send_message(
+            grantee.with_sequence(2),
+            Message::execute(
+                contracts.taxman,
+                &taxman::ExecuteMsg::Configure {
+                    new_cfg: taxman::Config {
+                        fee_denom: "uusdc".try_into().unwrap(),
+                        fee_rate: Default::default(),
+                        fee_market: None,
+                    },
+                },
+                Coins::new(),
+            )
+            .unwrap(),
+        )
+        .should_fail_with_error("message isn't permitted by the grant");
+
+    // Relayer attempts to execute the owner account to extend its own grant.
+    // Should fail.
+    suite
+        .send_message(
+            grantee.with_sequence(2),
+            Message::execute(
+                owner,
+                &single::ExecuteMsg::Grant {
+                    grantee: relayer,
+                    grant: Grant {
+                        authorizations: vec![Authorization::Transfer],
+                        spend_limit: None,
+                        expiration: None,
+                    },
+                },
+                Coins::new(),
+            )
+            .unwrap(),
+        )
+        .should_fail_with_error("grantee can't execute the granter account");
+
+    // Make time pass beyond the grant's expiration.
+    suite.block_time = Duration::from_seconds(20);
+    suite.make_empty_block();
+
+    // Relayer attempts to use the expired grant. Should fail.
+    suite
+        .transfer(
+            grantee.with_sequence(2),
+            recipient,
+            Coins::one("uusdc", 10).unwrap(),
+        )
+        .should_fail_with_error("has expired");
+
+    // Owner revokes the grant.
+    let sequence = suite
+        .query_wasm_smart(owner, QuerySequenceRequest {})
+        .should_succeed();
+
+    accounts.owner.sequence = sequence;
+
+    suite
+        .execute(
+            &mut accounts.owner,
+            owner,
+            &single::ExecuteMsg::Revoke { grantee: relayer },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(owner, QueryGrantRequest { grantee: relayer })
+        .should_succeed_and_equal(None);
+
+    // Relayer attempts to send a transfer without a grant. Should fail.
+    let mut grantee = Grantee::new(owner, &accounts.relayer);
+
+    suite
+        .transfer(
+            grantee.with_sequence(sequence + 1),
+            recipient,
+            Coins::one("uusdc", 10).unwrap(),
+        )
+        .should_fail_with_error("no grant found for grantee");
+}
+
+#[test]
+fn grant_fee_counts_against_spend_limit() {
+    let (mut suite, mut accounts, _, _) = setup_test();
+
+    let owner = accounts.owner.address();
+    let relayer = accounts.relayer.address();
+
+    // Owner authorizes relayer to make transfers, spending no more than
+    // 100 uusdc.
+    suite
+        .execute(
+            &mut accounts.owner,
+            owner,
+            &single::ExecuteMsg::Grant {
+                grantee: relayer,
+                grant: Grant {
+                    authorizations: vec![Authorization::Transfer],
+                    spend_limit: Some(Coins::one("uusdc", 100).unwrap()),
+                    expiration: None,
+                },
+            },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    let mut grantee = Grantee::new(owner, &accounts.relayer);
+
+    // Relayer sends an empty transfer, but offers a huge gas price, which the
+    // taxman would withhold from the owner's account. The maximum fee exceeds
+    // the spend limit, so the tx should be rejected from entering the mempool.
+    let tx = suite.sign_transaction(
+        grantee.with_sequence(1).with_gas_price(Udec128::new(1_000)),
+        vec![Message::transfer(relayer, Coins::new()).unwrap()],
+    );
+
+    suite
+        .check_tx(tx)
+        .should_fail_with_error("spend limit exceeded for denom `uusdc`");
+
+    // With a reasonable gas price, the same transfer is accepted.
+    let tx = suite.sign_transaction(
+        grantee.with_sequence(1).with_gas_price(Udec128::ZERO),
+        vec![Message::transfer(relayer, Coins::new()).unwrap()],
+    );
+
+    suite.check_tx(tx).should_succeed();
+}
+
+#[test]
+fn tampered_grantee_is_rejected() {
+    let (mut suite, mut accounts, codes, contracts) = setup_test();
+
+    let owner = accounts.owner.address();
+
+    // Relayer has a second account. Owner grants both of relayer's accounts.
+    let other = accounts
+        .relayer
+        .register_new_account(
+            &mut suite,
+            contracts.account_factory,
+            codes.account_spot.to_bytes().hash256(),
+            AccountParams::Spot(single::Params {
+                owner: accounts.relayer.username.clone(),
+            }),
+            Coins::new(),
+        )
+        .unwrap()
+        .address();
+
+    for grantee in [accounts.relayer.address(), other] {
+        suite
+            .execute(
+                &mut accounts.owner,
+                owner,
+                &single::ExecuteMsg::Grant {
+                    grantee,
+                    grant: Grant {
+                        authorizations: vec![Authorization::Transfer],
+                        spend_limit: None,
+                        expiration: None,
+                    },
+                },
+                Coins::new(),
+            )
+            .should_succeed();
+    }
+
+    // Relayer signs a transfer, acting through its first account.
+    let mut grantee = Grantee::new(owner, &accounts.relayer);
+
+    let tx = suite.sign_transaction(
+        grantee.with_sequence(2),
+        vec![Message::transfer(other, Coins::one("uusdc", 1).unwrap()).unwrap()],
+    );
+
+    // Someone relaying the transaction swaps the grantee for the other account.
+    // It's associated with the same user and holds a grant too, so only the
+    // signature can tell. Should fail.
+    let mut tampered = tx.clone();
+    let mut data: Metadata = tampered.data.deserialize_json().unwrap();
+    data.grantee = Some(other);
+    tampered.data = data.to_json_value().unwrap();
+
+    suite
+        .check_tx(tampered)
+        .should_fail_with_error("signature is unauthentic");
+
+    // The transaction as signed is accepted.
+    suite.check_tx(tx).should_succeed();
+}
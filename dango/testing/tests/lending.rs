@@ -4,14 +4,14 @@ use {
         account::single,
         account_factory::AccountParams,
         lending::{
-            self, MarketUpdates, QueryDebtRequest, QueryDebtsRequest, QueryMarketsRequest,
-            NAMESPACE, SUBNAMESPACE,
+            self, InterestRateModel, MarketUpdates, OptimalUtilization, QueryDebtRequest,
+            QueryDebtsRequest, QueryMarketRequest, QueryMarketsRequest, NAMESPACE, SUBNAMESPACE,
         },
         token_factory,
     },
     grug::{
-        btree_map, Addressable, Coin, Coins, Denom, HashExt, Message, MsgTransfer, NumberConst,
-        ResultExt, Uint128,
+        btree_map, Addressable, Coin, Coins, Denom, Duration, HashExt, Message, MsgTransfer,
+        NumberConst, ResultExt, Udec128, Uint128,
     },
     grug_vm_rust::VmError,
     std::{str::FromStr, sync::LazyLock},
@@ -21,6 +21,15 @@ static ATOM: LazyLock<Denom> = LazyLock::new(|| Denom::from_str("uatom").unwrap(
 static _OSMO: LazyLock<Denom> = LazyLock::new(|| Denom::from_str("uosmo").unwrap());
 static USDC: LazyLock<Denom> = LazyLock::new(|| Denom::from_str("uusdc").unwrap());
 
+fn interest_rate_model() -> InterestRateModel {
+    InterestRateModel {
+        base_rate: Udec128::ZERO,
+        optimal_utilization: OptimalUtilization::new_unchecked(Udec128::new_percent(80)),
+        first_slope: Udec128::new_percent(4),
+        second_slope: Udec128::new_percent(75),
+    }
+}
+
 #[test]
 fn cant_transfer_to_lending() {
     let (mut suite, mut accounts, _codes, contracts) = setup_test();
@@ -58,13 +67,29 @@ fn update_markets_works() {
         )
         .should_fail_with_error("Only the owner can whitelist denoms");
 
+    // Try to whitelist ATOM without an interest rate model, should fail.
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.lending,
+            &lending::ExecuteMsg::UpdateMarkets(btree_map! {
+                ATOM.clone() => MarketUpdates {
+                    interest_rate_model: None,
+                },
+            }),
+            Coins::new(),
+        )
+        .should_fail_with_error("interest rate model must be provided");
+
     // Whitelist ATOM from owner, should succeed.
     suite
         .execute(
             &mut accounts.owner,
             contracts.lending,
             &lending::ExecuteMsg::UpdateMarkets(btree_map! {
-                ATOM.clone() => MarketUpdates {},
+                ATOM.clone() => MarketUpdates {
+                    interest_rate_model: Some(interest_rate_model()),
+                },
             }),
             Coins::new(),
         )
@@ -194,7 +219,7 @@ fn borrowing_works() {
             &lending::ExecuteMsg::Borrow(Coins::one(USDC.clone(), 100).unwrap()),
             Coins::new(),
         )
-        .should_fail_with_error("subtraction overflow: 0 - 100");
+        .should_fail_with_error("insufficient liquidity! available: 0, requested: 100");
 
     // Deposit some USDC
     suite
@@ -238,6 +263,171 @@ fn borrowing_works() {
         });
 }
 
+#[test]
+fn interest_accrual_and_repay_works() {
+    let (mut suite, mut accounts, codes, contracts) = setup_test();
+
+    let lp_denom = USDC.prepend(&[&NAMESPACE, &SUBNAMESPACE]).unwrap();
+
+    // Create a margin account.
+    let mut margin_account = accounts
+        .relayer
+        .register_new_account(
+            &mut suite,
+            contracts.account_factory,
+            codes.account_margin.to_bytes().hash256(),
+            AccountParams::Margin(single::Params {
+                owner: accounts.relayer.username.clone(),
+            }),
+            Coins::new(),
+        )
+        .unwrap();
+
+    // Deposit some USDC, then borrow half of it, for a utilization of 50%.
+    suite
+        .execute(
+            &mut accounts.relayer,
+            contracts.lending,
+            &lending::ExecuteMsg::Deposit {},
+            Coins::one(USDC.clone(), 1_000_000_000).unwrap(),
+        )
+        .should_succeed();
+
+    suite
+        .execute(
+            &mut margin_account,
+            contracts.lending,
+            &lending::ExecuteMsg::Borrow(Coins::one(USDC.clone(), 500_000_000).unwrap()),
+            Coins::new(),
+        )
+        .should_succeed();
+
+    // Can't borrow more than the available liquidity.
+    suite
+        .execute(
+            &mut margin_account,
+            contracts.lending,
+            &lending::ExecuteMsg::Borrow(Coins::one(USDC.clone(), 500_000_001).unwrap()),
+            Coins::new(),
+        )
+        .should_fail_with_error("insufficient liquidity! available: 500000000");
+
+    // Let one year pass.
+    //
+    // borrow_rate = base_rate + first_slope * utilization / optimal_utilization
+    // = 0% + 4% * 50% / 80%
+    // = 2.5%
+    //
+    // borrow_index = 1 * (1 + 2.5%) = 1.025
+    // debt = 500,000,000 * 1.025 = 512,500,000
+    // total_supplied = 1,000,000,000 + 12,500,000 = 1,012,500,000
+    suite.block_time = Duration::from_seconds(365 * 24 * 60 * 60);
+    suite.make_empty_block();
+
+    suite
+        .query_wasm_smart(contracts.lending, QueryDebtRequest {
+            account: margin_account.address(),
+        })
+        .should_succeed_and_equal(Coins::one(USDC.clone(), 512_500_000).unwrap());
+
+    suite
+        .query_wasm_smart(contracts.lending, QueryMarketRequest {
+            denom: USDC.clone(),
+        })
+        .should_succeed_and(|market| {
+            market.borrow_index == Udec128::new_permille(1025)
+                && market.total_supplied == Uint128::new(1_012_500_000)
+                && market.total_lp_supply == Uint128::new(1_000_000_000)
+        });
+
+    // Send the margin account some extra USDC to pay for the interest.
+    suite.block_time = Duration::from_millis(250);
+    suite
+        .transfer(
+            &mut accounts.relayer,
+            margin_account.address(),
+            Coins::one(USDC.clone(), 20_000_000).unwrap(),
+        )
+        .should_succeed();
+
+    // Partially repay the debt.
+    suite
+        .execute(
+            &mut margin_account,
+            contracts.lending,
+            &lending::ExecuteMsg::Repay {},
+            Coins::one(USDC.clone(), 500_000_000).unwrap(),
+        )
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(contracts.lending, QueryDebtRequest {
+            account: margin_account.address(),
+        })
+        .should_succeed_and(|debt| {
+            let debt = debt.amount_of(&USDC);
+            debt >= Uint128::new(12_500_000) && debt < Uint128::new(12_600_000)
+        });
+
+    // Repay more than the remaining debt. The excess should be refunded, and
+    // the debt cleared.
+    let balance_before = suite
+        .query_balance(&margin_account, USDC.clone())
+        .should_succeed();
+
+    suite
+        .execute(
+            &mut margin_account,
+            contracts.lending,
+            &lending::ExecuteMsg::Repay {},
+            Coins::one(USDC.clone(), 20_000_000).unwrap(),
+        )
+        .should_succeed();
+
+    suite
+        .query_balance(&margin_account, USDC.clone())
+        .should_succeed_and(|balance| {
+            *balance > balance_before - Uint128::new(12_600_000)
+                && *balance <= balance_before - Uint128::new(12_500_000)
+        });
+
+    suite
+        .query_wasm_smart(contracts.lending, QueryDebtsRequest {
+            limit: None,
+            start_after: None,
+        })
+        .should_succeed_and_equal(btree_map! {});
+
+    // Withdraw all liquidity. The depositor should receive their deposit plus
+    // the interest paid by the borrower.
+    let balance_before = suite
+        .query_balance(&accounts.relayer, USDC.clone())
+        .should_succeed();
+
+    suite
+        .execute(
+            &mut accounts.relayer,
+            contracts.lending,
+            &lending::ExecuteMsg::Withdraw {},
+            Coins::one(lp_denom, 1_000_000_000).unwrap(),
+        )
+        .should_succeed();
+
+    suite
+        .query_balance(&accounts.relayer, USDC.clone())
+        .should_succeed_and(|balance| *balance >= balance_before + Uint128::new(1_012_500_000));
+
+    // No repay is possible without debt.
+    suite
+        .execute(
+            &mut margin_account,
+            contracts.lending,
+            &lending::ExecuteMsg::Repay {},
+            Coins::one(USDC.clone(), 1).unwrap(),
+        )
+        .should_fail_with_error("has no debt");
+}
+
 #[test]
 fn composite_denom() {
     let (mut suite, mut accounts, _, contracts) = setup_test();
@@ -268,7 +458,9 @@ fn composite_denom() {
             &mut accounts.owner,
             contracts.lending,
             &lending::ExecuteMsg::UpdateMarkets(btree_map! {
-                denom.clone() => MarketUpdates {},
+                denom.clone() => MarketUpdates {
+                    interest_rate_model: Some(interest_rate_model()),
+                },
             }),
             Coins::default(),
         )
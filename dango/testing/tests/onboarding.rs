@@ -89,6 +89,65 @@ fn user_onboarding() {
         .should_succeed_and_equal(Uint128::new(123));
 }
 
+/// Onboard a user with an Ed25519 key, such as one from a Solana-style wallet,
+/// then send a transaction signed with it.
+#[test]
+fn ed25519_user_onboarding() {
+    let (mut suite, mut accounts, codes, contracts) = setup_test();
+
+    let mut user = TestAccount::new_random_ed25519("user").predict_address(
+        contracts.account_factory,
+        codes.account_spot.to_bytes().hash256(),
+        true,
+    );
+
+    suite
+        .execute(
+            &mut accounts.relayer,
+            contracts.ibc_transfer,
+            &ibc_transfer::ExecuteMsg::ReceiveTransfer {
+                recipient: user.address(),
+            },
+            Coins::one("uusdc", 123).unwrap(),
+        )
+        .should_succeed();
+
+    suite
+        .execute(
+            &mut Factory::new(contracts.account_factory),
+            contracts.account_factory,
+            &account_factory::ExecuteMsg::RegisterUser {
+                username: user.username.clone(),
+                key: user.key,
+                key_hash: user.key_hash,
+            },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(
+            contracts.account_factory,
+            account_factory::QueryKeysByUserRequest {
+                username: user.username.clone(),
+            },
+        )
+        .should_succeed_and_equal(btree_map! { user.key_hash => user.key });
+
+    // The user sends a transaction signed with the Ed25519 key.
+    suite
+        .transfer(
+            &mut user,
+            accounts.relayer.address(),
+            Coins::one("uusdc", 23).unwrap(),
+        )
+        .should_succeed();
+
+    suite
+        .query_balance(&user, "uusdc")
+        .should_succeed_and_equal(Uint128::new(100));
+}
+
 /// Attempt to register a username twice.
 /// The transaction should fail `CheckTx` and be rejected from entering mempool.
 #[test]
@@ -0,0 +1,130 @@
+use {
+    dango_testing::{setup_test_with_db, TestAccount},
+    dango_types::{account::single, account_factory::AccountParams, taxman},
+    grug::{
+        Addr, Addressable, Coins, Denom, HashExt, Message, ResultExt, TestSuite, Udec128, Uint128,
+    },
+    grug_app::{Db, NaiveProposalPreparer},
+    grug_db_fork::ForkDb,
+    grug_db_memory::MemDb,
+    grug_vm_rust::RustVm,
+    std::{str::FromStr, sync::LazyLock},
+};
+
+static USDC: LazyLock<Denom> = LazyLock::new(|| Denom::from_str("uusdc").unwrap());
+
+/// Fork a test suite from the latest state of the given DB, executing
+/// transactions using the given number of threads.
+fn fork(base: &TestSuite, db: &MemDb, parallelism: usize) -> TestSuite<ForkDb<MemDb>> {
+    let fork_height = db.latest_version().unwrap();
+    let db = ForkDb::new(db.clone(), fork_height).unwrap();
+
+    let mut suite = TestSuite::new_from_state(
+        db,
+        RustVm::new(),
+        NaiveProposalPreparer,
+        base.block_time,
+        base.default_gas_limit,
+    );
+
+    suite.app = suite.app.with_parallelism(parallelism);
+    suite
+}
+
+#[test]
+fn parallel_execution_with_fees_matches_sequential() {
+    let db = MemDb::new();
+    let (mut suite, mut accounts, codes, contracts) = setup_test_with_db(db.clone());
+
+    // Charge transaction fees.
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.taxman,
+            &taxman::ExecuteMsg::Configure {
+                new_cfg: taxman::Config {
+                    fee_denom: USDC.clone(),
+                    fee_rate: Udec128::new_percent(1),
+                    fee_market: None,
+                },
+            },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    // Create a third sender, and three recipients.
+    let mut register = |funds| -> TestAccount {
+        accounts
+            .relayer
+            .register_new_account(
+                &mut suite,
+                contracts.account_factory,
+                codes.account_spot.to_bytes().hash256(),
+                AccountParams::Spot(single::Params {
+                    owner: accounts.relayer.username.clone(),
+                }),
+                funds,
+            )
+            .unwrap()
+    };
+
+    let mut sender = register(Coins::one(USDC.clone(), 1_000_000_000).unwrap());
+    let recipients = [(); 3].map(|_| register(Coins::new()).address());
+
+    // Sign the transactions once, and send the same ones to both forks.
+    let sign = |signer: &mut TestAccount, to: Addr, amount: u128| {
+        let msg = Message::transfer(to, Coins::one(USDC.clone(), amount).unwrap()).unwrap();
+
+        suite.sign_transaction(signer, vec![msg])
+    };
+
+    let txs = vec![
+        // Independent of each other. Each pays the fee from its own balance.
+        sign(&mut accounts.owner, recipients[0], 100),
+        sign(&mut accounts.relayer, recipients[1], 100),
+        sign(&mut sender, recipients[2], 100),
+        // Depends on the 2nd tx: same sender.
+        sign(&mut accounts.relayer, recipients[0], 50),
+    ];
+
+    let mut sequential = fork(&suite, &db, 1);
+    let mut parallel = fork(&suite, &db, 4);
+
+    let sequential_outcome = sequential.make_block(txs.clone());
+    let parallel_outcome = parallel.make_block(txs);
+
+    // The outcomes, including the events and the app hash, must be identical.
+    assert_eq!(sequential_outcome, parallel_outcome);
+
+    for outcome in parallel_outcome.tx_outcomes {
+        outcome.should_succeed();
+    }
+
+    // Only the transaction that depends on an earlier one is re-executed. The
+    // fees don't make the others conflict.
+    assert_eq!(parallel.app.reexecuted_txs(), 1);
+
+    for (recipient, balance) in recipients.into_iter().zip([150, 100, 100]) {
+        parallel
+            .query_balance(&recipient, USDC.clone())
+            .should_succeed_and_equal(Uint128::new(balance));
+    }
+
+    // The fees are credited to the taxman at the beginning of the next block,
+    // identically in both forks.
+    let taxman_balance = parallel
+        .query_balance(&contracts.taxman, USDC.clone())
+        .unwrap();
+
+    assert_eq!(sequential.make_empty_block(), parallel.make_empty_block());
+
+    let new_taxman_balance = parallel
+        .query_balance(&contracts.taxman, USDC.clone())
+        .unwrap();
+
+    assert!(new_taxman_balance > taxman_balance);
+
+    sequential
+        .query_balance(&contracts.taxman, USDC.clone())
+        .should_succeed_and_equal(new_taxman_balance);
+}
@@ -0,0 +1,240 @@
+use {
+    dango_testing::{setup_test, TestAccount},
+    dango_types::{
+        account::single::QuerySequenceRequest,
+        account_factory::{self, QuerySessionKeyRequest},
+        auth::Session,
+    },
+    grug::{
+        btree_map, btree_set, Addressable, Coins, Duration, JsonSerExt, Message, ResultExt,
+        Timestamp, Tx, Udec128, Uint128,
+    },
+};
+
+#[test]
+fn session_key() {
+    let (mut suite, mut accounts, _, contracts) = setup_test();
+
+    let owner = accounts.owner.address();
+    let relayer = accounts.relayer.address();
+
+    // Generate a session key for the owner.
+    let mut session_key = TestAccount::new_random("owner").set_address(&btree_map! {
+        accounts.owner.username.clone() => owner,
+    });
+
+    // Owner registers the session key, allowing it to send up to 100 uusdc to
+    // the relayer, until timestamp 10.
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.account_factory,
+            &account_factory::ExecuteMsg::RegisterSessionKey {
+                key: session_key.key,
+                key_hash: session_key.key_hash,
+                session: Session {
+                    expiry: Timestamp::from_seconds(10),
+                    max_spend: Coins::one("uusdc", 100).unwrap(),
+                    allowed_contracts: btree_set! { relayer },
+                },
+            },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(contracts.account_factory, QuerySessionKeyRequest {
+            username: accounts.owner.username.clone(),
+            key_hash: session_key.key_hash,
+        })
+        .should_succeed_and(|session| session.is_some());
+
+    // Registering the same key again should fail.
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.account_factory,
+            &account_factory::ExecuteMsg::RegisterSessionKey {
+                key: session_key.key,
+                key_hash: session_key.key_hash,
+                session: Session {
+                    expiry: Timestamp::from_seconds(1000),
+                    max_spend: Coins::new(),
+                    allowed_contracts: btree_set! {},
+                },
+            },
+            Coins::new(),
+        )
+        .should_fail_with_error("is already associated with user `owner`");
+
+    // The session key and the owner's primary key share the same sequence.
+    session_key.sequence = accounts.owner.sequence;
+
+    // Send 60 uusdc to the relayer with the session key. Should succeed.
+    suite
+        .transfer(&mut session_key, relayer, Coins::one("uusdc", 60).unwrap())
+        .should_succeed();
+
+    suite
+        .query_balance(&accounts.owner, "uusdc")
+        .should_succeed_and_equal(Uint128::new(100_000_000_000 - 60));
+
+    // Send another 60 uusdc, exceeding the max spend. Should fail.
+    suite
+        .transfer(&mut session_key, relayer, Coins::one("uusdc", 60).unwrap())
+        .should_fail_with_error("max spend exceeded for denom `uusdc`");
+
+    // Send tokens to an address that isn't allowed. Should fail.
+    session_key.sequence -= 1;
+
+    suite
+        .transfer(
+            &mut session_key,
+            contracts.taxman,
+            Coins::one("uusdc", 1).unwrap(),
+        )
+        .should_fail_with_error(format!("contract {} isn't allowed", contracts.taxman));
+
+    // Attempt to register another session key using the session key.
+    // Should fail.
+    session_key.sequence -= 1;
+
+    suite
+        .execute(
+            &mut session_key,
+            contracts.account_factory,
+            &account_factory::ExecuteMsg::RegisterSessionKey {
+                key: accounts.relayer.key,
+                key_hash: accounts.relayer.key_hash,
+                session: Session {
+                    expiry: Timestamp::from_seconds(1000),
+                    max_spend: Coins::new(),
+                    allowed_contracts: btree_set! { contracts.account_factory },
+                },
+            },
+            Coins::new(),
+        )
+        .should_fail_with_error("session key can't interact with the account factory");
+
+    // Make time pass beyond the session's expiry.
+    suite.block_time = Duration::from_seconds(20);
+    suite.make_empty_block();
+
+    // Attempt to use the expired session key. Should fail.
+    session_key.sequence -= 1;
+
+    suite
+        .transfer(&mut session_key, relayer, Coins::one("uusdc", 1).unwrap())
+        .should_fail_with_error("has expired");
+
+    // Owner revokes the session key.
+    accounts.owner.sequence = suite
+        .query_wasm_smart(owner, QuerySequenceRequest {})
+        .should_succeed();
+
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.account_factory,
+            &account_factory::ExecuteMsg::RevokeSessionKey {
+                key_hash: session_key.key_hash,
+            },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(contracts.account_factory, QuerySessionKeyRequest {
+            username: accounts.owner.username.clone(),
+            key_hash: session_key.key_hash,
+        })
+        .should_succeed_and_equal(None);
+
+    // The revoked key can no longer be used.
+    session_key.sequence = accounts.owner.sequence;
+
+    suite
+        .transfer(&mut session_key, relayer, Coins::one("uusdc", 1).unwrap())
+        .should_fail_with_error("isn't associated with user `owner`");
+
+    // Owner registers the same key again. The amount spent during the previous
+    // session shouldn't carry over.
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.account_factory,
+            &account_factory::ExecuteMsg::RegisterSessionKey {
+                key: session_key.key,
+                key_hash: session_key.key_hash,
+                session: Session {
+                    expiry: Timestamp::from_seconds(1000),
+                    max_spend: Coins::one("uusdc", 100).unwrap(),
+                    allowed_contracts: btree_set! { relayer },
+                },
+            },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    session_key.sequence = accounts.owner.sequence;
+
+    suite
+        .transfer(&mut session_key, relayer, Coins::one("uusdc", 60).unwrap())
+        .should_succeed();
+}
+
+#[test]
+fn session_key_fee_counts_against_max_spend() {
+    let (mut suite, mut accounts, _, contracts) = setup_test();
+
+    let owner = accounts.owner.address();
+    let relayer = accounts.relayer.address();
+
+    let session_key = TestAccount::new_random("owner").set_address(&btree_map! {
+        accounts.owner.username.clone() => owner,
+    });
+
+    // Owner registers the session key, allowing it to send up to 100 uusdc to
+    // the relayer.
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.account_factory,
+            &account_factory::ExecuteMsg::RegisterSessionKey {
+                key: session_key.key,
+                key_hash: session_key.key_hash,
+                session: Session {
+                    expiry: Timestamp::from_seconds(1000),
+                    max_spend: Coins::one("uusdc", 100).unwrap(),
+                    allowed_contracts: btree_set! { relayer },
+                },
+            },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    // The session key sends an empty transfer, but offers a huge gas price,
+    // which the taxman would withhold from the owner's account. The maximum
+    // fee exceeds the max spend, so the tx should be rejected from entering
+    // the mempool.
+    let msg = Message::transfer(relayer, Coins::new()).unwrap();
+    let (data, credential) = session_key
+        .sign_transaction_with_gas_price(
+            owner,
+            vec![msg.clone()],
+            &suite.chain_id,
+            accounts.owner.sequence,
+            Some(Udec128::new(1_000)),
+        )
+        .unwrap();
+
+    suite
+        .check_tx(Tx {
+            sender: owner,
+            gas_limit: suite.default_gas_limit,
+            msgs: vec![msg],
+            data: data.to_json_value().unwrap(),
+            credential: credential.to_json_value().unwrap(),
+        })
+        .should_fail_with_error("max spend exceeded for denom `uusdc`");
+}
@@ -1,7 +1,10 @@
 use {
     dango_testing::setup_test,
     dango_types::taxman,
-    grug::{Addressable, Coins, Denom, MultiplyFraction, Number, ResultExt, Udec128, Uint128},
+    grug::{
+        Addr, Addressable, Coins, Denom, JsonSerExt, Message, MultiplyFraction, Number, ResultExt,
+        Tx, Udec128, Uint128,
+    },
     std::{str::FromStr, sync::LazyLock},
 };
 
@@ -30,6 +33,7 @@ fn fee_rate_update_works() {
                 new_cfg: taxman::Config {
                     fee_denom: USDC.clone(),
                     fee_rate: OLD_FEE_RATE,
+                    fee_market: None,
                 },
             },
             Coins::new(),
@@ -53,6 +57,7 @@ fn fee_rate_update_works() {
                 new_cfg: taxman::Config {
                     fee_denom: USDC.clone(),
                     fee_rate: NEW_FEE_RATE,
+                    fee_market: None,
                 },
             },
             Coins::new(),
@@ -86,3 +91,146 @@ fn fee_rate_update_works() {
         .query_balance(&accounts.relayer, USDC.clone())
         .should_succeed_and_equal(user_usdc_balance.checked_sub(fee).unwrap());
 }
+
+#[test]
+fn fee_market_works() {
+    let (mut suite, mut accounts, _, contracts) = setup_test();
+
+    let tip_recipient = Addr::mock(123);
+
+    // Enable the fee market. Set the target so low that any transaction makes
+    // the block exceed it by more than 100%, causing the base fee to increase
+    // by the maximum rate.
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.taxman,
+            &taxman::ExecuteMsg::Configure {
+                new_cfg: taxman::Config {
+                    fee_denom: USDC.clone(),
+                    fee_rate: OLD_FEE_RATE,
+                    fee_market: Some(taxman::FeeMarket {
+                        target_gas_per_block: 1,
+                        max_change_rate: Udec128::new_permille(125), // 12.5%
+                        tip_recipient,
+                    }),
+                },
+            },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    // The base fee starts at the fee rate.
+    suite
+        .query_wasm_smart(contracts.taxman, taxman::QueryBaseFeeRequest {})
+        .should_succeed_and_equal(OLD_FEE_RATE);
+
+    // Make a block with a transaction. The base fee is adjusted at the
+    // beginning of the next block, so it's unchanged for now.
+    suite
+        .transfer(
+            &mut accounts.relayer,
+            accounts.owner.address(),
+            Coins::new(),
+        )
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(contracts.taxman, taxman::QueryBaseFeeRequest {})
+        .should_succeed_and_equal(OLD_FEE_RATE);
+
+    // Make an empty block. The base fee should increase by 12.5%, as the
+    // previous block was above target.
+    suite.make_empty_block();
+
+    let raised_base_fee = OLD_FEE_RATE
+        .checked_mul(Udec128::new_permille(1125))
+        .unwrap();
+
+    suite
+        .query_wasm_smart(contracts.taxman, taxman::QueryBaseFeeRequest {})
+        .should_succeed_and_equal(raised_base_fee);
+
+    // A transaction offering a gas price below the base fee is rejected.
+    {
+        let msg = Message::transfer(accounts.owner.address(), Coins::new()).unwrap();
+        let (data, credential) = accounts
+            .relayer
+            .sign_transaction_with_gas_price(
+                accounts.relayer.address(),
+                vec![msg.clone()],
+                &suite.chain_id,
+                accounts.relayer.sequence,
+                Some(OLD_FEE_RATE),
+            )
+            .unwrap();
+
+        suite
+            .check_tx(Tx {
+                sender: accounts.relayer.address(),
+                gas_limit: suite.default_gas_limit,
+                msgs: vec![msg],
+                data: data.to_json_value().unwrap(),
+                credential: credential.to_json_value().unwrap(),
+            })
+            .should_fail_with_error("gas price is lower than base fee");
+    }
+
+    // Make another empty block. The base fee should decrease, as the previous
+    // block was below target, but not below the fee rate.
+    suite.make_empty_block();
+
+    suite
+        .query_wasm_smart(contracts.taxman, taxman::QueryBaseFeeRequest {})
+        .should_succeed_and_equal(OLD_FEE_RATE);
+
+    // Send a transaction offering a gas price above the base fee. The sender
+    // pays the offered price; the amount above the base fee goes to the tip
+    // recipient.
+    let relayer_usdc_balance = suite
+        .query_balance(&accounts.relayer, USDC.clone())
+        .unwrap();
+
+    let gas_price = NEW_FEE_RATE;
+    let msg = Message::transfer(accounts.owner.address(), Coins::new()).unwrap();
+    let (data, credential) = accounts
+        .relayer
+        .sign_transaction_with_gas_price(
+            accounts.relayer.address(),
+            vec![msg.clone()],
+            &suite.chain_id,
+            accounts.relayer.sequence,
+            Some(gas_price),
+        )
+        .unwrap();
+
+    let outcome = suite.send_transaction(Tx {
+        sender: accounts.relayer.address(),
+        gas_limit: suite.default_gas_limit,
+        msgs: vec![msg],
+        data: data.to_json_value().unwrap(),
+        credential: credential.to_json_value().unwrap(),
+    });
+
+    let gas_used = Uint128::new(outcome.should_succeed().gas_used as u128);
+    let fee = gas_used.checked_mul_dec_ceil(gas_price).unwrap();
+    let tip = fee
+        .checked_sub(gas_used.checked_mul_dec_ceil(OLD_FEE_RATE).unwrap())
+        .unwrap();
+
+    suite
+        .query_balance(&accounts.relayer, USDC.clone())
+        .should_succeed_and_equal(relayer_usdc_balance.checked_sub(fee).unwrap());
+
+    // The tip is credited to the tip recipient at the beginning of the next
+    // block, when the taxman settles the fees.
+    suite
+        .query_balance(&tip_recipient, USDC.clone())
+        .should_succeed_and_equal(Uint128::ZERO);
+
+    suite.make_empty_block();
+
+    suite
+        .query_balance(&tip_recipient, USDC.clone())
+        .should_succeed_and_equal(tip);
+}
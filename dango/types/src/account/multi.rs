@@ -1,7 +1,7 @@
 use {
-    crate::account_factory::Username,
+    crate::{account_factory::Username, auth::Grant},
     anyhow::anyhow,
-    grug::{ChangeSet, Duration, Inner, Message, NonZero, Timestamp},
+    grug::{Addr, ChangeSet, Duration, EventName, Inner, Message, NonZero, Timestamp},
     std::collections::BTreeMap,
 };
 
@@ -127,6 +127,41 @@ pub enum Vote {
     No,
 }
 
+/// Emitted when a member creates a proposal.
+#[grug::derive(Serde)]
+pub struct ProposalCreated {
+    pub proposal_id: ProposalId,
+    pub title: String,
+}
+
+impl EventName for ProposalCreated {
+    const EVENT_NAME: &'static str = "proposal_created";
+}
+
+/// Emitted when a member votes on a proposal.
+#[grug::derive(Serde)]
+pub struct ProposalVoted {
+    pub proposal_id: ProposalId,
+    pub voter: Username,
+    pub vote: Vote,
+    /// The proposal's status after the vote is counted.
+    pub status: Status,
+}
+
+impl EventName for ProposalVoted {
+    const EVENT_NAME: &'static str = "proposal_voted";
+}
+
+/// Emitted when a proposal's messages are executed.
+#[grug::derive(Serde)]
+pub struct ProposalExecuted {
+    pub proposal_id: ProposalId,
+}
+
+impl EventName for ProposalExecuted {
+    const EVENT_NAME: &'static str = "proposal_executed";
+}
+
 #[grug::derive(Serde)]
 pub enum ExecuteMsg {
     /// Create a new proposal with the given title, descriptions, and messages.
@@ -149,6 +184,15 @@ pub enum ExecuteMsg {
     /// Execute a proposal once it's passed and the timelock (if there is one)
     /// has elapsed.
     Execute { proposal_id: ProposalId },
+    /// Authorize a grantee to send transactions on behalf of this Safe.
+    /// Replaces the grantee's existing grant, if any.
+    ///
+    /// Can only be done through a proposal.
+    Grant { grantee: Addr, grant: Grant },
+    /// Revoke the grant given to a grantee.
+    ///
+    /// Can only be done through a proposal.
+    Revoke { grantee: Addr },
 }
 
 // Note: we don't provide a method for querying the Safe's config. Query the
@@ -176,4 +220,13 @@ pub enum QueryMsg {
     /// Enumerate all votes in a proposal.
     #[returns(BTreeMap<Username, Vote>)]
     Votes { proposal_id: ProposalId },
+    /// Query the grant given to a grantee.
+    #[returns(Option<Grant>)]
+    Grant { grantee: Addr },
+    /// Enumerate all grants given by this Safe.
+    #[returns(BTreeMap<Addr, Grant>)]
+    Grants {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
 }
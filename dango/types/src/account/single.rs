@@ -1,4 +1,8 @@
-use crate::account_factory::Username;
+use {
+    crate::{account_factory::Username, auth::Grant},
+    grug::Addr,
+    std::collections::BTreeMap,
+};
 
 /// Parameters of a single-signature account.
 #[grug::derive(Serde, Borsh)]
@@ -10,9 +14,27 @@ pub struct Params {
     pub owner: Username,
 }
 
+#[grug::derive(Serde)]
+pub enum ExecuteMsg {
+    /// Authorize a grantee to send transactions on behalf of this account.
+    /// Replaces the grantee's existing grant, if any.
+    Grant { grantee: Addr, grant: Grant },
+    /// Revoke the grant given to a grantee.
+    Revoke { grantee: Addr },
+}
+
 #[grug::derive(Serde, QueryRequest)]
 pub enum QueryMsg {
     /// Query the account's current sequence number.
     #[returns(u32)]
     Sequence {},
+    /// Query the grant given to a grantee.
+    #[returns(Option<Grant>)]
+    Grant { grantee: Addr },
+    /// Enumerate all grants given by this account.
+    #[returns(BTreeMap<Addr, Grant>)]
+    Grants {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
 }
@@ -2,7 +2,7 @@ use {
     crate::{
         account::multi::ParamUpdates,
         account_factory::{Account, AccountIndex, AccountParams, AccountType, Username},
-        auth::Key,
+        auth::{Key, Session},
     },
     grug::{Addr, Coins, Hash160, Hash256},
     std::collections::{BTreeMap, BTreeSet},
@@ -49,6 +49,16 @@ pub enum ExecuteMsg {
     RegisterAccount { params: AccountParams },
     /// Update a Safe account's parameters.
     ConfigureSafe { updates: ParamUpdates },
+    /// Register a session key for the owner of the sender account.
+    ///
+    /// Only spot and margin accounts can register session keys.
+    RegisterSessionKey {
+        key: Key,
+        key_hash: Hash160,
+        session: Session,
+    },
+    /// Revoke a session key of the owner of the sender account.
+    RevokeSessionKey { key_hash: Hash160 },
 }
 
 #[grug::derive(Serde, QueryRequest)]
@@ -105,4 +115,14 @@ pub enum QueryMsg {
     /// Given a key hash, look up the usernames associated with it.
     #[returns(BTreeSet<Username>)]
     UsersByKey { hash: Hash160 },
+    /// Query the scope of a user's session key.
+    /// Returns `None` if the key isn't a session key of the user.
+    #[returns(Option<Session>)]
+    SessionKey {
+        username: Username,
+        key_hash: Hash160,
+    },
+    /// Find all session keys associated with a user.
+    #[returns(BTreeMap<Hash160, Session>)]
+    SessionKeysByUser { username: Username },
 }
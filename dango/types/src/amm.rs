@@ -1,9 +1,10 @@
 mod config;
+mod event;
 mod msg;
 mod namespace;
 mod pool;
 
-pub use {config::*, msg::*, namespace::*, pool::*};
+pub use {config::*, event::*, msg::*, namespace::*, pool::*};
 
 use grug::Uint128;
 
@@ -0,0 +1,20 @@
+use {
+    crate::amm::PoolId,
+    grug::{Addr, Coin, Coins, EventName, UniqueVec},
+};
+
+/// Emitted when a trader swaps coins through the AMM.
+#[grug::derive(Serde)]
+pub struct Swapped {
+    pub trader: Addr,
+    pub route: UniqueVec<PoolId>,
+    pub input: Coin,
+    /// The post-fee output returned to the trader.
+    pub output: Coin,
+    pub protocol_fee: Coin,
+    pub liquidity_fees: Coins,
+}
+
+impl EventName for Swapped {
+    const EVENT_NAME: &'static str = "swapped";
+}
@@ -1,6 +1,6 @@
 use {
     crate::amm::{Config, Pool, PoolId, PoolParams},
-    grug::{Coin, Coins, Uint128, UniqueVec},
+    grug::{Coin, Coins, Denom, Uint128, UniqueVec},
     std::collections::BTreeMap,
 };
 
@@ -20,6 +20,17 @@ pub enum ExecuteMsg {
         route: UniqueVec<PoolId>,
         minimum_output: Option<Uint128>,
     },
+    /// Perform a swap that yields exactly the given output.
+    ///
+    /// The trader should send more than enough of the input coin; the unused
+    /// portion is refunded.
+    SwapExactOutput {
+        // Note: the route must not contain any loop. We make sure of this by
+        // using a `UniqueVec`.
+        route: UniqueVec<PoolId>,
+        output: Coin,
+        maximum_input: Option<Uint128>,
+    },
     /// Provide liquidity to a trading pool.
     ProvideLiquidity {
         pool_id: PoolId,
@@ -49,11 +60,27 @@ pub enum QueryMsg {
         input: Coin,
         route: UniqueVec<PoolId>,
     },
+    /// Simulate the input needed for a swap to yield exactly the given output.
+    #[returns(SwapOutcome)]
+    SimulateExactOutput {
+        output: Coin,
+        route: UniqueVec<PoolId>,
+    },
+    /// Find the swap route through at most `max_hops` pools that yields the
+    /// most output, and simulate its outcome.
+    #[returns(RouteOutcome)]
+    BestRoute {
+        input: Coin,
+        target_denom: Denom,
+        max_hops: u32,
+    },
 }
 
 /// The outcome of performing a swap.
 #[grug::derive(Serde)]
 pub struct SwapOutcome {
+    /// The amount of coin taken from the trader.
+    pub input: Coin,
     /// The amount of coin to be returned to the trader.
     pub output: Coin,
     /// The amount of fee paid to the protocol's token stakers.
@@ -61,3 +88,11 @@ pub struct SwapOutcome {
     /// The amount of fee paid to liquidity providers.
     pub liquidity_fees: Coins,
 }
+
+/// A swap route found by the `BestRoute` query, and the outcome of swapping
+/// through it.
+#[grug::derive(Serde)]
+pub struct RouteOutcome {
+    pub route: UniqueVec<PoolId>,
+    pub outcome: SwapOutcome,
+}
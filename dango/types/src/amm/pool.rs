@@ -1,6 +1,6 @@
 use {
     crate::amm::FeeRate,
-    grug::{CoinPair, Denom, Uint128},
+    grug::{CoinPair, Denom, NonZero, Udec128, Uint128},
 };
 
 /// Identifier of an AMM pool.
@@ -26,7 +26,20 @@ pub struct XykParams {
 /// Parameter of a concentracted liquidity AMM pool (a.k.a. Curve V2 pool).
 #[grug::derive(Serde, Borsh)]
 pub struct ConcentratedParams {
-    // TODO
+    /// The amplification coefficient, which determines how concentrated the
+    /// liquidity is around the pool's price scale.
+    ///
+    /// The higher the amplification, the closer the pool behaves to a constant
+    /// sum AMM near the price scale. As the amplification approaches zero, the
+    /// pool behaves like a constant product AMM.
+    pub amplification: NonZero<u64>,
+    /// Percentage of swap output that is charged as liquidity fee, paid to
+    /// liquidity providers of the pool.
+    ///
+    /// Half of this rate is also charged on the imbalanced portion of a
+    /// liquidity provision, so that imbalanced provision followed by a
+    /// withdrawal can't be used as a fee-free swap.
+    pub liquidity_fee_rate: FeeRate,
 }
 
 // -------------------------------- pool state ---------------------------------
@@ -69,5 +82,11 @@ pub struct ConcentratedPool {
     pub liquidity: CoinPair,
     /// The total amount of liquidity shares outstanding.
     pub shares: Uint128,
-    // TODO: other params...
+    /// The price of the second asset in units of the first asset, around which
+    /// the liquidity is concentrated.
+    ///
+    /// This is determined by the ratio of the initial liquidity at the pool's
+    /// creation. Unlike in Curve V2, it doesn't repeg, so this pool type is
+    /// intended for pairs with a stable exchange rate, such as stablecoins.
+    pub price_scale: Udec128,
 }
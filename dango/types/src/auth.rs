@@ -1,6 +1,9 @@
 use {
     crate::account_factory::Username,
-    grug::{Addr, Binary, ByteArray, Hash160, Message},
+    grug::{
+        Addr, Binary, ByteArray, Coins, EventName, Hash160, Message, MsgExecute, Timestamp, Udec128,
+    },
+    std::collections::BTreeSet,
 };
 
 /// A public key that can be associated with a [`Username`](crate::auth::Username).
@@ -11,6 +14,35 @@ pub enum Key {
     Secp256r1(ByteArray<33>),
     /// An Secp256k1 public key in compressed form.
     Secp256k1(ByteArray<33>),
+    /// An Ed25519 public key.
+    Ed25519(ByteArray<32>),
+}
+
+/// Scope and validity of a session key.
+///
+/// A session key is a short-lived key that a user registers with the account
+/// factory, so that they can sign transactions without going through their
+/// primary key (typically a Passkey) each time.
+#[grug::derive(Serde, Borsh)]
+pub struct Session {
+    /// The time after which the session key can no longer be used.
+    pub expiry: Timestamp,
+    /// The maximum amount of tokens, per denom, that can be spent with the
+    /// session key, either as transfers or as funds attached to contract
+    /// executions. Tokens of denoms not included here can't be spent at all.
+    ///
+    /// Spending is tracked by each account separately.
+    pub max_spend: Coins,
+    /// The contracts that transactions signed by the session key may transfer
+    /// tokens to or execute.
+    pub allowed_contracts: BTreeSet<Addr>,
+}
+
+impl Session {
+    /// Return whether the session key has expired as of the given time.
+    pub fn is_expired(&self, current_time: Timestamp) -> bool {
+        current_time > self.expiry
+    }
 }
 
 /// Data that the account expects for the transaction's [`credential`](grug::Tx::credential)
@@ -21,6 +53,11 @@ pub enum Credential {
     Passkey(PasskeySignature),
     /// An Secp256k1 signature.
     Secp256k1(ByteArray<64>),
+    /// An Ed25519 signature, as produced by Solana-style wallets.
+    ///
+    /// Unlike Secp256k1 signatures, this is a signature over the SHA-256 hash
+    /// of the sign doc as a _raw message_, without Ed25519's own prehashing.
+    Ed25519(ByteArray<64>),
     /// An EIP712 signature signed by a compatible eth wallet.
     Eip712(Eip712Signature),
 }
@@ -28,13 +65,17 @@ pub enum Credential {
 /// Data that a transaction's sender must sign with their private key.
 ///
 /// This includes the messages to be included in the transaction, as well as
-/// chain ID, sender and account sequence number for replay protection.
+/// chain ID, sender and account sequence number for replay protection, the
+/// gas price the sender is willing to pay, if any, and the grantee account the
+/// signer acts through, if the transaction is sent under a grant.
 #[grug::derive(Serde)]
 pub struct SignDoc {
     pub sender: Addr,
     pub messages: Vec<Message>,
     pub chain_id: String,
     pub sequence: u32,
+    pub gas_price: Option<Udec128>,
+    pub grantee: Option<Addr>,
 }
 
 /// Data that the account expects for the transaction's [`data`](grug::Tx::data)
@@ -47,6 +88,18 @@ pub struct Metadata {
     pub key_hash: Hash160,
     /// The sequence number this transaction was signed with.
     pub sequence: u32,
+    /// The price per unit of gas the sender is willing to pay.
+    ///
+    /// Must be no less than the taxman's current base fee. The amount above
+    /// the base fee is paid as a tip. If not provided, the base fee is paid
+    /// without a tip.
+    pub gas_price: Option<Udec128>,
+    /// If the transaction is sent under an authorization [`Grant`], the
+    /// grantee account through which the signer is acting.
+    ///
+    /// In this case, `username` and `key_hash` identify the owner of the
+    /// grantee account and their key, instead of the owner of the sender.
+    pub grantee: Option<Addr>,
 }
 
 /// An Secp256r1 signature generated by a Passkey via Webauthn, along with
@@ -67,6 +120,84 @@ pub struct Eip712Signature {
     pub sig: ByteArray<64>,
 }
 
+/// A type of message that a grantee may send on behalf of the granter.
+#[grug::derive(Serde, Borsh)]
+pub enum Authorization {
+    /// Transfer tokens to any recipient.
+    Transfer,
+    /// Execute the given contract.
+    Execute { contract: Addr },
+}
+
+impl Authorization {
+    /// Return whether the given message is permitted by this authorization.
+    pub fn permits(&self, msg: &Message) -> bool {
+        match (self, msg) {
+            (Authorization::Transfer, Message::Transfer(_)) => true,
+            (
+                Authorization::Execute { contract },
+                Message::Execute(MsgExecute {
+                    contract: target, ..
+                }),
+            ) => contract == target,
+            _ => false,
+        }
+    }
+}
+
+/// Permission given by an account (the granter) to another address (the
+/// grantee) to send transactions on its behalf.
+#[grug::derive(Serde, Borsh)]
+pub struct Grant {
+    /// The messages the grantee is allowed to send. A message is permitted if
+    /// it matches any one of the authorizations.
+    pub authorizations: Vec<Authorization>,
+    /// The maximum amount of tokens the grantee may spend, either as transfers
+    /// or as funds attached to contract executions.
+    ///
+    /// This is reduced as tokens are spent. Tokens of denoms not included here
+    /// can't be spent at all. If not provided, there is no limit.
+    pub spend_limit: Option<Coins>,
+    /// The time after which the grant can no longer be used.
+    /// If not provided, the grant doesn't expire.
+    pub expiration: Option<Timestamp>,
+}
+
+impl Grant {
+    /// Return whether the grant has expired as of the given time.
+    pub fn is_expired(&self, current_time: Timestamp) -> bool {
+        self.expiration
+            .is_some_and(|expiration| current_time > expiration)
+    }
+
+    /// Return whether the given message is permitted by any of the
+    /// authorizations in this grant.
+    pub fn permits(&self, msg: &Message) -> bool {
+        self.authorizations.iter().any(|auth| auth.permits(msg))
+    }
+}
+
+/// Emitted when an account authorizes a grantee.
+#[grug::derive(Serde)]
+pub struct GrantCreated {
+    pub grantee: Addr,
+    pub grant: Grant,
+}
+
+impl EventName for GrantCreated {
+    const EVENT_NAME: &'static str = "grant_created";
+}
+
+/// Emitted when an account revokes the grant given to a grantee.
+#[grug::derive(Serde)]
+pub struct GrantRevoked {
+    pub grantee: Addr,
+}
+
+impl EventName for GrantRevoked {
+    const EVENT_NAME: &'static str = "grant_revoked";
+}
+
 /// Passkey client data.
 #[grug::derive(Serde)]
 pub struct ClientData {
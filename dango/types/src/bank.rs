@@ -42,7 +42,6 @@ pub enum ExecuteMsg {
     },
     /// Forcily transfer a coin from an account to a receiver.
     /// Can only be called by the chain's taxman contract.
-    /// Used by taxman to withhold pending transaction fees.
     ///
     /// Note: The `receive` method isn't invoked when calling this.
     ForceTransfer {
@@ -51,6 +50,33 @@ pub enum ExecuteMsg {
         denom: Denom,
         amount: Uint128,
     },
+    /// Deduct a coin from an account, to be credited to a receiver when the
+    /// withheld coins are settled. Until then, the coin is in no one's balance.
+    /// Can only be called by the chain's taxman contract.
+    ///
+    /// Used by taxman to withhold transaction fees. Crediting the fees once
+    /// per block, instead of in every transaction, means transactions of
+    /// different senders don't write to the same balance, so they don't
+    /// conflict with each other when executed in parallel.
+    ///
+    /// Note: The `receive` method isn't invoked when calling this.
+    Withhold {
+        from: Addr,
+        to: Addr,
+        denom: Denom,
+        amount: Uint128,
+    },
+    /// Return a coin withheld from an account for a receiver to the account.
+    /// Can only be called by the chain's taxman contract.
+    Release {
+        from: Addr,
+        to: Addr,
+        denom: Denom,
+        amount: Uint128,
+    },
+    /// Credit all withheld coins to their receivers.
+    /// Can only be called by the chain's taxman contract.
+    Settle {},
 }
 
 #[grug::derive(Serde, QueryRequest)]
@@ -1,5 +1,7 @@
 use {
-    grug::{Addr, Coins, Denom, Part},
+    grug::{
+        Addr, Bound, Bounded, Bounds, Coins, Denom, NumberConst, Part, Timestamp, Udec128, Uint128,
+    },
     std::{collections::BTreeMap, sync::LazyLock},
 };
 
@@ -10,16 +12,70 @@ pub static NAMESPACE: LazyLock<Part> = LazyLock::new(|| Part::new_unchecked("len
 /// Sub-namespace that liquidity share tokens will be minted under.
 pub static SUBNAMESPACE: LazyLock<Part> = LazyLock::new(|| Part::new_unchecked("pool"));
 
+/// Defines the bounds for the optimal utilization: 0 < OptimalUtilization < 1.
+#[grug::derive(Serde)]
+pub struct OptimalUtilizationBounds;
+
+impl Bounds<Udec128> for OptimalUtilizationBounds {
+    const MAX: Option<Bound<Udec128>> = Some(Bound::Exclusive(Udec128::ONE));
+    const MIN: Option<Bound<Udec128>> = Some(Bound::Exclusive(Udec128::ZERO));
+}
+
+/// A decimal bounded by the optimal utilization bounds.
+pub type OptimalUtilization = Bounded<Udec128, OptimalUtilizationBounds>;
+
+/// Determines a market's borrow rate as a function of its utilization, that is,
+/// the portion of the supplied liquidity that is borrowed.
+///
+/// The rate increases linearly with utilization, with a steeper slope once the
+/// utilization exceeds the optimal utilization (the "kink"):
+///
+/// ```plain
+/// if utilization <= optimal_utilization:
+///   rate = base_rate + first_slope * utilization / optimal_utilization
+/// else:
+///   rate = base_rate + first_slope + second_slope * (utilization - optimal_utilization) / (1 - optimal_utilization)
+/// ```
+///
+/// All rates are annualized.
+#[grug::derive(Serde, Borsh)]
+pub struct InterestRateModel {
+    /// The borrow rate when utilization is zero.
+    pub base_rate: Udec128,
+    /// The utilization at which the slope of the borrow rate changes.
+    pub optimal_utilization: OptimalUtilization,
+    /// Increase of the borrow rate as utilization goes from zero to optimal.
+    pub first_slope: Udec128,
+    /// Increase of the borrow rate as utilization goes from optimal to 100%.
+    pub second_slope: Udec128,
+}
+
 /// Configurations and state of a market.
 #[grug::derive(Serde, Borsh)]
 pub struct Market {
-    // TODO
+    /// The model that determines the market's borrow rate.
+    pub interest_rate_model: InterestRateModel,
+    /// The amount of the underlying asset owed to depositors, that is, the
+    /// total deposits plus accrued interest minus withdrawals.
+    pub total_supplied: Uint128,
+    /// The amount of LP tokens outstanding.
+    pub total_lp_supply: Uint128,
+    /// The amount of the underlying asset borrowed, scaled down by the borrow
+    /// index. Multiply by the borrow index to get the amount including the
+    /// accrued interest.
+    pub total_borrowed_scaled: Uint128,
+    /// The cumulative interest multiplier applied to borrowed amounts since the
+    /// market's creation. Starts at one.
+    pub borrow_index: Udec128,
+    /// The last time interest was accrued.
+    pub last_update_time: Timestamp,
 }
 
 /// A set of updates to be applied to a market.
 #[grug::derive(Serde)]
 pub struct MarketUpdates {
-    // TODO
+    /// A new interest rate model. Mandatory when creating a new market.
+    pub interest_rate_model: Option<InterestRateModel>,
 }
 
 #[grug::derive(Serde)]
@@ -40,6 +96,10 @@ pub enum ExecuteMsg {
     /// Borrow coins from the lending pool.
     /// Sender must be a margin account.
     Borrow(Coins),
+    /// Repay debts to the lending pool.
+    /// Sender must attach the coins to be repaid. Any amount in excess of the
+    /// debt is refunded.
+    Repay {},
 }
 
 #[grug::derive(Serde, QueryRequest)]
@@ -53,10 +113,10 @@ pub enum QueryMsg {
         start_after: Option<Denom>,
         limit: Option<u32>,
     },
-    /// Query the debt of a single margin account.
+    /// Query the debt of a single margin account, including accrued interest.
     #[returns(Coins)]
     Debt { account: Addr },
-    /// Enumerate debts of all margin accounts.
+    /// Enumerate debts of all margin accounts, including accrued interest.
     #[returns(BTreeMap<Addr, Coins>)]
     Debts {
         start_after: Option<Addr>,
@@ -3,7 +3,30 @@ use grug::{Addr, Denom, Udec128};
 #[grug::derive(Serde, Borsh)]
 pub struct Config {
     pub fee_denom: Denom,
+    /// The minimum gas price.
+    ///
+    /// If the fee market is enabled, the base fee can't go below this value.
+    /// Otherwise, this is the fixed price charged per unit of gas.
     pub fee_rate: Udec128,
+    /// Parameters of the fee market. `None` to always charge `fee_rate`.
+    pub fee_market: Option<FeeMarket>,
+}
+
+/// Parameters of an EIP-1559-style fee market.
+///
+/// At the beginning of each block, the base fee is raised or lowered depending
+/// on whether the previous block used more or less gas than the target.
+#[grug::derive(Serde, Borsh)]
+pub struct FeeMarket {
+    /// The amount of gas a block is expected to use. Must be non-zero.
+    pub target_gas_per_block: u64,
+    /// The maximum relative change of the base fee from one block to the next,
+    /// reached when the previous block used zero gas, or twice the target or
+    /// more. Must be no greater than 1.
+    pub max_change_rate: Udec128,
+    /// The account that receives the tips, i.e. the part of the fee that is
+    /// paid on top of the base fee.
+    pub tip_recipient: Addr,
 }
 
 #[grug::derive(Serde)]
@@ -25,4 +48,8 @@ pub enum QueryMsg {
     /// Query the fee configurations.
     #[returns(Config)]
     Config {},
+    /// Query the current base fee, i.e. the minimum gas price a transaction
+    /// must offer in order to be accepted.
+    #[returns(Udec128)]
+    BaseFee {},
 }
@@ -1,19 +1,25 @@
 use {
-    crate::{App, AppError, Db, NaiveProposalPreparer, NaiveQuerier, ProposalPreparer, Vm},
+    crate::{
+        App, AppError, Indexer, NaiveProposalPreparer, NaiveQuerier, ProposalPreparer, Snapshot,
+        SnapshotChunkOutcome, SnapshotDb, Vm,
+    },
     grug_math::Inner,
     grug_types::{
-        Attribute, BlockInfo, Duration, Event, GenericResult, Hash256, Outcome, QuerierWrapper,
-        Timestamp, TxOutcome, GENESIS_BLOCK_HASH,
+        BlockInfo, Duration, Event, FlatEvent, GenericResult, Hash256, Json, Outcome,
+        QuerierWrapper, Timestamp, TxOutcome, GENESIS_BLOCK_HASH,
     },
     prost::bytes::Bytes,
     std::{any::type_name, net::ToSocketAddrs},
     tendermint_abci::{Application, Error as ABCIError, ServerBuilder},
     tendermint_proto::{
         abci::{
-            Event as TmEvent, EventAttribute as TmAttribute, ExecTxResult, RequestCheckTx,
-            RequestFinalizeBlock, RequestInfo, RequestInitChain, RequestPrepareProposal,
-            RequestQuery, ResponseCheckTx, ResponseCommit, ResponseFinalizeBlock, ResponseInfo,
-            ResponseInitChain, ResponsePrepareProposal, ResponseQuery,
+            response_apply_snapshot_chunk, response_offer_snapshot, Event as TmEvent,
+            EventAttribute as TmAttribute, ExecTxResult, RequestApplySnapshotChunk, RequestCheckTx,
+            RequestFinalizeBlock, RequestInfo, RequestInitChain, RequestLoadSnapshotChunk,
+            RequestOfferSnapshot, RequestPrepareProposal, RequestQuery, ResponseApplySnapshotChunk,
+            ResponseCheckTx, ResponseCommit, ResponseFinalizeBlock, ResponseInfo,
+            ResponseInitChain, ResponseListSnapshots, ResponseLoadSnapshotChunk,
+            ResponseOfferSnapshot, ResponsePrepareProposal, ResponseQuery, Snapshot as TmSnapshot,
         },
         crypto::{ProofOp, ProofOps},
         google::protobuf::Timestamp as TmTimestamp,
@@ -21,12 +27,13 @@ use {
     tracing::error,
 };
 
-impl<DB, VM, PP> App<DB, VM, PP>
+impl<DB, VM, PP, ID> App<DB, VM, PP, ID>
 where
-    DB: Db + Clone + Send + 'static,
+    DB: SnapshotDb + Clone + Send + 'static,
     VM: Vm + Clone + Send + 'static,
     PP: ProposalPreparer + Clone + Send + 'static,
-    AppError: From<DB::Error> + From<VM::Error> + From<PP::Error>,
+    ID: Indexer + Clone + Send + 'static,
+    AppError: From<DB::Error> + From<VM::Error> + From<PP::Error> + From<ID::Error>,
 {
     pub fn start_abci_server<A>(self, read_buf_size: usize, addr: A) -> Result<(), ABCIError>
     where
@@ -36,12 +43,13 @@ where
     }
 }
 
-impl<DB, VM, PP> Application for App<DB, VM, PP>
+impl<DB, VM, PP, ID> Application for App<DB, VM, PP, ID>
 where
-    DB: Db + Clone + Send + 'static,
+    DB: SnapshotDb + Clone + Send + 'static,
     VM: Vm + Clone + Send + 'static,
     PP: ProposalPreparer + Clone + Send + 'static,
-    AppError: From<DB::Error> + From<VM::Error> + From<PP::Error>,
+    ID: Indexer + Clone + Send + 'static,
+    AppError: From<DB::Error> + From<VM::Error> + From<PP::Error> + From<ID::Error>,
 {
     fn info(&self, _req: RequestInfo) -> ResponseInfo {
         match self.do_info() {
@@ -245,6 +253,84 @@ where
             },
         }
     }
+
+    fn list_snapshots(&self) -> ResponseListSnapshots {
+        match self.do_list_snapshots() {
+            Ok(snapshots) => ResponseListSnapshots {
+                snapshots: snapshots.into_iter().map(into_tm_snapshot).collect(),
+            },
+            Err(err) => panic!("failed to list snapshots: {err}"),
+        }
+    }
+
+    fn offer_snapshot(&self, req: RequestOfferSnapshot) -> ResponseOfferSnapshot {
+        let (Some(snapshot), Ok(app_hash)) = (
+            req.snapshot.and_then(from_tm_snapshot),
+            req.app_hash.as_ref().try_into(),
+        ) else {
+            return ResponseOfferSnapshot {
+                result: response_offer_snapshot::Result::Reject as i32,
+            };
+        };
+
+        // Rejecting the snapshot makes CometBFT try the next one (if any),
+        // so we don't panic here.
+        let result = match self.do_offer_snapshot(snapshot, app_hash) {
+            Ok(()) => response_offer_snapshot::Result::Accept,
+            Err(err) => {
+                #[cfg(feature = "tracing")]
+                error!(err = err.to_string(), "Failed to offer snapshot!");
+
+                response_offer_snapshot::Result::Reject
+            },
+        };
+
+        ResponseOfferSnapshot {
+            result: result as i32,
+        }
+    }
+
+    fn load_snapshot_chunk(&self, req: RequestLoadSnapshotChunk) -> ResponseLoadSnapshotChunk {
+        match self.do_load_snapshot_chunk(req.height, req.format, req.chunk) {
+            Ok(chunk) => ResponseLoadSnapshotChunk {
+                // An empty chunk indicates to the requesting node that we don't
+                // have the chunk.
+                chunk: chunk.unwrap_or_default().into(),
+            },
+            Err(err) => panic!("failed to load snapshot chunk: {err}"),
+        }
+    }
+
+    fn apply_snapshot_chunk(&self, req: RequestApplySnapshotChunk) -> ResponseApplySnapshotChunk {
+        match self.do_apply_snapshot_chunk(req.index, req.chunk.to_vec()) {
+            Ok(SnapshotChunkOutcome::Applied | SnapshotChunkOutcome::Restored) => {
+                ResponseApplySnapshotChunk {
+                    result: response_apply_snapshot_chunk::Result::Accept as i32,
+                    ..Default::default()
+                }
+            },
+            // The chunk doesn't match the snapshot, but the snapshot itself may
+            // still be good. Fetch the chunk again, and stop trusting the node
+            // that sent it.
+            Ok(SnapshotChunkOutcome::Corrupted) => ResponseApplySnapshotChunk {
+                result: response_apply_snapshot_chunk::Result::Retry as i32,
+                refetch_chunks: vec![req.index],
+                reject_senders: vec![req.sender],
+            },
+            // The snapshot can't be restored, e.g. the restored state doesn't
+            // match the expected app hash, so it can't be trusted. Reject it
+            // and let CometBFT try the next one.
+            Err(err) => {
+                #[cfg(feature = "tracing")]
+                error!(err = err.to_string(), "Failed to apply snapshot chunk!");
+
+                ResponseApplySnapshotChunk {
+                    result: response_apply_snapshot_chunk::Result::RejectSnapshot as i32,
+                    ..Default::default()
+                }
+            },
+        }
+    }
 }
 
 fn from_tm_block(height: i64, time: Option<TmTimestamp>, hash: Option<Bytes>) -> BlockInfo {
@@ -266,6 +352,26 @@ fn from_tm_hash(bytes: Bytes) -> Hash256 {
         .expect("incorrect block hash length")
 }
 
+fn from_tm_snapshot(snapshot: TmSnapshot) -> Option<Snapshot> {
+    Some(Snapshot {
+        version: snapshot.height,
+        format: snapshot.format,
+        chunks: snapshot.chunks,
+        hash: snapshot.hash.as_ref().try_into().ok()?,
+        metadata: snapshot.metadata.to_vec(),
+    })
+}
+
+fn into_tm_snapshot(snapshot: Snapshot) -> TmSnapshot {
+    TmSnapshot {
+        height: snapshot.version,
+        format: snapshot.format,
+        chunks: snapshot.chunks,
+        hash: snapshot.hash.into_inner().to_vec().into(),
+        metadata: snapshot.metadata.into(),
+    }
+}
+
 fn into_tm_tx_result(outcome: TxOutcome) -> ExecTxResult {
     match outcome.result {
         GenericResult::Ok(_) => ExecTxResult {
@@ -291,27 +397,56 @@ fn into_tm_events<I>(events: I) -> Vec<TmEvent>
 where
     I: IntoIterator<Item = Event>,
 {
-    events.into_iter().map(into_tm_event).collect()
+    events
+        .into_iter()
+        .flat_map(|event| event.flatten())
+        .map(into_tm_event)
+        .collect()
 }
 
-fn into_tm_event(event: Event) -> TmEvent {
+// CometBFT events are flat lists of string key-value pairs. The event's data,
+// which is a JSON object for host events and usually for contract events too,
+// is turned into one attribute per field. String values are used as is, while
+// other values are JSON-encoded. Contracts can't emit fields under the
+// `category` and `contract` keys; see `RESERVED_EVENT_KEYS`.
+fn into_tm_event(event: FlatEvent) -> TmEvent {
+    let mut attributes = vec![into_tm_attribute("category", event.category.to_string())];
+
+    if let Some(contract) = event.contract {
+        attributes.push(into_tm_attribute("contract", contract.to_string()));
+    }
+
+    match event.data {
+        Json::Object(fields) => {
+            for (key, value) in fields {
+                attributes.push(into_tm_attribute(key, into_tm_value(value)));
+            }
+        },
+        data => {
+            attributes.push(into_tm_attribute("data", into_tm_value(data)));
+        },
+    }
+
     TmEvent {
-        r#type: event.r#type,
-        attributes: into_tm_attributes(event.attributes),
+        r#type: event.ty,
+        attributes,
     }
 }
 
-fn into_tm_attributes<I>(attrs: I) -> Vec<TmAttribute>
-where
-    I: IntoIterator<Item = Attribute>,
-{
-    attrs.into_iter().map(into_tm_attribute).collect()
+fn into_tm_value(value: Json) -> String {
+    match value {
+        Json::String(string) => string,
+        value => value.to_string(),
+    }
 }
 
-fn into_tm_attribute(attr: Attribute) -> TmAttribute {
+fn into_tm_attribute<K>(key: K, value: String) -> TmAttribute
+where
+    K: ToString,
+{
     TmAttribute {
-        key: attr.key,
-        value: attr.value,
+        key: key.to_string(),
+        value,
         index: true,
     }
 }
@@ -1,23 +1,28 @@
 #[cfg(feature = "abci")]
-use grug_types::{JsonDeExt, JsonSerExt};
+use grug_types::{HashExt, JsonDeExt, JsonSerExt};
 use {
     crate::{
-        do_authenticate, do_backrun, do_configure, do_cron_execute, do_execute, do_finalize_fee,
-        do_instantiate, do_migrate, do_transfer, do_upload, do_withhold_fee, query_app_config,
-        query_app_configs, query_balance, query_balances, query_code, query_codes, query_config,
-        query_contract, query_contracts, query_supplies, query_supply, query_wasm_raw,
-        query_wasm_scan, query_wasm_smart, AppCtx, AppError, AppResult, Buffer, Db, GasTracker,
-        NaiveProposalPreparer, ProposalPreparer, QuerierProvider, Shared, Vm, APP_CONFIGS,
-        CHAIN_ID, CODES, CONFIG, LAST_FINALIZED_BLOCK, NEXT_CRONJOBS,
+        apply_storage_usage_changes, do_authenticate, do_backrun, do_clear_admin, do_configure,
+        do_cron_execute, do_execute, do_finalize_fee, do_instantiate, do_migrate, do_transfer,
+        do_update_admin, do_upload, do_withhold_fee, query_app_config, query_app_configs,
+        query_balance, query_balances, query_code, query_codes, query_config, query_contract,
+        query_contracts, query_gas_costs, query_max_block_gas, query_state_deposit,
+        query_state_deposit_params, query_supplies, query_supply, query_wasm_raw, query_wasm_scan,
+        query_wasm_smart, AppCtx, AppError, AppResult, Buffer, CallTracer, Db, GasTracker, Indexer,
+        NaiveProposalPreparer, NullIndexer, ProposalPreparer, QuerierProvider, Shared, Snapshot,
+        SnapshotChunkOutcome, SnapshotDb, Tracked, Vm, APP_CONFIGS, CHAIN_ID, CODES, CONFIG,
+        GAS_COSTS, LAST_FINALIZED_BLOCK, MAX_BLOCK_GAS, NEXT_CRONJOBS, STATE_DEPOSIT_PARAMS,
+        STORAGE_USAGES, STORAGE_USAGE_CHANGES, VM_CONFIG_HASH,
     },
     grug_storage::PrefixBound,
     grug_types::{
         Addr, AuthMode, BlockInfo, BlockOutcome, BorshSerExt, CodeStatus, Duration, Event,
-        GenericResultExt, GenesisState, Hash256, Json, Message, Order, Outcome, Permission,
-        QuerierWrapper, Query, QueryResponse, StdResult, Storage, Timestamp, Tx, TxOutcome,
-        UnsignedTx, GENESIS_SENDER,
+        GasCosts, GenericResultExt, GenesisState, Hash256, Json, Message, Order, Outcome,
+        Permission, QuerierWrapper, Query, QueryResponse, StdResult, Storage, Timestamp, Tx,
+        TxOutcome, UnsignedTx, GENESIS_SENDER,
     },
     prost::bytes::Bytes,
+    std::{collections::BTreeSet, thread},
 };
 
 /// The ABCI application.
@@ -25,10 +30,11 @@ use {
 /// Must be clonable which is required by `tendermint-abci` library:
 /// <https://github.com/informalsystems/tendermint-rs/blob/v0.34.0/abci/src/application.rs#L22-L25>
 #[derive(Clone)]
-pub struct App<DB, VM, PP = NaiveProposalPreparer> {
+pub struct App<DB, VM, PP = NaiveProposalPreparer, ID = NullIndexer> {
     db: DB,
     vm: VM,
     pub pp: PP,
+    pub indexer: ID,
     /// The gas limit when serving ABCI `Query` calls.
     ///
     /// Prevents the situation where an attacker deploys a contract that
@@ -42,6 +48,25 @@ pub struct App<DB, VM, PP = NaiveProposalPreparer> {
     /// Related config in CosmWasm:
     /// <https://github.com/CosmWasm/wasmd/blob/v0.51.0/x/wasm/types/types.go#L322-L323>
     query_gas_limit: u64,
+    /// The number of threads to use for executing transactions in `FinalizeBlock`.
+    ///
+    /// With more than one thread, transactions are executed optimistically in
+    /// parallel, and those that conflict with earlier transactions in the same
+    /// block are re-executed. The result is identical to executing them one by
+    /// one. Defaults to 1, i.e. sequential execution.
+    ///
+    /// This only pays off if transactions rarely touch the same storage. Note
+    /// that every transaction runs the taxman's `withhold_fee` and
+    /// `finalize_fee`, so a taxman that writes to shared storage in these,
+    /// e.g. moving fees into and out of its own bank balance, makes every
+    /// transaction conflict with the ones before it. Transactions are then
+    /// effectively executed one by one, after a wasted speculative round. The
+    /// Dango taxman avoids this by having the bank credit the fees once per
+    /// block, in its cronjob.
+    parallelism: usize,
+    /// The number of transactions that were re-executed because of conflicts,
+    /// in the last block whose transactions were executed in parallel.
+    reexecuted_txs: Shared<usize>,
 }
 
 impl<DB, VM, PP> App<DB, VM, PP> {
@@ -50,17 +75,50 @@ impl<DB, VM, PP> App<DB, VM, PP> {
             db,
             vm,
             pp,
+            indexer: NullIndexer,
             query_gas_limit,
+            parallelism: 1,
+            reexecuted_txs: Shared::new(0),
+        }
+    }
+}
+
+impl<DB, VM, PP, ID> App<DB, VM, PP, ID> {
+    /// Execute transactions in `FinalizeBlock` using the given number of
+    /// threads.
+    pub fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism;
+        self
+    }
+
+    /// Return the number of transactions that were re-executed because of
+    /// conflicts, in the last block whose transactions were executed in
+    /// parallel.
+    pub fn reexecuted_txs(&self) -> usize {
+        *self.reexecuted_txs.read_access()
+    }
+
+    /// Hand finalized blocks to the given indexer.
+    pub fn with_indexer<ID2>(self, indexer: ID2) -> App<DB, VM, PP, ID2> {
+        App {
+            db: self.db,
+            vm: self.vm,
+            pp: self.pp,
+            indexer,
+            query_gas_limit: self.query_gas_limit,
+            parallelism: self.parallelism,
+            reexecuted_txs: self.reexecuted_txs,
         }
     }
 }
 
-impl<DB, VM, PP> App<DB, VM, PP>
+impl<DB, VM, PP, ID> App<DB, VM, PP, ID>
 where
     DB: Db,
     VM: Vm + Clone,
     PP: ProposalPreparer,
-    AppError: From<DB::Error> + From<VM::Error> + From<PP::Error>,
+    ID: Indexer,
+    AppError: From<DB::Error> + From<VM::Error> + From<PP::Error> + From<ID::Error>,
 {
     pub fn do_init_chain(
         &self,
@@ -87,8 +145,17 @@ where
         // executing genesis messages.
         CHAIN_ID.save(&mut buffer, &chain_id)?;
         CONFIG.save(&mut buffer, &genesis_state.config)?;
+        GAS_COSTS.save(&mut buffer, &GasCosts::default())?;
         LAST_FINALIZED_BLOCK.save(&mut buffer, &block)?;
 
+        if let Some(max_block_gas) = genesis_state.max_block_gas {
+            MAX_BLOCK_GAS.save(&mut buffer, &max_block_gas)?;
+        }
+
+        if let Some(vm_config_hash) = self.vm.config_hash() {
+            VM_CONFIG_HASH.save(&mut buffer, &vm_config_hash)?;
+        }
+
         // Save app configs.
         for (key, value) in genesis_state.app_configs {
             APP_CONFIGS.save(&mut buffer, &key, &value)?;
@@ -120,6 +187,21 @@ where
             process_msg(ctx.clone_boxing_storage(), 0, GENESIS_SENDER, msg)?;
         }
 
+        // Data written during genesis is exempt from state deposit, since the
+        // contracts instantiated during genesis typically don't hold any fund.
+        // Discard their storage usages, and only enable state deposit now, so
+        // that no deposit is settled during genesis either.
+        {
+            let mut storage = ctx.storage.clone();
+
+            STORAGE_USAGES.clear(&mut storage, None, None);
+            STORAGE_USAGE_CHANGES.clear(&mut storage, None, None);
+
+            if let Some(state_deposit) = &genesis_state.state_deposit {
+                STATE_DEPOSIT_PARAMS.save(&mut storage, state_deposit)?;
+            }
+        }
+
         // Persist the state changes to disk
         let (_, pending) = ctx.storage.disassemble().disassemble();
         let (version, root_hash) = self.db.flush_and_commit(pending)?;
@@ -153,10 +235,11 @@ where
         let storage = self.db.state_storage(None)?;
         let chain_id = CHAIN_ID.load(&storage)?;
         let block = LAST_FINALIZED_BLOCK.load(&storage)?;
+        let gas_costs = load_gas_costs(&storage)?;
         let querier = QuerierProvider::new(AppCtx::new(
             self.vm.clone(),
             Box::new(storage),
-            GasTracker::new_limitless(),
+            GasTracker::new_limitless().with_costs(gas_costs),
             chain_id,
             block,
         ));
@@ -166,15 +249,32 @@ where
             .prepare_proposal(QuerierWrapper::new(&querier), txs, max_tx_bytes)?)
     }
 
-    pub fn do_finalize_block(&self, block: BlockInfo, txs: Vec<Tx>) -> AppResult<BlockOutcome> {
+    /// Execute a block. Each transaction comes with its hash, i.e. the SHA-256
+    /// hash of the raw transaction as included in the block, under which the
+    /// indexer records it.
+    pub fn do_finalize_block(
+        &self,
+        block: BlockInfo,
+        txs: Vec<(Tx, Hash256)>,
+    ) -> AppResult<BlockOutcome> {
         let mut buffer = Shared::new(Buffer::new(self.db.state_storage(None)?, None));
         let chain_id = CHAIN_ID.load(&buffer)?;
         let cfg = CONFIG.load(&buffer)?;
         let last_finalized_block = LAST_FINALIZED_BLOCK.load(&buffer)?;
 
+        // Gas costs are loaded once at the beginning of the block. If they are
+        // updated by a transaction, the new costs take effect from the next block.
+        let gas_costs = load_gas_costs(&buffer)?;
+        let max_block_gas = load_max_block_gas(&buffer)?;
+
         let mut cron_outcomes = vec![];
         let mut tx_outcomes = vec![];
 
+        // The transactions are consumed when processed, so keep a copy to be
+        // handed to the indexer along with the outcomes.
+        let indexed_txs = txs.clone();
+        let txs = txs.into_iter().map(|(tx, _)| tx).collect::<Vec<_>>();
+
         // Make sure the new block height is exactly the last finalized height
         // plus one. This ensures that block height always matches the DB version.
         if block.height != last_finalized_block.height + 1 {
@@ -237,7 +337,7 @@ where
                 "Attempting to perform cronjob"
             );
 
-            let gas_tracker = GasTracker::new_limitless();
+            let gas_tracker = GasTracker::new_limitless().with_costs(gas_costs.clone());
 
             let result = do_cron_execute(
                 AppCtx::new(
@@ -250,7 +350,9 @@ where
                 contract,
             );
 
-            cron_outcomes.push(new_outcome(gas_tracker, result));
+            cron_outcomes.push(new_outcome(gas_tracker, result.map(|event| vec![event])));
+
+            apply_storage_usage_changes(&mut buffer)?;
 
             // Schedule the next time this cronjob is to be performed.
             schedule_cronjob(
@@ -261,19 +363,55 @@ where
             )?;
         }
 
-        // Process transactions one-by-one.
-        for (_idx, tx) in txs.into_iter().enumerate() {
-            #[cfg(feature = "tracing")]
-            tracing::debug!(idx = _idx, "Processing transaction");
-
-            tx_outcomes.push(process_tx(
+        // Process transactions, either in parallel if enabled, or one-by-one.
+        //
+        // A transaction is skipped if its gas limit exceeds the gas remaining
+        // in the block, i.e. the block gas limit minus the gas limits of the
+        // transactions before it. Gas limits are counted instead of the gas
+        // actually consumed, so that the proposer can tell which transactions
+        // fit without executing them.
+        if self.parallelism > 1 && txs.len() > 1 {
+            let reexecuted_txs;
+
+            (tx_outcomes, reexecuted_txs) = process_txs_in_parallel(
                 self.vm.clone(),
                 buffer.clone(),
                 chain_id.clone(),
                 block,
-                tx,
-                AuthMode::Finalize,
-            ));
+                gas_costs,
+                max_block_gas,
+                txs,
+                self.parallelism,
+            )?;
+
+            *self.reexecuted_txs.write_access() = reexecuted_txs;
+        } else {
+            let mut remaining_gas = max_block_gas;
+
+            for (_idx, tx) in txs.into_iter().enumerate() {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(idx = _idx, "Processing transaction");
+
+                if tx.gas_limit > remaining_gas {
+                    tx_outcomes.push(new_skipped_tx_outcome(&tx, remaining_gas));
+                    continue;
+                }
+
+                remaining_gas -= tx.gas_limit;
+
+                tx_outcomes.push(process_tx(
+                    self.vm.clone(),
+                    buffer.clone(),
+                    chain_id.clone(),
+                    block,
+                    gas_costs.clone(),
+                    tx,
+                    AuthMode::Finalize,
+                    None,
+                ));
+
+                apply_storage_usage_changes(&mut buffer)?;
+            }
         }
 
         // Save the last committed block.
@@ -302,11 +440,24 @@ where
             "Finalized block"
         );
 
-        Ok(BlockOutcome {
+        let outcome = BlockOutcome {
             app_hash: app_hash.unwrap(),
             cron_outcomes,
             tx_outcomes,
-        })
+        };
+
+        // Indexing isn't part of consensus, so a failure to index the block is
+        // logged but doesn't halt the chain.
+        if let Err(_err) = self.indexer.index_block(&block, &indexed_txs, &outcome) {
+            #[cfg(feature = "tracing")]
+            tracing::error!(
+                height = block.height,
+                err = _err.to_string(),
+                "Failed to index block"
+            );
+        }
+
+        Ok(outcome)
     }
 
     pub fn do_commit(&self) -> AppResult<()> {
@@ -315,9 +466,28 @@ where
         #[cfg(feature = "tracing")]
         tracing::info!(height = self.db.latest_version(), "Committed state");
 
+        self.commit_indexer();
+
         Ok(())
     }
 
+    /// Have the indexer persist the blocks up to the latest committed height.
+    ///
+    /// Besides being called at `Commit`, this should be called upon startup:
+    /// if the node crashed after committing the state but before the indexer
+    /// persisted the block, the block is recovered this way.
+    pub fn commit_indexer(&self) {
+        let Some(height) = self.db.latest_version() else {
+            return;
+        };
+
+        // Same as in `do_finalize_block`, indexer failures are only logged.
+        if let Err(_err) = self.indexer.commit(height) {
+            #[cfg(feature = "tracing")]
+            tracing::error!(height, err = _err.to_string(), "Failed to commit index");
+        }
+    }
+
     // For `CheckTx`, we only do the first two steps of the transaction
     // processing flow:
     // 1.`withhold_fee`, where the taxman makes sure the sender has sufficient
@@ -327,20 +497,33 @@ where
         let buffer = Shared::new(Buffer::new(self.db.state_storage(None)?, None));
         let chain_id = CHAIN_ID.load(&buffer)?;
         let block = LAST_FINALIZED_BLOCK.load(&buffer)?;
+        let gas_costs = load_gas_costs(&buffer)?;
+        let max_block_gas = load_max_block_gas(&buffer)?;
 
         let ctx = AppCtx::new(
             self.vm.clone(),
             Box::new(buffer) as _,
-            GasTracker::new_limited(tx.gas_limit),
+            GasTracker::new_limited(tx.gas_limit).with_costs(gas_costs),
             chain_id,
             block,
         );
 
+        // A transaction that can't possibly fit in a block is rejected.
+        if tx.gas_limit > max_block_gas {
+            return Ok(new_outcome(
+                ctx.gas_tracker,
+                Err(AppError::ExceedMaxBlockGas {
+                    gas_limit: tx.gas_limit,
+                    max_block_gas,
+                }),
+            ));
+        }
+
         let mut events = vec![];
 
         match do_withhold_fee(ctx.clone(), &tx, AuthMode::Check) {
-            Ok(new_events) => {
-                events.extend(new_events);
+            Ok(event) => {
+                events.push(event);
             },
             Err(err) => {
                 return Ok(new_outcome(ctx.gas_tracker, Err(err)));
@@ -348,8 +531,8 @@ where
         }
 
         match do_authenticate(ctx.clone(), &tx, AuthMode::Check) {
-            Ok((new_events, _)) => {
-                events.extend(new_events);
+            Ok((event, _)) => {
+                events.push(event);
             },
             Err(err) => {
                 return Ok(new_outcome(ctx.gas_tracker, Err(err)));
@@ -377,6 +560,19 @@ where
             panic!("root hash not found at the latest version ({version})");
         };
 
+        // `Info` is called when the node starts. Make sure the VM is configured
+        // the same way as when the chain started; otherwise, this node may
+        // disagree with others on the outcomes of transactions.
+        let storage = self.db.state_storage(Some(version))?;
+        let expect = VM_CONFIG_HASH.may_load(&storage)?;
+        let actual = self.vm.config_hash();
+
+        if let (Some(expect), Some(actual)) = (expect, actual) {
+            if expect != actual {
+                return Err(AppError::IncorrectVmConfig { expect, actual });
+            }
+        }
+
         Ok((version, root_hash))
     }
 
@@ -387,23 +583,18 @@ where
             return Err(AppError::ProofNotSupported);
         }
 
-        let version = if height == 0 {
-            // Height being zero means unspecified (Protobuf doesn't have a null
-            // type) in which case we use the latest version.
-            None
-        } else {
-            Some(height)
-        };
+        let version = self.resolve_query_height(height)?;
 
         // Use the state storage at the given version to perform the query.
         let storage = self.db.state_storage(version)?;
         let chain_id = CHAIN_ID.load(&storage)?;
         let block = LAST_FINALIZED_BLOCK.load(&storage)?;
+        let gas_costs = load_gas_costs(&storage)?;
 
         let ctx = AppCtx::new(
             self.vm.clone(),
             Box::new(storage.clone()) as _,
-            GasTracker::new_limited(self.query_gas_limit),
+            GasTracker::new_limited(self.query_gas_limit).with_costs(gas_costs),
             chain_id,
             block,
         );
@@ -422,13 +613,7 @@ where
         height: u64,
         prove: bool,
     ) -> AppResult<(Option<Vec<u8>>, Option<Vec<u8>>)> {
-        let version = if height == 0 {
-            // Height being zero means unspecified (Protobuf doesn't have a null
-            // type) in which case we use the latest version.
-            None
-        } else {
-            Some(height)
-        };
+        let version = self.resolve_query_height(height)?;
 
         let proof = if prove {
             Some(self.db.prove(key, version)?.to_borsh_vec()?)
@@ -441,15 +626,59 @@ where
         Ok((value, proof))
     }
 
+    /// Convert the block height of a query to the DB version to query at.
+    ///
+    /// Height being zero means unspecified (Protobuf doesn't have a null type)
+    /// in which case we use the latest version. Error if the data at the given
+    /// height has already been pruned.
+    fn resolve_query_height(&self, height: u64) -> AppResult<Option<u64>> {
+        if height == 0 {
+            return Ok(None);
+        }
+
+        if let Some(oldest_version) = self.db.oldest_version() {
+            if height < oldest_version && !self.db.has_checkpoint(height) {
+                return Err(AppError::HeightPruned {
+                    height,
+                    oldest_version,
+                });
+            }
+        }
+
+        Ok(Some(height))
+    }
+
     pub fn do_simulate(
         &self,
         unsigned_tx: UnsignedTx,
         height: u64,
         prove: bool,
+    ) -> AppResult<TxOutcome> {
+        self._do_simulate(unsigned_tx, height, prove, false)
+    }
+
+    /// Simulate a transaction, same as `do_simulate`, but additionally record
+    /// the call tree of the transaction in the outcome's `trace` field.
+    pub fn do_simulate_with_trace(
+        &self,
+        unsigned_tx: UnsignedTx,
+        height: u64,
+        prove: bool,
+    ) -> AppResult<TxOutcome> {
+        self._do_simulate(unsigned_tx, height, prove, true)
+    }
+
+    fn _do_simulate(
+        &self,
+        unsigned_tx: UnsignedTx,
+        height: u64,
+        prove: bool,
+        trace: bool,
     ) -> AppResult<TxOutcome> {
         let buffer = Buffer::new(self.db.state_storage(None)?, None);
         let chain_id = CHAIN_ID.load(&buffer)?;
         let block = LAST_FINALIZED_BLOCK.load(&buffer)?;
+        let gas_costs = load_gas_costs(&buffer)?;
 
         // We can't "prove" a gas simulation
         if prove {
@@ -472,16 +701,24 @@ where
             credential: Json::Null,
         };
 
+        let tracer = trace.then(CallTracer::new);
+
         // Run the transaction with `simulate` as `true`. Track how much gas was
         // consumed, and, if it was successful, what events were emitted.
-        Ok(process_tx(
+        let mut outcome = process_tx(
             self.vm.clone(),
             buffer,
             chain_id,
             block,
+            gas_costs,
             tx,
             AuthMode::Simulate,
-        ))
+            tracer.clone(),
+        );
+
+        outcome.trace = tracer.map(CallTracer::into_traces);
+
+        Ok(outcome)
     }
 }
 
@@ -489,12 +726,13 @@ where
 // Borsh encoding. This is because these are the methods that clients interact
 // with, and it's difficult to do Borsh encoding in JS client (JS sucks).
 #[cfg(feature = "abci")]
-impl<DB, VM, PP> App<DB, VM, PP>
+impl<DB, VM, PP, ID> App<DB, VM, PP, ID>
 where
     DB: Db,
     VM: Vm + Clone,
     PP: ProposalPreparer,
-    AppError: From<DB::Error> + From<VM::Error> + From<PP::Error>,
+    ID: Indexer,
+    AppError: From<DB::Error> + From<VM::Error> + From<PP::Error> + From<ID::Error>,
 {
     pub fn do_init_chain_raw(
         &self,
@@ -517,7 +755,7 @@ where
     {
         let txs = raw_txs
             .iter()
-            .map(|raw_tx| raw_tx.deserialize_json())
+            .map(|raw_tx| Ok((raw_tx.deserialize_json()?, raw_tx.as_ref().hash256())))
             .collect::<StdResult<Vec<_>>>()?;
 
         self.do_finalize_block(block, txs)
@@ -549,13 +787,225 @@ where
     }
 }
 
+// State sync methods. These are only available if the DB supports exporting
+// and importing snapshots.
+impl<DB, VM, PP, ID> App<DB, VM, PP, ID>
+where
+    DB: SnapshotDb,
+    AppError: From<DB::Error>,
+{
+    pub fn do_list_snapshots(&self) -> AppResult<Vec<Snapshot>> {
+        Ok(self.db.list_snapshots()?)
+    }
+
+    pub fn do_load_snapshot_chunk(
+        &self,
+        version: u64,
+        format: u32,
+        index: u32,
+    ) -> AppResult<Option<Vec<u8>>> {
+        Ok(self.db.load_snapshot_chunk(version, format, index)?)
+    }
+
+    pub fn do_offer_snapshot(&self, snapshot: Snapshot, app_hash: Hash256) -> AppResult<()> {
+        #[cfg(feature = "tracing")]
+        tracing::info!(
+            version = snapshot.version,
+            chunks = snapshot.chunks,
+            "Restoring state from snapshot"
+        );
+
+        Ok(self.db.offer_snapshot(snapshot, app_hash)?)
+    }
+
+    pub fn do_apply_snapshot_chunk(
+        &self,
+        index: u32,
+        chunk: Vec<u8>,
+    ) -> AppResult<SnapshotChunkOutcome> {
+        let outcome = self.db.apply_snapshot_chunk(index, chunk)?;
+
+        #[cfg(feature = "tracing")]
+        match outcome {
+            SnapshotChunkOutcome::Applied => {},
+            SnapshotChunkOutcome::Restored => {
+                tracing::info!(
+                    height = self.db.latest_version(),
+                    "Restored state from snapshot"
+                );
+            },
+            SnapshotChunkOutcome::Corrupted => {
+                tracing::warn!(index, "Received corrupted snapshot chunk");
+            },
+        }
+
+        Ok(outcome)
+    }
+}
+
+/// Process transactions optimistically in parallel, Block-STM style.
+///
+/// 1. Each transaction is executed speculatively, in a thread pool, against the
+///    same pre-state (the `storage` after cronjobs), on its own buffer, with
+///    the keys and ranges it reads being recorded.
+/// 2. Then, going through the transactions in order, we check whether each
+///    one has read anything written by the transactions before it. If not, its
+///    speculative result is exactly what sequential execution would produce,
+///    so we simply apply its writes. Otherwise, it's re-executed against the
+///    up-to-date state.
+///
+/// Each transaction is executed at most twice, and the outcomes and the state
+/// changes are identical to those of processing the transactions one-by-one.
+/// Return the outcomes, and the number of transactions that were re-executed.
+///
+/// The storage usage changes made by a transaction are applied right after it,
+/// so every transaction starts without any, and only reads the ones it makes
+/// itself. They are therefore not counted as written for the purpose of
+/// detecting conflicts, but the storage usages they are applied to are.
+fn process_txs_in_parallel<S, VM>(
+    vm: VM,
+    mut storage: S,
+    chain_id: String,
+    block: BlockInfo,
+    gas_costs: GasCosts,
+    max_block_gas: u64,
+    txs: Vec<Tx>,
+    parallelism: usize,
+) -> AppResult<(Vec<TxOutcome>, usize)>
+where
+    S: Storage + Clone + 'static,
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let threads = parallelism.min(txs.len());
+
+    // Speculatively execute the transactions. Worker `i` takes transactions
+    // `i`, `i + threads`, `i + 2 * threads`, ...
+    //
+    // Nothing writes to `storage` until all workers are done.
+    let mut speculations = thread::scope(|scope| {
+        let handles = (0..threads)
+            .map(|worker| {
+                let vm = vm.clone();
+                let storage = storage.clone();
+                let chain_id = &chain_id;
+                let gas_costs = &gas_costs;
+                let txs = &txs;
+
+                scope.spawn(move || {
+                    txs.iter()
+                        .enumerate()
+                        .skip(worker)
+                        .step_by(threads)
+                        .map(|(idx, tx)| {
+                            #[cfg(feature = "tracing")]
+                            tracing::debug!(idx, "Speculatively processing transaction");
+
+                            let tracked = Tracked::new(storage.clone());
+                            let tx_buffer = Shared::new(Buffer::new(tracked.clone(), None));
+                            let outcome = process_tx(
+                                vm.clone(),
+                                tx_buffer.clone(),
+                                chain_id.clone(),
+                                block,
+                                gas_costs.clone(),
+                                tx.clone(),
+                                AuthMode::Finalize,
+                                None,
+                            );
+                            let (_, writes) = tx_buffer.disassemble().disassemble();
+
+                            (idx, outcome, tracked.into_reads(), writes)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut speculations = Vec::with_capacity(txs.len());
+        for handle in handles {
+            match handle.join() {
+                Ok(results) => speculations.extend(results),
+                Err(err) => std::panic::resume_unwind(err),
+            }
+        }
+
+        speculations
+    });
+
+    speculations.sort_by_key(|(idx, ..)| *idx);
+
+    // Validate and apply the results in order. Keep track of all keys written
+    // by the transactions so far.
+    let mut written = BTreeSet::new();
+    let mut tx_outcomes = Vec::with_capacity(txs.len());
+    let mut remaining_gas = max_block_gas;
+    let mut reexecuted_txs = 0;
+
+    let usage_changes_prefix = STORAGE_USAGE_CHANGES.path_raw(&[]);
+    let usage_changes_prefix = usage_changes_prefix.storage_key();
+
+    for (tx, (_idx, outcome, reads, writes)) in txs.into_iter().zip(speculations) {
+        // A skipped transaction's speculative writes are discarded, so the
+        // transactions after it are validated as if it had never been run.
+        if tx.gas_limit > remaining_gas {
+            tx_outcomes.push(new_skipped_tx_outcome(&tx, remaining_gas));
+            continue;
+        }
+
+        remaining_gas -= tx.gas_limit;
+
+        let (outcome, writes) = if reads.conflicts_with(&written) {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(idx = _idx, "Re-processing conflicting transaction");
+
+            reexecuted_txs += 1;
+
+            let tx_buffer = Shared::new(Buffer::new(storage.clone(), None));
+            let outcome = process_tx(
+                vm.clone(),
+                tx_buffer.clone(),
+                chain_id.clone(),
+                block,
+                gas_costs.clone(),
+                tx,
+                AuthMode::Finalize,
+                None,
+            );
+            let (_, writes) = tx_buffer.disassemble().disassemble();
+
+            (outcome, writes)
+        } else {
+            (outcome, writes)
+        };
+
+        written.extend(
+            writes
+                .keys()
+                .filter(|key| !key.starts_with(usage_changes_prefix))
+                .cloned(),
+        );
+        storage.flush(writes);
+
+        for contract in apply_storage_usage_changes(&mut storage)? {
+            written.insert(STORAGE_USAGES.path(contract).storage_key().to_vec());
+        }
+
+        tx_outcomes.push(outcome);
+    }
+
+    Ok((tx_outcomes, reexecuted_txs))
+}
+
 fn process_tx<S, VM>(
     vm: VM,
     storage: S,
     chain_id: String,
     block: BlockInfo,
+    gas_costs: GasCosts,
     tx: Tx,
     mode: AuthMode,
+    tracer: Option<CallTracer>,
 ) -> TxOutcome
 where
     S: Storage + Clone + 'static,
@@ -564,7 +1014,13 @@ where
 {
     // Create the gas tracker, with the limit being the gas limit requested by
     // the transaction.
-    let gas_tracker = GasTracker::new_limited(tx.gas_limit);
+    //
+    // When simulating, also record a detailed breakdown of the gas consumption.
+    let mut gas_tracker = GasTracker::new_limited(tx.gas_limit).with_costs(gas_costs);
+
+    if mode == AuthMode::Simulate {
+        gas_tracker = gas_tracker.with_profiling();
+    }
 
     // Create two layers of buffers.
     //
@@ -574,14 +1030,19 @@ where
     let msg_buffer = Shared::new(Buffer::new(fee_buffer.clone(), None));
 
     // Create two layers of contexts using the two buffers.
-    let fee_ctx = AppCtx::new(
+    let mut fee_ctx = AppCtx::new(
         vm.clone(),
         fee_buffer,
         gas_tracker.clone(),
         chain_id.clone(),
         block,
     );
-    let msg_ctx = AppCtx::new(vm, msg_buffer, gas_tracker.clone(), chain_id, block);
+    let mut msg_ctx = AppCtx::new(vm, msg_buffer, gas_tracker.clone(), chain_id, block);
+
+    // If tracing is enabled, record the contract calls in both contexts into
+    // the same call tree.
+    fee_ctx.tracer = tracer.clone();
+    msg_ctx.tracer = tracer;
 
     // Record the events emitted during the processing of this transaction.
     let mut events = Vec::new();
@@ -595,8 +1056,8 @@ where
     //
     // If this fails, we abort the tx and return, discard all state changes.
     match do_withhold_fee(fee_ctx.clone_boxing_storage(), &tx, mode) {
-        Ok(new_events) => {
-            events.extend(new_events);
+        Ok(event) => {
+            events.push(event);
         },
         Err(err) => {
             return new_tx_outcome(gas_tracker, events.clone(), Err(err));
@@ -617,9 +1078,9 @@ where
     // If fails, discard state changes in `msg_buffer` (but keeping those in
     // `fee_buffer`), discard the events, and jump to `finalize_fee`.
     let request_backrun = match do_authenticate(msg_ctx.clone_boxing_storage(), &tx, mode) {
-        Ok((new_events, request_backrun)) => {
+        Ok((event, request_backrun)) => {
             msg_ctx.storage.write_access().commit();
-            events.extend(new_events);
+            events.push(event);
             request_backrun
         },
         Err(err) => {
@@ -678,11 +1139,11 @@ where
         #[cfg(feature = "tracing")]
         tracing::debug!(idx = _idx, "Processing message");
 
-        msg_events.extend(process_msg(ctx.clone(), 0, tx.sender, msg.clone())?);
+        msg_events.push(process_msg(ctx.clone(), 0, tx.sender, msg.clone())?);
     }
 
     if request_backrun {
-        msg_events.extend(do_backrun(ctx, tx, mode)?);
+        msg_events.push(do_backrun(ctx, tx, mode)?);
     }
 
     Ok(msg_events)
@@ -700,12 +1161,13 @@ where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
-    let gas_tracker = ctx.replace_gas_tracker(GasTracker::new_limitless());
+    let gas_costs = ctx.gas_tracker.costs().clone();
+    let gas_tracker = ctx.replace_gas_tracker(GasTracker::new_limitless().with_costs(gas_costs));
     let outcome_so_far = new_tx_outcome(gas_tracker.clone(), events.clone(), result.clone());
 
     match do_finalize_fee(ctx.clone_boxing_storage(), &tx, &outcome_so_far, mode) {
-        Ok(new_events) => {
-            events.extend(new_events);
+        Ok(event) => {
+            events.push(event);
             ctx.storage.disassemble().consume();
             new_tx_outcome(gas_tracker, events, result)
         },
@@ -722,7 +1184,7 @@ pub fn process_msg<VM>(
     msg_depth: usize,
     sender: Addr,
     msg: Message,
-) -> AppResult<Vec<Event>>
+) -> AppResult<Event>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
@@ -730,10 +1192,12 @@ where
     match msg {
         Message::Configure(msg) => do_configure(ctx.downcast(), sender, msg),
         Message::Transfer(msg) => do_transfer(ctx, msg_depth, sender, msg, true),
-        Message::Upload(msg) => do_upload(ctx.downcast(), sender, msg),
+        Message::Upload(msg) => do_upload(ctx, sender, msg),
         Message::Instantiate(msg) => do_instantiate(ctx, msg_depth, sender, msg),
         Message::Execute(msg) => do_execute(ctx, msg_depth, sender, msg),
         Message::Migrate(msg) => do_migrate(ctx, msg_depth, sender, msg),
+        Message::UpdateAdmin(msg) => do_update_admin(ctx.downcast(), sender, msg),
+        Message::ClearAdmin(msg) => do_clear_admin(ctx.downcast(), sender, msg),
     }
 }
 
@@ -751,6 +1215,18 @@ where
             let res = query_config(ctx.downcast())?;
             Ok(QueryResponse::Config(res))
         },
+        Query::GasCosts(..) => {
+            let res = query_gas_costs(ctx.downcast())?;
+            Ok(QueryResponse::GasCosts(res))
+        },
+        Query::MaxBlockGas(..) => {
+            let res = query_max_block_gas(ctx.downcast())?;
+            Ok(QueryResponse::MaxBlockGas(res))
+        },
+        Query::StateDepositParams(..) => {
+            let res = query_state_deposit_params(ctx.downcast())?;
+            Ok(QueryResponse::StateDepositParams(res))
+        },
         Query::AppConfig(req) => {
             let res = query_app_config(ctx.downcast(), req)?;
             Ok(QueryResponse::AppConfig(res))
@@ -791,6 +1267,10 @@ where
             let res = query_contracts(ctx.downcast(), req)?;
             Ok(QueryResponse::Contracts(res))
         },
+        Query::StateDeposit(req) => {
+            let res = query_state_deposit(ctx.downcast(), req)?;
+            Ok(QueryResponse::StateDeposit(res))
+        },
         Query::WasmRaw(req) => {
             let res = query_wasm_raw(ctx.downcast(), req)?;
             Ok(QueryResponse::WasmRaw(res))
@@ -849,6 +1329,18 @@ pub(crate) fn schedule_cronjob(
     NEXT_CRONJOBS.insert(storage, (next_time, contract))
 }
 
+/// Load the gas costs from the chain state. Use the default costs if they
+/// haven't been set.
+fn load_gas_costs(storage: &dyn Storage) -> StdResult<GasCosts> {
+    Ok(GAS_COSTS.may_load(storage)?.unwrap_or_default())
+}
+
+/// Load the block gas limit from the chain state. There is no limit if it
+/// hasn't been set.
+fn load_max_block_gas(storage: &dyn Storage) -> StdResult<u64> {
+    Ok(MAX_BLOCK_GAS.may_load(storage)?.unwrap_or(u64::MAX))
+}
+
 fn new_outcome(gas_tracker: GasTracker, result: AppResult<Vec<Event>>) -> Outcome {
     Outcome {
         gas_limit: gas_tracker.limit(),
@@ -863,9 +1355,24 @@ fn new_tx_outcome(gas_tracker: GasTracker, events: Vec<Event>, result: AppResult
         gas_used: gas_tracker.used(),
         events,
         result: result.into_generic_result(),
+        trace: None,
+        gas_profile: gas_tracker.profile(),
     }
 }
 
+/// Outcome of a transaction that is skipped because it doesn't fit in the
+/// remaining gas of the block. The transaction isn't executed at all.
+fn new_skipped_tx_outcome(tx: &Tx, remaining_gas: u64) -> TxOutcome {
+    new_tx_outcome(
+        GasTracker::new_limited(tx.gas_limit),
+        vec![],
+        Err(AppError::ExceedRemainingBlockGas {
+            gas_limit: tx.gas_limit,
+            remaining: remaining_gas,
+        }),
+    )
+}
+
 #[cfg(feature = "tracing")]
 pub fn into_utc_string(timestamp: Timestamp) -> String {
     // This panics if the timestamp (as nanoseconds) overflows `i64` range.
@@ -1,5 +1,5 @@
 use {
-    crate::GasTracker,
+    crate::{CallTracer, GasTracker},
     grug_types::{BlockInfo, Storage, Undefined},
     std::mem,
 };
@@ -15,6 +15,8 @@ pub struct AppCtx<VM = Undefined, S = Box<dyn Storage>> {
     pub gas_tracker: GasTracker,
     pub chain_id: String,
     pub block: BlockInfo,
+    /// Records the contract calls made, if tracing is enabled.
+    pub tracer: Option<CallTracer>,
 }
 
 impl<VM, S> AppCtx<VM, S> {
@@ -35,9 +37,17 @@ impl<VM, S> AppCtx<VM, S> {
             gas_tracker,
             chain_id: chain_id.into(),
             block,
+            tracer: None,
         }
     }
 
+    /// Record the contract calls made under this context using the given
+    /// tracer.
+    pub fn with_tracer(mut self, tracer: CallTracer) -> Self {
+        self.tracer = Some(tracer);
+        self
+    }
+
     /// Replace the gas tracker with a new one; return the old one.
     pub fn replace_gas_tracker(&mut self, gas_tracker: GasTracker) -> GasTracker {
         mem::replace(&mut self.gas_tracker, gas_tracker)
@@ -54,6 +64,7 @@ impl<VM, S> AppCtx<VM, S> {
             gas_tracker: self.gas_tracker,
             chain_id: self.chain_id,
             block: self.block,
+            tracer: self.tracer,
         }
     }
 }
@@ -70,6 +81,7 @@ where
             gas_tracker: self.gas_tracker.clone(),
             chain_id: self.chain_id.clone(),
             block: self.block,
+            tracer: self.tracer.clone(),
         }
     }
 }
@@ -87,6 +99,7 @@ where
             gas_tracker: self.gas_tracker.clone(),
             chain_id: self.chain_id.clone(),
             block: self.block,
+            tracer: self.tracer.clone(),
         }
     }
 }
@@ -1,4 +1,5 @@
 use {
+    grug_math::MathError,
     grug_types::{Addr, Hash256, StdError},
     thiserror::Error,
 };
@@ -8,6 +9,9 @@ pub enum AppError {
     #[error(transparent)]
     Std(#[from] StdError),
 
+    #[error(transparent)]
+    Math(#[from] MathError),
+
     #[error("VM error: {0}")]
     Vm(String),
 
@@ -17,6 +21,9 @@ pub enum AppError {
     #[error("proposal preparer error: {0}")]
     PrepareProposal(String),
 
+    #[error("indexer error: {0}")]
+    Indexer(String),
+
     #[error("contract returned error! address: {address}, method: {name}, msg: {msg}")]
     Guest {
         address: Addr,
@@ -30,12 +37,18 @@ pub enum AppError {
     #[error("simulating a transaction at past block height is not supported")]
     PastHeightNotSupported,
 
+    #[error("data at height {height} has been pruned! oldest available height: {oldest_version}")]
+    HeightPruned { height: u64, oldest_version: u64 },
+
     #[error("sender does not have permission to perform this action")]
     Unauthorized,
 
     #[error("incorrect block height! expecting: {expect}, actual: {actual}")]
     IncorrectBlockHeight { expect: u64, actual: u64 },
 
+    #[error("VM config differs from the one the chain started with! expecting: {expect}, actual: {actual}")]
+    IncorrectVmConfig { expect: Hash256, actual: Hash256 },
+
     #[error("sender is not the owner! sender: {sender}, owner: {owner}")]
     NotOwner { sender: Addr, owner: Addr },
 
@@ -48,11 +61,27 @@ pub enum AppError {
     #[error("code with hash `{code_hash}` already exists")]
     CodeExists { code_hash: Hash256 },
 
+    #[error("code with hash `{code_hash}` is invalid: {reason}")]
+    InvalidCode { code_hash: Hash256, reason: String },
+
     #[error("account with address `{address}` already exists")]
     AccountExists { address: Addr },
 
     #[error("max message depth exceeded")]
     ExceedMaxMessageDepth,
+
+    #[error("contract emitted an event with a reserved key! address: {address}, event: {ty}, key: {key}")]
+    ReservedEventKey {
+        address: Addr,
+        ty: String,
+        key: &'static str,
+    },
+
+    #[error("transaction gas limit exceeds block gas limit! gas limit: {gas_limit}, block gas limit: {max_block_gas}")]
+    ExceedMaxBlockGas { gas_limit: u64, max_block_gas: u64 },
+
+    #[error("transaction gas limit exceeds remaining block gas! gas limit: {gas_limit}, remaining: {remaining}")]
+    ExceedRemainingBlockGas { gas_limit: u64, remaining: u64 },
 }
 
 pub type AppResult<T> = core::result::Result<T, AppError>;
@@ -1,27 +1,31 @@
 use {
     crate::{
         call_in_0_out_1_handle_response, call_in_1_out_1, call_in_1_out_1_handle_response,
-        call_in_2_out_1_handle_response, handle_response, has_permission, schedule_cronjob, AppCtx,
-        AppError, AppResult, MeteredItem, MeteredMap, Vm, APP_CONFIGS, CODES, CONFIG, CONTRACTS,
-        NEXT_CRONJOBS,
+        call_in_2_out_1_handle_response, handle_response, has_permission, schedule_cronjob,
+        storage_usage, trace_call, AppCtx, AppError, AppResult, MeteredItem, MeteredMap, Vm,
+        APP_CONFIGS, CODES, CONFIG, CONTRACTS, GAS_COSTS, MAX_BLOCK_GAS, NEXT_CRONJOBS,
+        STATE_DEPOSITS, STATE_DEPOSIT_PARAMS,
     },
-    grug_math::Inner,
+    grug_math::{Inner, IsZero, Number, Uint128},
     grug_types::{
-        Addr, AuthMode, AuthResponse, BankMsg, Code, CodeStatus, Context, ContractInfo, Event,
-        GenericResult, Hash256, HashExt, Json, MsgConfigure, MsgExecute, MsgInstantiate,
-        MsgMigrate, MsgTransfer, MsgUpload, Op, StdResult, SubMsgResult, Tx, TxOutcome,
+        Addr, AuthMode, AuthResponse, BankMsg, Code, CodeStatus, Coin, Context, ContractInfo,
+        Event, EvtClearAdmin, EvtConfigure, EvtCron, EvtExecute, EvtGuest, EvtInstantiate,
+        EvtMigrate, EvtReply, EvtTransfer, EvtUpdateAdmin, EvtUpload, GenericResult, Hash256,
+        HashExt, Json, JsonSerExt, MsgClearAdmin, MsgConfigure, MsgExecute, MsgInstantiate,
+        MsgMigrate, MsgTransfer, MsgUpdateAdmin, MsgUpload, Op, StdResult, SubMsgResult, Tx,
+        TxOutcome,
     },
 };
 
 // ---------------------------------- config -----------------------------------
 
-pub fn do_configure(ctx: AppCtx, sender: Addr, msg: MsgConfigure) -> AppResult<Vec<Event>> {
+pub fn do_configure(ctx: AppCtx, sender: Addr, msg: MsgConfigure) -> AppResult<Event> {
     match _do_configure(ctx, sender, msg) {
         Ok(event) => {
             #[cfg(feature = "tracing")]
             tracing::info!("Config updated");
 
-            Ok(vec![event])
+            Ok(event)
         },
         Err(err) => {
             #[cfg(feature = "tracing")]
@@ -76,6 +80,29 @@ fn _do_configure(mut ctx: AppCtx, sender: Addr, msg: MsgConfigure) -> AppResult<
     // Save the updated config.
     CONFIG.save(&mut ctx.storage, &cfg)?;
 
+    // Update gas costs. The new costs take effect starting from the next block.
+    if let Some(new_gas_costs) = msg.updates.gas_costs {
+        GAS_COSTS.save(&mut ctx.storage, &new_gas_costs)?;
+    }
+
+    // Like gas costs, the new block gas limit takes effect starting from the
+    // next block.
+    if let Some(new_max_block_gas) = msg.updates.max_block_gas {
+        MAX_BLOCK_GAS.save(&mut ctx.storage, &new_max_block_gas)?;
+    }
+
+    // Deposits already held aren't adjusted right away, but the next time each
+    // contract is called.
+    match msg.updates.state_deposit {
+        Some(Some(new_params)) => {
+            STATE_DEPOSIT_PARAMS.save(&mut ctx.storage, &new_params)?;
+        },
+        Some(None) => {
+            STATE_DEPOSIT_PARAMS.remove(&mut ctx.storage);
+        },
+        None => {},
+    }
+
     // Update app configs
     for (key, op) in msg.app_updates {
         if let Op::Insert(value) = op {
@@ -85,18 +112,21 @@ fn _do_configure(mut ctx: AppCtx, sender: Addr, msg: MsgConfigure) -> AppResult<
         }
     }
 
-    Ok(Event::new("configure").add_attribute("sender", sender))
+    Ok(Event::Configure(EvtConfigure { sender }))
 }
 
 // ---------------------------------- upload -----------------------------------
 
-pub fn do_upload(ctx: AppCtx, uploader: Addr, msg: MsgUpload) -> AppResult<Vec<Event>> {
+pub fn do_upload<VM>(ctx: AppCtx<VM>, uploader: Addr, msg: MsgUpload) -> AppResult<Event>
+where
+    VM: Vm,
+{
     match _do_upload(ctx, uploader, msg) {
         Ok((event, _code_hash)) => {
             #[cfg(feature = "tracing")]
             tracing::info!(code_hash = _code_hash.to_string(), "Uploaded code");
 
-            Ok(vec![event])
+            Ok(event)
         },
         Err(err) => {
             #[cfg(feature = "tracing")]
@@ -108,7 +138,14 @@ pub fn do_upload(ctx: AppCtx, uploader: Addr, msg: MsgUpload) -> AppResult<Vec<E
 }
 
 // Return the hash of the code that is stored, for logging purpose.
-fn _do_upload(mut ctx: AppCtx, uploader: Addr, msg: MsgUpload) -> AppResult<(Event, Hash256)> {
+fn _do_upload<VM>(
+    mut ctx: AppCtx<VM>,
+    uploader: Addr,
+    msg: MsgUpload,
+) -> AppResult<(Event, Hash256)>
+where
+    VM: Vm,
+{
     // Make sure the user has the permission to upload contracts
     let cfg = CONFIG.load_with_gas(&ctx.storage, ctx.gas_tracker.clone())?;
 
@@ -123,6 +160,21 @@ fn _do_upload(mut ctx: AppCtx, uploader: Addr, msg: MsgUpload) -> AppResult<(Eve
         return Err(AppError::CodeExists { code_hash });
     }
 
+    // Validating the code may involve compiling it, which takes time growing
+    // with its size, so charge for it beforehand.
+    ctx.gas_tracker.consume(
+        ctx.gas_tracker.costs().validate_code.cost(msg.code.len()),
+        "validate_code",
+    )?;
+
+    // Make sure the code is a valid program for the VM
+    if let Err(err) = ctx.vm.validate_code(&msg.code, code_hash) {
+        return Err(AppError::InvalidCode {
+            code_hash,
+            reason: err.to_string(),
+        });
+    }
+
     CODES.save_with_gas(&mut ctx.storage, ctx.gas_tracker, code_hash, &Code {
         code: msg.code,
         status: CodeStatus::Orphaned {
@@ -131,7 +183,10 @@ fn _do_upload(mut ctx: AppCtx, uploader: Addr, msg: MsgUpload) -> AppResult<(Eve
     })?;
 
     Ok((
-        Event::new("upload").add_attribute("code_hash", code_hash),
+        Event::Upload(EvtUpload {
+            sender: uploader,
+            code_hash,
+        }),
         code_hash,
     ))
 }
@@ -144,13 +199,13 @@ pub fn do_transfer<VM>(
     sender: Addr,
     msg: MsgTransfer,
     do_receive: bool,
-) -> AppResult<Vec<Event>>
+) -> AppResult<Event>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
     match _do_transfer(ctx, msg_depth, sender, msg.clone(), do_receive) {
-        Ok(events) => {
+        Ok(event) => {
             #[cfg(feature = "tracing")]
             tracing::info!(
                 from = sender.to_string(),
@@ -159,7 +214,7 @@ where
                 "Transferred coins"
             );
 
-            Ok(events)
+            Ok(Event::Transfer(event))
         },
         Err(err) => {
             #[cfg(feature = "tracing")]
@@ -180,7 +235,7 @@ fn _do_transfer<VM>(
     // - `true` when handling `Message::Transfer`
     // - `false` when handling `Message::{Instantaite,Execute}`
     do_receive: bool,
-) -> AppResult<Vec<Event>>
+) -> AppResult<EvtTransfer>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
@@ -203,7 +258,7 @@ where
         coins: msg.coins,
     };
 
-    let mut events = call_in_1_out_1_handle_response(
+    let bank_guest = call_in_1_out_1_handle_response(
         app_ctx.clone(),
         msg_depth,
         0,
@@ -214,14 +269,22 @@ where
         &msg,
     )?;
 
-    if do_receive {
-        events.extend(_do_receive(app_ctx, msg_depth, msg)?);
-    }
+    let receive_guest = if do_receive {
+        Some(_do_receive(app_ctx, msg_depth, msg.clone())?)
+    } else {
+        None
+    };
 
-    Ok(events)
+    Ok(EvtTransfer {
+        sender: msg.from,
+        recipient: msg.to,
+        coins: msg.coins,
+        bank_guest,
+        receive_guest,
+    })
 }
 
-fn _do_receive<VM>(app_ctx: AppCtx<VM>, msg_depth: usize, msg: BankMsg) -> AppResult<Vec<Event>>
+fn _do_receive<VM>(app_ctx: AppCtx<VM>, msg_depth: usize, msg: BankMsg) -> AppResult<EvtGuest>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
@@ -240,6 +303,108 @@ where
     call_in_0_out_1_handle_response(app_ctx, msg_depth, 0, true, "receive", code_hash, &ctx)
 }
 
+// ------------------------------- state deposit -------------------------------
+
+/// Compare the state deposit required for the contract's storage usage with
+/// the deposit it currently holds, and transfer the difference between the
+/// contract and the bank. Return the events of the transfers made, if any.
+///
+/// The bank contract is exempt, since the transfers themselves write to its
+/// storage.
+pub(crate) fn settle_state_deposit<VM>(
+    app_ctx: AppCtx<VM>,
+    msg_depth: usize,
+    contract: Addr,
+) -> AppResult<Vec<EvtTransfer>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let cfg = CONFIG.load(&app_ctx.storage)?;
+
+    if contract == cfg.bank {
+        return Ok(vec![]);
+    }
+
+    let required = match STATE_DEPOSIT_PARAMS.may_load(&app_ctx.storage)? {
+        Some(params) => {
+            let bytes = storage_usage(&app_ctx.storage, contract)?;
+            let amount = params
+                .amount_per_byte
+                .checked_mul(Uint128::new(bytes as u128))?;
+
+            Some(Coin {
+                denom: params.denom,
+                amount,
+            })
+        },
+        None => None,
+    }
+    .filter(|coin| coin.amount.is_non_zero());
+
+    let held = STATE_DEPOSITS.may_load(&app_ctx.storage, contract)?;
+
+    // Work out how much to charge the contract, and how much to refund to it.
+    let (charge, refund) = match (&held, &required) {
+        (Some(held), Some(required)) if held.denom == required.denom => {
+            if required.amount > held.amount {
+                let coin = Coin {
+                    denom: required.denom.clone(),
+                    amount: required.amount.checked_sub(held.amount)?,
+                };
+                (Some(coin), None)
+            } else {
+                let coin = Coin {
+                    denom: held.denom.clone(),
+                    amount: held.amount.checked_sub(required.amount)?,
+                };
+                (None, Some(coin))
+            }
+        },
+        // The denom has been changed, or either one is zero: refund the entire
+        // deposit held, and charge the entire required deposit.
+        _ => (required.clone(), held.clone()),
+    };
+
+    let mut events = vec![];
+
+    if let Some(coin) = refund.filter(|coin| coin.amount.is_non_zero()) {
+        events.push(_do_transfer(
+            app_ctx.clone(),
+            msg_depth,
+            cfg.bank,
+            MsgTransfer {
+                to: contract,
+                coins: coin.into(),
+            },
+            false,
+        )?);
+    }
+
+    if let Some(coin) = charge.filter(|coin| coin.amount.is_non_zero()) {
+        events.push(_do_transfer(
+            app_ctx.clone(),
+            msg_depth,
+            contract,
+            MsgTransfer {
+                to: cfg.bank,
+                coins: coin.into(),
+            },
+            false,
+        )?);
+    }
+
+    let mut storage = app_ctx.storage;
+
+    if let Some(required) = required {
+        STATE_DEPOSITS.save(&mut storage, contract, &required)?;
+    } else if held.is_some() {
+        STATE_DEPOSITS.remove(&mut storage, contract);
+    }
+
+    Ok(events)
+}
+
 // -------------------------------- instantiate --------------------------------
 
 pub fn do_instantiate<VM>(
@@ -247,17 +412,20 @@ pub fn do_instantiate<VM>(
     msg_depth: usize,
     sender: Addr,
     msg: MsgInstantiate,
-) -> AppResult<Vec<Event>>
+) -> AppResult<Event>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
     match _do_instantiate(ctx, msg_depth, sender, msg) {
-        Ok((events, _address)) => {
+        Ok(event) => {
             #[cfg(feature = "tracing")]
-            tracing::info!(address = _address.to_string(), "Instantiated contract");
+            tracing::info!(
+                address = event.contract.to_string(),
+                "Instantiated contract"
+            );
 
-            Ok(events)
+            Ok(Event::Instantiate(event))
         },
         Err(err) => {
             #[cfg(feature = "tracing")]
@@ -268,12 +436,12 @@ where
     }
 }
 
-pub fn _do_instantiate<VM>(
+fn _do_instantiate<VM>(
     mut app_ctx: AppCtx<VM>,
     msg_depth: usize,
     sender: Addr,
     msg: MsgInstantiate,
-) -> AppResult<(Vec<Event>, Addr)>
+) -> AppResult<EvtInstantiate>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
@@ -297,7 +465,7 @@ where
 
         Ok(ContractInfo {
             code_hash: msg.code_hash,
-            label: msg.label.map(Inner::into_inner),
+            label: msg.label.clone().map(Inner::into_inner),
             admin: msg.admin,
         })
     })?;
@@ -321,10 +489,8 @@ where
     )?;
 
     // Make the fund transfer
-    let mut events = vec![];
-
-    if !msg.funds.is_empty() {
-        events.extend(_do_transfer(
+    let transfer = if !msg.funds.is_empty() {
+        Some(_do_transfer(
             app_ctx.clone(),
             msg_depth,
             sender,
@@ -333,8 +499,10 @@ where
                 coins: msg.funds.clone(),
             },
             false,
-        )?);
-    }
+        )?)
+    } else {
+        None
+    };
 
     // Call the contract's `instantiate` entry point
     let ctx = Context {
@@ -342,11 +510,11 @@ where
         block: app_ctx.block,
         contract: address,
         sender: Some(sender),
-        funds: Some(msg.funds),
+        funds: Some(msg.funds.clone()),
         mode: None,
     };
 
-    events.extend(call_in_1_out_1_handle_response(
+    let guest = call_in_1_out_1_handle_response(
         app_ctx,
         msg_depth,
         0,
@@ -355,9 +523,18 @@ where
         contract.code_hash,
         &ctx,
         &msg.msg,
-    )?);
+    )?;
 
-    Ok((events, ctx.contract))
+    Ok(EvtInstantiate {
+        sender,
+        code_hash: msg.code_hash,
+        contract: address,
+        label: contract.label,
+        admin: contract.admin,
+        funds: msg.funds,
+        transfer,
+        guest,
+    })
 }
 
 // ---------------------------------- execute ----------------------------------
@@ -367,17 +544,17 @@ pub fn do_execute<VM>(
     msg_depth: usize,
     sender: Addr,
     msg: MsgExecute,
-) -> AppResult<Vec<Event>>
+) -> AppResult<Event>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
     match _do_execute(app_ctx, msg_depth, sender, msg.clone()) {
-        Ok(events) => {
+        Ok(event) => {
             #[cfg(feature = "tracing")]
             tracing::info!(contract = msg.contract.to_string(), "Executed contract");
 
-            Ok(events)
+            Ok(Event::Execute(event))
         },
         Err(err) => {
             #[cfg(feature = "tracing")]
@@ -393,7 +570,7 @@ fn _do_execute<VM>(
     msg_depth: usize,
     sender: Addr,
     msg: MsgExecute,
-) -> AppResult<Vec<Event>>
+) -> AppResult<EvtExecute>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
@@ -401,10 +578,8 @@ where
     let code_hash = CONTRACTS.load(&app_ctx.storage, msg.contract)?.code_hash;
 
     // Make the fund transfer
-    let mut events = vec![];
-
-    if !msg.funds.is_empty() {
-        events.extend(_do_transfer(
+    let transfer = if !msg.funds.is_empty() {
+        Some(_do_transfer(
             app_ctx.clone(),
             msg_depth,
             sender,
@@ -413,8 +588,10 @@ where
                 coins: msg.funds.clone(),
             },
             false,
-        )?);
-    }
+        )?)
+    } else {
+        None
+    };
 
     // Call the contract's `execute` entry point
     let ctx = Context {
@@ -422,15 +599,21 @@ where
         block: app_ctx.block,
         contract: msg.contract,
         sender: Some(sender),
-        funds: Some(msg.funds),
+        funds: Some(msg.funds.clone()),
         mode: None,
     };
 
-    events.extend(call_in_1_out_1_handle_response(
+    let guest = call_in_1_out_1_handle_response(
         app_ctx, msg_depth, 0, true, "execute", code_hash, &ctx, &msg.msg,
-    )?);
+    )?;
 
-    Ok(events)
+    Ok(EvtExecute {
+        sender,
+        contract: msg.contract,
+        funds: msg.funds,
+        transfer,
+        guest,
+    })
 }
 
 // ---------------------------------- migrate ----------------------------------
@@ -440,17 +623,17 @@ pub fn do_migrate<VM>(
     msg_depth: usize,
     sender: Addr,
     msg: MsgMigrate,
-) -> AppResult<Vec<Event>>
+) -> AppResult<Event>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
     match _do_migrate(ctx, msg_depth, sender, msg.clone()) {
-        Ok(events) => {
+        Ok(event) => {
             #[cfg(feature = "tracing")]
             tracing::info!(contract = msg.contract.to_string(), "Migrated contract");
 
-            Ok(events)
+            Ok(Event::Migrate(event))
         },
         Err(err) => {
             #[cfg(feature = "tracing")]
@@ -466,7 +649,7 @@ fn _do_migrate<VM>(
     msg_depth: usize,
     sender: Addr,
     msg: MsgMigrate,
-) -> AppResult<Vec<Event>>
+) -> AppResult<EvtMigrate>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
@@ -536,7 +719,7 @@ where
         mode: None,
     };
 
-    call_in_1_out_1_handle_response(
+    let guest = call_in_1_out_1_handle_response(
         app_ctx,
         msg_depth,
         0,
@@ -545,7 +728,101 @@ where
         contract_info.code_hash,
         &ctx,
         &msg.msg,
-    )
+    )?;
+
+    Ok(EvtMigrate {
+        sender,
+        contract: msg.contract,
+        old_code_hash: old_code_hash.unwrap(),
+        new_code_hash: msg.new_code_hash,
+        guest,
+    })
+}
+
+// ----------------------------------- admin -----------------------------------
+
+pub fn do_update_admin(ctx: AppCtx, sender: Addr, msg: MsgUpdateAdmin) -> AppResult<Event> {
+    match _do_update_admin(ctx, sender, msg.clone()) {
+        Ok(event) => {
+            #[cfg(feature = "tracing")]
+            tracing::info!(
+                contract = msg.contract.to_string(),
+                new_admin = msg.new_admin.to_string(),
+                "Updated contract admin"
+            );
+
+            Ok(Event::UpdateAdmin(event))
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(err = err.to_string(), "Failed to update contract admin");
+
+            Err(err)
+        },
+    }
+}
+
+fn _do_update_admin(
+    mut ctx: AppCtx,
+    sender: Addr,
+    msg: MsgUpdateAdmin,
+) -> AppResult<EvtUpdateAdmin> {
+    CONTRACTS.update(&mut ctx.storage, msg.contract, |mut info| {
+        ensure_admin(&info, sender)?;
+
+        info.admin = Some(msg.new_admin);
+
+        Ok::<_, AppError>(info)
+    })?;
+
+    Ok(EvtUpdateAdmin {
+        sender,
+        contract: msg.contract,
+        new_admin: msg.new_admin,
+    })
+}
+
+pub fn do_clear_admin(ctx: AppCtx, sender: Addr, msg: MsgClearAdmin) -> AppResult<Event> {
+    match _do_clear_admin(ctx, sender, msg.clone()) {
+        Ok(event) => {
+            #[cfg(feature = "tracing")]
+            tracing::info!(
+                contract = msg.contract.to_string(),
+                "Cleared contract admin"
+            );
+
+            Ok(Event::ClearAdmin(event))
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(err = err.to_string(), "Failed to clear contract admin");
+
+            Err(err)
+        },
+    }
+}
+
+fn _do_clear_admin(mut ctx: AppCtx, sender: Addr, msg: MsgClearAdmin) -> AppResult<EvtClearAdmin> {
+    CONTRACTS.update(&mut ctx.storage, msg.contract, |mut info| {
+        ensure_admin(&info, sender)?;
+
+        info.admin = None;
+
+        Ok::<_, AppError>(info)
+    })?;
+
+    Ok(EvtClearAdmin {
+        sender,
+        contract: msg.contract,
+    })
+}
+
+fn ensure_admin(info: &ContractInfo, sender: Addr) -> AppResult<()> {
+    match info.admin {
+        Some(admin) if admin == sender => Ok(()),
+        Some(admin) => Err(AppError::NotAdmin { sender, admin }),
+        None => Err(AppError::AdminNotSet),
+    }
 }
 
 // ----------------------------------- reply -----------------------------------
@@ -556,17 +833,17 @@ pub fn do_reply<VM>(
     contract: Addr,
     msg: &Json,
     result: &SubMsgResult,
-) -> AppResult<Vec<Event>>
+) -> AppResult<Event>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
     match _do_reply(ctx, msg_depth, contract, msg, result) {
-        Ok(events) => {
+        Ok(event) => {
             #[cfg(feature = "tracing")]
             tracing::info!(contract = contract.to_string(), "Performed reply");
 
-            Ok(events)
+            Ok(Event::Reply(event))
         },
         Err(err) => {
             #[cfg(feature = "tracing")]
@@ -583,7 +860,7 @@ fn _do_reply<VM>(
     contract: Addr,
     msg: &Json,
     result: &SubMsgResult,
-) -> AppResult<Vec<Event>>
+) -> AppResult<EvtReply>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
@@ -599,18 +876,20 @@ where
         mode: None,
     };
 
-    call_in_2_out_1_handle_response(
+    let guest = call_in_2_out_1_handle_response(
         app_ctx, msg_depth, 0, true, "reply", code_hash, &ctx, msg, result,
-    )
+    )?;
+
+    Ok(EvtReply {
+        contract,
+        ok: result.is_ok(),
+        guest,
+    })
 }
 
 // ------------------------------- authenticate --------------------------------
 
-pub fn do_authenticate<VM>(
-    app_ctx: AppCtx<VM>,
-    tx: &Tx,
-    mode: AuthMode,
-) -> AppResult<(Vec<Event>, bool)>
+pub fn do_authenticate<VM>(app_ctx: AppCtx<VM>, tx: &Tx, mode: AuthMode) -> AppResult<(Event, bool)>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
@@ -626,26 +905,32 @@ where
         mode: Some(mode),
     };
 
-    let result = || -> AppResult<_> {
-        let auth_response = call_in_1_out_1::<_, _, GenericResult<AuthResponse>>(
-            app_ctx.clone(),
-            0,
-            true,
-            "authenticate",
-            code_hash,
-            &ctx,
-            tx,
-        )?
-        .map_err(|msg| AppError::Guest {
-            address: ctx.contract,
-            name: "authenticate",
-            msg,
-        })?;
-
-        let events = handle_response(app_ctx, 0, "authenticate", &ctx, auth_response.response)?;
-
-        Ok((events, auth_response.request_backrun))
-    }();
+    let result = trace_call(
+        app_ctx,
+        "authenticate",
+        &ctx,
+        || tx.to_json_value(),
+        |app_ctx| {
+            let auth_response = call_in_1_out_1::<_, _, GenericResult<AuthResponse>>(
+                app_ctx.clone(),
+                0,
+                true,
+                "authenticate",
+                code_hash,
+                &ctx,
+                tx,
+            )?
+            .map_err(|msg| AppError::Guest {
+                address: ctx.contract,
+                name: "authenticate",
+                msg,
+            })?;
+
+            let guest = handle_response(app_ctx, 0, "authenticate", &ctx, auth_response.response)?;
+
+            Ok((Event::Guest(guest), auth_response.request_backrun))
+        },
+    );
 
     match result {
         Ok(data) => {
@@ -665,7 +950,7 @@ where
 
 // ---------------------------------- backrun ----------------------------------
 
-pub fn do_backrun<VM>(app_ctx: AppCtx<VM>, tx: &Tx, mode: AuthMode) -> AppResult<Vec<Event>>
+pub fn do_backrun<VM>(app_ctx: AppCtx<VM>, tx: &Tx, mode: AuthMode) -> AppResult<Event>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
@@ -682,11 +967,11 @@ where
     };
 
     match call_in_1_out_1_handle_response(app_ctx, 0, 0, true, "backrun", code_hash, &ctx, tx) {
-        Ok(events) => {
+        Ok(guest) => {
             #[cfg(feature = "tracing")]
             tracing::debug!(sender = tx.sender.to_string(), "Backran transaction");
 
-            Ok(events)
+            Ok(Event::Guest(guest))
         },
         Err(err) => {
             #[cfg(feature = "tracing")]
@@ -699,7 +984,7 @@ where
 
 // ---------------------------------- taxman -----------------------------------
 
-pub fn do_withhold_fee<VM>(app_ctx: AppCtx<VM>, tx: &Tx, mode: AuthMode) -> AppResult<Vec<Event>>
+pub fn do_withhold_fee<VM>(app_ctx: AppCtx<VM>, tx: &Tx, mode: AuthMode) -> AppResult<Event>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
@@ -730,11 +1015,11 @@ where
     })();
 
     match result {
-        Ok(events) => {
+        Ok(guest) => {
             #[cfg(feature = "tracing")]
             tracing::debug!(sender = tx.sender.to_string(), "Withheld fee");
 
-            Ok(events)
+            Ok(Event::Guest(guest))
         },
         Err(err) => {
             #[cfg(feature = "tracing")]
@@ -750,7 +1035,7 @@ pub fn do_finalize_fee<VM>(
     tx: &Tx,
     outcome: &TxOutcome,
     mode: AuthMode,
-) -> AppResult<Vec<Event>>
+) -> AppResult<Event>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
@@ -782,11 +1067,11 @@ where
     })();
 
     match result {
-        Ok(events) => {
+        Ok(guest) => {
             #[cfg(feature = "tracing")]
             tracing::debug!(sender = tx.sender.to_string(), "Finalized fee");
 
-            Ok(events)
+            Ok(Event::Guest(guest))
         },
         Err(err) => {
             // `finalize_fee` is supposed to always succeed, so if it doesn't,
@@ -801,17 +1086,17 @@ where
 
 // ----------------------------------- cron ------------------------------------
 
-pub fn do_cron_execute<VM>(ctx: AppCtx<VM>, contract: Addr) -> AppResult<Vec<Event>>
+pub fn do_cron_execute<VM>(ctx: AppCtx<VM>, contract: Addr) -> AppResult<Event>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
     match _do_cron_execute(ctx, contract) {
-        Ok(events) => {
+        Ok(event) => {
             #[cfg(feature = "tracing")]
             tracing::info!(contract = contract.to_string(), "Performed cronjob");
 
-            Ok(events)
+            Ok(Event::Cron(event))
         },
         Err(err) => {
             #[cfg(feature = "tracing")]
@@ -826,7 +1111,7 @@ where
     }
 }
 
-fn _do_cron_execute<VM>(app_ctx: AppCtx<VM>, contract: Addr) -> AppResult<Vec<Event>>
+fn _do_cron_execute<VM>(app_ctx: AppCtx<VM>, contract: Addr) -> AppResult<EvtCron>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
@@ -841,5 +1126,8 @@ where
         mode: None,
     };
 
-    call_in_0_out_1_handle_response(app_ctx, 0, 0, true, "cron_execute", code_hash, &ctx)
+    let guest =
+        call_in_0_out_1_handle_response(app_ctx, 0, 0, true, "cron_execute", code_hash, &ctx)?;
+
+    Ok(EvtCron { contract, guest })
 }
@@ -1,5 +1,5 @@
 use {
-    crate::{GasTracker, GAS_COSTS},
+    crate::GasTracker,
     grug_storage::{Codec, IndexedMap, Item, Map, PrimaryKey},
     grug_types::{Bound, Order, Record, StdResult, Storage},
 };
@@ -27,10 +27,13 @@ where
 
         match &maybe_data {
             Some(data) => {
-                gas_tracker.consume(GAS_COSTS.db_read.cost(data.len()), "db_read/found")?;
+                gas_tracker.consume(
+                    gas_tracker.costs().db_read.cost(data.len()),
+                    "db_read/found",
+                )?;
             },
             None => {
-                gas_tracker.consume(GAS_COSTS.db_read.cost(0), "db_read/not_found")?;
+                gas_tracker.consume(gas_tracker.costs().db_read.cost(0), "db_read/not_found")?;
             },
         }
 
@@ -45,7 +48,7 @@ where
         order: Order,
     ) -> StdResult<Box<dyn Iterator<Item = StdResult<Record>> + 'a>> {
         // Gas cost for creating an iterator.
-        gas_tracker.consume(GAS_COSTS.db_scan, "db_scan")?;
+        gas_tracker.consume(gas_tracker.costs().db_scan, "db_scan")?;
 
         Ok(Box::new(self.scan(min, max, order).metered(gas_tracker)))
     }
@@ -55,6 +58,12 @@ where
 
 pub trait MeteredItem<T> {
     fn load_with_gas(&self, storage: &dyn Storage, gas_tracker: GasTracker) -> StdResult<T>;
+
+    fn may_load_with_gas(
+        &self,
+        storage: &dyn Storage,
+        gas_tracker: GasTracker,
+    ) -> StdResult<Option<T>>;
 }
 
 impl<'a, T, C> MeteredItem<T> for Item<'a, T, C>
@@ -64,10 +73,33 @@ where
     fn load_with_gas(&self, storage: &dyn Storage, gas_tracker: GasTracker) -> StdResult<T> {
         let data_raw = self.load_raw(storage)?;
 
-        gas_tracker.consume(GAS_COSTS.db_read.cost(data_raw.len()), "db_read/found")?;
+        gas_tracker.consume(
+            gas_tracker.costs().db_read.cost(data_raw.len()),
+            "db_read/found",
+        )?;
 
         C::decode(&data_raw)
     }
+
+    fn may_load_with_gas(
+        &self,
+        storage: &dyn Storage,
+        gas_tracker: GasTracker,
+    ) -> StdResult<Option<T>> {
+        match self.may_load_raw(storage) {
+            Some(data_raw) => {
+                gas_tracker.consume(
+                    gas_tracker.costs().db_read.cost(data_raw.len()),
+                    "db_read/found",
+                )?;
+                C::decode(&data_raw).map(Some)
+            },
+            None => {
+                gas_tracker.consume(gas_tracker.costs().db_read.cost(0), "db_read/not_found")?;
+                Ok(None)
+            },
+        }
+    }
 }
 
 // ------------------------------------ map ------------------------------------
@@ -79,6 +111,13 @@ where
     fn load_with_gas(&self, storage: &dyn Storage, gas_tracker: GasTracker, key: K)
         -> StdResult<T>;
 
+    fn may_load_with_gas(
+        &self,
+        storage: &dyn Storage,
+        gas_tracker: GasTracker,
+        key: K,
+    ) -> StdResult<Option<T>>;
+
     fn has_with_gas(
         &self,
         storage: &dyn Storage,
@@ -119,11 +158,35 @@ where
     ) -> StdResult<T> {
         let data_raw = self.path(key).as_path().load_raw(storage)?;
 
-        gas_tracker.consume(GAS_COSTS.db_read.cost(data_raw.len()), "db_read/found")?;
+        gas_tracker.consume(
+            gas_tracker.costs().db_read.cost(data_raw.len()),
+            "db_read/found",
+        )?;
 
         C::decode(&data_raw)
     }
 
+    fn may_load_with_gas(
+        &self,
+        storage: &dyn Storage,
+        gas_tracker: GasTracker,
+        key: K,
+    ) -> StdResult<Option<T>> {
+        match self.path(key).as_path().may_load_raw(storage) {
+            Some(data_raw) => {
+                gas_tracker.consume(
+                    gas_tracker.costs().db_read.cost(data_raw.len()),
+                    "db_read/found",
+                )?;
+                C::decode(&data_raw).map(Some)
+            },
+            None => {
+                gas_tracker.consume(gas_tracker.costs().db_read.cost(0), "db_read/not_found")?;
+                Ok(None)
+            },
+        }
+    }
+
     fn has_with_gas(
         &self,
         storage: &dyn Storage,
@@ -132,11 +195,14 @@ where
     ) -> StdResult<bool> {
         match self.path(key).as_path().may_load_raw(storage) {
             Some(data) => {
-                gas_tracker.consume(GAS_COSTS.db_read.cost(data.len()), "db_read/found")?;
+                gas_tracker.consume(
+                    gas_tracker.costs().db_read.cost(data.len()),
+                    "db_read/found",
+                )?;
                 Ok(true)
             },
             None => {
-                gas_tracker.consume(GAS_COSTS.db_read.cost(0), "db_read/not_found")?;
+                gas_tracker.consume(gas_tracker.costs().db_read.cost(0), "db_read/not_found")?;
                 Ok(false)
             },
         }
@@ -154,7 +220,7 @@ where
         T: 'b,
     {
         // Gas cost for creating an iterator.
-        gas_tracker.consume(GAS_COSTS.db_scan, "db_scan")?;
+        gas_tracker.consume(gas_tracker.costs().db_scan, "db_scan")?;
 
         let iter = self
             .range_raw(storage, min, max, order)
@@ -179,7 +245,8 @@ where
         let data_raw = C::encode(value)?;
         let path = self.path(key);
 
-        let gas_cost = GAS_COSTS
+        let gas_cost = gas_tracker
+            .costs()
             .db_write
             .cost(data_raw.len() + path.as_path().storage_key().len());
 
@@ -209,6 +276,15 @@ where
         self.primary.load_with_gas(storage, gas_tracker, key)
     }
 
+    fn may_load_with_gas(
+        &self,
+        storage: &dyn Storage,
+        gas_tracker: GasTracker,
+        key: K,
+    ) -> StdResult<Option<T>> {
+        self.primary.may_load_with_gas(storage, gas_tracker, key)
+    }
+
     fn has_with_gas(
         &self,
         storage: &dyn Storage,
@@ -274,7 +350,10 @@ where
         if let Some((k_raw, v_raw)) = self.iter.next() {
             // A record is found. We charge both the cost for advancing the
             // iterator (`db_next`) and for reading the record (`db_read`).
-            let cost = GAS_COSTS.db_next + GAS_COSTS.db_read.cost(k_raw.len() + v_raw.len());
+            let costs = self.gas_tracker.costs();
+            let cost = costs
+                .db_next
+                .saturating_add(costs.db_read.cost(k_raw.len() + v_raw.len()));
 
             match self.gas_tracker.consume(cost, "db_next/found") {
                 Ok(()) => Some(Ok((k_raw, v_raw))),
@@ -283,7 +362,7 @@ where
         } else {
             // No record is found; iterator has reached its end.
             // Charge only the cost for advanding iterator.
-            let cost = GAS_COSTS.db_next;
+            let cost = self.gas_tracker.costs().db_next;
 
             match self.gas_tracker.consume(cost, "db_next/not_found") {
                 Ok(()) => None,
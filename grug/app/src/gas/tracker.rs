@@ -1,20 +1,95 @@
 use {
     crate::Shared,
-    grug_types::{StdError, StdResult},
-    std::fmt::{self, Display},
+    grug_types::{Addr, GasBreakdown, GasCosts, GasProfile, StdError, StdResult},
+    std::{
+        fmt::{self, Display},
+        sync::Arc,
+    },
 };
 
+/// Category of gas consumed by executing Wasm instructions.
+const WASM_INSTRUCTIONS: &str = "wasm_instructions";
+
 struct GasTrackerInner {
     // `None` means there is no gas limit. This is the case during genesis, and
     // for begin/end blockers.
     limit: Option<u64>,
     used: u64,
+    breakdown: GasBreakdown,
+    // The contracts currently being executed, from the outermost to the
+    // innermost. Gas consumed is attributed to the innermost one.
+    contracts: Vec<Addr>,
+    // `None` unless profiling is enabled.
+    profile: Option<GasProfile>,
+}
+
+impl GasTrackerInner {
+    // Attribute the consumed gas to the operation in the breakdown, and in the
+    // profile if profiling is enabled. This doesn't change the gas used.
+    fn attribute(&mut self, consumed: u64, comment: &'static str) {
+        let category = category(comment);
+
+        *breakdown_field(&mut self.breakdown, category) += consumed;
+
+        if let Some(profile) = &mut self.profile {
+            *profile.by_category.entry(category.to_string()).or_default() += consumed;
+
+            if let Some(contract) = self.contracts.last() {
+                *profile.by_contract.entry(*contract).or_default() += consumed;
+            }
+        }
+    }
+}
+
+/// Return the category of the operation of the given comment.
+///
+/// Host functions are charged under their own names, with the `/found` or
+/// `/not_found` suffix removed if there is one. Everything else is consumed by
+/// the VM executing contract code, under the name of the function being called.
+fn category(comment: &'static str) -> &'static str {
+    if comment.starts_with("db_") || comment.starts_with("storage_") {
+        return comment.split('/').next().unwrap_or(comment);
+    }
+
+    match comment {
+        "secp256r1_verify"
+        | "secp256k1_verify"
+        | "secp256k1_pubkey_recover"
+        | "ed25519_verify"
+        | "ed25519_batch_verify"
+        | "sha2_256"
+        | "sha2_512"
+        | "sha2_512_truncated"
+        | "sha3_256"
+        | "sha3_512"
+        | "sha3_512_truncated"
+        | "keccak256"
+        | "blake2s_256"
+        | "blake2b_512"
+        | "blake3" => comment,
+        _ => WASM_INSTRUCTIONS,
+    }
+}
+
+/// Return a mutable reference to the field in the breakdown that gas of the
+/// given category belongs to.
+fn breakdown_field<'a>(breakdown: &'a mut GasBreakdown, category: &str) -> &'a mut u64 {
+    if category == WASM_INSTRUCTIONS {
+        &mut breakdown.wasm
+    } else if category.starts_with("db_") || category.starts_with("storage_") {
+        &mut breakdown.storage
+    } else {
+        &mut breakdown.crypto
+    }
 }
 
 /// Tracks gas consumption; throws error if gas limit is exceeded.
 #[derive(Clone)]
 pub struct GasTracker {
     inner: Shared<GasTrackerInner>,
+    /// The gas costs of host operations, which are read from the chain state
+    /// at the beginning of each block.
+    costs: Arc<GasCosts>,
 }
 
 impl GasTracker {
@@ -24,7 +99,11 @@ impl GasTracker {
             inner: Shared::new(GasTrackerInner {
                 limit: maybe_limit,
                 used: 0,
+                breakdown: GasBreakdown::default(),
+                contracts: vec![],
+                profile: None,
             }),
+            costs: Arc::new(GasCosts::default()),
         }
     }
 
@@ -34,7 +113,11 @@ impl GasTracker {
             inner: Shared::new(GasTrackerInner {
                 limit: None,
                 used: 0,
+                breakdown: GasBreakdown::default(),
+                contracts: vec![],
+                profile: None,
             }),
+            costs: Arc::new(GasCosts::default()),
         }
     }
 
@@ -44,10 +127,34 @@ impl GasTracker {
             inner: Shared::new(GasTrackerInner {
                 limit: Some(limit),
                 used: 0,
+                breakdown: GasBreakdown::default(),
+                contracts: vec![],
+                profile: None,
             }),
+            costs: Arc::new(GasCosts::default()),
         }
     }
 
+    /// Use the given gas costs instead of the default ones.
+    pub fn with_costs(mut self, costs: GasCosts) -> Self {
+        self.costs = Arc::new(costs);
+        self
+    }
+
+    /// Record a detailed breakdown of the gas consumption, which can then be
+    /// retrieved with [`profile`](Self::profile).
+    ///
+    /// This is enabled when simulating transactions.
+    pub fn with_profiling(self) -> Self {
+        self.inner.write_access().profile = Some(GasProfile::default());
+        self
+    }
+
+    /// Return the gas costs of host operations.
+    pub fn costs(&self) -> &GasCosts {
+        &self.costs
+    }
+
     /// Return the gas limit. `None` if there isn't a limit.
     ///
     /// Panics if lock is poisoned.
@@ -62,6 +169,22 @@ impl GasTracker {
         self.inner.read_access().used
     }
 
+    /// Return the amount of gas already used, broken down by the kind of
+    /// operation that used it.
+    ///
+    /// Panics if lock is poisoned.
+    pub fn breakdown(&self) -> GasBreakdown {
+        self.inner.read_access().breakdown
+    }
+
+    /// Return the detailed breakdown of gas consumption. `None` if profiling
+    /// isn't enabled.
+    ///
+    /// Panics if lock is poisoned.
+    pub fn profile(&self) -> Option<GasProfile> {
+        self.inner.read_access().profile.clone()
+    }
+
     /// Return the amount of gas remaining. `None` if there isn't a limit.
     ///
     /// Panics if lock is poisoned.
@@ -74,10 +197,14 @@ impl GasTracker {
 
     /// Consume the given amount of gas. Error if the limit is exceeded.
     ///
+    /// If the limit is exceeded, the gas used isn't changed, but the remaining
+    /// gas is attributed to the operation that ran out of gas in the breakdown
+    /// and the profile, so that it shows up as the culprit.
+    ///
     /// Panics if lock is poisoned.
     pub fn consume(&self, consumed: u64, comment: &'static str) -> StdResult<()> {
         self.inner.write_with(|mut inner| {
-            let used = inner.used + consumed;
+            let used = inner.used.saturating_add(consumed);
 
             // If there is a limit, and the limit is exceeded, then throw error.
             if let Some(limit) = inner.limit {
@@ -85,6 +212,9 @@ impl GasTracker {
                     #[cfg(feature = "tracing")]
                     tracing::warn!(limit = inner.limit, used, comment, "Out of gas");
 
+                    let remaining = limit - inner.used;
+                    inner.attribute(remaining, comment);
+
                     return Err(StdError::OutOfGas {
                         limit,
                         used,
@@ -97,10 +227,28 @@ impl GasTracker {
             tracing::debug!(limit = inner.limit, consumed, comment, "Gas consumed");
 
             inner.used = used;
+            inner.attribute(consumed, comment);
 
             Ok(())
         })
     }
+
+    /// Perform an action that executes the given contract, attributing gas
+    /// consumed during the action to this contract.
+    ///
+    /// Panics if lock is poisoned.
+    pub fn within_contract<F, T>(&self, contract: Addr, action: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        self.inner.write_access().contracts.push(contract);
+
+        let output = action();
+
+        self.inner.write_access().contracts.pop();
+
+        output
+    }
 }
 
 impl Display for GasTracker {
@@ -114,3 +262,101 @@ impl Display for GasTracker {
         })
     }
 }
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::collections::BTreeMap};
+
+    #[test]
+    fn profiling_works() {
+        let gas_tracker = GasTracker::new_limitless().with_profiling();
+        let alice = Addr::mock(1);
+        let bob = Addr::mock(2);
+
+        // Gas consumed outside of contract calls isn't attributed to any contract.
+        gas_tracker.consume(100, "db_read/found").unwrap();
+
+        gas_tracker
+            .within_contract(alice, || -> StdResult<()> {
+                gas_tracker.consume(10, "execute")?;
+                gas_tracker.consume(20, "db_read/not_found")?;
+
+                // Alice queries Bob.
+                gas_tracker.within_contract(bob, || -> StdResult<()> {
+                    gas_tracker.consume(30, "query")?;
+                    gas_tracker.consume(40, "sha2_256")
+                })?;
+
+                gas_tracker.consume(50, "wasm_instructions")?;
+                gas_tracker.consume(60, "db_write")
+            })
+            .unwrap();
+
+        assert_eq!(gas_tracker.used(), 310);
+        assert_eq!(gas_tracker.breakdown(), GasBreakdown {
+            storage: 180,
+            crypto: 40,
+            wasm: 90,
+        });
+        assert_eq!(
+            gas_tracker.profile(),
+            Some(GasProfile {
+                by_category: BTreeMap::from([
+                    ("db_read".to_string(), 120),
+                    ("db_write".to_string(), 60),
+                    ("sha2_256".to_string(), 40),
+                    ("wasm_instructions".to_string(), 90),
+                ]),
+                by_contract: BTreeMap::from([(alice, 140), (bob, 70)]),
+            })
+        );
+    }
+
+    #[test]
+    fn out_of_gas_is_attributed_to_the_operation() {
+        let gas_tracker = GasTracker::new_limited(100).with_profiling();
+        let alice = Addr::mock(1);
+
+        gas_tracker
+            .within_contract(alice, || -> StdResult<()> {
+                gas_tracker.consume(30, "execute")?;
+                gas_tracker.consume(1_000, "sha2_256")
+            })
+            .unwrap_err();
+
+        // The gas used is unchanged, since it goes into the tx outcome, which
+        // is part of consensus. But the remaining 70 gas is attributed to the
+        // hasher, which ran out of gas.
+        assert_eq!(gas_tracker.used(), 30);
+        assert_eq!(gas_tracker.remaining(), Some(70));
+        assert_eq!(gas_tracker.breakdown(), GasBreakdown {
+            storage: 0,
+            crypto: 70,
+            wasm: 30,
+        });
+        assert_eq!(
+            gas_tracker.profile(),
+            Some(GasProfile {
+                by_category: BTreeMap::from([
+                    ("sha2_256".to_string(), 70),
+                    ("wasm_instructions".to_string(), 30),
+                ]),
+                by_contract: BTreeMap::from([(alice, 100)]),
+            })
+        );
+    }
+
+    #[test]
+    fn profiling_is_disabled_by_default() {
+        let gas_tracker = GasTracker::new_limitless();
+
+        gas_tracker
+            .within_contract(Addr::mock(1), || gas_tracker.consume(10, "execute"))
+            .unwrap();
+
+        assert_eq!(gas_tracker.used(), 10);
+        assert_eq!(gas_tracker.profile(), None);
+    }
+}
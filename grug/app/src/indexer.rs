@@ -0,0 +1,49 @@
+use {
+    crate::{AppError, Indexer},
+    grug_types::{BlockInfo, BlockOutcome, Hash256, Tx},
+    std::{
+        convert::Infallible,
+        fmt::{self, Display},
+    },
+};
+
+/// An indexer that does nothing.
+///
+/// This is the default indexer used by the app, for nodes that don't need to
+/// record blocks and transactions beyond what's in the state.
+#[derive(Debug, Clone, Copy)]
+pub struct NullIndexer;
+
+impl Indexer for NullIndexer {
+    type Error = NullIndexerError;
+
+    fn index_block(
+        &self,
+        _block: &BlockInfo,
+        _txs: &[(Tx, Hash256)],
+        _outcome: &BlockOutcome,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn commit(&self, _height: u64) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// A placeholder error type that is never constructed.
+/// Used in conjunction with [`NullIndexer`](crate::NullIndexer).
+#[derive(Debug, Clone, Copy)]
+pub struct NullIndexerError(Infallible);
+
+impl Display for NullIndexerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<NullIndexerError> for AppError {
+    fn from(err: NullIndexerError) -> Self {
+        AppError::Indexer(err.to_string())
+    }
+}
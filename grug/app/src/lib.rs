@@ -4,19 +4,22 @@ mod app;
 mod buffer;
 mod context;
 mod error;
-mod events;
 mod execute;
 mod gas;
+mod indexer;
 mod proposal;
 mod providers;
 mod query;
 mod shared;
 mod state;
 mod submessage;
+mod tracer;
+mod tracked;
 mod traits;
 mod vm;
 
 pub use crate::{
-    app::*, buffer::*, context::*, error::*, events::*, execute::*, gas::*, proposal::*,
-    providers::*, query::*, shared::*, state::*, submessage::*, traits::*, vm::*,
+    app::*, buffer::*, context::*, error::*, execute::*, gas::*, indexer::*, proposal::*,
+    providers::*, query::*, shared::*, state::*, submessage::*, tracer::*, tracked::*, traits::*,
+    vm::*,
 };
@@ -2,9 +2,14 @@
 use tracing::info;
 use {
     crate::{AppError, ProposalPreparer},
-    grug_types::{Querier, QuerierWrapper, Query, QueryResponse, StdResult},
+    grug_math::Udec128,
+    grug_types::{
+        json, Addr, JsonDeExt, Querier, QuerierWrapper, Query, QueryResponse, StdResult, Tx,
+    },
     prost::bytes::Bytes,
     std::{
+        cmp::Reverse,
+        collections::{BTreeMap, BinaryHeap, VecDeque},
         convert::Infallible,
         fmt::{self, Display},
     },
@@ -47,6 +52,176 @@ impl ProposalPreparer for NaiveProposalPreparer {
     }
 }
 
+/// A proposal preparer that prioritizes transactions paying higher fees, and
+/// limits how much of the block a single sender can take up.
+///
+/// Transactions are decoded and checked against a few rules that don't require
+/// executing them. Those that fail to decode, request zero gas or more than the
+/// block gas limit, or offer a gas price lower than the taxman's base fee, are
+/// dropped. The rest are included in descending order of gas price, until the
+/// block's total gas limit or size limit is reached.
+///
+/// The block gas limit is the lower of the one given to the preparer and the
+/// one set for the chain.
+///
+/// A transaction's gas price is read from the `gas_price` field of its `data`.
+/// If not present, the transaction is assumed to pay the base fee.
+///
+/// Transactions from the same sender are kept in the order they are received,
+/// as they typically need to be executed in sequence.
+#[derive(Debug, Clone, Copy)]
+pub struct PriorityProposalPreparer {
+    max_block_gas: u64,
+    max_txs_per_sender: usize,
+    base_fee_query: BaseFeeQuery,
+}
+
+/// A function that queries the taxman contract of the given address for the
+/// minimum gas price.
+pub type BaseFeeQuery = fn(QuerierWrapper, Addr) -> StdResult<Udec128>;
+
+impl PriorityProposalPreparer {
+    pub fn new(max_block_gas: u64, max_txs_per_sender: usize) -> Self {
+        Self {
+            max_block_gas,
+            max_txs_per_sender,
+            base_fee_query: query_base_fee,
+        }
+    }
+
+    /// Use a custom function to query the base fee, for taxman contracts that
+    /// don't follow the default format. See [`query_base_fee`].
+    pub fn with_base_fee_query(mut self, base_fee_query: BaseFeeQuery) -> Self {
+        self.base_fee_query = base_fee_query;
+        self
+    }
+}
+
+/// Query the base fee with the taxman's `{"base_fee":{}}` query, which returns
+/// the minimum gas price for the current block, e.g. as adjusted by an EIP-1559
+/// fee market.
+pub fn query_base_fee(querier: QuerierWrapper, taxman: Addr) -> StdResult<Udec128> {
+    querier
+        .query(Query::wasm_smart(taxman, &json!({ "base_fee": {} }))?)?
+        .as_wasm_smart()
+        .deserialize_json()
+}
+
+impl Default for PriorityProposalPreparer {
+    fn default() -> Self {
+        Self::new(u64::MAX, usize::MAX)
+    }
+}
+
+struct Candidate {
+    raw: Bytes,
+    gas_limit: u64,
+    gas_price: Udec128,
+}
+
+impl ProposalPreparer for PriorityProposalPreparer {
+    type Error = AppError;
+
+    fn prepare_proposal(
+        &self,
+        querier: QuerierWrapper,
+        txs: Vec<Bytes>,
+        max_tx_bytes: usize,
+    ) -> Result<Vec<Bytes>, Self::Error> {
+        // The block can't take more gas than the chain allows, regardless of
+        // the preparer's own limit.
+        let max_block_gas = self.max_block_gas.min(querier.query_max_block_gas()?);
+
+        let taxman = querier.query_config()?.taxman;
+        let base_fee = (self.base_fee_query)(querier, taxman)?;
+
+        // Decode the transactions and drop the invalid ones. Group the rest by
+        // sender, in the order they are received. Also remember the order in
+        // which the senders are first seen, which is used to break ties.
+        let mut queues = BTreeMap::<Addr, (usize, VecDeque<Candidate>)>::new();
+
+        for raw in txs {
+            let Ok(tx) = raw.deserialize_json::<Tx>() else {
+                continue;
+            };
+
+            if tx.gas_limit == 0 || tx.gas_limit > max_block_gas {
+                continue;
+            }
+
+            let gas_price = match tx.data.get("gas_price") {
+                Some(gas_price) => match gas_price.clone().deserialize_json() {
+                    Ok(gas_price) => gas_price,
+                    Err(_) => continue,
+                },
+                None => base_fee,
+            };
+
+            if gas_price < base_fee {
+                continue;
+            }
+
+            let num_senders = queues.len();
+            let (_, queue) = queues
+                .entry(tx.sender)
+                .or_insert_with(|| (num_senders, VecDeque::new()));
+
+            if queue.len() >= self.max_txs_per_sender {
+                continue;
+            }
+
+            queue.push_back(Candidate {
+                raw,
+                gas_limit: tx.gas_limit,
+                gas_price,
+            });
+        }
+
+        // Repeatedly pick the highest paying transaction among the first ones
+        // of each sender.
+        let mut heap = queues
+            .iter()
+            .filter_map(|(sender, (order, queue))| {
+                queue
+                    .front()
+                    .map(|first| (first.gas_price, Reverse(*order), *sender))
+            })
+            .collect::<BinaryHeap<_>>();
+
+        let mut selected = vec![];
+        let mut total_gas: u64 = 0;
+        let mut total_tx_bytes: usize = 0;
+
+        while let Some((_, order, sender)) = heap.pop() {
+            let (_, queue) = queues.get_mut(&sender).unwrap();
+            let candidate = queue.pop_front().unwrap();
+
+            let new_total_gas = total_gas.saturating_add(candidate.gas_limit);
+            let new_total_tx_bytes = total_tx_bytes.saturating_add(candidate.raw.len());
+
+            // If the transaction doesn't fit, the sender's subsequent ones are
+            // also dropped, as they may depend on it. Other senders' transactions
+            // may still fit.
+            if new_total_gas > max_block_gas || new_total_tx_bytes > max_tx_bytes {
+                continue;
+            }
+
+            total_gas = new_total_gas;
+            total_tx_bytes = new_total_tx_bytes;
+            selected.push(candidate.raw);
+
+            if let Some(next) = queue.front() {
+                heap.push((next.gas_price, order, sender));
+            }
+        }
+
+        #[cfg(feature = "tracing")]
+        info!(num_txs = selected.len(), total_gas, "Prepared proposal");
+
+        Ok(selected)
+    }
+}
+
 /// A placeholder error type that is never constructed.
 /// Used in conjunction with [`NaiveProposalPreparer`](crate::NaiveProposalPreparer).
 #[derive(Debug, Clone, Copy)]
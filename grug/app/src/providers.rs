@@ -1,21 +1,36 @@
 use {
-    crate::{process_query, AppCtx, AppError, Vm},
+    crate::{process_query, AppCtx, AppError, Vm, STORAGE_USAGES, STORAGE_USAGE_CHANGES},
     grug_types::{
-        concat, increment_last_byte, trim, GenericResult, GenericResultExt, Order, Querier, Query,
-        QueryResponse, Record, StdError, StdResult, Storage,
+        concat, increment_last_byte, trim, Addr, GasCosts, GenericResult, GenericResultExt, Order,
+        Querier, Query, QueryResponse, Record, StdError, StdResult, Storage,
     },
 };
 
+/// Size of a storage usage, which is a Borsh-encoded `u64`, in bytes.
+const USAGE_SIZE: usize = 8;
+
 // ---------------------------------- storage ----------------------------------
 
 /// Provides access to an account's storage to the VM.
 ///
 /// Essentially, this is a prefixed key-value storage. In Grug, the prefix is
 /// the single byte `b"w"` (referring to Wasm) followed by the account address.
+///
+/// Optionally, it keeps track of the number of bytes stored under the prefix,
+/// for the purpose of state deposit. The storage accesses this takes are
+/// priced, and the VM is expected to charge the gas, which it can get with
+/// [`take_usage_gas`](Self::take_usage_gas).
 #[derive(Clone)]
 pub struct StorageProvider {
     storage: Box<dyn Storage>,
     namespace: Vec<u8>,
+    // If set, the net change in the number of bytes stored is recorded under
+    // this contract in `STORAGE_USAGES`.
+    usage_owner: Option<Addr>,
+    // Gas costs used to price the storage accesses made for usage tracking.
+    gas_costs: GasCosts,
+    // Gas consumed by usage tracking that hasn't been charged yet.
+    usage_gas: u64,
 }
 
 impl StorageProvider {
@@ -30,12 +45,73 @@ impl StorageProvider {
             namespace.extend_from_slice(prefix);
         }
 
-        Self { storage, namespace }
+        Self {
+            storage,
+            namespace,
+            usage_owner: None,
+            gas_costs: GasCosts::default(),
+            usage_gas: 0,
+        }
+    }
+
+    /// Record the number of bytes stored under the given contract, pricing the
+    /// storage accesses this takes with the given gas costs.
+    pub fn with_usage_tracking(mut self, contract: Addr, gas_costs: GasCosts) -> Self {
+        self.usage_owner = Some(contract);
+        self.gas_costs = gas_costs;
+        self
     }
 
     pub fn namespace(&self) -> &[u8] {
         &self.namespace
     }
+
+    /// Return the gas consumed by usage tracking since the last time this was
+    /// called. The VM should call this after each write or removal, and charge
+    /// the gas to the contract.
+    pub fn take_usage_gas(&mut self) -> u64 {
+        std::mem::take(&mut self.usage_gas)
+    }
+
+    fn consume_usage_gas(&mut self, gas: u64) {
+        self.usage_gas = self.usage_gas.saturating_add(gas);
+    }
+
+    fn update_usage(&mut self, added: usize, removed: usize) {
+        let Some(contract) = self.usage_owner else {
+            return;
+        };
+
+        if added == removed {
+            return;
+        }
+
+        // Reading and writing the usage.
+        let key_len = STORAGE_USAGES.path(contract).storage_key().len();
+        self.consume_usage_gas(
+            self.gas_costs
+                .db_read
+                .cost(USAGE_SIZE)
+                .saturating_add(self.gas_costs.db_write.cost(key_len + USAGE_SIZE)),
+        );
+
+        // Only record the change here. It's applied to the usage once the
+        // transaction is done, with `apply_storage_usage_changes`.
+        let change = STORAGE_USAGE_CHANGES
+            .may_load(&self.storage, contract)
+            .unwrap_or_else(|err| {
+                panic!("failed to load storage usage change of {contract}: {err}");
+            })
+            .unwrap_or(0)
+            .saturating_add(added as i64)
+            .saturating_sub(removed as i64);
+
+        STORAGE_USAGE_CHANGES
+            .save(&mut self.storage, contract, &change)
+            .unwrap_or_else(|err| {
+                panic!("failed to save storage usage change of {contract}: {err}");
+            });
+    }
 }
 
 impl Storage for StorageProvider {
@@ -86,20 +162,99 @@ impl Storage for StorageProvider {
 
     fn write(&mut self, key: &[u8], value: &[u8]) {
         let prefixed_key = concat(&self.namespace, key);
+
+        if self.usage_owner.is_some() {
+            let old_value = self.storage.read(&prefixed_key);
+            self.consume_usage_gas(
+                self.gas_costs
+                    .db_read
+                    .cost(old_value.as_ref().map_or(0, |v| v.len())),
+            );
+
+            let removed = old_value
+                .map(|old_value| key.len() + old_value.len())
+                .unwrap_or(0);
+            self.update_usage(key.len() + value.len(), removed);
+        }
+
         self.storage.write(&prefixed_key, value);
     }
 
     fn remove(&mut self, key: &[u8]) {
         let prefixed_key = concat(&self.namespace, key);
+
+        if self.usage_owner.is_some() {
+            let old_value = self.storage.read(&prefixed_key);
+            self.consume_usage_gas(
+                self.gas_costs
+                    .db_read
+                    .cost(old_value.as_ref().map_or(0, |v| v.len())),
+            );
+
+            if let Some(old_value) = old_value {
+                self.update_usage(0, key.len() + old_value.len());
+            }
+        }
+
         self.storage.remove(&prefixed_key);
     }
 
     fn remove_range(&mut self, min: Option<&[u8]>, max: Option<&[u8]>) {
         let (min, max) = prefixed_range_bounds(&self.namespace, min, max);
+
+        if self.usage_owner.is_some() {
+            // Priced the same as iterating through the records.
+            let mut gas = self.gas_costs.db_scan;
+            let mut removed = 0;
+
+            for (key, value) in self.storage.scan(Some(&min), Some(&max), Order::Ascending) {
+                gas = gas
+                    .saturating_add(self.gas_costs.db_next)
+                    .saturating_add(self.gas_costs.db_read.cost(key.len() + value.len()));
+                removed += key.len() - self.namespace.len() + value.len();
+            }
+
+            self.consume_usage_gas(gas);
+            self.update_usage(0, removed);
+        }
+
         self.storage.remove_range(Some(&min), Some(&max))
     }
 }
 
+/// Return the number of bytes stored by a contract, including the changes made
+/// by the transaction or cronjob being executed.
+pub fn storage_usage(storage: &dyn Storage, contract: Addr) -> StdResult<u64> {
+    let usage = STORAGE_USAGES.may_load(storage, contract)?.unwrap_or(0);
+    let change = STORAGE_USAGE_CHANGES
+        .may_load(storage, contract)?
+        .unwrap_or(0);
+
+    Ok(usage.saturating_add_signed(change))
+}
+
+/// Apply the storage usage changes made by the transaction or cronjob that has
+/// just been executed, and clear them. Return the contracts whose usages have
+/// been changed.
+///
+/// Data written before usage tracking was introduced isn't counted, so the
+/// usage may underflow if such data is deleted. Saturate at zero.
+pub fn apply_storage_usage_changes(storage: &mut dyn Storage) -> StdResult<Vec<Addr>> {
+    let changes = STORAGE_USAGE_CHANGES
+        .range(storage, None, None, Order::Ascending)
+        .filter(|res| !matches!(res, Ok((_, 0))))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (contract, change) in &changes {
+        let usage = STORAGE_USAGES.may_load(storage, *contract)?.unwrap_or(0);
+        STORAGE_USAGES.save(storage, *contract, &usage.saturating_add_signed(*change))?;
+    }
+
+    STORAGE_USAGE_CHANGES.clear(storage, None, None);
+
+    Ok(changes.into_iter().map(|(contract, _)| contract).collect())
+}
+
 #[inline]
 fn prefixed_range_bounds(
     prefix: &[u8],
@@ -155,3 +310,71 @@ where
         self.do_query_chain(req, 0).map_err(StdError::host)
     }
 }
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::Shared, grug_types::MockStorage};
+
+    #[test]
+    fn usage_tracking_is_metered() {
+        let contract = Addr::mock(1);
+        let costs = GasCosts::default();
+        let mut storage = Shared::new(MockStorage::new());
+        let mut provider = StorageProvider::new(Box::new(storage.clone()), &[b"prefix"])
+            .with_usage_tracking(contract, costs.clone());
+
+        // Nothing has been done yet.
+        assert_eq!(provider.take_usage_gas(), 0);
+
+        // Writing reads the old value, and updates the usage.
+        provider.write(b"foo", b"bar");
+        let key_len = STORAGE_USAGES.path(contract).storage_key().len();
+        let update_cost =
+            costs.db_read.cost(USAGE_SIZE) + costs.db_write.cost(key_len + USAGE_SIZE);
+        assert_eq!(
+            provider.take_usage_gas(),
+            costs.db_read.cost(0) + update_cost
+        );
+        assert_eq!(storage_usage(&storage, contract).unwrap(), 6);
+
+        // The gas is only taken once.
+        assert_eq!(provider.take_usage_gas(), 0);
+
+        // Overwriting with a value of the same size doesn't update the usage.
+        provider.write(b"foo", b"baz");
+        assert_eq!(provider.take_usage_gas(), costs.db_read.cost(3));
+
+        // Removing a range scans through the records.
+        provider.remove_range(None, None);
+        assert_eq!(
+            provider.take_usage_gas(),
+            costs.db_scan
+                + costs.db_next
+                + costs.db_read.cost(b"prefixfoo".len() + 3)
+                + update_cost
+        );
+        assert_eq!(storage_usage(&storage, contract).unwrap(), 0);
+
+        // The changes are only applied to the usage afterwards. Contracts
+        // whose usages end up unchanged are skipped.
+        assert_eq!(apply_storage_usage_changes(&mut storage).unwrap(), vec![]);
+        assert!(STORAGE_USAGES
+            .may_load(&storage, contract)
+            .unwrap()
+            .is_none());
+
+        provider.write(b"foo", b"bar");
+        assert!(STORAGE_USAGES
+            .may_load(&storage, contract)
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            apply_storage_usage_changes(&mut storage).unwrap(),
+            vec![contract]
+        );
+        assert_eq!(STORAGE_USAGES.load(&storage, contract).unwrap(), 6);
+        assert!(STORAGE_USAGE_CHANGES.is_empty(&storage));
+    }
+}
@@ -1,15 +1,16 @@
 use {
     crate::{
         call_in_1_out_1, AppCtx, AppError, AppResult, MeteredItem, MeteredMap, MeteredStorage,
-        StorageProvider, Vm, APP_CONFIGS, CODES, CONFIG, CONTRACTS, CONTRACT_NAMESPACE,
+        StorageProvider, Vm, APP_CONFIGS, CODES, CONFIG, CONTRACTS, CONTRACT_NAMESPACE, GAS_COSTS,
+        MAX_BLOCK_GAS, STATE_DEPOSITS, STATE_DEPOSIT_PARAMS, STORAGE_USAGES, STORAGE_USAGE_CHANGES,
     },
     grug_types::{
         Addr, BankQuery, BankQueryResponse, Binary, Bound, Code, Coin, Coins, Config, Context,
-        ContractInfo, GenericResult, Hash256, Json, Order, QueryAppConfigRequest,
+        ContractInfo, GasCosts, GenericResult, Hash256, Json, Order, QueryAppConfigRequest,
         QueryAppConfigsRequest, QueryBalanceRequest, QueryBalancesRequest, QueryCodeRequest,
-        QueryCodesRequest, QueryContractRequest, QueryContractsRequest, QuerySuppliesRequest,
-        QuerySupplyRequest, QueryWasmRawRequest, QueryWasmScanRequest, QueryWasmSmartRequest,
-        StdResult,
+        QueryCodesRequest, QueryContractRequest, QueryContractsRequest, QueryStateDepositRequest,
+        QuerySuppliesRequest, QuerySupplyRequest, QueryWasmRawRequest, QueryWasmScanRequest,
+        QueryWasmSmartRequest, StateDeposit, StateDepositParams, StdResult,
     },
     std::collections::BTreeMap,
 };
@@ -20,6 +21,22 @@ pub fn query_config(ctx: AppCtx) -> StdResult<Config> {
     CONFIG.load_with_gas(&ctx.storage, ctx.gas_tracker)
 }
 
+pub fn query_gas_costs(ctx: AppCtx) -> StdResult<GasCosts> {
+    Ok(GAS_COSTS
+        .may_load_with_gas(&ctx.storage, ctx.gas_tracker)?
+        .unwrap_or_default())
+}
+
+pub fn query_max_block_gas(ctx: AppCtx) -> StdResult<u64> {
+    Ok(MAX_BLOCK_GAS
+        .may_load_with_gas(&ctx.storage, ctx.gas_tracker)?
+        .unwrap_or(u64::MAX))
+}
+
+pub fn query_state_deposit_params(ctx: AppCtx) -> StdResult<Option<StateDepositParams>> {
+    STATE_DEPOSIT_PARAMS.may_load_with_gas(&ctx.storage, ctx.gas_tracker)
+}
+
 pub fn query_app_config(ctx: AppCtx, req: QueryAppConfigRequest) -> StdResult<Json> {
     APP_CONFIGS.load_with_gas(&ctx.storage, ctx.gas_tracker, &req.key)
 }
@@ -153,6 +170,22 @@ pub fn query_contracts(
         .collect()
 }
 
+pub fn query_state_deposit(ctx: AppCtx, req: QueryStateDepositRequest) -> StdResult<StateDeposit> {
+    let bytes = STORAGE_USAGES
+        .may_load_with_gas(&ctx.storage, ctx.gas_tracker.clone(), req.contract)?
+        .unwrap_or(0)
+        // Changes made by the transaction being executed, if queried by a
+        // contract. This is bookkeeping of the transaction, so not metered.
+        .saturating_add_signed(
+            STORAGE_USAGE_CHANGES
+                .may_load(&ctx.storage, req.contract)?
+                .unwrap_or(0),
+        );
+    let deposit = STATE_DEPOSITS.may_load_with_gas(&ctx.storage, ctx.gas_tracker, req.contract)?;
+
+    Ok(StateDeposit { bytes, deposit })
+}
+
 pub fn query_wasm_raw(ctx: AppCtx, req: QueryWasmRawRequest) -> StdResult<Option<Binary>> {
     StorageProvider::new(ctx.storage, &[CONTRACT_NAMESPACE, &req.contract])
         .read_with_gas(ctx.gas_tracker, &req.key)
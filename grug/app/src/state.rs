@@ -1,7 +1,8 @@
 use {
     grug_storage::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, Set},
     grug_types::{
-        Addr, BlockInfo, Code, CodeStatus, Config, ContractInfo, Hash256, Json, Timestamp,
+        Addr, BlockInfo, Code, CodeStatus, Coin, Config, ContractInfo, GasCosts, Hash256, Json,
+        StateDepositParams, Timestamp,
     },
 };
 
@@ -11,6 +12,31 @@ pub const CHAIN_ID: Item<String> = Item::new("chain_id");
 /// Chain-level configuration
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Gas costs of host operations, such as storage access and cryptography.
+///
+/// If not set, the default costs are used.
+pub const GAS_COSTS: Item<GasCosts> = Item::new("gas_costs");
+
+/// Maximum sum of the gas limits of all transactions in a block.
+///
+/// A transaction whose gas limit exceeds this is rejected from entering the
+/// mempool. During block execution, a transaction whose gas limit exceeds the
+/// remaining gas of the block is skipped.
+///
+/// If not set, there is no limit.
+pub const MAX_BLOCK_GAS: Item<u64> = Item::new("max_block_gas");
+
+/// Hash of the VM's consensus-critical settings, as returned by
+/// `Vm::config_hash`, saved at genesis.
+///
+/// Not set if the VM doesn't have such settings.
+pub const VM_CONFIG_HASH: Item<Hash256> = Item::new("vm_config_hash");
+
+/// Parameters of the state deposit.
+///
+/// If not set, state deposit is disabled.
+pub const STATE_DEPOSIT_PARAMS: Item<StateDepositParams> = Item::new("state_deposit_params");
+
 /// Application-specific configurations.
 pub const APP_CONFIGS: Map<&str, Json> = Map::new("app_config");
 
@@ -31,6 +57,22 @@ pub const CODES: IndexedMap<Hash256, Code, CodeIndexes> = IndexedMap::new("codes
 /// Contract metadata: address => contract_info
 pub const CONTRACTS: Map<Addr, ContractInfo> = Map::new("contract");
 
+/// Total number of bytes of the keys and values in each contract's substore:
+/// address => bytes
+pub const STORAGE_USAGES: Map<Addr, u64> = Map::new("storage_usage");
+
+/// Changes to the storage usages made by the transaction or cronjob being
+/// executed, that are yet to be applied to `STORAGE_USAGES`:
+/// address => bytes added minus bytes removed
+///
+/// They are applied once the transaction or cronjob is done, such that
+/// transactions executed in parallel that write to the same contract, e.g. the
+/// bank, don't conflict with each other by updating its usage.
+pub const STORAGE_USAGE_CHANGES: Map<Addr, i64> = Map::new("storage_usage_change");
+
+/// State deposits held by contracts for their storage usage: address => deposit
+pub const STATE_DEPOSITS: Map<Addr, Coin> = Map::new("state_deposit");
+
 /// Each contract has its own storage space, which we term the "substore".
 /// A key in a contract's substore is prefixed by the word "wasm" + contract address.
 pub const CONTRACT_NAMESPACE: &[u8] = b"wasm";
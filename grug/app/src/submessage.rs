@@ -49,21 +49,21 @@ where
         match (submsg.reply_on, result) {
             // Success - callback requested
             // Flush state changes, log events, give callback.
-            (ReplyOn::Success(payload) | ReplyOn::Always(payload), Result::Ok(submsg_events)) => {
+            (ReplyOn::Success(payload) | ReplyOn::Always(payload), Result::Ok(submsg_event)) => {
                 buffer.disassemble().consume();
-                events.extend(submsg_events.clone());
-                events.extend(do_reply(
+                events.push(submsg_event.clone());
+                events.push(do_reply(
                     ctx.clone(),
                     msg_depth + 1, // important: increase message depth
                     sender,
                     &payload,
-                    &GenericResult::Ok(submsg_events),
+                    &GenericResult::Ok(submsg_event),
                 )?);
             },
             // Error - callback requested
             // Discard uncommitted state changes, give callback.
             (ReplyOn::Error(payload) | ReplyOn::Always(payload), Result::Err(err)) => {
-                events.extend(do_reply(
+                events.push(do_reply(
                     ctx.clone(),
                     msg_depth + 1, // important: increase message depth
                     sender,
@@ -73,9 +73,9 @@ where
             },
             // Success - callback not requested
             // Flush state changes, log events, move on to the next submsg.
-            (ReplyOn::Error(_) | ReplyOn::Never, Result::Ok(submsg_events)) => {
+            (ReplyOn::Error(_) | ReplyOn::Never, Result::Ok(submsg_event)) => {
                 buffer.disassemble().consume();
-                events.extend(submsg_events);
+                events.push(submsg_event);
             },
             // Error - callback not requested
             // Abort by throwing error.
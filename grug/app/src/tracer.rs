@@ -0,0 +1,211 @@
+use {
+    crate::{AppCtx, AppResult, Shared},
+    grug_types::{CallTrace, Context, ContractEvent, GasBreakdown, Json, StdResult},
+};
+
+#[derive(Default)]
+struct CallTracerInner {
+    // Calls that have been entered but not yet exited, from the outermost to
+    // the innermost, each along with the gas breakdown at the time of entering.
+    pending: Vec<(CallTrace, GasBreakdown)>,
+    // Top level calls that have been exited.
+    finished: Vec<CallTrace>,
+}
+
+/// Records the contract calls made during the processing of a transaction
+/// into a call tree.
+///
+/// The tracer is carried in the [`AppCtx`](crate::AppCtx). Tracing is opt-in,
+/// and a context without a tracer doesn't incur any overhead.
+#[derive(Default, Clone)]
+pub struct CallTracer {
+    inner: Shared<CallTracerInner>,
+}
+
+impl CallTracer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the start of a call. Calls entered before this one is exited
+    /// become its children.
+    pub fn enter(&self, ctx: &Context, entry_point: &str, msg: Json, gas: GasBreakdown) {
+        let trace = CallTrace {
+            contract: ctx.contract,
+            entry_point: entry_point.to_string(),
+            sender: ctx.sender,
+            funds: ctx.funds.clone(),
+            msg,
+            gas_used: GasBreakdown::default(),
+            events: vec![],
+            error: None,
+            calls: vec![],
+        };
+
+        self.inner.write_access().pending.push((trace, gas));
+    }
+
+    /// Record an event emitted by the innermost call that hasn't been exited.
+    pub fn emit(&self, event: ContractEvent) {
+        if let Some((trace, _)) = self.inner.write_access().pending.last_mut() {
+            trace.events.push(event);
+        }
+    }
+
+    /// Record the end of the innermost call that hasn't been exited.
+    ///
+    /// Panics if there isn't such a call.
+    pub fn exit(&self, error: Option<String>, gas: GasBreakdown) {
+        self.inner.write_with(|mut inner| {
+            let (mut trace, gas_before) = inner
+                .pending
+                .pop()
+                .unwrap_or_else(|| panic!("exiting a call that was never entered"));
+
+            trace.gas_used = gas.since(&gas_before);
+
+            // State changes and events of a failed call are discarded.
+            if error.is_some() {
+                trace.events.clear();
+            }
+
+            trace.error = error;
+
+            match inner.pending.last_mut() {
+                Some((parent, _)) => parent.calls.push(trace),
+                None => inner.finished.push(trace),
+            }
+        });
+    }
+
+    /// Consume self, return the top level calls.
+    ///
+    /// Panics if there are other clones of this tracer still alive.
+    pub fn into_traces(self) -> Vec<CallTrace> {
+        self.inner.disassemble().finished
+    }
+}
+
+/// Perform a contract call, recording it in the context's tracer if there is
+/// one.
+///
+/// `msg` is only evaluated if the call is traced.
+pub(crate) fn trace_call<VM, T, M, F>(
+    app_ctx: AppCtx<VM>,
+    name: &'static str,
+    ctx: &Context,
+    msg: M,
+    call: F,
+) -> AppResult<T>
+where
+    M: FnOnce() -> StdResult<Json>,
+    F: FnOnce(AppCtx<VM>) -> AppResult<T>,
+{
+    let Some(tracer) = app_ctx.tracer.clone() else {
+        return call(app_ctx);
+    };
+
+    let gas_tracker = app_ctx.gas_tracker.clone();
+
+    tracer.enter(ctx, name, msg()?, gas_tracker.breakdown());
+
+    let result = call(app_ctx);
+
+    tracer.exit(
+        result.as_ref().err().map(ToString::to_string),
+        gas_tracker.breakdown(),
+    );
+
+    result
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        grug_types::{Addr, BlockInfo, Hash256, Timestamp},
+    };
+
+    fn mock_context(contract: Addr) -> Context {
+        Context {
+            chain_id: "dev-1".to_string(),
+            block: BlockInfo {
+                height: 1,
+                timestamp: Timestamp::from_nanos(100),
+                hash: Hash256::ZERO,
+            },
+            contract,
+            sender: None,
+            funds: None,
+            mode: None,
+        }
+    }
+
+    fn mock_event() -> ContractEvent {
+        ContractEvent {
+            ty: "foo".to_string(),
+            data: Json::Null,
+        }
+    }
+
+    fn gas(storage: u64, crypto: u64, wasm: u64) -> GasBreakdown {
+        GasBreakdown {
+            storage,
+            crypto,
+            wasm,
+        }
+    }
+
+    #[test]
+    fn building_call_tree_works() {
+        let tracer = CallTracer::new();
+        let alice = mock_context(Addr::mock(1));
+        let bob = mock_context(Addr::mock(2));
+
+        tracer.enter(&alice, "execute", Json::Null, gas(0, 0, 0));
+        tracer.emit(mock_event());
+
+        // The 1st submessage succeeds.
+        tracer.enter(&bob, "execute", Json::Null, gas(10, 0, 5));
+        tracer.emit(mock_event());
+        tracer.exit(None, gas(20, 0, 10));
+
+        // The 2nd submessage fails; its events are discarded.
+        tracer.enter(&bob, "execute", Json::Null, gas(20, 0, 10));
+        tracer.emit(mock_event());
+        tracer.exit(Some("oops".to_string()), gas(20, 100, 20));
+
+        // The reply to the 2nd submessage.
+        tracer.enter(&alice, "reply", Json::Null, gas(20, 100, 20));
+        tracer.exit(None, gas(30, 100, 30));
+
+        tracer.exit(None, gas(30, 100, 40));
+
+        let traces = tracer.into_traces();
+        assert_eq!(traces.len(), 1);
+
+        let root = &traces[0];
+        assert_eq!(root.entry_point, "execute");
+        assert_eq!(root.gas_used, gas(30, 100, 40));
+        assert_eq!(root.events, vec![mock_event()]);
+        assert_eq!(
+            root.calls
+                .iter()
+                .map(|call| (
+                    call.contract,
+                    call.entry_point.as_str(),
+                    call.gas_used,
+                    call.events.len(),
+                    call.error.as_deref(),
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (Addr::mock(2), "execute", gas(10, 0, 5), 1, None),
+                (Addr::mock(2), "execute", gas(0, 100, 10), 0, Some("oops")),
+                (Addr::mock(1), "reply", gas(10, 0, 10), 0, None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use {
+    crate::Shared,
+    grug_types::{Batch, Order, Record, Storage},
+    std::{collections::BTreeSet, ops::Bound},
+};
+
+/// The keys and key ranges a transaction has read from the underlying storage
+/// during its execution.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct ReadSet {
+    keys: BTreeSet<Vec<u8>>,
+    ranges: Vec<(Option<Vec<u8>>, Option<Vec<u8>>)>,
+}
+
+impl ReadSet {
+    /// Return whether any of the keys or ranges read is affected by the given
+    /// set of written keys.
+    pub fn conflicts_with(&self, written: &BTreeSet<Vec<u8>>) -> bool {
+        if self.keys.iter().any(|key| written.contains(key)) {
+            return true;
+        }
+
+        self.ranges.iter().any(|(min, max)| {
+            let min = min
+                .as_ref()
+                .map_or(Bound::Unbounded, |bytes| Bound::Included(bytes.clone()));
+            let max = max
+                .as_ref()
+                .map_or(Bound::Unbounded, |bytes| Bound::Excluded(bytes.clone()));
+
+            written.range((min, max)).next().is_some()
+        })
+    }
+}
+
+/// A read-only wrapper over a storage that records every key and range read
+/// from it.
+///
+/// Used in parallel transaction execution, where each transaction runs
+/// speculatively against the same pre-state, and its read set is later checked
+/// against the writes of the transactions that precede it in the block.
+#[derive(Clone)]
+pub struct Tracked<S> {
+    base: S,
+    reads: Shared<ReadSet>,
+}
+
+impl<S> Tracked<S> {
+    pub fn new(base: S) -> Self {
+        Self {
+            base,
+            reads: Shared::new(ReadSet::default()),
+        }
+    }
+
+    /// Consume self, return the recorded read set.
+    ///
+    /// Panics if there are other clones of this storage still alive.
+    pub fn into_reads(self) -> ReadSet {
+        self.reads.disassemble()
+    }
+}
+
+impl<S> Storage for Tracked<S>
+where
+    S: Storage + Clone,
+{
+    fn read(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.reads.write_access().keys.insert(key.to_vec());
+        self.base.read(key)
+    }
+
+    fn scan<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        // An inverted range is empty, so nothing is actually read.
+        if !matches!((min, max), (Some(min), Some(max)) if min > max) {
+            self.reads
+                .write_access()
+                .ranges
+                .push((min.map(|min| min.to_vec()), max.map(|max| max.to_vec())));
+        }
+
+        self.base.scan(min, max, order)
+    }
+
+    fn scan_keys<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        Box::new(self.scan(min, max, order).map(|(k, _)| k))
+    }
+
+    fn scan_values<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        Box::new(self.scan(min, max, order).map(|(_, v)| v))
+    }
+
+    fn write(&mut self, _key: &[u8], _value: &[u8]) {
+        unreachable!("write function called on read-only storage");
+    }
+
+    fn remove(&mut self, _key: &[u8]) {
+        unreachable!("write function called on read-only storage");
+    }
+
+    fn remove_range(&mut self, _min: Option<&[u8]>, _max: Option<&[u8]>) {
+        unreachable!("write function called on read-only storage");
+    }
+
+    fn flush(&mut self, _batch: Batch) {
+        unreachable!("write function called on read-only storage");
+    }
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::Buffer, grug_types::MockStorage};
+
+    #[test]
+    fn tracking_reads_works() {
+        let mut base = MockStorage::new();
+        base.write(b"apple", b"1");
+        base.write(b"banana", b"2");
+        base.write(b"cherry", b"3");
+
+        let tracked = Tracked::new(base);
+        let mut buffer = Buffer::new(tracked.clone(), None);
+
+        // Keys written by the transaction itself are served by the buffer, so
+        // reading them back doesn't count as reading from the base.
+        buffer.write(b"durian", b"4");
+        assert_eq!(buffer.read(b"durian"), Some(b"4".to_vec()));
+        assert_eq!(buffer.read(b"apple"), Some(b"1".to_vec()));
+        assert_eq!(
+            buffer
+                .scan_keys(Some(b"b"), Some(b"c"), Order::Ascending)
+                .collect::<Vec<_>>(),
+            vec![b"banana".to_vec()]
+        );
+
+        drop(buffer);
+        let reads = tracked.into_reads();
+
+        // Writes to keys that weren't read don't conflict.
+        assert!(!reads.conflicts_with(&BTreeSet::from([b"cherry".to_vec()])));
+        assert!(!reads.conflicts_with(&BTreeSet::from([b"durian".to_vec()])));
+
+        // Writes to a key that was read conflict.
+        assert!(reads.conflicts_with(&BTreeSet::from([b"apple".to_vec()])));
+
+        // Writes to a key within a range that was scanned conflict, even if
+        // the key didn't exist when the transaction ran.
+        assert!(reads.conflicts_with(&BTreeSet::from([b"blueberry".to_vec()])));
+
+        // The upper bound of a scan is exclusive.
+        assert!(!reads.conflicts_with(&BTreeSet::from([b"c".to_vec()])));
+    }
+}
@@ -1,7 +1,7 @@
 use {
     crate::{GasTracker, QuerierProvider, StorageProvider},
     borsh::{BorshDeserialize, BorshSerialize},
-    grug_types::{Batch, Context, Hash256, QuerierWrapper, Storage},
+    grug_types::{Batch, BlockInfo, BlockOutcome, Context, Hash256, QuerierWrapper, Storage, Tx},
     ics23::CommitmentProof,
     prost::bytes::Bytes,
 };
@@ -58,6 +58,20 @@ pub trait Db {
     /// `None` if not a single version has been committed.
     fn latest_version(&self) -> Option<u64>;
 
+    /// Return the oldest version available in the database.
+    /// Versions older than this have been pruned.
+    ///
+    /// `None` if the DB hasn't been pruned once, or doesn't support pruning.
+    fn oldest_version(&self) -> Option<u64> {
+        None
+    }
+
+    /// Return whether the state at the given version is kept as a checkpoint,
+    /// such that it remains available even if older than the oldest version.
+    fn has_checkpoint(&self, _version: u64) -> bool {
+        false
+    }
+
     /// Return the Merkle root hash at the specified version.
     ///
     /// If version is unspecified, return that of the latest committed version.
@@ -115,14 +129,10 @@ pub trait Db {
 
 /// Represents a database that can be pruned.
 ///
-/// These methods aren't used by the app, so we split them off into a separate
-/// trait.
+/// Pruning isn't performed by the app, so we split it off into a separate
+/// trait. The oldest available version is part of `Db`, since the app needs
+/// it to tell whether a query can be served.
 pub trait PrunableDb: Db {
-    /// Return the oldest version available in the database.
-    /// Versions older than this have been pruned.
-    /// Return `None` if the DB hasn't been pruned once.
-    fn oldest_version(&self) -> Option<u64>;
-
     /// Prune data of less or equal to the given version.
     ///
     /// That is, `up_to_version` will be thd oldest version available in the
@@ -130,10 +140,83 @@ pub trait PrunableDb: Db {
     fn prune(&self, up_to_version: u64) -> Result<(), Self::Error>;
 }
 
+/// Metadata of a state snapshot, which a new node can use to bootstrap itself
+/// without replaying every block from genesis (known as "state sync").
+///
+/// The fields correspond to those of the ABCI `Snapshot` type.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// The version (block height) at which the snapshot was taken.
+    pub version: u64,
+    /// The format of the snapshot. Can be used to distinguish incompatible
+    /// snapshot encodings.
+    pub format: u32,
+    /// Number of chunks in the snapshot.
+    pub chunks: u32,
+    /// A hash that uniquely identifies the snapshot; typically, the hash of
+    /// the metadata.
+    pub hash: Hash256,
+    /// Arbitrary metadata, such as the hashes of the chunks.
+    pub metadata: Vec<u8>,
+}
+
+/// The outcome of applying a chunk of the snapshot being restored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotChunkOutcome {
+    /// The chunk has been applied, and more chunks are expected.
+    Applied,
+    /// This is the last chunk, and the state has been successfully restored.
+    Restored,
+    /// The chunk doesn't match the hash committed to by the snapshot, likely
+    /// because the node that sent it is faulty. The chunk isn't applied, but
+    /// the rest of the snapshot is still usable, so the chunk should be fetched
+    /// again from another node.
+    Corrupted,
+}
+
+/// Represents a database that can export and import state snapshots.
+///
+/// These methods are only used by the ABCI state sync handlers, so we split
+/// them off into a separate trait.
+pub trait SnapshotDb: Db {
+    /// Return the snapshots that are available to be served to other nodes.
+    fn list_snapshots(&self) -> Result<Vec<Snapshot>, Self::Error>;
+
+    /// Return a chunk of the snapshot of the given version and format.
+    ///
+    /// `None` if the snapshot or the chunk doesn't exist.
+    fn load_snapshot_chunk(
+        &self,
+        version: u64,
+        format: u32,
+        index: u32,
+    ) -> Result<Option<Vec<u8>>, Self::Error>;
+
+    /// Begin restoring state from the given snapshot. Once all chunks are
+    /// applied, the resulting root hash must equal `app_hash`.
+    ///
+    /// Error if the snapshot is of an unsupported format, or if the database
+    /// isn't empty.
+    fn offer_snapshot(&self, snapshot: Snapshot, app_hash: Hash256) -> Result<(), Self::Error>;
+
+    /// Apply a chunk of the snapshot being restored. Chunks must be applied in
+    /// order.
+    ///
+    /// Error if the snapshot can't be restored, e.g. if the restored state
+    /// doesn't match the app hash.
+    fn apply_snapshot_chunk(
+        &self,
+        index: u32,
+        chunk: Vec<u8>,
+    ) -> Result<SnapshotChunkOutcome, Self::Error>;
+}
+
 // ------------------------------------ vm -------------------------------------
 
 /// Represents a virtual machine that can execute programs.
-pub trait Vm: Sized {
+///
+/// Must be `Send`, so that transactions can be executed in parallel threads.
+pub trait Vm: Sized + Send {
     type Error: ToString;
     type Instance: Instance<Error = Self::Error>;
 
@@ -152,6 +235,30 @@ pub trait Vm: Sized {
         query_depth: usize,
         gas_tracker: GasTracker,
     ) -> Result<Self::Instance, Self::Error>;
+
+    /// Statically check that a program is valid before it's uploaded, so that
+    /// problems are caught early rather than when it's first called.
+    ///
+    /// The app charges gas proportional to the program's size before calling
+    /// this. The VM may keep whatever it prepares here (e.g. a compiled module)
+    /// for when the program is first called.
+    ///
+    /// By default, all programs are accepted.
+    fn validate_code(&mut self, _code: &[u8], _code_hash: Hash256) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Return a hash of the VM's settings that all nodes in the network must
+    /// agree on, such as gas costs and resource limits.
+    ///
+    /// The hash is saved in the state at genesis, and checked when the node
+    /// starts, such that a node configured differently from the rest of the
+    /// network refuses to start instead of forking off the chain.
+    ///
+    /// By default, `None`, meaning the VM has no such settings.
+    fn config_hash(&self) -> Option<Hash256> {
+        None
+    }
 }
 
 pub trait Instance {
@@ -203,3 +310,39 @@ pub trait ProposalPreparer {
         max_tx_bytes: usize,
     ) -> Result<Vec<Bytes>, Self::Error>;
 }
+
+// ---------------------------------- indexer ----------------------------------
+
+/// Represents a worker that records finalized blocks, transactions, and their
+/// outcomes, typically into an external database that can be queried by
+/// explorers and other off-chain services.
+///
+/// Indexing is done in two steps, mirroring the ABCI `FinalizeBlock` and
+/// `Commit` calls: the block is handed to the indexer once it's finalized, but
+/// the indexer should only make it visible once the state is committed.
+///
+/// Indexing isn't part of consensus. Errors are logged by the app, but don't
+/// halt the chain.
+pub trait Indexer {
+    type Error: ToString;
+
+    /// Called at the end of `FinalizeBlock`, with the transactions in the block
+    /// along with their hashes, and the outcome of executing them.
+    ///
+    /// The block should be kept such that it survives a restart, since the
+    /// node may crash after the state is committed but before `commit` is
+    /// called. The same height may be handed over more than once, in which
+    /// case the later one replaces the earlier one.
+    fn index_block(
+        &self,
+        block: &BlockInfo,
+        txs: &[(Tx, Hash256)],
+        outcome: &BlockOutcome,
+    ) -> Result<(), Self::Error>;
+
+    /// Called at `Commit`, after the state of the given height has been
+    /// persisted to the DB, as well as upon startup with the latest committed
+    /// height. All blocks handed over up to and including this height should
+    /// be persisted. Must be idempotent.
+    fn commit(&self, height: u64) -> Result<(), Self::Error>;
+}
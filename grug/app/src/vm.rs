@@ -1,10 +1,14 @@
 use {
     crate::{
-        handle_submessages, AppCtx, AppError, AppResult, Instance, QuerierProvider,
-        StorageProvider, Vm, CODES, CONTRACT_ADDRESS_KEY, CONTRACT_NAMESPACE,
+        handle_submessages, settle_state_deposit, trace_call, AppCtx, AppError, AppResult, Buffer,
+        Instance, QuerierProvider, Shared, StorageProvider, Vm, CODES, CONTRACT_NAMESPACE,
     },
     borsh::{BorshDeserialize, BorshSerialize},
-    grug_types::{Addr, BorshDeExt, BorshSerExt, Context, Event, GenericResult, Hash256, Response},
+    grug_types::{
+        Addr, BorshDeExt, BorshSerExt, Context, Event, EvtGuest, GenericResult, Hash256, Json,
+        JsonSerExt, Response, RESERVED_EVENT_KEYS,
+    },
+    serde::Serialize,
 };
 
 /// Create a VM instance, and call a function that takes no input parameter and
@@ -22,12 +26,15 @@ where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
+    let gas_tracker = app_ctx.gas_tracker.clone();
+
     // Create the VM instance
     let instance =
         create_vm_instance(app_ctx, query_depth, state_mutable, ctx.contract, code_hash)?;
 
     // Call the function; deserialize the output as Borsh.
-    let out_raw = instance.call_in_0_out_1(name, ctx)?;
+    let out_raw =
+        gas_tracker.within_contract(ctx.contract, || instance.call_in_0_out_1(name, ctx))?;
     let out = out_raw.deserialize_borsh()?;
 
     Ok(out)
@@ -50,6 +57,8 @@ where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
+    let gas_tracker = app_ctx.gas_tracker.clone();
+
     // Create the VM instance
     let instance =
         create_vm_instance(app_ctx, query_depth, state_mutable, ctx.contract, code_hash)?;
@@ -58,7 +67,9 @@ where
     let param_raw = param.to_borsh_vec()?;
 
     // Call the function; deserialize the output as Borsh.
-    let out_raw = instance.call_in_1_out_1(name, ctx, &param_raw)?;
+    let out_raw = gas_tracker.within_contract(ctx.contract, || {
+        instance.call_in_1_out_1(name, ctx, &param_raw)
+    })?;
     let out = out_raw.deserialize_borsh()?;
 
     Ok(out)
@@ -83,6 +94,8 @@ where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
+    let gas_tracker = app_ctx.gas_tracker.clone();
+
     // Create the VM instance
     let instance =
         create_vm_instance(app_ctx, query_depth, state_mutable, ctx.contract, code_hash)?;
@@ -92,15 +105,17 @@ where
     let param2_raw = param2.to_borsh_vec()?;
 
     // Call the function; deserialize the output as Borsh.
-    let out_raw = instance.call_in_2_out_1(name, ctx, &param1_raw, &param2_raw)?;
+    let out_raw = gas_tracker.within_contract(ctx.contract, || {
+        instance.call_in_2_out_1(name, ctx, &param1_raw, &param2_raw)
+    })?;
     let out = out_raw.deserialize_borsh()?;
 
     Ok(out)
 }
 
 /// Create a VM instance, call a function that takes exactly no input parameter
-/// and returns [`Response`], and handle the submessages. Return a vector of
-/// events emitted.
+/// and returns [`Response`], and handle the submessages. Return the event of
+/// the call.
 pub fn call_in_0_out_1_handle_response<VM>(
     app_ctx: AppCtx<VM>,
     msg_depth: usize,
@@ -109,31 +124,39 @@ pub fn call_in_0_out_1_handle_response<VM>(
     name: &'static str,
     code_hash: Hash256,
     ctx: &Context,
-) -> AppResult<Vec<Event>>
+) -> AppResult<EvtGuest>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
-    let response = call_in_0_out_1::<_, GenericResult<Response>>(
-        app_ctx.clone(),
-        query_depth,
-        state_mutable,
+    trace_call(
+        app_ctx,
         name,
-        code_hash,
         ctx,
-    )?
-    .map_err(|msg| AppError::Guest {
-        address: ctx.contract,
-        name,
-        msg,
-    })?;
+        || Ok(Json::Null),
+        |app_ctx| {
+            let response = call_in_0_out_1::<_, GenericResult<Response>>(
+                app_ctx.clone(),
+                query_depth,
+                state_mutable,
+                name,
+                code_hash,
+                ctx,
+            )?
+            .map_err(|msg| AppError::Guest {
+                address: ctx.contract,
+                name,
+                msg,
+            })?;
 
-    handle_response(app_ctx, msg_depth, name, ctx, response)
+            handle_response(app_ctx, msg_depth, name, ctx, response)
+        },
+    )
 }
 
 /// Create a VM instance, call a function that takes exactly one parameter and
-/// returns [`Response`], and handle the submessages. Return a vector of events
-/// emitted.
+/// returns [`Response`], and handle the submessages. Return the event of
+/// the call.
 pub fn call_in_1_out_1_handle_response<VM, P>(
     app_ctx: AppCtx<VM>,
     msg_depth: usize,
@@ -143,33 +166,41 @@ pub fn call_in_1_out_1_handle_response<VM, P>(
     code_hash: Hash256,
     ctx: &Context,
     param: &P,
-) -> AppResult<Vec<Event>>
+) -> AppResult<EvtGuest>
 where
-    P: BorshSerialize,
+    P: BorshSerialize + Serialize,
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
-    let response = call_in_1_out_1::<_, _, GenericResult<Response>>(
-        app_ctx.clone(),
-        query_depth,
-        state_mutable,
+    trace_call(
+        app_ctx,
         name,
-        code_hash,
         ctx,
-        param,
-    )?
-    .map_err(|msg| AppError::Guest {
-        address: ctx.contract,
-        name,
-        msg,
-    })?;
+        || param.to_json_value(),
+        |app_ctx| {
+            let response = call_in_1_out_1::<_, _, GenericResult<Response>>(
+                app_ctx.clone(),
+                query_depth,
+                state_mutable,
+                name,
+                code_hash,
+                ctx,
+                param,
+            )?
+            .map_err(|msg| AppError::Guest {
+                address: ctx.contract,
+                name,
+                msg,
+            })?;
 
-    handle_response(app_ctx, msg_depth, name, ctx, response)
+            handle_response(app_ctx, msg_depth, name, ctx, response)
+        },
+    )
 }
 
 /// Create a VM instance, call a function that takes exactly two parameter and
-/// returns [`Response`], and handle the submessages. Return a vector of events
-/// emitted.
+/// returns [`Response`], and handle the submessages. Return the event of
+/// the call.
 pub fn call_in_2_out_1_handle_response<VM, P1, P2>(
     app_ctx: AppCtx<VM>,
     msg_depth: usize,
@@ -180,30 +211,43 @@ pub fn call_in_2_out_1_handle_response<VM, P1, P2>(
     ctx: &Context,
     param1: &P1,
     param2: &P2,
-) -> AppResult<Vec<Event>>
+) -> AppResult<EvtGuest>
 where
-    P1: BorshSerialize,
-    P2: BorshSerialize,
+    P1: BorshSerialize + Serialize,
+    P2: BorshSerialize + Serialize,
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
-    let response = call_in_2_out_1::<_, _, _, GenericResult<Response>>(
-        app_ctx.clone(),
-        query_depth,
-        state_mutable,
+    trace_call(
+        app_ctx,
         name,
-        code_hash,
         ctx,
-        param1,
-        param2,
-    )?
-    .map_err(|msg| AppError::Guest {
-        address: ctx.contract,
-        name,
-        msg,
-    })?;
+        || {
+            Ok(Json::Array(vec![
+                param1.to_json_value()?,
+                param2.to_json_value()?,
+            ]))
+        },
+        |app_ctx| {
+            let response = call_in_2_out_1::<_, _, _, GenericResult<Response>>(
+                app_ctx.clone(),
+                query_depth,
+                state_mutable,
+                name,
+                code_hash,
+                ctx,
+                param1,
+                param2,
+            )?
+            .map_err(|msg| AppError::Guest {
+                address: ctx.contract,
+                name,
+                msg,
+            })?;
 
-    handle_response(app_ctx, msg_depth, name, ctx, response)
+            handle_response(app_ctx, msg_depth, name, ctx, response)
+        },
+    )
 }
 
 fn create_vm_instance<VM>(
@@ -222,7 +266,8 @@ where
 
     // Create the providers
     let querier = QuerierProvider::new(ctx.clone());
-    let storage = StorageProvider::new(ctx.storage.clone(), &[CONTRACT_NAMESPACE, &contract]);
+    let storage = StorageProvider::new(ctx.storage.clone(), &[CONTRACT_NAMESPACE, &contract])
+        .with_usage_tracking(contract, ctx.gas_tracker.costs().clone());
 
     Ok(ctx.vm.build_instance(
         &code.code,
@@ -241,24 +286,87 @@ pub(crate) fn handle_response<VM>(
     name: &'static str,
     ctx: &Context,
     response: Response,
-) -> AppResult<Vec<Event>>
+) -> AppResult<EvtGuest>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
-    // Create an event for this call
-    let event = Event::new(name)
-        .add_attribute(CONTRACT_ADDRESS_KEY, ctx.contract)
-        .add_attributes(response.attributes);
-
-    // Handle submessages; append events emitted during submessage handling
-    let mut events = vec![event];
-    events.extend(handle_submessages(
+    for event in &response.events {
+        let Json::Object(fields) = &event.data else {
+            continue;
+        };
+
+        if let Some(key) = RESERVED_EVENT_KEYS
+            .into_iter()
+            .find(|key| fields.contains_key(*key))
+        {
+            return Err(AppError::ReservedEventKey {
+                address: ctx.contract,
+                ty: event.ty.clone(),
+                key,
+            });
+        }
+    }
+
+    if let Some(tracer) = &app_ctx.tracer {
+        for event in &response.events {
+            tracer.emit(event.clone());
+        }
+    }
+
+    // Settle the contract's state deposit, now that the call has finished
+    // writing to its storage. This is done before handling the submessages, so
+    // that they can't spend the funds needed for the deposit.
+    //
+    // A failure to settle fails the call, except in `finalize_fee`, where it
+    // would revert the entire transaction, fee payment included. There, the
+    // settlement is discarded instead, and retried the next time the contract
+    // is called.
+    let settlement = if name == "finalize_fee" {
+        let buffer = Shared::new(Buffer::new(app_ctx.storage.clone(), None));
+
+        match settle_state_deposit(
+            app_ctx.clone_with_storage(Box::new(buffer.clone())),
+            msg_depth,
+            ctx.contract,
+        ) {
+            Ok(events) => {
+                buffer.disassemble().consume();
+                events
+            },
+            Err(_err) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    contract = ctx.contract.to_string(),
+                    err = _err.to_string(),
+                    "Failed to settle state deposit in `finalize_fee`"
+                );
+
+                vec![]
+            },
+        }
+    } else {
+        settle_state_deposit(app_ctx.clone(), msg_depth, ctx.contract)?
+    };
+
+    let mut sub_events = settlement
+        .into_iter()
+        .map(Event::Transfer)
+        .collect::<Vec<_>>();
+
+    // Handle submessages; nest the events emitted during submessage handling
+    // under the event of this call.
+    sub_events.extend(handle_submessages(
         app_ctx,
         msg_depth,
         ctx.contract,
         response.submsgs,
     )?);
 
-    Ok(events)
+    Ok(EvtGuest {
+        contract: ctx.contract,
+        method: name.to_string(),
+        contract_events: response.events,
+        sub_events,
+    })
 }
@@ -1,13 +1,15 @@
 use {
     clap::Subcommand,
     colored::Colorize,
-    grug_app::PrunableDb,
+    grug_app::{Db, PrunableDb},
     grug_db_disk::DiskDb,
     std::{fs, path::PathBuf},
 };
 
 #[derive(Subcommand)]
 pub enum DbCmd {
+    /// Print the latest and oldest available versions, the checkpoints, and the size of each column family
+    Info,
     /// Delete data up to a version
     Prune {
         /// Cutoff version for the pruning
@@ -32,6 +34,39 @@ impl DbCmd {
         }
 
         match self {
+            DbCmd::Info => {
+                let db = DiskDb::open(data_dir)?;
+
+                let latest_version = match db.latest_version() {
+                    Some(version) => version.to_string(),
+                    None => "none".to_string(),
+                };
+                let oldest_version = match db.oldest_version() {
+                    Some(version) => version.to_string(),
+                    None => "0 (never pruned)".to_string(),
+                };
+
+                println!("{:<20}{latest_version}", "latest version");
+                println!("{:<20}{oldest_version}", "oldest version");
+
+                let checkpoints = db
+                    .list_checkpoints()?
+                    .into_iter()
+                    .map(|version| version.to_string())
+                    .collect::<Vec<_>>();
+                if !checkpoints.is_empty() {
+                    println!("{:<20}{}", "checkpoints", checkpoints.join(", "));
+                }
+
+                for size in db.column_family_sizes()? {
+                    println!(
+                        "{:<20}{} bytes on disk, {} bytes in memory",
+                        size.name, size.sst_files, size.mem_tables
+                    );
+                }
+
+                Ok(())
+            },
             DbCmd::Prune { up_to_version, yes } => {
                 if !yes {
                     confirm(
@@ -41,6 +41,12 @@ enum SubCmd {
     },
     /// Query the chain's global configuration
     Config,
+    /// Query the gas costs of host operations
+    GasCosts,
+    /// Query the maximum sum of the gas limits of all transactions in a block
+    MaxBlockGas,
+    /// Query the parameters of the state deposit
+    StateDepositParams,
     /// Query a single application-specific configuration
     AppConfig { key: String },
     /// Enumerate all application-specific configurations
@@ -97,6 +103,11 @@ enum SubCmd {
         /// Maximum number of items to display
         limit: Option<u32>,
     },
+    /// Query a contract's storage usage and the state deposit it holds
+    StateDeposit {
+        /// Contract address
+        contract: Addr,
+    },
     /// Query the raw value in a contract store by raw key
     WasmRaw {
         /// Contract address
@@ -159,6 +170,9 @@ impl QueryCmd {
                 return print_json_pretty(res);
             },
             SubCmd::Config => Query::config(),
+            SubCmd::GasCosts => Query::gas_costs(),
+            SubCmd::MaxBlockGas => Query::max_block_gas(),
+            SubCmd::StateDepositParams => Query::state_deposit_params(),
             SubCmd::AppConfig { key } => Query::app_config(key),
             SubCmd::AppConfigs { start_after, limit } => Query::app_configs(start_after, limit),
             SubCmd::Balance { address, denom } => {
@@ -185,6 +199,7 @@ impl QueryCmd {
             SubCmd::Codes { start_after, limit } => Query::codes(start_after, limit),
             SubCmd::Contract { address } => Query::contract(address),
             SubCmd::Contracts { start_after, limit } => Query::contracts(start_after, limit),
+            SubCmd::StateDeposit { contract } => Query::state_deposit(contract),
             SubCmd::WasmRaw { contract, key } => {
                 // We interpret the input raw key as Hex encoded
                 let key = Binary::from(hex::decode(key)?);
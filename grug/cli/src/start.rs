@@ -1,8 +1,17 @@
 use {
     clap::Parser,
-    grug_app::{App, NaiveProposalPreparer},
-    grug_db_disk::DiskDb,
-    grug_vm_wasm::WasmVm,
+    grug_app::{
+        App, AppError, Indexer, NaiveProposalPreparer, PriorityProposalPreparer, ProposalPreparer,
+    },
+    grug_client::Client,
+    grug_db_disk::{DiskDb, PruningStrategy},
+    grug_gateway::Gateway,
+    grug_indexer::SqlIndexer,
+    grug_types::Hash256,
+    grug_vm_wasm::{
+        OperatorGasCosts, WasmVm, WasmVmConfig, GAS_PER_DIVISION, GAS_PER_MEMORY_GROW,
+        GAS_PER_OPERATION, MAX_MEMORY_PAGES, MAX_MODULE_SIZE, MAX_QUERY_DEPTH,
+    },
     std::path::PathBuf,
 };
 
@@ -20,22 +29,191 @@ pub struct StartCmd {
     #[arg(long, default_value = "1000")]
     wasm_cache_capacity: usize,
 
+    /// Directory for persisting compiled wasm modules across restarts; if unset, modules are only cached in memory
+    #[arg(long)]
+    wasm_cache_dir: Option<PathBuf>,
+
+    /// Hashes of contract codes whose compiled modules are kept in memory permanently, e.g. the bank and taxman
+    #[arg(long, value_delimiter = ',')]
+    wasm_pinned_codes: Vec<Hash256>,
+
+    /// Maximum memory of a wasm contract instance, in 64 KiB pages; must be smaller than 65536 and the same across the network
+    #[arg(long, default_value_t = MAX_MEMORY_PAGES)]
+    wasm_max_memory_pages: u32,
+
+    /// Maximum number of chained queries between wasm contracts
+    #[arg(long, default_value_t = MAX_QUERY_DEPTH)]
+    wasm_max_query_depth: usize,
+
+    /// Maximum size of an uploaded wasm module, in bytes
+    #[arg(long, default_value_t = MAX_MODULE_SIZE)]
+    wasm_max_module_size: usize,
+
+    /// Gas cost of a wasm operator not covered by the other gas flags
+    #[arg(long, default_value_t = GAS_PER_OPERATION)]
+    wasm_gas_per_operation: u64,
+
+    /// Gas cost of a wasm division or remainder operator
+    #[arg(long, default_value_t = GAS_PER_DIVISION)]
+    wasm_gas_per_division: u64,
+
+    /// Gas cost of the wasm `memory.grow` operator, charged once regardless of the number of pages
+    #[arg(long, default_value_t = GAS_PER_MEMORY_GROW)]
+    wasm_gas_per_memory_grow: u64,
+
     /// Gas limit when serving query requests [default: u64::MAX]
     #[arg(long)]
     query_gas_limit: Option<u64>,
+
+    /// Number of threads for executing transactions in a block; one means sequential execution.
+    /// No speedup if the taxman writes to shared storage on every transaction
+    #[arg(long, default_value = "1")]
+    parallelism: usize,
+
+    /// Create a state snapshot every this many blocks; zero means do not create snapshots
+    #[arg(long, default_value = "0")]
+    snapshot_interval: u64,
+
+    /// Number of most recent state snapshots to keep
+    #[arg(long, default_value = "2")]
+    snapshot_keep_recent: usize,
+
+    /// Prune historical state, keeping only this many most recent blocks; if unset, all blocks are kept
+    #[arg(long)]
+    pruning_keep_recent: Option<u64>,
+
+    /// Additionally keep a full copy of the state at every block that is a multiple of this, so that it can still be queried after pruning
+    #[arg(long, requires = "pruning_keep_recent")]
+    pruning_interval: Option<u64>,
+
+    /// In blocks proposed by this node, order transactions by gas price and drop those paying less than the base fee, instead of keeping the mempool order
+    #[arg(long)]
+    proposal_prioritize: bool,
+
+    /// Maximum total gas limit of transactions in a block proposed by this node [default: u64::MAX]
+    #[arg(long, requires = "proposal_prioritize")]
+    proposal_max_gas: Option<u64>,
+
+    /// Maximum number of transactions from one sender in a block proposed by this node [default: usize::MAX]
+    #[arg(long, requires = "proposal_prioritize")]
+    proposal_max_txs_per_sender: Option<usize>,
+
+    /// HTTP gateway listening address, e.g. 127.0.0.1:8080; if unset, the gateway is not started
+    #[arg(long)]
+    gateway_addr: Option<String>,
+
+    /// Tendermint RPC address, used by the HTTP gateway for broadcasting transactions and looking up blocks
+    #[arg(long, default_value = "http://127.0.0.1:26657")]
+    tendermint_rpc_addr: String,
+
+    /// Path to a SQLite database for indexing blocks, transactions, and events; if unset, nothing is indexed
+    #[arg(long)]
+    indexer_path: Option<PathBuf>,
 }
 
 impl StartCmd {
     pub async fn run(self, data_dir: PathBuf) -> anyhow::Result<()> {
-        let db = DiskDb::open(data_dir)?;
-        let vm = WasmVm::new(self.wasm_cache_capacity);
-        let app = App::new(
-            db,
-            vm,
-            NaiveProposalPreparer,
-            self.query_gas_limit.unwrap_or(u64::MAX),
+        let mut db = DiskDb::open(data_dir)?;
+        if self.snapshot_interval > 0 {
+            db = db.with_snapshots(self.snapshot_interval, self.snapshot_keep_recent);
+        }
+
+        let pruning_strategy = match (self.pruning_keep_recent, self.pruning_interval) {
+            (Some(keep_recent), Some(interval)) => PruningStrategy::KeepEvery {
+                keep_recent,
+                interval,
+            },
+            (Some(keep_recent), None) => PruningStrategy::KeepRecent { keep_recent },
+            (None, _) => PruningStrategy::KeepAll,
+        };
+        db = db.with_pruning(pruning_strategy);
+
+        let vm_config = WasmVmConfig {
+            max_memory_pages: self.wasm_max_memory_pages,
+            max_query_depth: self.wasm_max_query_depth,
+            max_module_size: self.wasm_max_module_size,
+            gas_costs: OperatorGasCosts {
+                default: self.wasm_gas_per_operation,
+                division: self.wasm_gas_per_division,
+                memory_grow: self.wasm_gas_per_memory_grow,
+            },
+        };
+
+        // The Wasm VM config is consensus-critical. Upon startup, the app
+        // checks its hash against the one saved at genesis.
+        tracing::info!(
+            config = ?vm_config,
+            hash = vm_config.hash().to_string(),
+            "Using Wasm VM config"
         );
 
-        Ok(app.start_abci_server(self.read_buf_size, self.abci_addr)?)
+        let mut vm = WasmVm::new(self.wasm_cache_capacity, vm_config)?;
+        if let Some(wasm_cache_dir) = &self.wasm_cache_dir {
+            vm = vm.with_file_system_cache(wasm_cache_dir)?;
+        }
+        for code_hash in &self.wasm_pinned_codes {
+            vm.pin(*code_hash);
+        }
+
+        if self.proposal_prioritize {
+            let pp = PriorityProposalPreparer::new(
+                self.proposal_max_gas.unwrap_or(u64::MAX),
+                self.proposal_max_txs_per_sender.unwrap_or(usize::MAX),
+            );
+
+            self.start_with_pp(db, vm, pp).await
+        } else {
+            self.start_with_pp(db, vm, NaiveProposalPreparer).await
+        }
+    }
+
+    async fn start_with_pp<PP>(mut self, db: DiskDb, vm: WasmVm, pp: PP) -> anyhow::Result<()>
+    where
+        PP: ProposalPreparer + Clone + Send + Sync + 'static,
+        AppError: From<PP::Error>,
+    {
+        let app = App::new(db, vm, pp, self.query_gas_limit.unwrap_or(u64::MAX))
+            .with_parallelism(self.parallelism);
+
+        match self.indexer_path.take() {
+            Some(indexer_path) => {
+                let indexer = SqlIndexer::open(indexer_path)?;
+                let app = app.with_indexer(indexer);
+
+                // Recover blocks that were committed to the state but not to
+                // the index, in case the node crashed in between.
+                app.commit_indexer();
+
+                self.start_app(app).await
+            },
+            None => self.start_app(app).await,
+        }
+    }
+
+    async fn start_app<PP, ID>(self, app: App<DiskDb, WasmVm, PP, ID>) -> anyhow::Result<()>
+    where
+        PP: ProposalPreparer + Clone + Send + Sync + 'static,
+        ID: Indexer + Clone + Send + Sync + 'static,
+        AppError: From<PP::Error> + From<ID::Error>,
+    {
+        // The gateway serves queries from the same DB as the ABCI server, so
+        // it's run in the same process, on the async runtime.
+        if let Some(gateway_addr) = self.gateway_addr {
+            let client = Client::connect(&self.tendermint_rpc_addr)?;
+            let gateway = Gateway::new(app.clone(), client);
+            tokio::spawn(async move {
+                if let Err(err) = gateway.serve(&gateway_addr).await {
+                    tracing::error!(err = err.to_string(), "HTTP gateway stopped");
+                }
+            });
+        }
+
+        // The ABCI server handles requests synchronously, so it's run on a
+        // blocking thread, instead of tying up one of the runtime's workers.
+        let (read_buf_size, abci_addr) = (self.read_buf_size, self.abci_addr);
+        tokio::task::spawn_blocking(move || app.start_abci_server(read_buf_size, abci_addr))
+            .await??;
+
+        Ok(())
     }
 }
@@ -5,8 +5,8 @@ use {
     grug_math::Inner,
     grug_types::{
         Addr, AsyncSigner, Binary, Code, Coin, Coins, Config, ConfigUpdates, ContractInfo, Denom,
-        GenericResult, Hash256, HashExt, Json, JsonDeExt, JsonSerExt, Message, Op, Query,
-        QueryResponse, StdError, Tx, TxOutcome, UnsignedTx,
+        GasCosts, GenericResult, Hash256, HashExt, Json, JsonDeExt, JsonSerExt, Message, Op, Query,
+        QueryResponse, StateDeposit, StdError, Tx, TxOutcome, UnsignedTx,
     },
     serde::{de::DeserializeOwned, ser::Serialize},
     std::{any::type_name, collections::BTreeMap},
@@ -177,6 +177,13 @@ impl Client {
             .map(|res| res.as_config())
     }
 
+    /// Query the gas costs of host operations.
+    pub async fn query_gas_costs(&self, height: Option<u64>) -> anyhow::Result<GasCosts> {
+        self.query_app(&Query::gas_costs(), height)
+            .await
+            .map(|res| res.as_gas_costs())
+    }
+
     /// Query an account's balance in a single denom.
     pub async fn query_balance(
         &self,
@@ -263,6 +270,17 @@ impl Client {
             .map(|res| res.as_contracts())
     }
 
+    /// Query a contract's storage usage and the state deposit it holds.
+    pub async fn query_state_deposit(
+        &self,
+        contract: Addr,
+        height: Option<u64>,
+    ) -> anyhow::Result<StateDeposit> {
+        self.query_app(&Query::state_deposit(contract), height)
+            .await
+            .map(|res| res.as_state_deposit())
+    }
+
     /// Query a raw key-value pair in a contract's internal state.
     pub async fn query_wasm_raw<B>(
         &self,
@@ -582,6 +600,35 @@ impl Client {
         let msg = Message::migrate(contract, new_code_hash, msg)?;
         self.send_message(msg, gas_opt, sign_opt).await
     }
+
+    /// Send a transaction with a single [`Message::UpdateAdmin`](grug_types::Message::UpdateAdmin).
+    pub async fn update_admin<S>(
+        &self,
+        contract: Addr,
+        new_admin: Addr,
+        gas_opt: GasOption,
+        sign_opt: SigningOption<'_, S>,
+    ) -> anyhow::Result<tx_sync::Response>
+    where
+        S: AsyncSigner,
+    {
+        let msg = Message::update_admin(contract, new_admin);
+        self.send_message(msg, gas_opt, sign_opt).await
+    }
+
+    /// Send a transaction with a single [`Message::ClearAdmin`](grug_types::Message::ClearAdmin).
+    pub async fn clear_admin<S>(
+        &self,
+        contract: Addr,
+        gas_opt: GasOption,
+        sign_opt: SigningOption<'_, S>,
+    ) -> anyhow::Result<tx_sync::Response>
+    where
+        S: AsyncSigner,
+    {
+        let msg = Message::clear_admin(contract);
+        self.send_message(msg, gas_opt, sign_opt).await
+    }
 }
 
 /// Skip the CLI prompt confirmation, always consider it as if the user accepted.
@@ -1,8 +1,12 @@
 use {
-    crate::{DbError, DbResult, U64Comparator, U64Timestamp},
-    grug_app::{Buffer, Db, PrunableDb},
-    grug_jmt::{MerkleTree, Proof, ICS23_PROOF_SPEC},
-    grug_types::{Batch, Hash256, HashExt, Op, Order, Record, Storage},
+    crate::{DbError, DbResult, PruningStrategy, U64Comparator, U64Timestamp},
+    borsh::{BorshDeserialize, BorshSerialize},
+    grug_app::{Buffer, Db, PrunableDb, Snapshot, SnapshotChunkOutcome, SnapshotDb},
+    grug_jmt::{verify_proof, MerkleTree, Proof, ICS23_PROOF_SPEC},
+    grug_types::{
+        increment_last_byte, Batch, BorshDeExt, BorshSerExt, Hash256, HashExt, Op, Order, Record,
+        Storage,
+    },
     ics23::{
         commitment_proof::Proof as CommitmentProofInner, CommitmentProof, ExistenceProof,
         NonExistenceProof,
@@ -12,14 +16,16 @@ use {
         ReadOptions, WriteBatch,
     },
     std::{
+        mem,
         path::Path,
-        sync::{Arc, RwLock},
+        sync::{mpsc, Arc, RwLock},
+        thread,
     },
 };
 
-/// We use three column families (CFs) for storing data.
-/// The default family is used for metadata. Currently the only metadata we have
-/// is the latest version.
+/// We use seven column families (CFs) for storing data.
+/// The default family is used for metadata, namely the latest and oldest
+/// available versions.
 const CF_NAME_DEFAULT: &str = "default";
 
 /// The preimage column family maps key hashes to raw keys. This is necessary
@@ -43,12 +49,79 @@ const CF_NAME_STATE_COMMITMENT: &str = "state_commitment";
 /// https://github.com/left-curve/rust-rocksdb/tree/v0.21.0-cw
 const CF_NAME_STATE_STORAGE: &str = "state_storage";
 
+/// The snapshots column family stores state snapshots that are served to other
+/// nodes that wish to bootstrap themselves using state sync.
+///
+/// For each snapshot, we store its metadata under `b"m" | version`, and its
+/// chunks under `b"c" | version | index`.
+const CF_NAME_SNAPSHOTS: &str = "snapshots";
+
+/// The restoration column family stages the data of a snapshot that is being
+/// restored. Chunks are written here as they are received, so that they don't
+/// have to be held in memory. Once all chunks are received and the data is
+/// verified against the app hash, it's moved to the other CFs.
+///
+/// State storage records are staged under `b"s" | key`, and Merkle tree nodes
+/// under `b"c" | key`.
+const CF_NAME_RESTORATION: &str = "restoration";
+
+/// The checkpoints column family stores a full copy of the state at versions
+/// that are to be kept despite pruning, as configured by the pruning strategy.
+///
+/// For each checkpoint, state storage records are stored under
+/// `b"s" | version | key`, and Merkle tree nodes under `b"c" | version | key`.
+/// Once both are fully written, a marker is stored under `b"v" | version`.
+const CF_NAME_CHECKPOINTS: &str = "checkpoints";
+
+/// Names of all column families, in the order they are opened.
+const CF_NAMES: [&str; 7] = [
+    CF_NAME_DEFAULT,
+    CF_NAME_PREIMAGES,
+    CF_NAME_STATE_STORAGE,
+    CF_NAME_STATE_COMMITMENT,
+    CF_NAME_SNAPSHOTS,
+    CF_NAME_RESTORATION,
+    CF_NAME_CHECKPOINTS,
+];
+
 /// Storage key for the latest version.
 const LATEST_VERSION_KEY: &[u8] = b"latest_version";
 
 /// Storage key for the oldest version.
 const OLDEST_VERSION_KEY: &[u8] = b"oldest_version";
 
+/// Storage key prefix for snapshot metadata.
+const SNAPSHOT_METADATA_PREFIX: &[u8] = b"m";
+
+/// Storage key prefix for snapshot chunks.
+const SNAPSHOT_CHUNK_PREFIX: &[u8] = b"c";
+
+/// Storage key prefix for state storage records staged for restoration.
+const STAGED_STATE_STORAGE_PREFIX: &[u8] = b"s";
+
+/// Storage key prefix for Merkle tree nodes staged for restoration.
+const STAGED_STATE_COMMITMENT_PREFIX: &[u8] = b"c";
+
+/// Storage key prefix for the markers of complete checkpoints.
+const CHECKPOINT_MARKER_PREFIX: &[u8] = b"v";
+
+/// Storage key prefix for state storage records of checkpoints.
+const CHECKPOINT_STATE_STORAGE_PREFIX: &[u8] = b"s";
+
+/// Storage key prefix for Merkle tree nodes of checkpoints.
+const CHECKPOINT_STATE_COMMITMENT_PREFIX: &[u8] = b"c";
+
+/// The snapshot format we currently use. Should be incremented if the encoding
+/// of chunks is changed.
+pub const SNAPSHOT_FORMAT: u32 = 1;
+
+/// Approximate size of each snapshot chunk, in bytes. A chunk is sealed once
+/// the sum of the sizes of its records exceeds this.
+///
+/// CometBFT limits chunks to 16 MiB, so we leave some room for encoding
+/// overhead.
+pub const SNAPSHOT_CHUNK_SIZE: usize = 10 * 1024 * 1024;
+
 /// Jellyfish Merkle tree (JMT) using default namespaces.
 const MERKLE_TREE: MerkleTree = MerkleTree::new_default();
 
@@ -73,7 +146,7 @@ const MERKLE_TREE: MerkleTree = MerkleTree::new_default();
 /// - we use a binary Jellyfish Merkle tree (JMT) instead of IAVL;
 /// - we store JMT data in a RocksDB instance, instead of using memory map (mmap);
 /// - we don't have asynchronous commit;
-/// - we don't store snapshots or use a WAL to recover the latest state.
+/// - we don't use a WAL to recover the latest state.
 ///
 /// These differences are not because we don't agree with Sei's approach...
 /// it's just because we're having here is sort of a quick hack and we don't
@@ -81,6 +154,15 @@ const MERKLE_TREE: MerkleTree = MerkleTree::new_default();
 /// and maybe our implementation will converge with Sei's some time later.
 pub struct DiskDb {
     inner: Arc<DiskDbInner>,
+    // If set, a snapshot is created every `interval` versions after commit.
+    snapshot_config: Option<SnapshotConfig>,
+    // Which historical versions to prune after commit.
+    pruning_strategy: PruningStrategy,
+    // Sends jobs to the background thread, which performs the work that
+    // doesn't need to be done before a commit returns, such as snapshotting
+    // and pruning. Jobs are run in the order they are queued.
+    // `None` for the instance owned by the background thread itself.
+    background: Option<mpsc::Sender<BackgroundJob>>,
 }
 
 struct DiskDbInner {
@@ -89,6 +171,8 @@ struct DiskDbInner {
     // Ideally we want to just use a `rocksdb::WriteBatch` here, but it's not
     // thread-safe.
     pending_data: RwLock<Option<PendingData>>,
+    // The snapshot that is being restored, if any.
+    restoration: RwLock<Option<Restoration>>,
 }
 
 pub(crate) struct PendingData {
@@ -97,6 +181,53 @@ pub(crate) struct PendingData {
     state_storage: Batch,
 }
 
+/// Approximate size of a column family's data, in bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnFamilySize {
+    pub name: &'static str,
+    /// Total size of the SST files, i.e. data that has been flushed to disk.
+    pub sst_files: u64,
+    /// Size of the memtables, i.e. data yet to be flushed to disk.
+    pub mem_tables: u64,
+}
+
+#[derive(Clone, Copy)]
+struct SnapshotConfig {
+    interval: u64,
+    keep_recent: usize,
+}
+
+enum BackgroundJob {
+    Snapshot { version: u64, keep_recent: usize },
+    Checkpoint { version: u64 },
+    Prune { up_to_version: u64 },
+    // Signals the sender once all jobs queued before it are done.
+    Sync(mpsc::Sender<()>),
+}
+
+// Only the progress of the restoration is kept in memory. The data are staged
+// in the restoration column family.
+pub(crate) struct Restoration {
+    snapshot: Snapshot,
+    app_hash: Hash256,
+    chunk_hashes: Vec<Hash256>,
+    next_index: u32,
+}
+
+/// A chunk of a state snapshot. The records of the state storage come first,
+/// followed by those of the state commitment (i.e. the Merkle tree nodes).
+#[derive(BorshSerialize, BorshDeserialize, Default, Clone)]
+struct SnapshotChunk {
+    state_storage: Vec<Record>,
+    state_commitment: Vec<Record>,
+}
+
+impl SnapshotChunk {
+    fn is_empty(&self) -> bool {
+        self.state_storage.is_empty() && self.state_commitment.is_empty()
+    }
+}
+
 impl DiskDb {
     /// Create a DiskDb instance by opening a physical RocksDB instance.
     pub fn open<P>(data_dir: P) -> DbResult<Self>
@@ -110,21 +241,433 @@ impl DiskDb {
             (CF_NAME_PREIMAGES, new_cf_options_with_ts()),
             (CF_NAME_STATE_STORAGE, new_cf_options_with_ts()),
             (CF_NAME_STATE_COMMITMENT, Options::default()),
+            (CF_NAME_SNAPSHOTS, Options::default()),
+            (CF_NAME_RESTORATION, Options::default()),
+            (CF_NAME_CHECKPOINTS, Options::default()),
         ])?;
 
+        let inner = Arc::new(DiskDbInner {
+            db,
+            pending_data: RwLock::new(None),
+            restoration: RwLock::new(None),
+        });
+
+        // The background thread stops once all instances that can send it
+        // jobs are dropped.
+        let (sender, receiver) = mpsc::channel();
+        let background_db = Self {
+            inner: Arc::clone(&inner),
+            snapshot_config: None,
+            pruning_strategy: PruningStrategy::default(),
+            background: None,
+        };
+        thread::spawn(move || background_db.run_background_jobs(receiver));
+
         Ok(Self {
-            inner: Arc::new(DiskDbInner {
-                db,
-                pending_data: RwLock::new(None),
-            }),
+            inner,
+            snapshot_config: None,
+            pruning_strategy: PruningStrategy::default(),
+            background: Some(sender),
+        })
+    }
+
+    /// Create a snapshot every `interval` versions after commit, keeping only
+    /// the `keep_recent` most recent ones.
+    ///
+    /// Snapshots are created in a background thread, so a commit doesn't wait
+    /// for them. Failures are logged rather than returned.
+    pub fn with_snapshots(mut self, interval: u64, keep_recent: usize) -> Self {
+        self.snapshot_config = Some(SnapshotConfig {
+            interval,
+            keep_recent,
+        });
+        self
+    }
+
+    /// Prune historical versions after commit, following the given strategy.
+    ///
+    /// Pruning is done in a background thread, so a commit doesn't wait for it.
+    pub fn with_pruning(mut self, strategy: PruningStrategy) -> Self {
+        self.pruning_strategy = strategy;
+        self
+    }
+
+    /// Block until all background jobs queued so far, such as snapshotting and
+    /// pruning, are done.
+    pub fn wait_for_background_jobs(&self) {
+        let (sender, receiver) = mpsc::channel();
+        if self.queue_background_job(BackgroundJob::Sync(sender)) {
+            receiver.recv().ok();
+        }
+    }
+
+    // Return false if the job can't be queued, either because this is the
+    // background thread's own instance, or because the thread has died.
+    fn queue_background_job(&self, job: BackgroundJob) -> bool {
+        self.background
+            .as_ref()
+            .is_some_and(|background| background.send(job).is_ok())
+    }
+
+    fn run_background_jobs(self, receiver: mpsc::Receiver<BackgroundJob>) {
+        for job in receiver {
+            match job {
+                BackgroundJob::Snapshot {
+                    version,
+                    keep_recent,
+                } => {
+                    if let Err(_err) = self.create_snapshot_and_delete_old(version, keep_recent) {
+                        #[cfg(feature = "tracing")]
+                        tracing::error!(
+                            version,
+                            err = _err.to_string(),
+                            "Failed to create snapshot"
+                        );
+                    }
+                },
+                BackgroundJob::Checkpoint { version } => {
+                    if let Err(_err) = self.create_checkpoint(version) {
+                        #[cfg(feature = "tracing")]
+                        tracing::error!(
+                            version,
+                            err = _err.to_string(),
+                            "Failed to create checkpoint"
+                        );
+                    }
+                },
+                BackgroundJob::Prune { up_to_version } => {
+                    // Versions may have been pruned by the time this job is
+                    // run, e.g. with the `db prune` command.
+                    if up_to_version <= self.oldest_version().unwrap_or(0) {
+                        continue;
+                    }
+
+                    if let Err(_err) = self.prune(up_to_version) {
+                        #[cfg(feature = "tracing")]
+                        tracing::error!(
+                            up_to_version,
+                            err = _err.to_string(),
+                            "Failed to prune historical versions"
+                        );
+                    }
+                },
+                BackgroundJob::Sync(done) => {
+                    done.send(()).ok();
+                },
+            }
+        }
+    }
+
+    /// Return the approximate size of each column family.
+    pub fn column_family_sizes(&self) -> DbResult<Vec<ColumnFamilySize>> {
+        CF_NAMES
+            .into_iter()
+            .map(|name| {
+                let cf = self.inner.db.cf_handle(name).unwrap_or_else(|| {
+                    panic!("failed to find {name} column family");
+                });
+                let sst_files = self
+                    .inner
+                    .db
+                    .property_int_value_cf(&cf, "rocksdb.total-sst-files-size")?
+                    .unwrap_or(0);
+                let mem_tables = self
+                    .inner
+                    .db
+                    .property_int_value_cf(&cf, "rocksdb.size-all-mem-tables")?
+                    .unwrap_or(0);
+
+                Ok(ColumnFamilySize {
+                    name,
+                    sst_files,
+                    mem_tables,
+                })
+            })
+            .collect()
+    }
+
+    /// Export the state at the given version into a snapshot, and store it in
+    /// the database so that it can be served to other nodes.
+    pub fn create_snapshot(&self, version: u64) -> DbResult<Snapshot> {
+        let state_storage = self.state_storage(Some(version))?;
+        let nodes = MERKLE_TREE.export(&self.state_commitment(), version)?;
+
+        let records = state_storage
+            .scan(None, None, Order::Ascending)
+            .map(|record| (true, record))
+            .chain(nodes.into_iter().map(|record| (false, record)));
+
+        let mut chunks = Vec::new();
+        let mut chunk = SnapshotChunk::default();
+        let mut chunk_size = 0;
+
+        for (is_state_storage, (key, value)) in records {
+            chunk_size += key.len() + value.len();
+
+            if is_state_storage {
+                chunk.state_storage.push((key, value));
+            } else {
+                chunk.state_commitment.push((key, value));
+            }
+
+            if chunk_size >= SNAPSHOT_CHUNK_SIZE {
+                chunks.push(mem::take(&mut chunk).to_borsh_vec()?);
+                chunk_size = 0;
+            }
+        }
+
+        // Seal the last chunk. A snapshot always has at least one chunk, even
+        // if the state is empty.
+        if !chunk.is_empty() || chunks.is_empty() {
+            chunks.push(chunk.to_borsh_vec()?);
+        }
+
+        let chunk_hashes = chunks
+            .iter()
+            .map(|chunk| chunk.hash256())
+            .collect::<Vec<_>>();
+        let metadata = chunk_hashes.to_borsh_vec()?;
+        let snapshot = Snapshot {
+            version,
+            format: SNAPSHOT_FORMAT,
+            chunks: chunks.len() as u32,
+            hash: metadata.hash256(),
+            metadata,
+        };
+
+        let mut batch = WriteBatch::default();
+        let cf = cf_snapshots(&self.inner.db);
+        batch.put_cf(
+            &cf,
+            snapshot_metadata_key(version),
+            snapshot.to_borsh_vec()?,
+        );
+        for (index, chunk) in chunks.into_iter().enumerate() {
+            batch.put_cf(&cf, snapshot_chunk_key(version, index as u32), chunk);
+        }
+
+        self.inner.db.write(batch)?;
+
+        Ok(snapshot)
+    }
+
+    fn create_snapshot_and_delete_old(&self, version: u64, keep_recent: usize) -> DbResult<()> {
+        self.create_snapshot(version)?;
+
+        let snapshots = self.list_snapshots()?;
+        for snapshot in snapshots.iter().rev().skip(keep_recent) {
+            self.delete_snapshot(snapshot.version)?;
+        }
+
+        Ok(())
+    }
+
+    /// Delete the snapshot of the given version, if it exists.
+    pub fn delete_snapshot(&self, version: u64) -> DbResult<()> {
+        let mut batch = WriteBatch::default();
+        let cf = cf_snapshots(&self.inner.db);
+        batch.delete_cf(&cf, snapshot_metadata_key(version));
+        batch.delete_range_cf(
+            &cf,
+            snapshot_chunk_key(version, 0),
+            snapshot_chunk_key(version, u32::MAX),
+        );
+        batch.delete_cf(&cf, snapshot_chunk_key(version, u32::MAX));
+
+        Ok(self.inner.db.write(batch)?)
+    }
+
+    /// Copy the state at the given version into the checkpoints column family,
+    /// so that it remains available after the version is pruned.
+    pub fn create_checkpoint(&self, version: u64) -> DbResult<()> {
+        let state_storage = self.state_storage(Some(version))?;
+        let nodes = MERKLE_TREE.export(&self.state_commitment(), version)?;
+
+        let mut batch = WriteBatch::default();
+        let cf = cf_checkpoints(&self.inner.db);
+
+        let prefix = checkpoint_prefix(CHECKPOINT_STATE_STORAGE_PREFIX, version);
+        for (key, value) in state_storage.scan(None, None, Order::Ascending) {
+            batch.put_cf(&cf, prefixed_key(&prefix, &key), value);
+            self.write_if_full(&mut batch)?;
+        }
+
+        let prefix = checkpoint_prefix(CHECKPOINT_STATE_COMMITMENT_PREFIX, version);
+        for (key, value) in nodes {
+            batch.put_cf(&cf, prefixed_key(&prefix, &key), value);
+            self.write_if_full(&mut batch)?;
+        }
+
+        // Write the marker last, so that a checkpoint is only considered to
+        // exist once all its data has been written.
+        let marker = checkpoint_prefix(CHECKPOINT_MARKER_PREFIX, version);
+        batch.put_cf(&cf, marker, b"");
+
+        Ok(self.inner.db.write(batch)?)
+    }
+
+    /// Return the versions of all checkpoints, in ascending order.
+    pub fn list_checkpoints(&self) -> DbResult<Vec<u64>> {
+        let opts = new_read_options(
+            None,
+            Some(CHECKPOINT_MARKER_PREFIX),
+            Some(&increment_last_byte(CHECKPOINT_MARKER_PREFIX.to_vec())),
+        );
+
+        self.inner
+            .db
+            .iterator_cf_opt(&cf_checkpoints(&self.inner.db), opts, IteratorMode::Start)
+            .map(|item| {
+                let (key, _) = item?;
+                let array = key[CHECKPOINT_MARKER_PREFIX.len()..]
+                    .try_into()
+                    .unwrap_or_else(|_| {
+                        panic!(
+                            "checkpoint version is of incorrect byte length: {}",
+                            key.len()
+                        );
+                    });
+                Ok(u64::from_be_bytes(array))
+            })
+            .collect()
+    }
+
+    // Return the storage of the checkpoint under the given prefix, if a
+    // checkpoint exists at this version and it has been pruned from the
+    // regular column families.
+    fn checkpoint(&self, prefix: &[u8], version: u64) -> Option<PrefixedStorage> {
+        let pruned = self
+            .oldest_version()
+            .is_some_and(|oldest_version| version < oldest_version);
+
+        if !pruned || !self.has_checkpoint(version) {
+            return None;
+        }
+
+        Some(PrefixedStorage {
+            inner: Arc::clone(&self.inner),
+            cf_name: CF_NAME_CHECKPOINTS,
+            prefix: checkpoint_prefix(prefix, version),
         })
     }
+
+    // Verify the staged data against the app hash. If they match, move them
+    // to the other column families.
+    fn restore(&self, restoration: Restoration) -> DbResult<()> {
+        let version = restoration.snapshot.version;
+        let app_hash = restoration.app_hash;
+        let nodes = self.staged(STAGED_STATE_COMMITMENT_PREFIX);
+        let records = self.staged(STAGED_STATE_STORAGE_PREFIX);
+
+        let computed = MERKLE_TREE.root_hash(&nodes, version)?;
+        if computed != Some(app_hash) {
+            return Err(DbError::RestoredRootHashMismatch {
+                computed,
+                expected: app_hash,
+            });
+        }
+
+        // Check that the staged nodes are exactly those of the tree committed
+        // to by the app hash: each node must hash to the value committed to by
+        // its parent, and there must not be any node that isn't part of the
+        // tree.
+        let size = MERKLE_TREE
+            .verify(&nodes, version, app_hash)?
+            .ok_or(DbError::SnapshotTreeInvalid)?;
+
+        let num_nodes = nodes.scan_keys(None, None, Order::Ascending).count();
+        if num_nodes != size.nodes {
+            return Err(DbError::SnapshotNodeCountMismatch {
+                expected: size.nodes,
+                actual: num_nodes,
+            });
+        }
+
+        // Check the KV pairs. Every one of them must be provable against the
+        // app hash, and there must be as many of them as there are leaves in
+        // the tree. This proves that the state storage is complete and correct.
+        let mut num_records = 0;
+        for (key, value) in records.scan(None, None, Order::Ascending) {
+            let key_hash = key.hash256();
+            let proof = MERKLE_TREE.prove(&nodes, key_hash, version)?;
+            verify_proof(app_hash, key_hash, Some(value.hash256()), &proof)?;
+            num_records += 1;
+        }
+
+        if num_records != size.leaves {
+            return Err(DbError::SnapshotRecordCountMismatch {
+                expected: size.leaves,
+                actual: num_records,
+            });
+        }
+
+        // All checks passed. Move the data to the other column families, in
+        // batches of about the size of a chunk.
+        let ts = U64Timestamp::from(version);
+        let mut batch = WriteBatch::default();
+
+        let cf = cf_state_commitment(&self.inner.db);
+        for (key, value) in nodes.scan(None, None, Order::Ascending) {
+            batch.put_cf(&cf, key, value);
+            self.write_if_full(&mut batch)?;
+        }
+
+        let cf_preimages = cf_preimages(&self.inner.db);
+        let cf_state_storage = cf_state_storage(&self.inner.db);
+        for (key, value) in records.scan(None, None, Order::Ascending) {
+            batch.put_cf_with_ts(&cf_preimages, key.hash256(), ts, &key);
+            batch.put_cf_with_ts(&cf_state_storage, key, ts, value);
+            self.write_if_full(&mut batch)?;
+        }
+
+        // Finally, set the versions, which marks the DB as no longer empty, and
+        // clear the staged data.
+        //
+        // Data prior to the snapshot version is not available, so we set the
+        // oldest version as if the DB has been pruned.
+        let cf = cf_default(&self.inner.db);
+        batch.put_cf(&cf, LATEST_VERSION_KEY, version.to_le_bytes());
+        batch.put_cf(&cf, OLDEST_VERSION_KEY, version.to_le_bytes());
+
+        self.clear_staged_records(&mut batch);
+
+        Ok(self.inner.db.write(batch)?)
+    }
+
+    // Return the records staged for restoration under the given prefix.
+    fn staged(&self, prefix: &[u8]) -> PrefixedStorage {
+        PrefixedStorage {
+            inner: Arc::clone(&self.inner),
+            cf_name: CF_NAME_RESTORATION,
+            prefix: prefix.to_vec(),
+        }
+    }
+
+    fn clear_staged_records(&self, batch: &mut WriteBatch) {
+        let cf = cf_restoration(&self.inner.db);
+        for prefix in [STAGED_STATE_STORAGE_PREFIX, STAGED_STATE_COMMITMENT_PREFIX] {
+            batch.delete_range_cf(&cf, prefix, increment_last_byte(prefix.to_vec()));
+        }
+    }
+
+    // Write the batch to the DB and start a new one, if it has reached the size
+    // of a snapshot chunk.
+    fn write_if_full(&self, batch: &mut WriteBatch) -> DbResult<()> {
+        if batch.size_in_bytes() >= SNAPSHOT_CHUNK_SIZE {
+            self.inner.db.write(mem::take(batch))?;
+        }
+
+        Ok(())
+    }
 }
 
 impl Clone for DiskDb {
     fn clone(&self) -> Self {
         Self {
             inner: Arc::clone(&self.inner),
+            snapshot_config: self.snapshot_config,
+            pruning_strategy: self.pruning_strategy,
+            background: self.background.clone(),
         }
     }
 }
@@ -165,8 +708,9 @@ impl Db for DiskDb {
         // If the oldest version record exists (meaning, pruning has been
         // performed at least once), and the requested version is older than it,
         // return error.
+        // Unless a checkpoint is kept at this version.
         if let Some(oldest_version) = self.oldest_version() {
-            if version < oldest_version {
+            if version < oldest_version && !self.has_checkpoint(version) {
                 return Err(DbError::VersionTooOld {
                     version,
                     oldest_version,
@@ -177,6 +721,7 @@ impl Db for DiskDb {
         Ok(StateStorage {
             inner: Arc::clone(&self.inner),
             version,
+            checkpoint: self.checkpoint(CHECKPOINT_STATE_STORAGE_PREFIX, version),
         })
     }
 
@@ -198,13 +743,54 @@ impl Db for DiskDb {
         Some(u64::from_le_bytes(array))
     }
 
+    fn oldest_version(&self) -> Option<u64> {
+        let cf = cf_default(&self.inner.db);
+        let bytes = self
+            .inner
+            .db
+            .get_cf(&cf, OLDEST_VERSION_KEY)
+            .unwrap_or_else(|err| {
+                panic!("failed to read from default column family: {err}");
+            })?;
+        let array = bytes.try_into().unwrap_or_else(|bytes: Vec<u8>| {
+            panic!(
+                "oldest version is of incorrect byte length: {}",
+                bytes.len()
+            );
+        });
+        Some(u64::from_le_bytes(array))
+    }
+
+    fn has_checkpoint(&self, version: u64) -> bool {
+        self.inner
+            .db
+            .get_cf(
+                &cf_checkpoints(&self.inner.db),
+                checkpoint_prefix(CHECKPOINT_MARKER_PREFIX, version),
+            )
+            .unwrap_or_else(|err| {
+                panic!("failed to read from checkpoints: {err}");
+            })
+            .is_some()
+    }
+
     fn root_hash(&self, version: Option<u64>) -> DbResult<Option<Hash256>> {
         let version = version.unwrap_or_else(|| self.latest_version().unwrap_or(0));
+
+        if let Some(nodes) = self.checkpoint(CHECKPOINT_STATE_COMMITMENT_PREFIX, version) {
+            return Ok(MERKLE_TREE.root_hash(&nodes, version)?);
+        }
+
         Ok(MERKLE_TREE.root_hash(&self.state_commitment(), version)?)
     }
 
     fn prove(&self, key: &[u8], version: Option<u64>) -> DbResult<Proof> {
         let version = version.unwrap_or_else(|| self.latest_version().unwrap_or(0));
+
+        if let Some(nodes) = self.checkpoint(CHECKPOINT_STATE_COMMITMENT_PREFIX, version) {
+            return Ok(MERKLE_TREE.prove(&nodes, key.hash256(), version)?);
+        }
+
         Ok(MERKLE_TREE.prove(&self.state_commitment(), key.hash256(), version)?)
     }
 
@@ -214,6 +800,18 @@ impl Db for DiskDb {
         version: Option<u64>,
     ) -> Result<CommitmentProof, Self::Error> {
         let version = version.unwrap_or_else(|| self.latest_version().unwrap_or(0));
+
+        // Checkpoints don't keep the preimages, which are needed to find the
+        // neighbors for non-existence proofs.
+        if let Some(oldest_version) = self.oldest_version() {
+            if version < oldest_version {
+                return Err(DbError::VersionTooOld {
+                    version,
+                    oldest_version,
+                });
+            }
+        }
+
         let state_storage = self.state_storage(Some(version))?;
         let state_commitment = self.state_commitment();
 
@@ -359,29 +957,42 @@ impl Db for DiskDb {
             }
         }
 
-        Ok(self.inner.db.write(batch)?)
+        self.inner.db.write(batch)?;
+
+        // Create a snapshot in the background if it's time to, and delete the
+        // old ones. This must be queued before pruning, which may remove the
+        // version to be snapshotted.
+        if let Some(SnapshotConfig {
+            interval,
+            keep_recent,
+        }) = self.snapshot_config
+        {
+            if pending.version > 0 && pending.version % interval == 0 {
+                self.queue_background_job(BackgroundJob::Snapshot {
+                    version: pending.version,
+                    keep_recent,
+                });
+            }
+        }
+
+        // Keep a checkpoint of this version, if the strategy says so. This too
+        // must be queued before pruning.
+        if self.pruning_strategy.is_checkpoint(pending.version) {
+            self.queue_background_job(BackgroundJob::Checkpoint {
+                version: pending.version,
+            });
+        }
+
+        // Prune historical versions in the background, if the strategy says so.
+        if let Some(up_to_version) = self.pruning_strategy.prune_up_to(pending.version) {
+            self.queue_background_job(BackgroundJob::Prune { up_to_version });
+        }
+
+        Ok(())
     }
 }
 
 impl PrunableDb for DiskDb {
-    fn oldest_version(&self) -> Option<u64> {
-        let cf = cf_default(&self.inner.db);
-        let bytes = self
-            .inner
-            .db
-            .get_cf(&cf, OLDEST_VERSION_KEY)
-            .unwrap_or_else(|err| {
-                panic!("failed to read from default column family: {err}");
-            })?;
-        let array = bytes.try_into().unwrap_or_else(|bytes: Vec<u8>| {
-            panic!(
-                "oldest version is of incorrect byte length: {}",
-                bytes.len()
-            );
-        });
-        Some(u64::from_le_bytes(array))
-    }
-
     fn prune(&self, up_to_version: u64) -> DbResult<()> {
         let ts = U64Timestamp::from(up_to_version);
 
@@ -424,6 +1035,243 @@ impl PrunableDb for DiskDb {
     }
 }
 
+impl SnapshotDb for DiskDb {
+    fn list_snapshots(&self) -> DbResult<Vec<Snapshot>> {
+        let opts = new_read_options(
+            None,
+            Some(SNAPSHOT_METADATA_PREFIX),
+            Some(&increment_last_byte(SNAPSHOT_METADATA_PREFIX.to_vec())),
+        );
+
+        self.inner
+            .db
+            .iterator_cf_opt(&cf_snapshots(&self.inner.db), opts, IteratorMode::Start)
+            .map(|item| {
+                let (_, value) = item?;
+                Ok(value.deserialize_borsh()?)
+            })
+            .collect()
+    }
+
+    fn load_snapshot_chunk(
+        &self,
+        version: u64,
+        format: u32,
+        index: u32,
+    ) -> DbResult<Option<Vec<u8>>> {
+        if format != SNAPSHOT_FORMAT {
+            return Ok(None);
+        }
+
+        Ok(self.inner.db.get_cf(
+            &cf_snapshots(&self.inner.db),
+            snapshot_chunk_key(version, index),
+        )?)
+    }
+
+    fn offer_snapshot(&self, snapshot: Snapshot, app_hash: Hash256) -> DbResult<()> {
+        if snapshot.format != SNAPSHOT_FORMAT {
+            return Err(DbError::UnsupportedSnapshotFormat {
+                format: snapshot.format,
+            });
+        }
+
+        // We can only restore into an empty DB. CometBFT only does state sync
+        // for a fresh node anyways.
+        if self.latest_version().is_some() {
+            return Err(DbError::DbNotEmpty);
+        }
+
+        // The metadata consists of the hashes of the chunks. Make sure it
+        // matches the snapshot hash.
+        let computed = snapshot.metadata.hash256();
+        if computed != snapshot.hash {
+            return Err(DbError::SnapshotHashMismatch {
+                computed,
+                expected: snapshot.hash,
+            });
+        }
+
+        let chunk_hashes: Vec<Hash256> = snapshot.metadata.deserialize_borsh()?;
+        if snapshot.chunks == 0 || chunk_hashes.len() != snapshot.chunks as usize {
+            return Err(DbError::SnapshotChunksMismatch {
+                chunks: snapshot.chunks,
+                hashes: chunk_hashes.len(),
+            });
+        }
+
+        // If a previous restoration is in progress, it's discarded, along with
+        // the data it has staged.
+        let mut guard = self.inner.restoration.write()?;
+
+        let mut batch = WriteBatch::default();
+        self.clear_staged_records(&mut batch);
+        self.inner.db.write(batch)?;
+
+        *guard = Some(Restoration {
+            snapshot,
+            app_hash,
+            chunk_hashes,
+            next_index: 0,
+        });
+
+        Ok(())
+    }
+
+    fn apply_snapshot_chunk(&self, index: u32, chunk: Vec<u8>) -> DbResult<SnapshotChunkOutcome> {
+        let mut guard = self.inner.restoration.write()?;
+        let restoration = guard.as_mut().ok_or(DbError::RestorationNotStarted)?;
+
+        if index != restoration.next_index {
+            return Err(DbError::SnapshotChunkOutOfOrder {
+                expected: restoration.next_index,
+                actual: index,
+            });
+        }
+
+        // The chunk may have been corrupted by the node that sent it. Don't
+        // apply it, but the restoration can continue once a good one is
+        // received.
+        if chunk.hash256() != restoration.chunk_hashes[index as usize] {
+            return Ok(SnapshotChunkOutcome::Corrupted);
+        }
+
+        // Stage the records on disk until all chunks are received, at which
+        // point we can verify them against the app hash.
+        let chunk: SnapshotChunk = chunk.deserialize_borsh()?;
+        let mut batch = WriteBatch::default();
+        let cf = cf_restoration(&self.inner.db);
+        for (key, value) in chunk.state_storage {
+            let key = prefixed_key(STAGED_STATE_STORAGE_PREFIX, &key);
+            batch.put_cf(&cf, key, value);
+        }
+        for (key, value) in chunk.state_commitment {
+            let key = prefixed_key(STAGED_STATE_COMMITMENT_PREFIX, &key);
+            batch.put_cf(&cf, key, value);
+        }
+        self.inner.db.write(batch)?;
+
+        restoration.next_index += 1;
+
+        if restoration.next_index < restoration.snapshot.chunks {
+            return Ok(SnapshotChunkOutcome::Applied);
+        }
+
+        // The restoration is finished whether it succeeds or not. Either way,
+        // take it out.
+        let restoration = guard.take().unwrap();
+
+        let result = self.restore(restoration);
+
+        // If the restoration failed, the staged data are of no use.
+        if result.is_err() {
+            let mut batch = WriteBatch::default();
+            self.clear_staged_records(&mut batch);
+            self.inner.db.write(batch)?;
+        }
+
+        result?;
+
+        Ok(SnapshotChunkOutcome::Restored)
+    }
+}
+
+// ----------------------------- prefixed storage ------------------------------
+
+// Read-only access to the records under a prefix in a column family, with the
+// prefix stripped from the keys. Used for snapshot data staged for restoration,
+// and for checkpoints.
+#[derive(Clone)]
+struct PrefixedStorage {
+    inner: Arc<DiskDbInner>,
+    cf_name: &'static str,
+    prefix: Vec<u8>,
+}
+
+impl PrefixedStorage {
+    fn iter<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: Order,
+    ) -> impl Iterator<Item = Record> + 'a {
+        let min = prefixed_key(&self.prefix, min.unwrap_or_default());
+        let max = match max {
+            Some(max) => prefixed_key(&self.prefix, max),
+            None => increment_last_byte(self.prefix.clone()),
+        };
+        let opts = new_read_options(None, Some(&min), Some(&max));
+        let mode = into_iterator_mode(order);
+        let cf = self.inner.db.cf_handle(self.cf_name).unwrap_or_else(|| {
+            panic!("failed to find {} column family", self.cf_name);
+        });
+
+        self.inner
+            .db
+            .iterator_cf_opt(&cf, opts, mode)
+            .map(move |item| {
+                let (k, v) = item.unwrap_or_else(|err| {
+                    panic!("failed to iterate in {}: {err}", self.cf_name);
+                });
+                (k[self.prefix.len()..].to_vec(), v.to_vec())
+            })
+    }
+}
+
+impl Storage for PrefixedStorage {
+    fn read(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let cf = self.inner.db.cf_handle(self.cf_name).unwrap_or_else(|| {
+            panic!("failed to find {} column family", self.cf_name);
+        });
+
+        self.inner
+            .db
+            .get_cf(&cf, prefixed_key(&self.prefix, key))
+            .unwrap_or_else(|err| {
+                panic!("failed to read from {}: {err}", self.cf_name);
+            })
+    }
+
+    fn scan<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        Box::new(self.iter(min, max, order))
+    }
+
+    fn scan_keys<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        Box::new(self.iter(min, max, order).map(|(k, _)| k))
+    }
+
+    fn scan_values<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        Box::new(self.iter(min, max, order).map(|(_, v)| v))
+    }
+
+    fn write(&mut self, _key: &[u8], _value: &[u8]) {
+        unreachable!("write function called on read-only storage");
+    }
+
+    fn remove(&mut self, _key: &[u8]) {
+        unreachable!("write function called on read-only storage");
+    }
+
+    fn remove_range(&mut self, _min: Option<&[u8]>, _max: Option<&[u8]>) {
+        unreachable!("write function called on read-only storage");
+    }
+}
+
 // ----------------------------- state commitment ------------------------------
 
 pub struct StateCommitment {
@@ -530,10 +1378,16 @@ impl Storage for StateCommitment {
 pub struct StateStorage {
     inner: Arc<DiskDbInner>,
     version: u64,
+    // If the version has been pruned, the checkpoint to read from instead.
+    checkpoint: Option<PrefixedStorage>,
 }
 
 impl Storage for StateStorage {
     fn read(&self, key: &[u8]) -> Option<Vec<u8>> {
+        if let Some(checkpoint) = &self.checkpoint {
+            return checkpoint.read(key);
+        }
+
         let opts = new_read_options(Some(self.version), None, None);
         self.inner
             .db
@@ -549,6 +1403,10 @@ impl Storage for StateStorage {
         max: Option<&[u8]>,
         order: Order,
     ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        if let Some(checkpoint) = &self.checkpoint {
+            return checkpoint.scan(min, max, order);
+        }
+
         let opts = new_read_options(Some(self.version), min, max);
         let mode = into_iterator_mode(order);
         let iter = self
@@ -570,6 +1428,10 @@ impl Storage for StateStorage {
         max: Option<&[u8]>,
         order: Order,
     ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        if let Some(checkpoint) = &self.checkpoint {
+            return checkpoint.scan_keys(min, max, order);
+        }
+
         let opts = new_read_options(Some(self.version), min, max);
         let mode = into_iterator_mode(order);
         let iter = self
@@ -591,6 +1453,10 @@ impl Storage for StateStorage {
         max: Option<&[u8]>,
         order: Order,
     ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        if let Some(checkpoint) = &self.checkpoint {
+            return checkpoint.scan_values(min, max, order);
+        }
+
         let opts = new_read_options(Some(self.version), min, max);
         let mode = into_iterator_mode(order);
         let iter = self
@@ -694,17 +1560,61 @@ fn cf_state_commitment(db: &DBWithThreadMode<MultiThreaded>) -> Arc<BoundColumnF
     })
 }
 
+fn cf_snapshots(db: &DBWithThreadMode<MultiThreaded>) -> Arc<BoundColumnFamily> {
+    db.cf_handle(CF_NAME_SNAPSHOTS).unwrap_or_else(|| {
+        panic!("failed to find snapshots column family");
+    })
+}
+
+fn cf_restoration(db: &DBWithThreadMode<MultiThreaded>) -> Arc<BoundColumnFamily> {
+    db.cf_handle(CF_NAME_RESTORATION).unwrap_or_else(|| {
+        panic!("failed to find restoration column family");
+    })
+}
+
+fn cf_checkpoints(db: &DBWithThreadMode<MultiThreaded>) -> Arc<BoundColumnFamily> {
+    db.cf_handle(CF_NAME_CHECKPOINTS).unwrap_or_else(|| {
+        panic!("failed to find checkpoints column family");
+    })
+}
+
+// Note: use big endian, so that snapshots are ordered by version.
+fn snapshot_metadata_key(version: u64) -> Vec<u8> {
+    let mut key = SNAPSHOT_METADATA_PREFIX.to_vec();
+    key.extend(version.to_be_bytes());
+    key
+}
+
+fn snapshot_chunk_key(version: u64, index: u32) -> Vec<u8> {
+    let mut key = SNAPSHOT_CHUNK_PREFIX.to_vec();
+    key.extend(version.to_be_bytes());
+    key.extend(index.to_be_bytes());
+    key
+}
+
+// Note: use big endian, such that checkpoints are listed in ascending order.
+fn checkpoint_prefix(prefix: &[u8], version: u64) -> Vec<u8> {
+    prefixed_key(prefix, &version.to_be_bytes())
+}
+
+fn prefixed_key(prefix: &[u8], key: &[u8]) -> Vec<u8> {
+    let mut prefixed_key = prefix.to_vec();
+    prefixed_key.extend_from_slice(key);
+    prefixed_key
+}
+
 // ----------------------------------- test ------------------------------------
 
 #[cfg(test)]
 mod tests {
     use {
-        crate::{DiskDb, TempDataDir},
-        grug_app::{Db, PrunableDb},
+        super::{SnapshotChunk, STAGED_STATE_COMMITMENT_PREFIX, STAGED_STATE_STORAGE_PREFIX},
+        crate::{DbError, DiskDb, PruningStrategy, TempDataDir, SNAPSHOT_FORMAT},
+        grug_app::{Db, PrunableDb, Snapshot, SnapshotChunkOutcome, SnapshotDb},
         grug_jmt::{
             verify_proof, MembershipProof, NonMembershipProof, Proof, ProofNode, ICS23_PROOF_SPEC,
         },
-        grug_types::{Batch, Hash256, HashExt, Op, Order, Storage},
+        grug_types::{Batch, BorshDeExt, BorshSerExt, Hash256, HashExt, Op, Order, Storage},
         hex_literal::hex,
         ics23::HostFunctionsManager,
         proptest::prelude::*,
@@ -1105,6 +2015,131 @@ mod tests {
         }
     }
 
+    #[test]
+    fn disk_db_auto_pruning_works() {
+        for (name, strategy, oldest_version) in [
+            ("keep_all", PruningStrategy::KeepAll, None),
+            (
+                "keep_recent",
+                PruningStrategy::KeepRecent { keep_recent: 3 },
+                Some(3),
+            ),
+            (
+                "keep_every",
+                PruningStrategy::KeepEvery {
+                    keep_recent: 3,
+                    interval: 2,
+                },
+                Some(3),
+            ),
+        ] {
+            let path = TempDataDir::new(&format!("_grug_disk_db_auto_pruning_works_{name}"));
+            let db = DiskDb::open(&path).unwrap().with_pruning(strategy);
+
+            // Commit versions 0-5, each writing a different key.
+            for i in 0..=5_u8 {
+                db.flush_and_commit(Batch::from([(vec![i], Op::Insert(vec![i]))]))
+                    .unwrap();
+            }
+
+            // Pruning is done in the background.
+            db.wait_for_background_jobs();
+
+            assert_eq!(db.oldest_version(), oldest_version);
+
+            // Versions older than the oldest available one can't be accessed,
+            // unless they are kept as checkpoints.
+            for version in 0..oldest_version.unwrap_or(0) {
+                if strategy.is_checkpoint(version) {
+                    assert!(db.state_storage(Some(version)).is_ok());
+                    continue;
+                }
+
+                assert!(matches!(
+                    db.state_storage(Some(version)),
+                    Err(DbError::VersionTooOld { oldest_version: v, .. }) if Some(v) == oldest_version
+                ));
+            }
+
+            // The rest can.
+            for version in oldest_version.unwrap_or(0)..=5 {
+                assert!(db.state_storage(Some(version)).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn disk_db_checkpoints_work() {
+        let path = TempDataDir::new("_grug_disk_db_checkpoints_work");
+        let db = DiskDb::open(&path).unwrap().with_pruning(PruningStrategy::KeepEvery {
+            keep_recent: 2,
+            interval: 3,
+        });
+
+        // Commit versions 0-7. Each version overwrites the key `a`, and writes
+        // a different key, such that the states of different versions differ.
+        let mut root_hashes = Vec::new();
+        for i in 0..=7_u8 {
+            let (_, root_hash) = db
+                .flush_and_commit(Batch::from([
+                    (b"a".to_vec(), Op::Insert(vec![i])),
+                    (vec![b'b', i], Op::Insert(vec![i])),
+                ]))
+                .unwrap();
+            root_hashes.push(root_hash);
+        }
+
+        db.wait_for_background_jobs();
+
+        assert_eq!(db.oldest_version(), Some(6));
+        assert_eq!(db.list_checkpoints().unwrap(), vec![0, 3, 6]);
+
+        for version in [0, 3] {
+            assert!(db.has_checkpoint(version));
+
+            // The state at the checkpoint can be read and iterated.
+            let storage = db.state_storage(Some(version)).unwrap();
+            assert_eq!(storage.read(b"a"), Some(vec![version as u8]));
+            assert_eq!(
+                storage
+                    .scan(Some(b"b".as_slice()), None, Order::Ascending)
+                    .collect::<Vec<_>>(),
+                (0..=version as u8)
+                    .map(|i| (vec![b'b', i], vec![i]))
+                    .collect::<Vec<_>>()
+            );
+
+            // The root hash is the same as when the version was committed.
+            let root_hash = db.root_hash(Some(version)).unwrap();
+            assert_eq!(root_hash, root_hashes[version as usize]);
+
+            // Merkle proofs can be generated and verified.
+            let proof = db.prove(b"a", Some(version)).unwrap();
+            verify_proof(
+                root_hash.unwrap(),
+                b"a".hash256(),
+                Some(vec![version as u8].hash256()),
+                &proof,
+            )
+            .unwrap();
+
+            // ICS-23 proofs can't, as the preimages aren't kept.
+            assert!(matches!(
+                db.ics23_prove(b"a".to_vec(), Some(version)),
+                Err(DbError::VersionTooOld { .. })
+            ));
+        }
+
+        // Versions in between checkpoints have been pruned.
+        for version in [1, 2, 4, 5] {
+            assert!(!db.has_checkpoint(version));
+            assert!(matches!(
+                db.state_storage(Some(version)),
+                Err(DbError::VersionTooOld { .. })
+            ));
+        }
+    }
+
     #[test]
     fn ics23_prove_works() {
         let path = TempDataDir::new("_grug_disk_db_ics23_proving_works");
@@ -1208,6 +2243,209 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn snapshot_works() {
+        let path = TempDataDir::new("_grug_disk_db_snapshot_works");
+        let db = DiskDb::open(&path).unwrap().with_snapshots(1, 1);
+
+        // Write the same two batches as in the previous tests. A snapshot is
+        // created at version 1 but not at version 0.
+        db.flush_and_commit(Batch::from([
+            (b"donald".to_vec(), Op::Insert(b"trump".to_vec())),
+            (b"jake".to_vec(), Op::Insert(b"shepherd".to_vec())),
+            (b"joe".to_vec(), Op::Insert(b"biden".to_vec())),
+            (b"larry".to_vec(), Op::Insert(b"engineer".to_vec())),
+        ]))
+        .unwrap();
+        db.flush_and_commit(Batch::from([
+            (b"donald".to_vec(), Op::Insert(b"duck".to_vec())),
+            (b"joe".to_vec(), Op::Delete),
+            (b"pumpkin".to_vec(), Op::Insert(b"cat".to_vec())),
+        ]))
+        .unwrap();
+
+        // Snapshots are created in the background.
+        db.wait_for_background_jobs();
+
+        let snapshots = db.list_snapshots().unwrap();
+        assert_eq!(snapshots.len(), 1);
+
+        let snapshot = snapshots[0].clone();
+        assert_eq!(snapshot.version, 1);
+        assert_eq!(snapshot.format, SNAPSHOT_FORMAT);
+        assert_eq!(snapshot.chunks, 1);
+
+        let chunk = db
+            .load_snapshot_chunk(1, SNAPSHOT_FORMAT, 0)
+            .unwrap()
+            .unwrap();
+
+        // Chunks of unknown formats or indexes aren't found.
+        assert!(db.load_snapshot_chunk(1, 0, 0).unwrap().is_none());
+        assert!(db
+            .load_snapshot_chunk(1, SNAPSHOT_FORMAT, 1)
+            .unwrap()
+            .is_none());
+
+        // Restore the snapshot in a new DB, but with an incorrect app hash.
+        // This should fail.
+        let new_path = TempDataDir::new("_grug_disk_db_snapshot_works_new");
+        let new_db = DiskDb::open(&new_path).unwrap();
+        new_db
+            .offer_snapshot(snapshot.clone(), v0::ROOT_HASH)
+            .unwrap();
+        assert!(matches!(
+            new_db.apply_snapshot_chunk(0, chunk.clone()),
+            Err(DbError::RestoredRootHashMismatch { .. })
+        ));
+        assert!(new_db.latest_version().is_none());
+
+        // Restore with the correct app hash.
+        new_db
+            .offer_snapshot(snapshot.clone(), v1::ROOT_HASH)
+            .unwrap();
+
+        // Tampered chunks should be rejected, without aborting the restoration.
+        let mut tampered = chunk.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert_eq!(
+            new_db.apply_snapshot_chunk(0, tampered).unwrap(),
+            SnapshotChunkOutcome::Corrupted
+        );
+        assert!(new_db.latest_version().is_none());
+
+        // The chunk can be applied again once a good one is received.
+        assert_eq!(
+            new_db.apply_snapshot_chunk(0, chunk).unwrap(),
+            SnapshotChunkOutcome::Restored
+        );
+        assert_eq!(new_db.latest_version(), Some(1));
+        assert_eq!(new_db.oldest_version(), Some(1));
+        assert_eq!(new_db.root_hash(Some(1)).unwrap(), Some(v1::ROOT_HASH));
+
+        // The restored DB should contain the same data and proofs.
+        for key in ["donald", "jake", "joe", "larry", "pumpkin"] {
+            assert_eq!(
+                new_db.state_storage(None).unwrap().read(key.as_bytes()),
+                db.state_storage(None).unwrap().read(key.as_bytes())
+            );
+            assert_eq!(
+                new_db.prove(key.as_bytes(), None).unwrap(),
+                db.prove(key.as_bytes(), None).unwrap()
+            );
+        }
+
+        // The restored DB can't restore another snapshot...
+        assert!(matches!(
+            new_db.offer_snapshot(snapshot, v1::ROOT_HASH),
+            Err(DbError::DbNotEmpty)
+        ));
+
+        // ...but it can continue to process new batches.
+        let (version, _) = new_db
+            .flush_and_commit(Batch::from([(
+                b"joe".to_vec(),
+                Op::Insert(b"biden".to_vec()),
+            )]))
+            .unwrap();
+        assert_eq!(version, 2);
+        assert_eq!(
+            new_db.state_storage(None).unwrap().read(b"joe"),
+            Some(b"biden".to_vec())
+        );
+    }
+
+    #[test]
+    fn restoring_tampered_snapshot_fails() {
+        let path = TempDataDir::new("_grug_disk_db_restoring_tampered_snapshot_fails");
+        let db = DiskDb::open(&path).unwrap();
+
+        db.flush_and_commit(Batch::from([
+            (b"donald".to_vec(), Op::Insert(b"trump".to_vec())),
+            (b"jake".to_vec(), Op::Insert(b"shepherd".to_vec())),
+            (b"joe".to_vec(), Op::Insert(b"biden".to_vec())),
+            (b"larry".to_vec(), Op::Insert(b"engineer".to_vec())),
+        ]))
+        .unwrap();
+
+        db.create_snapshot(0).unwrap();
+        let chunk: SnapshotChunk = db
+            .load_snapshot_chunk(0, SNAPSHOT_FORMAT, 0)
+            .unwrap()
+            .unwrap()
+            .deserialize_borsh()
+            .unwrap();
+
+        let new_path = TempDataDir::new("_grug_disk_db_restoring_tampered_snapshot_fails_new");
+        let new_db = DiskDb::open(&new_path).unwrap();
+
+        // Create a snapshot consisting of the given chunk, and attempt to
+        // restore it. The chunk matches the snapshot's metadata, so the only
+        // line of defense is the verification against the app hash.
+        let restore = |chunk: &SnapshotChunk| {
+            let chunk = chunk.to_borsh_vec().unwrap();
+            let metadata = vec![chunk.hash256()].to_borsh_vec().unwrap();
+            let snapshot = Snapshot {
+                version: 0,
+                format: SNAPSHOT_FORMAT,
+                chunks: 1,
+                hash: metadata.hash256(),
+                metadata,
+            };
+
+            new_db.offer_snapshot(snapshot, v0::ROOT_HASH).unwrap();
+            new_db.apply_snapshot_chunk(0, chunk)
+        };
+
+        // A KV pair is omitted.
+        let mut tampered = chunk.clone();
+        tampered.state_storage.pop();
+        assert!(matches!(
+            restore(&tampered),
+            Err(DbError::SnapshotRecordCountMismatch {
+                expected: 4,
+                actual: 3,
+            })
+        ));
+
+        // A KV pair is altered.
+        let mut tampered = chunk.clone();
+        tampered.state_storage[0].1 = b"duck".to_vec();
+        assert!(matches!(restore(&tampered), Err(DbError::Proof(_))));
+
+        // A tree node is omitted.
+        let mut tampered = chunk.clone();
+        tampered.state_commitment.pop();
+        assert!(matches!(
+            restore(&tampered),
+            Err(DbError::SnapshotTreeInvalid)
+        ));
+
+        // A node that isn't part of the tree is added.
+        let mut tampered = chunk.clone();
+        tampered
+            .state_commitment
+            .push((b"junk".to_vec(), b"junk".to_vec()));
+        assert!(matches!(
+            restore(&tampered),
+            Err(DbError::SnapshotNodeCountMismatch { .. })
+        ));
+
+        // The DB is still empty, and nothing is left staged.
+        assert!(new_db.latest_version().is_none());
+        for prefix in [STAGED_STATE_STORAGE_PREFIX, STAGED_STATE_COMMITMENT_PREFIX] {
+            assert!(new_db
+                .staged(prefix)
+                .scan(None, None, Order::Ascending)
+                .next()
+                .is_none());
+        }
+
+        // The untampered snapshot can be restored.
+        assert_eq!(restore(&chunk).unwrap(), SnapshotChunkOutcome::Restored);
+        assert_eq!(new_db.root_hash(Some(0)).unwrap(), Some(v0::ROOT_HASH));
+    }
+
     proptest! {
         /// Apply three batches as follows:
         ///
@@ -1,7 +1,8 @@
 use {
-    crate::PendingData,
+    crate::{PendingData, Restoration},
     grug_app::AppError,
-    grug_types::StdError,
+    grug_jmt::ProofError,
+    grug_types::{Hash256, StdError},
     std::sync::{PoisonError, RwLockReadGuard, RwLockWriteGuard},
     thiserror::Error,
 };
@@ -14,6 +15,9 @@ pub enum DbError {
     #[error(transparent)]
     RocksDb(#[from] rocksdb::Error),
 
+    #[error(transparent)]
+    Proof(#[from] ProofError),
+
     #[error("cannot flush when the in-memory write batch is already set")]
     PendingDataAlreadySet,
 
@@ -28,6 +32,47 @@ pub enum DbError {
 
     #[error("requested version ({version}) is older than the oldest available version ({oldest_version})")]
     VersionTooOld { version: u64, oldest_version: u64 },
+
+    #[error("unsupported snapshot format: {format}")]
+    UnsupportedSnapshotFormat { format: u32 },
+
+    #[error("cannot restore from snapshot when the database isn't empty")]
+    DbNotEmpty,
+
+    #[error("snapshot hash mismatch! computed: {computed}, expected: {expected}")]
+    SnapshotHashMismatch {
+        computed: Hash256,
+        expected: Hash256,
+    },
+
+    #[error("snapshot has {chunks} chunks, but its metadata contains {hashes} chunk hashes")]
+    SnapshotChunksMismatch { chunks: u32, hashes: usize },
+
+    #[error("cannot apply snapshot chunk when no snapshot is being restored")]
+    RestorationNotStarted,
+
+    #[error("rwlock for the snapshot restoration is poisoned")]
+    RestorationPoisoned,
+
+    #[error("snapshot chunk applied out of order! expected: {expected}, actual: {actual}")]
+    SnapshotChunkOutOfOrder { expected: u32, actual: u32 },
+
+    #[error(
+        "root hash of the restored state mismatch! computed: {computed:?}, expected: {expected}"
+    )]
+    RestoredRootHashMismatch {
+        computed: Option<Hash256>,
+        expected: Hash256,
+    },
+
+    #[error("snapshot contains tree nodes that don't match the root hash")]
+    SnapshotTreeInvalid,
+
+    #[error("snapshot contains {actual} tree nodes, expecting {expected}")]
+    SnapshotNodeCountMismatch { expected: usize, actual: usize },
+
+    #[error("snapshot contains {actual} state storage records, expecting {expected}")]
+    SnapshotRecordCountMismatch { expected: usize, actual: usize },
 }
 
 impl<'a> From<PoisonError<RwLockReadGuard<'a, Option<PendingData>>>> for DbError {
@@ -42,6 +87,18 @@ impl<'a> From<PoisonError<RwLockWriteGuard<'a, Option<PendingData>>>> for DbErro
     }
 }
 
+impl<'a> From<PoisonError<RwLockReadGuard<'a, Option<Restoration>>>> for DbError {
+    fn from(_: PoisonError<RwLockReadGuard<'a, Option<Restoration>>>) -> Self {
+        Self::RestorationPoisoned
+    }
+}
+
+impl<'a> From<PoisonError<RwLockWriteGuard<'a, Option<Restoration>>>> for DbError {
+    fn from(_: PoisonError<RwLockWriteGuard<'a, Option<Restoration>>>) -> Self {
+        Self::RestorationPoisoned
+    }
+}
+
 impl From<DbError> for AppError {
     fn from(err: DbError) -> Self {
         AppError::Db(err.to_string())
@@ -1,6 +1,7 @@
 mod db;
 mod error;
+mod pruning;
 mod testing;
 mod timestamp;
 
-pub use {db::*, error::*, testing::*, timestamp::*};
+pub use {db::*, error::*, pruning::*, testing::*, timestamp::*};
@@ -0,0 +1,72 @@
+/// Which historical versions `DiskDb` keeps, and which it prunes after commit.
+///
+/// Pruning works by raising a low watermark: all versions older than the
+/// oldest available version are removed from the state storage and state
+/// commitment. Sparse versions (e.g. every 100th one) are instead kept as
+/// checkpoints: a full copy of the state and Merkle tree at that version,
+/// stored separately so that it survives pruning.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PruningStrategy {
+    /// Never prune. Use this for archive nodes.
+    #[default]
+    KeepAll,
+    /// Keep the `keep_recent` most recent versions, including the latest one,
+    /// pruning one version upon each commit.
+    ///
+    /// A value of zero is treated as one, as the latest version is always kept.
+    KeepRecent { keep_recent: u64 },
+    /// Same as `KeepRecent`, but additionally keep every version that is a
+    /// multiple of `interval` as a checkpoint.
+    ///
+    /// A checkpoint stores the full state rather than a diff, so the interval
+    /// shouldn't be too small. An interval of zero means no checkpoint.
+    KeepEvery { keep_recent: u64, interval: u64 },
+}
+
+impl PruningStrategy {
+    /// Return the version up to which data should be pruned, after the given
+    /// version is committed. `None` if no pruning is to be done.
+    pub fn prune_up_to(&self, latest_version: u64) -> Option<u64> {
+        match *self {
+            PruningStrategy::KeepAll => None,
+            PruningStrategy::KeepRecent { keep_recent }
+            | PruningStrategy::KeepEvery { keep_recent, .. } => {
+                (latest_version + 1).checked_sub(keep_recent.max(1))
+            },
+        }
+    }
+
+    /// Return whether the given version is to be kept as a checkpoint.
+    pub fn is_checkpoint(&self, version: u64) -> bool {
+        match *self {
+            PruningStrategy::KeepEvery { interval, .. } => interval > 0 && version % interval == 0,
+            _ => false,
+        }
+    }
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {super::*, test_case::test_case};
+
+    #[test_case(PruningStrategy::KeepAll, 100, None; "keep all")]
+    #[test_case(PruningStrategy::KeepRecent { keep_recent: 10 }, 5, None; "keep recent, too few versions")]
+    #[test_case(PruningStrategy::KeepRecent { keep_recent: 10 }, 9, Some(0); "keep recent, just enough versions")]
+    #[test_case(PruningStrategy::KeepRecent { keep_recent: 10 }, 100, Some(91); "keep recent")]
+    #[test_case(PruningStrategy::KeepRecent { keep_recent: 0 }, 100, Some(100); "keep recent, zero")]
+    #[test_case(PruningStrategy::KeepEvery { keep_recent: 10, interval: 50 }, 100, Some(91); "keep every")]
+    fn computing_prune_up_to(strategy: PruningStrategy, latest_version: u64, expect: Option<u64>) {
+        assert_eq!(strategy.prune_up_to(latest_version), expect);
+    }
+
+    #[test_case(PruningStrategy::KeepAll, 100, false; "keep all")]
+    #[test_case(PruningStrategy::KeepRecent { keep_recent: 10 }, 100, false; "keep recent")]
+    #[test_case(PruningStrategy::KeepEvery { keep_recent: 10, interval: 50 }, 100, true; "keep every, multiple")]
+    #[test_case(PruningStrategy::KeepEvery { keep_recent: 10, interval: 50 }, 99, false; "keep every, not multiple")]
+    #[test_case(PruningStrategy::KeepEvery { keep_recent: 10, interval: 0 }, 100, false; "keep every, zero")]
+    fn computing_is_checkpoint(strategy: PruningStrategy, version: u64, expect: bool) {
+        assert_eq!(strategy.is_checkpoint(version), expect);
+    }
+}
@@ -0,0 +1,756 @@
+use {
+    crate::{DbError, DbResult, QueryStore},
+    grug_app::{Buffer, Db},
+    grug_jmt::{verify_proof, MerkleTree, Proof},
+    grug_types::{Batch, Hash256, HashExt, Op, Order, Record, Storage},
+    ics23::CommitmentProof,
+    std::{
+        collections::{BTreeMap, HashMap},
+        ops::Bound,
+        sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    },
+};
+
+const MERKLE_TREE: MerkleTree = MerkleTree::new_default();
+
+struct ChangeSet {
+    version: u64,
+    state_commitment: Batch,
+    state_storage: Batch,
+}
+
+struct ForkDbInner {
+    /// Version of the DB. Initialized to the fork height, and incremented by 1
+    /// each time a batch of data is committed.
+    latest_version: u64,
+    /// Values pulled from the remote store at the fork height, that have been
+    /// verified against the fork's root hash. `None` means the key is proven
+    /// to not exist.
+    remote_cache: HashMap<Vec<u8>, Option<Vec<u8>>>,
+    /// A key-value store backing the Merkle tree of the state changes made
+    /// since the fork.
+    state_commitment: HashMap<Vec<u8>, Vec<u8>>,
+    /// State changes made since the fork: key => (version => op)
+    state_storage: BTreeMap<Vec<u8>, BTreeMap<u64, Op>>,
+    /// Uncommitted changes
+    changeset: Option<ChangeSet>,
+}
+
+/// A DB that pulls data from a remote store, such as an archive node, at a
+/// pinned height ("mainnet forking").
+///
+/// State storage keys are fetched lazily the first time they are read, along
+/// with Merkle proofs, which are verified against the root hash at the fork
+/// height. Verified values are cached locally, so each key is fetched at most
+/// once. New blocks are written into an in-memory overlay on top of the remote
+/// state; the remote store is never written to.
+///
+/// ## Note
+///
+/// We don't have access to the remote node's Merkle tree nodes, so we can't
+/// update its root hash as new blocks are committed. Instead, we maintain a
+/// separate Merkle tree that only commits to the state changes made since
+/// the fork. This means app hashes after the fork won't match those of a real
+/// node executing the same blocks.
+///
+/// Range queries are served by [`QueryStore::scan_store`]. Each record acquired
+/// this way is Merkle proved and cached, same as a single key. However, the
+/// proofs can't show that the remote store hasn't omitted any record within the
+/// range. If the remote store can't serve a range (for example, a remote node
+/// can only serve ranges within a single contract's substore), the range query
+/// fails with [`DbError::ScanNotSupported`].
+pub struct ForkDb<R> {
+    remote: Arc<R>,
+    fork_height: u64,
+    fork_root_hash: Hash256,
+    inner: Arc<RwLock<ForkDbInner>>,
+}
+
+impl<R> ForkDb<R>
+where
+    R: QueryStore,
+{
+    /// Create a new DB forked from the given remote store at the given height.
+    pub fn new(remote: R, fork_height: u64) -> DbResult<Self> {
+        let fork_root_hash = remote
+            .query_root_hash(fork_height)
+            .map_err(|err| DbError::Remote(err.to_string()))?
+            .ok_or(DbError::RootHashNotFound { fork_height })?;
+
+        Ok(Self {
+            remote: Arc::new(remote),
+            fork_height,
+            fork_root_hash,
+            inner: Arc::new(RwLock::new(ForkDbInner {
+                latest_version: fork_height,
+                remote_cache: HashMap::new(),
+                state_commitment: HashMap::new(),
+                state_storage: BTreeMap::new(),
+                changeset: None,
+            })),
+        })
+    }
+
+    /// Return the height at which the DB is forked.
+    pub fn fork_height(&self) -> u64 {
+        self.fork_height
+    }
+
+    /// Return the root hash of the remote store at the fork height.
+    pub fn fork_root_hash(&self) -> Hash256 {
+        self.fork_root_hash
+    }
+
+    /// Read a key from the remote store at the fork height, if it's not
+    /// already cached. Verify the value against the fork's root hash.
+    fn fetch(&self, key: &[u8]) -> DbResult<Option<Vec<u8>>> {
+        if let Some(value) = self.with_read(|inner| inner.remote_cache.get(key).cloned()) {
+            return Ok(value);
+        }
+
+        let key_hash = key.hash256();
+
+        let (value, proof) = self
+            .remote
+            .query_store(key, self.fork_height, true)
+            .map_err(|err| DbError::Remote(err.to_string()))?;
+        let proof = proof.ok_or(DbError::ProofNotFound { key_hash })?;
+
+        let value_hash = value.as_ref().map(|value| value.hash256());
+        verify_proof(self.fork_root_hash, key_hash, value_hash, &proof)?;
+
+        self.with_write(|mut inner| {
+            inner.remote_cache.insert(key.to_vec(), value.clone());
+        });
+
+        Ok(value)
+    }
+
+    /// Read records within a range from the remote store at the fork height.
+    /// Verify each record against the fork's root hash.
+    fn fetch_range(&self, min: Option<&[u8]>, max: Option<&[u8]>) -> DbResult<Vec<Record>> {
+        let records = self
+            .remote
+            .scan_store(min, max, self.fork_height)
+            .map_err(|err| DbError::Remote(err.to_string()))?
+            .ok_or(DbError::ScanNotSupported)?;
+
+        for (key, value) in &records {
+            let cached = self.with_read(|inner| inner.remote_cache.get(key).cloned());
+
+            // Skip records that have already been verified.
+            if cached.is_some_and(|cached| cached.as_ref() == Some(value)) {
+                continue;
+            }
+
+            let key_hash = key.hash256();
+
+            let (_, proof) = self
+                .remote
+                .query_store(key, self.fork_height, true)
+                .map_err(|err| DbError::Remote(err.to_string()))?;
+            let proof = proof.ok_or(DbError::ProofNotFound { key_hash })?;
+
+            verify_proof(self.fork_root_hash, key_hash, Some(value.hash256()), &proof)?;
+
+            self.with_write(|mut inner| {
+                inner.remote_cache.insert(key.clone(), Some(value.clone()));
+            });
+        }
+
+        Ok(records)
+    }
+
+    fn with_read<C, T>(&self, callback: C) -> T
+    where
+        C: FnOnce(RwLockReadGuard<ForkDbInner>) -> T,
+    {
+        let lock = self.inner.read().unwrap_or_else(|err| {
+            panic!("ForkDb is poisoned: {err:?}");
+        });
+        callback(lock)
+    }
+
+    fn with_write<C, T>(&self, callback: C) -> T
+    where
+        C: FnOnce(RwLockWriteGuard<ForkDbInner>) -> T,
+    {
+        let lock = self.inner.write().unwrap_or_else(|err| {
+            panic!("ForkDb is poisoned: {err:?}");
+        });
+        callback(lock)
+    }
+}
+
+impl<R> Clone for ForkDb<R> {
+    fn clone(&self) -> Self {
+        Self {
+            remote: Arc::clone(&self.remote),
+            fork_height: self.fork_height,
+            fork_root_hash: self.fork_root_hash,
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<R> Db for ForkDb<R>
+where
+    R: QueryStore + Send + Sync + 'static,
+{
+    type Error = DbError;
+    type Proof = Proof;
+    type StateCommitment = StateCommitment<R>;
+    type StateStorage = StateStorage<R>;
+
+    fn state_commitment(&self) -> StateCommitment<R> {
+        StateCommitment { db: self.clone() }
+    }
+
+    fn state_storage(&self, version: Option<u64>) -> DbResult<StateStorage<R>> {
+        let latest_version = self.with_read(|inner| inner.latest_version);
+
+        let version = match version {
+            Some(version) if version < self.fork_height => {
+                return Err(DbError::VersionTooOld {
+                    version,
+                    fork_height: self.fork_height,
+                });
+            },
+            Some(version) if version > latest_version => {
+                return Err(DbError::VersionTooNew {
+                    version,
+                    latest_version,
+                });
+            },
+            Some(version) => version,
+            None => latest_version,
+        };
+
+        Ok(StateStorage {
+            db: self.clone(),
+            version,
+        })
+    }
+
+    fn latest_version(&self) -> Option<u64> {
+        Some(self.with_read(|inner| inner.latest_version))
+    }
+
+    fn root_hash(&self, version: Option<u64>) -> DbResult<Option<Hash256>> {
+        let version = version.unwrap_or_else(|| self.with_read(|inner| inner.latest_version));
+
+        if version < self.fork_height {
+            return Err(DbError::VersionTooOld {
+                version,
+                fork_height: self.fork_height,
+            });
+        }
+
+        if version == self.fork_height {
+            return Ok(Some(self.fork_root_hash));
+        }
+
+        Ok(MERKLE_TREE.root_hash(&self.state_commitment(), version)?)
+    }
+
+    // At the fork height, the proof is provided by the remote store. After the
+    // fork, the proof is against our local Merkle tree, which only commits to
+    // state changes made since the fork.
+    fn prove(&self, key: &[u8], version: Option<u64>) -> DbResult<Proof> {
+        let version = version.unwrap_or_else(|| self.with_read(|inner| inner.latest_version));
+
+        if version < self.fork_height {
+            return Err(DbError::VersionTooOld {
+                version,
+                fork_height: self.fork_height,
+            });
+        }
+
+        if version == self.fork_height {
+            let (_, proof) = self
+                .remote
+                .query_store(key, self.fork_height, true)
+                .map_err(|err| DbError::Remote(err.to_string()))?;
+
+            return proof.ok_or(DbError::ProofNotFound {
+                key_hash: key.hash256(),
+            });
+        }
+
+        Ok(MERKLE_TREE.prove(&self.state_commitment(), key.hash256(), version)?)
+    }
+
+    fn ics23_prove(
+        &self,
+        _key: Vec<u8>,
+        _version: Option<u64>,
+    ) -> Result<CommitmentProof, Self::Error> {
+        unimplemented!("don't need this for testing")
+    }
+
+    // Same as in `MemDb`, we must make sure to not attempt to lock the DB
+    // inside the `with_write` callback, otherwise it deadlocks.
+    fn flush_but_not_commit(&self, batch: Batch) -> DbResult<(u64, Option<Hash256>)> {
+        let (new_version, root_hash, changeset) = self.with_read(|inner| {
+            if inner.changeset.is_some() {
+                return Err(DbError::ChangeSetAlreadySet);
+            }
+
+            let old_version = inner.latest_version;
+            let new_version = old_version + 1;
+
+            // At the fork height, the local Merkle tree is empty, so the first
+            // batch written after the fork is applied to an empty tree.
+            let mut cache = Buffer::new(self.state_commitment(), None);
+            let root_hash = MERKLE_TREE.apply_raw(&mut cache, old_version, new_version, &batch)?;
+            let (_, changeset) = cache.disassemble();
+
+            Ok((new_version, root_hash, changeset))
+        })?;
+
+        self.with_write(|mut inner| {
+            inner.changeset = Some(ChangeSet {
+                version: new_version,
+                state_commitment: changeset,
+                state_storage: batch,
+            });
+        });
+
+        Ok((new_version, root_hash))
+    }
+
+    fn commit(&self) -> DbResult<()> {
+        self.with_write(|mut inner| {
+            let changeset = inner.changeset.take().ok_or(DbError::ChangeSetNotSet)?;
+
+            // Update the version
+            inner.latest_version = changeset.version;
+
+            // Write changes to state commitment
+            for (key, op) in changeset.state_commitment {
+                if let Op::Insert(value) = op {
+                    inner.state_commitment.insert(key, value);
+                } else {
+                    inner.state_commitment.remove(&key);
+                }
+            }
+
+            // Write changes to state storage
+            for (key, op) in changeset.state_storage {
+                inner
+                    .state_storage
+                    .entry(key)
+                    .or_default()
+                    .insert(changeset.version, op);
+            }
+
+            Ok(())
+        })
+    }
+}
+
+// ----------------------------- state commitment ------------------------------
+
+pub struct StateCommitment<R> {
+    db: ForkDb<R>,
+}
+
+impl<R> Clone for StateCommitment<R> {
+    fn clone(&self) -> Self {
+        Self {
+            db: self.db.clone(),
+        }
+    }
+}
+
+impl<R> Storage for StateCommitment<R>
+where
+    R: QueryStore + Send + Sync + 'static,
+{
+    fn read(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.db
+            .with_read(|inner| inner.state_commitment.get(key).cloned())
+    }
+
+    fn scan<'a>(
+        &'a self,
+        _min: Option<&[u8]>,
+        _max: Option<&[u8]>,
+        _order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        unimplemented!("this isn't used by the Merkle tree");
+    }
+
+    fn scan_keys<'a>(
+        &'a self,
+        _min: Option<&[u8]>,
+        _max: Option<&[u8]>,
+        _order: Order,
+    ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        unimplemented!("this isn't used by the Merkle tree");
+    }
+
+    fn scan_values<'a>(
+        &'a self,
+        _min: Option<&[u8]>,
+        _max: Option<&[u8]>,
+        _order: Order,
+    ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        unimplemented!("this isn't used by the Merkle tree");
+    }
+
+    fn write(&mut self, _key: &[u8], _value: &[u8]) {
+        unreachable!("write function called on read-only storage");
+    }
+
+    fn remove(&mut self, _key: &[u8]) {
+        unreachable!("write function called on read-only storage");
+    }
+
+    fn remove_range(&mut self, _min: Option<&[u8]>, _max: Option<&[u8]>) {
+        unreachable!("write function called on read-only storage");
+    }
+}
+
+// ------------------------------- state storage -------------------------------
+
+pub struct StateStorage<R> {
+    db: ForkDb<R>,
+    version: u64,
+}
+
+impl<R> Clone for StateStorage<R> {
+    fn clone(&self) -> Self {
+        Self {
+            db: self.db.clone(),
+            version: self.version,
+        }
+    }
+}
+
+impl<R> Storage for StateStorage<R>
+where
+    R: QueryStore + Send + Sync + 'static,
+{
+    fn read(&self, key: &[u8]) -> Option<Vec<u8>> {
+        // If the key has been written to since the fork, use the local value.
+        // Otherwise, pull it from the remote store.
+        let local = self.db.with_read(|inner| {
+            inner
+                .state_storage
+                .get(key)?
+                .range(..=self.version)
+                .next_back()
+                .map(|(_, op)| op.clone())
+        });
+
+        match local {
+            Some(Op::Insert(value)) => Some(value),
+            Some(Op::Delete) => None,
+            None => self.db.fetch(key).unwrap_or_else(|err| {
+                panic!("failed to read from remote store: {err}");
+            }),
+        }
+    }
+
+    fn scan<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        if let (Some(min), Some(max)) = (min, max) {
+            if min > max {
+                return Box::new(std::iter::empty());
+            }
+        }
+
+        let mut records = self
+            .db
+            .fetch_range(min, max)
+            .unwrap_or_else(|err| {
+                panic!("failed to iterate in remote store: {err}");
+            })
+            .into_iter()
+            .collect::<BTreeMap<_, _>>();
+
+        // Apply the state changes made since the fork on top of the remote
+        // records. Same as in `MemDb`, we have to collect the records, because
+        // the iterator can't outlive the read lock.
+        let min = min.map_or(Bound::Unbounded, Bound::Included);
+        let max = max.map_or(Bound::Unbounded, Bound::Excluded);
+        self.db.with_read(|inner| {
+            for (key, ops) in inner.state_storage.range::<[u8], _>((min, max)) {
+                match ops.range(..=self.version).next_back() {
+                    Some((_, Op::Insert(value))) => {
+                        records.insert(key.clone(), value.clone());
+                    },
+                    Some((_, Op::Delete)) => {
+                        records.remove(key);
+                    },
+                    None => {},
+                }
+            }
+        });
+
+        match order {
+            Order::Ascending => Box::new(records.into_iter()),
+            Order::Descending => Box::new(records.into_iter().rev()),
+        }
+    }
+
+    fn scan_keys<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        let iter = self.scan(min, max, order).map(|(k, _)| k);
+        Box::new(iter)
+    }
+
+    fn scan_values<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        let iter = self.scan(min, max, order).map(|(_, v)| v);
+        Box::new(iter)
+    }
+
+    fn write(&mut self, _key: &[u8], _value: &[u8]) {
+        unreachable!("write function called on read-only storage");
+    }
+
+    fn remove(&mut self, _key: &[u8]) {
+        unreachable!("write function called on read-only storage");
+    }
+
+    fn remove_range(&mut self, _min: Option<&[u8]>, _max: Option<&[u8]>) {
+        unreachable!("write function called on read-only storage");
+    }
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::{DbError, ForkDb, QueryStore},
+        grug_app::Db,
+        grug_db_memory::{DbError as MemDbError, MemDb},
+        grug_jmt::Proof,
+        grug_types::{Batch, Hash256, Op, Order, Record, Storage},
+    };
+
+    /// Create a remote store with two versions:
+    /// - v0: donald => trump, jake => shepherd, joe => biden
+    /// - v1: delete joe, insert larry => engineer
+    fn setup_remote() -> MemDb {
+        let remote = MemDb::new();
+
+        let batch = Batch::from([
+            (b"donald".to_vec(), Op::Insert(b"trump".to_vec())),
+            (b"jake".to_vec(), Op::Insert(b"shepherd".to_vec())),
+            (b"joe".to_vec(), Op::Insert(b"biden".to_vec())),
+        ]);
+        remote.flush_and_commit(batch).unwrap();
+
+        let batch = Batch::from([
+            (b"joe".to_vec(), Op::Delete),
+            (b"larry".to_vec(), Op::Insert(b"engineer".to_vec())),
+        ]);
+        remote.flush_and_commit(batch).unwrap();
+
+        remote
+    }
+
+    fn collect_records(storage: &dyn Storage, order: Order) -> Vec<Record> {
+        storage.scan(None, None, order).collect()
+    }
+
+    #[test]
+    fn fork_db_works() {
+        let remote = setup_remote();
+        let remote_root_hash = remote.root_hash(Some(1)).unwrap();
+        let db = ForkDb::new(remote, 1).unwrap();
+
+        assert_eq!(db.latest_version(), Some(1));
+        assert_eq!(db.root_hash(None).unwrap(), remote_root_hash);
+
+        // Read the remote state at the fork height.
+        let storage = db.state_storage(None).unwrap();
+        assert_eq!(storage.read(b"donald"), Some(b"trump".to_vec()));
+        assert_eq!(storage.read(b"joe"), None);
+        assert_eq!(storage.read(b"larry"), Some(b"engineer".to_vec()));
+
+        // Records acquired by range queries are verified and cached.
+        assert_eq!(collect_records(&storage, Order::Ascending), [
+            (b"donald".to_vec(), b"trump".to_vec()),
+            (b"jake".to_vec(), b"shepherd".to_vec()),
+            (b"larry".to_vec(), b"engineer".to_vec()),
+        ]);
+        assert_eq!(
+            db.with_read(|inner| inner.remote_cache.get(b"jake".as_slice()).cloned()),
+            Some(Some(b"shepherd".to_vec()))
+        );
+
+        // Versions prior to the fork are not available.
+        assert!(matches!(
+            db.state_storage(Some(0)),
+            Err(DbError::VersionTooOld {
+                version: 0,
+                fork_height: 1,
+            })
+        ));
+
+        // Write a new block on top of the fork.
+        let batch = Batch::from([
+            (b"donald".to_vec(), Op::Insert(b"duck".to_vec())),
+            (b"jake".to_vec(), Op::Delete),
+            (b"pumpkin".to_vec(), Op::Insert(b"cat".to_vec())),
+        ]);
+        let (version, root_hash) = db.flush_and_commit(batch).unwrap();
+        assert_eq!(version, 2);
+        assert!(root_hash.is_some());
+        assert_eq!(db.root_hash(Some(2)).unwrap(), root_hash);
+
+        // Read the new state. Keys that haven't been written to since the fork
+        // are still pulled from the remote store.
+        let storage = db.state_storage(None).unwrap();
+        assert_eq!(storage.read(b"donald"), Some(b"duck".to_vec()));
+        assert_eq!(storage.read(b"jake"), None);
+        assert_eq!(storage.read(b"larry"), Some(b"engineer".to_vec()));
+        assert_eq!(storage.read(b"pumpkin"), Some(b"cat".to_vec()));
+        assert_eq!(collect_records(&storage, Order::Ascending), [
+            (b"donald".to_vec(), b"duck".to_vec()),
+            (b"larry".to_vec(), b"engineer".to_vec()),
+            (b"pumpkin".to_vec(), b"cat".to_vec()),
+        ]);
+        assert_eq!(collect_records(&storage, Order::Descending), [
+            (b"pumpkin".to_vec(), b"cat".to_vec()),
+            (b"larry".to_vec(), b"engineer".to_vec()),
+            (b"donald".to_vec(), b"duck".to_vec()),
+        ]);
+
+        // The state at the fork height is unaffected.
+        let storage = db.state_storage(Some(1)).unwrap();
+        assert_eq!(storage.read(b"donald"), Some(b"trump".to_vec()));
+        assert_eq!(storage.read(b"jake"), Some(b"shepherd".to_vec()));
+        assert_eq!(collect_records(&storage, Order::Ascending), [
+            (b"donald".to_vec(), b"trump".to_vec()),
+            (b"jake".to_vec(), b"shepherd".to_vec()),
+            (b"larry".to_vec(), b"engineer".to_vec()),
+        ]);
+    }
+
+    /// A remote store that lies about values, but returns the honest proofs.
+    struct DishonestStore {
+        inner: MemDb,
+    }
+
+    impl QueryStore for DishonestStore {
+        type Error = MemDbError;
+
+        fn query_root_hash(&self, height: u64) -> Result<Option<Hash256>, MemDbError> {
+            self.inner.query_root_hash(height)
+        }
+
+        fn query_store(
+            &self,
+            key: &[u8],
+            height: u64,
+            prove: bool,
+        ) -> Result<(Option<Vec<u8>>, Option<Proof>), MemDbError> {
+            let (_, proof) = self.inner.query_store(key, height, prove)?;
+            Ok((Some(b"fake".to_vec()), proof))
+        }
+
+        fn scan_store(
+            &self,
+            min: Option<&[u8]>,
+            max: Option<&[u8]>,
+            height: u64,
+        ) -> Result<Option<Vec<Record>>, MemDbError> {
+            let records = self.inner.scan_store(min, max, height)?.map(|records| {
+                records
+                    .into_iter()
+                    .map(|(key, _)| (key, b"fake".to_vec()))
+                    .collect()
+            });
+            Ok(records)
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "failed to read from remote store")]
+    fn fork_db_rejects_invalid_proof() {
+        let remote = DishonestStore {
+            inner: setup_remote(),
+        };
+        let db = ForkDb::new(remote, 1).unwrap();
+
+        db.state_storage(None).unwrap().read(b"donald");
+    }
+
+    #[test]
+    #[should_panic(expected = "failed to iterate in remote store")]
+    fn fork_db_rejects_invalid_range() {
+        let remote = DishonestStore {
+            inner: setup_remote(),
+        };
+        let db = ForkDb::new(remote, 1).unwrap();
+
+        collect_records(&db.state_storage(None).unwrap(), Order::Ascending);
+    }
+
+    /// A remote store that can't serve range queries, like a remote node
+    /// queried for a range across multiple contracts.
+    struct UnscannableStore {
+        inner: MemDb,
+    }
+
+    impl QueryStore for UnscannableStore {
+        type Error = MemDbError;
+
+        fn query_root_hash(&self, height: u64) -> Result<Option<Hash256>, MemDbError> {
+            self.inner.query_root_hash(height)
+        }
+
+        fn query_store(
+            &self,
+            key: &[u8],
+            height: u64,
+            prove: bool,
+        ) -> Result<(Option<Vec<u8>>, Option<Proof>), MemDbError> {
+            self.inner.query_store(key, height, prove)
+        }
+
+        fn scan_store(
+            &self,
+            _min: Option<&[u8]>,
+            _max: Option<&[u8]>,
+            _height: u64,
+        ) -> Result<Option<Vec<Record>>, MemDbError> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn fork_db_rejects_unsupported_range() {
+        let remote = UnscannableStore {
+            inner: setup_remote(),
+        };
+        let db = ForkDb::new(remote, 1).unwrap();
+
+        // Read a key, so that it's cached. The range query must still fail,
+        // instead of returning only the cached keys.
+        db.state_storage(None).unwrap().read(b"donald");
+
+        assert!(matches!(
+            db.fetch_range(None, None),
+            Err(DbError::ScanNotSupported)
+        ));
+    }
+}
@@ -0,0 +1,47 @@
+use {
+    grug_app::AppError,
+    grug_jmt::ProofError,
+    grug_types::{Hash256, StdError},
+    thiserror::Error,
+};
+
+#[derive(Debug, Error)]
+pub enum DbError {
+    #[error(transparent)]
+    Std(#[from] StdError),
+
+    #[error(transparent)]
+    Proof(#[from] ProofError),
+
+    #[error("remote store error: {0}")]
+    Remote(String),
+
+    #[error("remote store can't serve the range query")]
+    ScanNotSupported,
+
+    #[error("root hash not found at the fork height ({fork_height})")]
+    RootHashNotFound { fork_height: u64 },
+
+    #[error("remote store didn't return a Merkle proof! key hash: {key_hash}")]
+    ProofNotFound { key_hash: Hash256 },
+
+    #[error("requested version ({version}) is older than the fork height ({fork_height})")]
+    VersionTooOld { version: u64, fork_height: u64 },
+
+    #[error("requested version ({version}) is newer than the latest version ({latest_version})")]
+    VersionTooNew { version: u64, latest_version: u64 },
+
+    #[error("cannot flush when changeset is already set")]
+    ChangeSetAlreadySet,
+
+    #[error("cannot commit when changeset is not yet set")]
+    ChangeSetNotSet,
+}
+
+impl From<DbError> for AppError {
+    fn from(err: DbError) -> Self {
+        AppError::Db(err.to_string())
+    }
+}
+
+pub type DbResult<T> = core::result::Result<T, DbError>;
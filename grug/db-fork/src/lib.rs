@@ -0,0 +1,5 @@
+mod db;
+mod error;
+mod remote;
+
+pub use {db::*, error::*, remote::*};
@@ -0,0 +1,222 @@
+use {
+    grug_app::{Db, CONTRACT_NAMESPACE},
+    grug_client::Client,
+    grug_jmt::Proof,
+    grug_math::Inner,
+    grug_types::{
+        concat, extend_one_byte, increment_last_byte, Addr, Bound, Hash256, Order, Query, Record,
+        Storage,
+    },
+    tokio::runtime::{self, Runtime},
+};
+
+/// Number of records to request per `WasmScan` query when scanning a remote
+/// node's storage.
+const SCAN_PAGE_LIMIT: u32 = 30;
+
+/// Represents a source of historical chain state, such as an archive node,
+/// from which [`ForkDb`](crate::ForkDb) pulls data.
+pub trait QueryStore {
+    type Error: ToString;
+
+    /// Return the Merkle root hash of the state at the given height.
+    ///
+    /// `None` if the state tree is empty or the height has been pruned.
+    fn query_root_hash(&self, height: u64) -> Result<Option<Hash256>, Self::Error>;
+
+    /// Return the value corresponding to the given raw key at the given height;
+    /// `None` if the key doesn't exist.
+    ///
+    /// If `prove` is true, also return a Merkle proof of the key against the
+    /// root hash at that height.
+    fn query_store(
+        &self,
+        key: &[u8],
+        height: u64,
+        prove: bool,
+    ) -> Result<(Option<Vec<u8>>, Option<Proof>), Self::Error>;
+
+    /// Return the records within the given range at the given height, in
+    /// ascending order.
+    ///
+    /// `None` if the store isn't able to serve this range.
+    fn scan_store(
+        &self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        height: u64,
+    ) -> Result<Option<Vec<Record>>, Self::Error>;
+}
+
+// Any local DB using the Jellyfish Merkle tree can stand in for an archive
+// node. This is useful for testing the forking logic offline.
+impl<DB> QueryStore for DB
+where
+    DB: Db<Proof = Proof>,
+{
+    type Error = DB::Error;
+
+    fn query_root_hash(&self, height: u64) -> Result<Option<Hash256>, Self::Error> {
+        self.root_hash(Some(height))
+    }
+
+    fn query_store(
+        &self,
+        key: &[u8],
+        height: u64,
+        prove: bool,
+    ) -> Result<(Option<Vec<u8>>, Option<Proof>), Self::Error> {
+        let value = self.state_storage(Some(height))?.read(key);
+
+        let proof = if prove {
+            Some(self.prove(key, Some(height))?)
+        } else {
+            None
+        };
+
+        Ok((value, proof))
+    }
+
+    fn scan_store(
+        &self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        height: u64,
+    ) -> Result<Option<Vec<Record>>, Self::Error> {
+        let records = self
+            .state_storage(Some(height))?
+            .scan(min, max, Order::Ascending)
+            .collect();
+
+        Ok(Some(records))
+    }
+}
+
+// --------------------------------- client ------------------------------------
+
+/// A [`QueryStore`] backed by a remote node, accessed via Tendermint RPC.
+///
+/// The [`Client`](grug_client::Client) is async while the `Storage` trait is
+/// sync, so we block on each request using a dedicated single-threaded Tokio
+/// runtime. For this reason, this must not be used from within another Tokio
+/// runtime.
+pub struct ClientStore {
+    client: Client,
+    runtime: Runtime,
+}
+
+impl ClientStore {
+    /// Create a new client store by connecting to a Tendermint RPC endpoint.
+    pub fn connect(endpoint: &str) -> anyhow::Result<Self> {
+        let client = Client::connect(endpoint)?;
+        let runtime = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+
+        Ok(Self { client, runtime })
+    }
+}
+
+impl QueryStore for ClientStore {
+    type Error = anyhow::Error;
+
+    fn query_root_hash(&self, height: u64) -> anyhow::Result<Option<Hash256>> {
+        // The app hash resulted from executing a block isn't included in the
+        // header of that block, but that of the next block.
+        let res = self
+            .runtime
+            .block_on(self.client.query_block(Some(height + 1)))?;
+        let app_hash = res.block.header.app_hash;
+
+        if app_hash.as_bytes().is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(Hash256::try_from(app_hash.as_bytes())?))
+    }
+
+    fn query_store(
+        &self,
+        key: &[u8],
+        height: u64,
+        prove: bool,
+    ) -> anyhow::Result<(Option<Vec<u8>>, Option<Proof>)> {
+        self.runtime
+            .block_on(self.client.query_store(key.to_vec(), Some(height), prove))
+    }
+
+    // The `/store` ABCI query only supports point lookups. However, if the
+    // range falls within a single contract's substore, which is the case for
+    // all scans made by contracts, we can serve it using the `WasmScan` query.
+    //
+    // Unlike point lookups, records acquired this way aren't Merkle proved.
+    fn scan_store(
+        &self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        height: u64,
+    ) -> anyhow::Result<Option<Vec<Record>>> {
+        let Some((contract, mut min, max)) = split_contract_range(min, max) else {
+            return Ok(None);
+        };
+
+        let namespace = concat(CONTRACT_NAMESPACE, &contract);
+        let mut records = Vec::new();
+
+        loop {
+            let query = Query::wasm_scan(
+                contract,
+                min.clone().map(|min| Bound::Inclusive(min.into())),
+                max.clone().map(|max| Bound::Exclusive(max.into())),
+                Some(SCAN_PAGE_LIMIT),
+            );
+            let page = self
+                .runtime
+                .block_on(self.client.query_app(&query, Some(height)))?
+                .as_wasm_scan();
+            let page_len = page.len();
+
+            for (key, value) in page {
+                records.push((concat(&namespace, &key), value.into_inner()));
+            }
+
+            if page_len < SCAN_PAGE_LIMIT as usize {
+                break;
+            }
+
+            // Records are ordered ascendingly, so the next page starts right
+            // after the last key we've received.
+            let (last_key, _) = records.last().unwrap();
+            min = Some(extend_one_byte(last_key[namespace.len()..].to_vec()));
+        }
+
+        Ok(Some(records))
+    }
+}
+
+/// If the range `[min, max)` falls within a single contract's substore, return
+/// the contract address and the range's bounds within the substore.
+#[allow(clippy::type_complexity)]
+fn split_contract_range(
+    min: Option<&[u8]>,
+    max: Option<&[u8]>,
+) -> Option<(Addr, Option<Vec<u8>>, Option<Vec<u8>>)> {
+    let prefix_len = CONTRACT_NAMESPACE.len() + Addr::LENGTH;
+
+    let min = min?;
+    if min.len() < prefix_len || !min.starts_with(CONTRACT_NAMESPACE) {
+        return None;
+    }
+
+    let contract = Addr::try_from(&min[CONTRACT_NAMESPACE.len()..prefix_len]).ok()?;
+    let namespace = &min[..prefix_len];
+
+    // The upper bound is either within the same substore, or the end of it.
+    let max = match max? {
+        max if max.starts_with(namespace) => Some(max[prefix_len..].to_vec()),
+        max if max == increment_last_byte(namespace.to_vec()) => None,
+        _ => return None,
+    };
+
+    Some((contract, Some(min[prefix_len..].to_vec()), max))
+}
@@ -0,0 +1,49 @@
+use {
+    axum::{
+        http::StatusCode,
+        response::{IntoResponse, Response},
+        Json,
+    },
+    grug_app::AppError,
+    grug_types::{json, StdError},
+    thiserror::Error,
+};
+
+#[derive(Debug, Error)]
+pub enum GatewayError {
+    #[error(transparent)]
+    App(#[from] AppError),
+
+    #[error(transparent)]
+    Std(#[from] StdError),
+
+    #[error("invalid hex string: {0}")]
+    Hex(#[from] hex::FromHexError),
+
+    #[error("failed to reach the node: {0}")]
+    Client(#[from] anyhow::Error),
+
+    #[error("query task failed: {0}")]
+    Join(#[from] tokio::task::JoinError),
+}
+
+impl GatewayError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            GatewayError::App(_) | GatewayError::Std(_) | GatewayError::Hex(_) => {
+                StatusCode::BAD_REQUEST
+            },
+            GatewayError::Client(_) => StatusCode::BAD_GATEWAY,
+            GatewayError::Join(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoResponse for GatewayError {
+    fn into_response(self) -> Response {
+        let body = json!({ "error": self.to_string() });
+        (self.status_code(), Json(body)).into_response()
+    }
+}
+
+pub type GatewayResult<T> = core::result::Result<T, GatewayError>;
@@ -0,0 +1,484 @@
+use {
+    crate::GatewayResult,
+    axum::{
+        extract::{Path, Query as Params, State},
+        routing::{get, post},
+        Json, Router,
+    },
+    grug_app::{App, AppError, AppResult, Db, Indexer, ProposalPreparer, Vm},
+    grug_client::Client,
+    grug_jmt::Proof,
+    grug_types::{
+        Addr, Binary, BorshDeExt, Bound, Denom, Hash256, JsonSerExt, Query, QueryResponse, Tx,
+        TxOutcome, UnsignedTx,
+    },
+    serde::{Deserialize, Serialize},
+    std::{str::FromStr, sync::Arc},
+    tokio::net::TcpListener,
+};
+
+/// The subset of the app's functionalities that the gateway serves.
+///
+/// The gateway holds the app as a trait object, so that the router doesn't
+/// need to carry the app's DB, VM, and proposal preparer type parameters.
+pub trait QueryApp: Send + Sync {
+    fn query_app(&self, req: Query, height: u64) -> AppResult<QueryResponse>;
+
+    fn query_store(
+        &self,
+        key: &[u8],
+        height: u64,
+        prove: bool,
+    ) -> AppResult<(Option<Vec<u8>>, Option<Vec<u8>>)>;
+
+    fn simulate(&self, unsigned_tx: UnsignedTx, trace: bool) -> AppResult<TxOutcome>;
+}
+
+impl<DB, VM, PP, ID> QueryApp for App<DB, VM, PP, ID>
+where
+    DB: Db + Send + Sync,
+    VM: Vm + Clone + Send + Sync,
+    PP: ProposalPreparer + Send + Sync,
+    ID: Indexer + Send + Sync,
+    AppError: From<DB::Error> + From<VM::Error> + From<PP::Error> + From<ID::Error>,
+{
+    fn query_app(&self, req: Query, height: u64) -> AppResult<QueryResponse> {
+        self.do_query_app(req, height, false)
+    }
+
+    fn query_store(
+        &self,
+        key: &[u8],
+        height: u64,
+        prove: bool,
+    ) -> AppResult<(Option<Vec<u8>>, Option<Vec<u8>>)> {
+        self.do_query_store(key, height, prove)
+    }
+
+    fn simulate(&self, unsigned_tx: UnsignedTx, trace: bool) -> AppResult<TxOutcome> {
+        if trace {
+            self.do_simulate_with_trace(unsigned_tx, 0, false)
+        } else {
+            self.do_simulate(unsigned_tx, 0, false)
+        }
+    }
+}
+
+/// An HTTP server exposing the node's queries as JSON REST routes.
+///
+/// Queries and simulations are served directly from the node's local database,
+/// which means historical queries are available for as far back as the
+/// database keeps. Transaction broadcasting and block lookups are forwarded to
+/// the CometBFT node via the [`Client`](grug_client::Client).
+///
+/// ## Routes
+///
+/// All `GET` query routes take an optional `height` parameter; if omitted, the
+/// latest height is used. Enumeration routes additionally take `start_after`
+/// and `limit`.
+///
+/// | Method | Path                                  | Description                          |
+/// | ------ | ------------------------------------- | ------------------------------------ |
+/// | GET    | `/config`                             | chain-level configuration            |
+/// | GET    | `/gas_costs`                          | gas costs of host operations         |
+/// | GET    | `/app_configs`                        | all app-specific configurations      |
+/// | GET    | `/app_configs/{key}`                  | a single app-specific configuration  |
+/// | GET    | `/balances/{address}`                 | an account's balances in all denoms  |
+/// | GET    | `/balances/{address}/{denom}`         | an account's balance in one denom    |
+/// | GET    | `/supplies`                           | all tokens' total supplies           |
+/// | GET    | `/supplies/{denom}`                   | a token's total supply               |
+/// | GET    | `/codes`                              | all Wasm byte codes                  |
+/// | GET    | `/codes/{hash}`                       | a single Wasm byte code              |
+/// | GET    | `/contracts`                          | metadata of all contracts            |
+/// | GET    | `/contracts/{address}`                | metadata of a single contract        |
+/// | GET    | `/contracts/{address}/raw/{key}`      | raw value in a contract's store      |
+/// | GET    | `/contracts/{address}/scan`           | key-value pairs in a contract store  |
+/// | POST   | `/contracts/{address}/query`          | call a contract's query entry point  |
+/// | POST   | `/query`                              | any query, including multi queries   |
+/// | GET    | `/store/{key}`                        | raw value in the app's store         |
+/// | POST   | `/simulate`                           | simulate an unsigned transaction     |
+/// | POST   | `/txs`                                | broadcast a signed transaction       |
+/// | GET    | `/txs/{hash}`                         | a transaction and its result         |
+/// | GET    | `/blocks/latest`                      | the latest block                     |
+/// | GET    | `/blocks/{height}`                    | a block by height                    |
+/// | GET    | `/blocks/{height}/results`            | a block's execution results          |
+///
+/// Raw keys and bounds are hex-encoded; hashes are hex-encoded in either case.
+#[derive(Clone)]
+pub struct Gateway {
+    app: Arc<dyn QueryApp>,
+    client: Arc<Client>,
+}
+
+impl Gateway {
+    pub fn new<A>(app: A, client: Client) -> Self
+    where
+        A: QueryApp + 'static,
+    {
+        Self {
+            app: Arc::new(app),
+            client: Arc::new(client),
+        }
+    }
+
+    pub fn router(self) -> Router {
+        Router::new()
+            .route("/config", get(query_config))
+            .route("/gas_costs", get(query_gas_costs))
+            .route("/app_configs", get(query_app_configs))
+            .route("/app_configs/{key}", get(query_app_config))
+            .route("/balances/{address}", get(query_balances))
+            .route("/balances/{address}/{*denom}", get(query_balance))
+            .route("/supplies", get(query_supplies))
+            .route("/supplies/{*denom}", get(query_supply))
+            .route("/codes", get(query_codes))
+            .route("/codes/{hash}", get(query_code))
+            .route("/contracts", get(query_contracts))
+            .route("/contracts/{address}", get(query_contract))
+            .route("/contracts/{address}/raw/{key}", get(query_wasm_raw))
+            .route("/contracts/{address}/scan", get(query_wasm_scan))
+            .route("/contracts/{address}/query", post(query_wasm_smart))
+            .route("/query", post(query))
+            .route("/store/{key}", get(query_store))
+            .route("/simulate", post(simulate))
+            .route("/txs", post(broadcast_tx))
+            .route("/txs/{hash}", get(query_tx))
+            .route("/blocks/latest", get(query_latest_block))
+            .route("/blocks/{height}", get(query_block))
+            .route("/blocks/{height}/results", get(query_block_result))
+            .with_state(self)
+    }
+
+    /// Start serving HTTP requests at the given address, e.g. `127.0.0.1:8080`.
+    pub async fn serve(self, addr: &str) -> anyhow::Result<()> {
+        let listener = TcpListener::bind(addr).await?;
+        axum::serve(listener, self.router()).await?;
+        Ok(())
+    }
+
+    /// Run a query against the app on a blocking thread, since the app's DB
+    /// and VM calls are synchronous.
+    async fn query_app(&self, req: Query, height: Option<u64>) -> GatewayResult<QueryResponse> {
+        let app = self.app.clone();
+        let height = height.unwrap_or(0);
+        Ok(tokio::task::spawn_blocking(move || app.query_app(req, height)).await??)
+    }
+}
+
+// --------------------------------- params ------------------------------------
+
+#[derive(Deserialize)]
+struct HeightParams {
+    height: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct PageParams {
+    start_after: Option<String>,
+    limit: Option<u32>,
+    height: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct ScanParams {
+    /// Minimum bound in hex encoding.
+    min: Option<String>,
+    /// Maximum bound in hex encoding.
+    max: Option<String>,
+    limit: Option<u32>,
+    /// Use exclusive minimum bound.
+    #[serde(default)]
+    min_exclusive: bool,
+    /// Use inclusive maximum bound.
+    #[serde(default)]
+    max_inclusive: bool,
+    height: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct StoreParams {
+    #[serde(default)]
+    prove: bool,
+    height: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct SimulateParams {
+    #[serde(default)]
+    trace: bool,
+}
+
+#[derive(Serialize)]
+pub struct QueryStoreResponse {
+    pub key: String,
+    pub value: Option<String>,
+    pub proof: Option<Proof>,
+}
+
+// -------------------------------- handlers -----------------------------------
+
+async fn query_config(
+    State(gateway): State<Gateway>,
+    Params(params): Params<HeightParams>,
+) -> GatewayResult<Json<grug_types::Json>> {
+    let res = gateway.query_app(Query::config(), params.height).await?;
+    Ok(Json(res.as_config().to_json_value()?))
+}
+
+async fn query_gas_costs(
+    State(gateway): State<Gateway>,
+    Params(params): Params<HeightParams>,
+) -> GatewayResult<Json<grug_types::Json>> {
+    let res = gateway.query_app(Query::gas_costs(), params.height).await?;
+    Ok(Json(res.as_gas_costs().to_json_value()?))
+}
+
+async fn query_app_config(
+    State(gateway): State<Gateway>,
+    Path(key): Path<String>,
+    Params(params): Params<HeightParams>,
+) -> GatewayResult<Json<grug_types::Json>> {
+    let res = gateway
+        .query_app(Query::app_config(key), params.height)
+        .await?;
+    Ok(Json(res.as_app_config().to_json_value()?))
+}
+
+async fn query_app_configs(
+    State(gateway): State<Gateway>,
+    Params(params): Params<PageParams>,
+) -> GatewayResult<Json<grug_types::Json>> {
+    let req = Query::app_configs(params.start_after, params.limit);
+    let res = gateway.query_app(req, params.height).await?;
+    Ok(Json(res.as_app_configs().to_json_value()?))
+}
+
+async fn query_balance(
+    State(gateway): State<Gateway>,
+    Path((address, denom)): Path<(String, String)>,
+    Params(params): Params<HeightParams>,
+) -> GatewayResult<Json<grug_types::Json>> {
+    let req = Query::balance(Addr::from_str(&address)?, Denom::from_str(&denom)?);
+    let res = gateway.query_app(req, params.height).await?;
+    Ok(Json(res.as_balance().to_json_value()?))
+}
+
+async fn query_balances(
+    State(gateway): State<Gateway>,
+    Path(address): Path<String>,
+    Params(params): Params<PageParams>,
+) -> GatewayResult<Json<grug_types::Json>> {
+    let start_after = parse_opt::<Denom>(params.start_after)?;
+    let req = Query::balances(Addr::from_str(&address)?, start_after, params.limit);
+    let res = gateway.query_app(req, params.height).await?;
+    Ok(Json(res.as_balances().to_json_value()?))
+}
+
+async fn query_supply(
+    State(gateway): State<Gateway>,
+    Path(denom): Path<String>,
+    Params(params): Params<HeightParams>,
+) -> GatewayResult<Json<grug_types::Json>> {
+    let req = Query::supply(Denom::from_str(&denom)?);
+    let res = gateway.query_app(req, params.height).await?;
+    Ok(Json(res.as_supply().to_json_value()?))
+}
+
+async fn query_supplies(
+    State(gateway): State<Gateway>,
+    Params(params): Params<PageParams>,
+) -> GatewayResult<Json<grug_types::Json>> {
+    let start_after = parse_opt::<Denom>(params.start_after)?;
+    let req = Query::supplies(start_after, params.limit);
+    let res = gateway.query_app(req, params.height).await?;
+    Ok(Json(res.as_supplies().to_json_value()?))
+}
+
+async fn query_code(
+    State(gateway): State<Gateway>,
+    Path(hash): Path<String>,
+    Params(params): Params<HeightParams>,
+) -> GatewayResult<Json<grug_types::Json>> {
+    let req = Query::code(parse_hash(&hash)?);
+    let res = gateway.query_app(req, params.height).await?;
+    Ok(Json(res.as_code().to_json_value()?))
+}
+
+async fn query_codes(
+    State(gateway): State<Gateway>,
+    Params(params): Params<PageParams>,
+) -> GatewayResult<Json<grug_types::Json>> {
+    let start_after = params.start_after.as_deref().map(parse_hash).transpose()?;
+    let req = Query::codes(start_after, params.limit);
+    let res = gateway.query_app(req, params.height).await?;
+    Ok(Json(res.as_codes().to_json_value()?))
+}
+
+async fn query_contract(
+    State(gateway): State<Gateway>,
+    Path(address): Path<String>,
+    Params(params): Params<HeightParams>,
+) -> GatewayResult<Json<grug_types::Json>> {
+    let req = Query::contract(Addr::from_str(&address)?);
+    let res = gateway.query_app(req, params.height).await?;
+    Ok(Json(res.as_contract().to_json_value()?))
+}
+
+async fn query_contracts(
+    State(gateway): State<Gateway>,
+    Params(params): Params<PageParams>,
+) -> GatewayResult<Json<grug_types::Json>> {
+    let start_after = parse_opt::<Addr>(params.start_after)?;
+    let req = Query::contracts(start_after, params.limit);
+    let res = gateway.query_app(req, params.height).await?;
+    Ok(Json(res.as_contracts().to_json_value()?))
+}
+
+async fn query_wasm_raw(
+    State(gateway): State<Gateway>,
+    Path((address, key)): Path<(String, String)>,
+    Params(params): Params<HeightParams>,
+) -> GatewayResult<Json<grug_types::Json>> {
+    let key = Binary::from(hex::decode(key)?);
+    let req = Query::wasm_raw(Addr::from_str(&address)?, key);
+    let res = gateway.query_app(req, params.height).await?;
+    Ok(Json(res.as_wasm_raw().to_json_value()?))
+}
+
+async fn query_wasm_scan(
+    State(gateway): State<Gateway>,
+    Path(address): Path<String>,
+    Params(params): Params<ScanParams>,
+) -> GatewayResult<Json<grug_types::Json>> {
+    let min = params
+        .min
+        .map(|min| -> GatewayResult<_> {
+            let min = Binary::from(hex::decode(min)?);
+            if params.min_exclusive {
+                Ok(Bound::Exclusive(min))
+            } else {
+                Ok(Bound::Inclusive(min))
+            }
+        })
+        .transpose()?;
+    let max = params
+        .max
+        .map(|max| -> GatewayResult<_> {
+            let max = Binary::from(hex::decode(max)?);
+            if params.max_inclusive {
+                Ok(Bound::Inclusive(max))
+            } else {
+                Ok(Bound::Exclusive(max))
+            }
+        })
+        .transpose()?;
+    let req = Query::wasm_scan(Addr::from_str(&address)?, min, max, params.limit);
+    let res = gateway.query_app(req, params.height).await?;
+    Ok(Json(res.as_wasm_scan().to_json_value()?))
+}
+
+async fn query_wasm_smart(
+    State(gateway): State<Gateway>,
+    Path(address): Path<String>,
+    Params(params): Params<HeightParams>,
+    Json(msg): Json<grug_types::Json>,
+) -> GatewayResult<Json<grug_types::Json>> {
+    let req = Query::wasm_smart(Addr::from_str(&address)?, &msg)?;
+    let res = gateway.query_app(req, params.height).await?;
+    Ok(Json(res.as_wasm_smart().to_json_value()?))
+}
+
+async fn query(
+    State(gateway): State<Gateway>,
+    Params(params): Params<HeightParams>,
+    Json(req): Json<Query>,
+) -> GatewayResult<Json<QueryResponse>> {
+    gateway.query_app(req, params.height).await.map(Json)
+}
+
+async fn query_store(
+    State(gateway): State<Gateway>,
+    Path(key_hex): Path<String>,
+    Params(params): Params<StoreParams>,
+) -> GatewayResult<Json<QueryStoreResponse>> {
+    let key = hex::decode(&key_hex)?;
+    let app = gateway.app.clone();
+    let height = params.height.unwrap_or(0);
+    let (value, proof) =
+        tokio::task::spawn_blocking(move || app.query_store(&key, height, params.prove)).await??;
+    let proof = proof
+        .map(|proof| proof.deserialize_borsh::<Proof>())
+        .transpose()?;
+
+    Ok(Json(QueryStoreResponse {
+        key: key_hex,
+        value: value.map(hex::encode),
+        proof,
+    }))
+}
+
+async fn simulate(
+    State(gateway): State<Gateway>,
+    Params(params): Params<SimulateParams>,
+    Json(unsigned_tx): Json<UnsignedTx>,
+) -> GatewayResult<Json<TxOutcome>> {
+    let app = gateway.app.clone();
+    let outcome =
+        tokio::task::spawn_blocking(move || app.simulate(unsigned_tx, params.trace)).await??;
+    Ok(Json(outcome))
+}
+
+async fn broadcast_tx(
+    State(gateway): State<Gateway>,
+    Json(tx): Json<Tx>,
+) -> GatewayResult<Json<grug_types::Json>> {
+    let res = gateway.client.broadcast_tx(tx).await?;
+    Ok(Json(res.to_json_value()?))
+}
+
+async fn query_tx(
+    State(gateway): State<Gateway>,
+    Path(hash): Path<String>,
+) -> GatewayResult<Json<grug_types::Json>> {
+    let res = gateway.client.query_tx(parse_hash(&hash)?).await?;
+    Ok(Json(res.to_json_value()?))
+}
+
+async fn query_latest_block(
+    State(gateway): State<Gateway>,
+) -> GatewayResult<Json<grug_types::Json>> {
+    let res = gateway.client.query_block(None).await?;
+    Ok(Json(res.to_json_value()?))
+}
+
+async fn query_block(
+    State(gateway): State<Gateway>,
+    Path(height): Path<u64>,
+) -> GatewayResult<Json<grug_types::Json>> {
+    let res = gateway.client.query_block(Some(height)).await?;
+    Ok(Json(res.to_json_value()?))
+}
+
+async fn query_block_result(
+    State(gateway): State<Gateway>,
+    Path(height): Path<u64>,
+) -> GatewayResult<Json<grug_types::Json>> {
+    let res = gateway.client.query_block_result(Some(height)).await?;
+    Ok(Json(res.to_json_value()?))
+}
+
+// --------------------------------- helpers -----------------------------------
+
+fn parse_opt<T>(s: Option<String>) -> GatewayResult<Option<T>>
+where
+    T: FromStr,
+    crate::GatewayError: From<T::Err>,
+{
+    Ok(s.map(|s| T::from_str(&s)).transpose()?)
+}
+
+/// Cast the hex string to uppercase, so that users can use either upper or
+/// lowercase hashes.
+fn parse_hash(s: &str) -> GatewayResult<Hash256> {
+    Ok(Hash256::from_str(&s.to_ascii_uppercase())?)
+}
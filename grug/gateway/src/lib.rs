@@ -0,0 +1,4 @@
+mod error;
+mod gateway;
+
+pub use crate::{error::*, gateway::*};
@@ -0,0 +1,139 @@
+use {
+    axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        Router,
+    },
+    grug_client::Client,
+    grug_gateway::Gateway,
+    grug_testing::{TestAccounts, TestBuilder, TestSuite},
+    grug_types::{
+        AuthCtx, AuthMode, AuthResponse, Coins, Json, JsonDeExt, JsonSerExt, Message, ResultExt,
+        StdError, StdResult, Tx, TxOutcome, UnsignedTx,
+    },
+    grug_vm_rust::ContractBuilder,
+    http_body_util::BodyExt,
+    tower::ServiceExt,
+};
+
+/// An account that skips authentication when simulating, so that transactions
+/// can be simulated without a credential.
+fn authenticate_unless_simulating(ctx: AuthCtx, tx: Tx) -> StdResult<AuthResponse> {
+    if ctx.mode == AuthMode::Simulate {
+        return Ok(AuthResponse::new());
+    }
+
+    grug_mock_account::authenticate(ctx, tx).map_err(|err| StdError::host(err.to_string()))
+}
+
+fn setup_gateway() -> (TestSuite, TestAccounts, Router) {
+    let account_code = ContractBuilder::new(Box::new(grug_mock_account::instantiate))
+        .with_receive(Box::new(grug_mock_account::receive))
+        .with_authenticate(Box::new(authenticate_unless_simulating))
+        .build();
+
+    let (suite, accounts) = TestBuilder::new()
+        .set_account_code(account_code, |public_key| {
+            grug_mock_account::InstantiateMsg { public_key }
+        })
+        .add_account("owner", Coins::new())
+        .add_account("alice", Coins::one("uatom", 100).unwrap())
+        .add_account("bob", Coins::new())
+        .set_owner("owner")
+        .build();
+
+    // The client is only used for broadcasting and block lookups, which these
+    // tests don't exercise, so it doesn't need a running node.
+    let client = Client::connect("http://127.0.0.1:26657").unwrap();
+    let router = Gateway::new(suite.app.clone(), client).router();
+
+    (suite, accounts, router)
+}
+
+async fn call(router: &Router, req: Request<Body>) -> (StatusCode, Json) {
+    let res = router.clone().oneshot(req).await.unwrap();
+    let status = res.status();
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+
+    (status, body.deserialize_json().unwrap())
+}
+
+async fn get(router: &Router, uri: &str) -> (StatusCode, Json) {
+    call(router, Request::get(uri).body(Body::empty()).unwrap()).await
+}
+
+#[tokio::test]
+async fn querying_through_gateway_works() {
+    let (mut suite, mut accounts, router) = setup_gateway();
+
+    // Query the chain config.
+    let (status, res) = get(&router, "/config").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        res["owner"],
+        accounts["owner"].address.to_json_value().unwrap()
+    );
+
+    // Query a balance, then make a transfer and query again. The balance at
+    // the previous height should still be available.
+    //
+    // Note that height zero means the latest height, so make a block first.
+    suite.make_empty_block();
+    let height = suite.block.height;
+    let uri = format!("/balances/{}/uatom", accounts["alice"].address);
+
+    let (status, res) = get(&router, &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(res["amount"], "100");
+
+    let bob = accounts["bob"].address;
+    suite
+        .transfer(
+            &mut accounts["alice"],
+            bob,
+            Coins::one("uatom", 30).unwrap(),
+        )
+        .should_succeed();
+
+    let (_, res) = get(&router, &uri).await;
+    assert_eq!(res["amount"], "70");
+
+    let (_, res) = get(&router, &format!("{uri}?height={height}")).await;
+    assert_eq!(res["amount"], "100");
+
+    let (_, res) = get(&router, &format!("/balances/{bob}")).await;
+    assert_eq!(
+        res,
+        Coins::one("uatom", 30).unwrap().to_json_value().unwrap()
+    );
+
+    // Invalid inputs are rejected as bad requests.
+    let (status, res) = get(&router, "/balances/notanaddress").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(res["error"].is_string());
+}
+
+#[tokio::test]
+async fn simulating_through_gateway_works() {
+    let (_suite, accounts, router) = setup_gateway();
+
+    let unsigned_tx = UnsignedTx {
+        sender: accounts["alice"].address,
+        msgs: vec![
+            Message::transfer(accounts["bob"].address, Coins::one("uatom", 1).unwrap()).unwrap(),
+        ],
+        data: Json::Null,
+    };
+
+    let req = Request::post("/simulate?trace=true")
+        .header("content-type", "application/json")
+        .body(Body::from(unsigned_tx.to_json_vec().unwrap()))
+        .unwrap();
+
+    let (status, res) = call(&router, req).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let outcome: TxOutcome = res.deserialize_json().unwrap();
+    assert!(outcome.trace.is_some());
+    outcome.should_succeed();
+}
@@ -0,0 +1,21 @@
+use {grug_app::AppError, grug_types::StdError, std::num::TryFromIntError, thiserror::Error};
+
+#[derive(Debug, Error)]
+pub enum IndexerError {
+    #[error(transparent)]
+    Std(#[from] StdError),
+
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error(transparent)]
+    Int(#[from] TryFromIntError),
+}
+
+impl From<IndexerError> for AppError {
+    fn from(err: IndexerError) -> Self {
+        AppError::Indexer(err.to_string())
+    }
+}
+
+pub type IndexerResult<T> = core::result::Result<T, IndexerError>;
@@ -0,0 +1,505 @@
+use {
+    crate::{IndexedBlock, IndexedEvent, IndexedTx, IndexerError, IndexerResult},
+    grug_app::Indexer,
+    grug_types::{
+        Addr, BlockInfo, BlockOutcome, Event, EventCategory, EventName, FlatEvent, Hash256, Json,
+        JsonDeExt, JsonSerExt, Message, Timestamp, Tx, TxOutcome,
+    },
+    rusqlite::{params, Connection, OptionalExtension, Row},
+    serde::{de::DeserializeOwned, Deserialize, Serialize},
+    std::{
+        path::Path,
+        str::FromStr,
+        sync::{Arc, Mutex, MutexGuard},
+    },
+};
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS pending_blocks (
+    height INTEGER PRIMARY KEY,
+    block  TEXT    NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS blocks (
+    height    INTEGER PRIMARY KEY,
+    timestamp INTEGER NOT NULL,
+    hash      TEXT    NOT NULL,
+    app_hash  TEXT    NOT NULL,
+    num_txs   INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS txs (
+    hash    TEXT    NOT NULL,
+    height  INTEGER NOT NULL,
+    idx     INTEGER NOT NULL,
+    sender  TEXT    NOT NULL,
+    success INTEGER NOT NULL,
+    tx      TEXT    NOT NULL,
+    outcome TEXT    NOT NULL,
+    PRIMARY KEY (height, idx)
+);
+
+CREATE INDEX IF NOT EXISTS txs_by_hash ON txs (hash);
+CREATE INDEX IF NOT EXISTS txs_by_sender ON txs (sender, height, idx);
+
+CREATE TABLE IF NOT EXISTS messages (
+    height   INTEGER NOT NULL,
+    tx_idx   INTEGER NOT NULL,
+    idx      INTEGER NOT NULL,
+    kind     TEXT    NOT NULL,
+    contract TEXT,
+    msg      TEXT    NOT NULL,
+    PRIMARY KEY (height, tx_idx, idx)
+);
+
+CREATE INDEX IF NOT EXISTS messages_by_contract ON messages (contract, height, tx_idx);
+
+CREATE TABLE IF NOT EXISTS events (
+    id       INTEGER PRIMARY KEY AUTOINCREMENT,
+    height   INTEGER NOT NULL,
+    tx_idx   INTEGER,
+    tx_hash  TEXT,
+    category TEXT    NOT NULL,
+    type     TEXT    NOT NULL,
+    contract TEXT,
+    event    TEXT    NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS events_by_type ON events (category, type, id);
+CREATE INDEX IF NOT EXISTS events_by_contract ON events (contract, id);
+
+CREATE TABLE IF NOT EXISTS event_attributes (
+    event_id INTEGER NOT NULL,
+    key      TEXT    NOT NULL,
+    value    TEXT    NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS event_attributes_by_key_value ON event_attributes (key, value, event_id);
+"#;
+
+/// A block that has been finalized, but not yet committed.
+#[derive(Serialize, Deserialize)]
+struct PendingBlock {
+    info: BlockInfo,
+    txs: Vec<(Tx, Hash256)>,
+    outcome: BlockOutcome,
+}
+
+/// An [`Indexer`](grug_app::Indexer) that records blocks, transactions, their
+/// messages, and events into a SQLite database.
+///
+/// Blocks handed over in `FinalizeBlock` are saved in a table of pending
+/// blocks, and are moved into the other tables in a single SQL transaction
+/// upon `Commit`. This way, queries never return blocks that weren't committed,
+/// while a block committed right before the node crashed can still be recovered
+/// from the pending table after restarting.
+#[derive(Clone)]
+pub struct SqlIndexer {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqlIndexer {
+    /// Open the SQLite database at the given path, creating it if it doesn't
+    /// exist.
+    pub fn open<P>(path: P) -> IndexerResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Self::new(Connection::open(path)?)
+    }
+
+    /// Create an indexer backed by an in-memory SQLite database. Useful for
+    /// testing.
+    pub fn open_in_memory() -> IndexerResult<Self> {
+        Self::new(Connection::open_in_memory()?)
+    }
+
+    fn new(conn: Connection) -> IndexerResult<Self> {
+        conn.execute_batch(SCHEMA)?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<Connection> {
+        self.conn
+            .lock()
+            .unwrap_or_else(|err| panic!("poisoned lock: {err:?}"))
+    }
+
+    /// Query a block by height.
+    pub fn query_block(&self, height: u64) -> IndexerResult<Option<IndexedBlock>> {
+        self.lock()
+            .query_row(
+                "SELECT height, timestamp, hash, app_hash, num_txs FROM blocks WHERE height = ?1",
+                params![height],
+                |row| Ok(read_block(row)),
+            )
+            .optional()?
+            .transpose()
+    }
+
+    /// Query a transaction by hash.
+    ///
+    /// If the same transaction was included more than once (which can happen
+    /// if it failed authentication the first time), the earliest is returned.
+    pub fn query_tx(&self, hash: Hash256) -> IndexerResult<Option<IndexedTx>> {
+        self.lock()
+            .query_row(
+                "SELECT hash, height, idx, tx, outcome FROM txs WHERE hash = ?1 \
+                 ORDER BY height, idx LIMIT 1",
+                params![hash.to_string()],
+                |row| Ok(read_tx(row)),
+            )
+            .optional()?
+            .transpose()
+    }
+
+    /// Enumerate transactions sent by the given account, in the order they
+    /// were executed.
+    pub fn query_txs_by_sender(&self, sender: Addr) -> IndexerResult<Vec<IndexedTx>> {
+        self.query_txs(
+            "SELECT hash, height, idx, tx, outcome FROM txs WHERE sender = ?1 \
+             ORDER BY height, idx",
+            sender,
+        )
+    }
+
+    /// Enumerate transactions that involve the given contract, in the order
+    /// they were executed.
+    ///
+    /// A transaction involves a contract if any of its messages targets the
+    /// contract (e.g. executes it, or transfers tokens to it), or if the
+    /// contract was called during its execution.
+    pub fn query_txs_by_contract(&self, contract: Addr) -> IndexerResult<Vec<IndexedTx>> {
+        self.query_txs(
+            "SELECT hash, height, idx, tx, outcome FROM txs WHERE (height, idx) IN ( \
+                 SELECT height, tx_idx FROM messages WHERE contract = ?1 \
+                 UNION \
+                 SELECT height, tx_idx FROM events WHERE contract = ?1 AND tx_idx IS NOT NULL \
+             ) ORDER BY height, idx",
+            contract,
+        )
+    }
+
+    /// Enumerate events of the given category and type, in the order they
+    /// were emitted.
+    pub fn query_events_by_type(
+        &self,
+        category: EventCategory,
+        ty: &str,
+    ) -> IndexerResult<Vec<IndexedEvent>> {
+        self.query_events(
+            "SELECT height, tx_hash, event FROM events WHERE category = ?1 AND type = ?2 \
+             ORDER BY height, id",
+            params![category.to_string(), ty],
+        )
+    }
+
+    /// Enumerate events emitted by contracts of the given type, decoded as `E`,
+    /// in the order they were emitted.
+    pub fn query_contract_events<E>(&self) -> IndexerResult<Vec<(IndexedEvent, E)>>
+    where
+        E: EventName + DeserializeOwned,
+    {
+        self.query_events_by_type(EventCategory::Contract, E::EVENT_NAME)?
+            .into_iter()
+            .map(|indexed| {
+                let event = indexed.event.data.clone().deserialize_json()?;
+                Ok((indexed, event))
+            })
+            .collect()
+    }
+
+    /// Enumerate events that concern the given contract, in the order they
+    /// were emitted. These include the contract's calls, the events it emitted,
+    /// and host events about it, such as its execution.
+    pub fn query_events_by_contract(&self, contract: Addr) -> IndexerResult<Vec<IndexedEvent>> {
+        self.query_events(
+            "SELECT height, tx_hash, event FROM events WHERE contract = ?1 ORDER BY height, id",
+            params![contract.to_string()],
+        )
+    }
+
+    /// Enumerate events of which the data contains a top level field of the
+    /// given key and value, in the order they were emitted.
+    ///
+    /// String values are matched as is; other values are matched by their
+    /// JSON encoding.
+    pub fn query_events_by_attribute(
+        &self,
+        key: &str,
+        value: &str,
+    ) -> IndexerResult<Vec<IndexedEvent>> {
+        self.query_events(
+            "SELECT height, tx_hash, event FROM events WHERE id IN ( \
+                 SELECT event_id FROM event_attributes WHERE key = ?1 AND value = ?2 \
+             ) ORDER BY height, id",
+            params![key, value],
+        )
+    }
+
+    fn query_txs(&self, sql: &str, addr: Addr) -> IndexerResult<Vec<IndexedTx>> {
+        let conn = self.lock();
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map(params![addr.to_string()], |row| Ok(read_tx(row)))?;
+        rows.map(|res| res?).collect()
+    }
+
+    fn query_events<P>(&self, sql: &str, params: P) -> IndexerResult<Vec<IndexedEvent>>
+    where
+        P: rusqlite::Params,
+    {
+        let conn = self.lock();
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map(params, |row| Ok(read_event(row)))?;
+        rows.map(|res| res?).collect()
+    }
+}
+
+impl Indexer for SqlIndexer {
+    type Error = IndexerError;
+
+    fn index_block(
+        &self,
+        block: &BlockInfo,
+        txs: &[(Tx, Hash256)],
+        outcome: &BlockOutcome,
+    ) -> IndexerResult<()> {
+        let block = PendingBlock {
+            info: *block,
+            txs: txs.to_vec(),
+            outcome: outcome.clone(),
+        };
+
+        // If the same height is finalized again (e.g. the node crashed before
+        // committing), the later one replaces the earlier one.
+        self.lock().execute(
+            "INSERT OR REPLACE INTO pending_blocks (height, block) VALUES (?1, ?2)",
+            params![block.info.height, block.to_json_string()?],
+        )?;
+
+        Ok(())
+    }
+
+    fn commit(&self, height: u64) -> IndexerResult<()> {
+        let mut conn = self.lock();
+        let db_tx = conn.transaction()?;
+
+        // Move the pending blocks up to and including the committed height.
+        let blocks = {
+            let mut stmt = db_tx
+                .prepare("SELECT block FROM pending_blocks WHERE height <= ?1 ORDER BY height")?;
+            let rows = stmt.query_map(params![height], |row| row.get::<_, String>(0))?;
+            rows.map(|res| Ok(res?.deserialize_json()?))
+                .collect::<IndexerResult<Vec<PendingBlock>>>()?
+        };
+
+        for block in blocks {
+            write_block(&db_tx, block)?;
+        }
+
+        db_tx.execute(
+            "DELETE FROM pending_blocks WHERE height <= ?1",
+            params![height],
+        )?;
+
+        db_tx.commit()?;
+
+        Ok(())
+    }
+}
+
+// --------------------------------- writing -----------------------------------
+
+fn write_block(conn: &Connection, block: PendingBlock) -> IndexerResult<()> {
+    let height = block.info.height;
+
+    // Remove whatever was recorded for this height before, so that writing the
+    // same block twice doesn't duplicate its events.
+    conn.execute(
+        "DELETE FROM event_attributes WHERE event_id IN ( \
+             SELECT id FROM events WHERE height = ?1 \
+         )",
+        params![height],
+    )?;
+
+    for table in ["events", "messages", "txs"] {
+        conn.execute(
+            &format!("DELETE FROM {table} WHERE height = ?1"),
+            params![height],
+        )?;
+    }
+
+    conn.execute(
+        "INSERT OR REPLACE INTO blocks (height, timestamp, hash, app_hash, num_txs) \
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            height,
+            i64::try_from(block.info.timestamp.into_nanos())?,
+            block.info.hash.to_string(),
+            block.outcome.app_hash.to_string(),
+            block.txs.len(),
+        ],
+    )?;
+
+    for cron_outcome in block.outcome.cron_outcomes {
+        // Failed cronjobs are reverted, so they don't emit any event.
+        if let Ok(events) = cron_outcome.result {
+            for event in events.iter().flat_map(Event::flatten) {
+                write_event(conn, height, None, event)?;
+            }
+        }
+    }
+
+    for (idx, ((tx, hash), outcome)) in block
+        .txs
+        .into_iter()
+        .zip(block.outcome.tx_outcomes)
+        .enumerate()
+    {
+        write_tx(conn, height, idx, tx, hash, outcome)?;
+    }
+
+    Ok(())
+}
+
+fn write_tx(
+    conn: &Connection,
+    height: u64,
+    idx: usize,
+    tx: Tx,
+    hash: Hash256,
+    outcome: TxOutcome,
+) -> IndexerResult<()> {
+    let tx_json = tx.to_json_string()?;
+
+    conn.execute(
+        "INSERT OR REPLACE INTO txs (hash, height, idx, sender, success, tx, outcome) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            hash.to_string(),
+            height,
+            idx,
+            tx.sender.to_string(),
+            outcome.result.is_ok(),
+            tx_json,
+            outcome.to_json_string()?,
+        ],
+    )?;
+
+    for (msg_idx, msg) in tx.msgs.iter().enumerate() {
+        let (kind, contract) = match msg {
+            Message::Configure(_) => ("configure", None),
+            Message::Transfer(msg) => ("transfer", Some(msg.to)),
+            Message::Upload(_) => ("upload", None),
+            Message::Instantiate(_) => ("instantiate", None),
+            Message::Execute(msg) => ("execute", Some(msg.contract)),
+            Message::Migrate(msg) => ("migrate", Some(msg.contract)),
+            Message::UpdateAdmin(msg) => ("update_admin", Some(msg.contract)),
+            Message::ClearAdmin(msg) => ("clear_admin", Some(msg.contract)),
+        };
+
+        conn.execute(
+            "INSERT OR REPLACE INTO messages (height, tx_idx, idx, kind, contract, msg) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                height,
+                idx,
+                msg_idx,
+                kind,
+                contract.map(|addr| addr.to_string()),
+                msg.to_json_string()?,
+            ],
+        )?;
+    }
+
+    for event in outcome.events.iter().flat_map(Event::flatten) {
+        write_event(conn, height, Some((idx, hash)), event)?;
+    }
+
+    Ok(())
+}
+
+fn write_event(
+    conn: &Connection,
+    height: u64,
+    tx: Option<(usize, Hash256)>,
+    event: FlatEvent,
+) -> IndexerResult<()> {
+    conn.execute(
+        "INSERT INTO events (height, tx_idx, tx_hash, category, type, contract, event) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            height,
+            tx.map(|(idx, _)| idx),
+            tx.map(|(_, hash)| hash.to_string()),
+            event.category.to_string(),
+            event.ty,
+            event.contract.map(|addr| addr.to_string()),
+            event.to_json_string()?,
+        ],
+    )?;
+
+    let event_id = conn.last_insert_rowid();
+
+    if let Json::Object(fields) = &event.data {
+        for (key, value) in fields {
+            let value = match value {
+                Json::String(string) => string.clone(),
+                value => value.to_string(),
+            };
+
+            conn.execute(
+                "INSERT INTO event_attributes (event_id, key, value) VALUES (?1, ?2, ?3)",
+                params![event_id, key, value],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+// --------------------------------- reading -----------------------------------
+
+fn read_block(row: &Row) -> IndexerResult<IndexedBlock> {
+    let timestamp: i64 = row.get(1)?;
+    let hash: String = row.get(2)?;
+    let app_hash: String = row.get(3)?;
+
+    Ok(IndexedBlock {
+        info: BlockInfo {
+            height: row.get(0)?,
+            timestamp: Timestamp::from_nanos(u128::try_from(timestamp)?),
+            hash: Hash256::from_str(&hash)?,
+        },
+        app_hash: Hash256::from_str(&app_hash)?,
+        num_txs: row.get(4)?,
+    })
+}
+
+fn read_tx(row: &Row) -> IndexerResult<IndexedTx> {
+    let hash: String = row.get(0)?;
+    let tx: String = row.get(3)?;
+    let outcome: String = row.get(4)?;
+
+    Ok(IndexedTx {
+        hash: Hash256::from_str(&hash)?,
+        height: row.get(1)?,
+        index: row.get(2)?,
+        tx: tx.deserialize_json()?,
+        outcome: outcome.deserialize_json()?,
+    })
+}
+
+fn read_event(row: &Row) -> IndexerResult<IndexedEvent> {
+    let tx_hash: Option<String> = row.get(1)?;
+    let event: String = row.get(2)?;
+
+    Ok(IndexedEvent {
+        height: row.get(0)?,
+        tx_hash: tx_hash.map(|hash| Hash256::from_str(&hash)).transpose()?,
+        event: event.deserialize_json()?,
+    })
+}
@@ -0,0 +1,5 @@
+mod error;
+mod indexer;
+mod types;
+
+pub use crate::{error::*, indexer::*, types::*};
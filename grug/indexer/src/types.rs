@@ -0,0 +1,39 @@
+use {
+    grug_types::{BlockInfo, FlatEvent, Hash256, Tx, TxOutcome},
+    serde::{Deserialize, Serialize},
+};
+
+/// A block, as recorded by the indexer.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IndexedBlock {
+    pub info: BlockInfo,
+    /// The Merkle root hash after executing this block.
+    pub app_hash: Hash256,
+    /// Number of transactions in the block, including failed ones.
+    pub num_txs: u32,
+}
+
+/// A transaction and the outcome of executing it, as recorded by the indexer.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IndexedTx {
+    /// SHA-256 hash of the raw transaction, as included in the block.
+    pub hash: Hash256,
+    pub height: u64,
+    /// Position of the transaction in the block.
+    pub index: u32,
+    pub tx: Tx,
+    pub outcome: TxOutcome,
+}
+
+/// An event, as recorded by the indexer.
+///
+/// The indexer records events flattened, such that they can be queried
+/// regardless of how deeply they're nested.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IndexedEvent {
+    pub height: u64,
+    /// Hash of the transaction that emitted the event; `None` if the event was
+    /// emitted by a cronjob.
+    pub tx_hash: Option<Hash256>,
+    pub event: FlatEvent,
+}
@@ -0,0 +1,169 @@
+use {
+    grug_app::Indexer,
+    grug_indexer::SqlIndexer,
+    grug_testing::TestBuilder,
+    grug_types::{Coins, EventCategory, HashExt, JsonSerExt, Message, ResultExt},
+};
+
+#[test]
+fn indexing_blocks_works() {
+    let (suite, mut accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new())
+        .add_account("alice", Coins::one("uatom", 100).unwrap())
+        .add_account("bob", Coins::new())
+        .set_owner("owner")
+        .build();
+
+    let indexer = SqlIndexer::open_in_memory().unwrap();
+    let mut suite = suite.with_indexer(indexer.clone());
+
+    let bank = suite.query_config().unwrap().bank;
+    let alice = accounts["alice"].address;
+    let bob = accounts["bob"].address;
+
+    // Make a block with two transactions: one succeeds and one fails.
+    let txs = [30, 200]
+        .into_iter()
+        .map(|amount| {
+            let msg = Message::transfer(bob, Coins::one("uatom", amount).unwrap()).unwrap();
+            suite
+                .sign_transaction_with_gas(accounts.get_mut("alice").unwrap(), 1_000_000, vec![msg])
+        })
+        .collect::<Vec<_>>();
+
+    let hashes = txs
+        .iter()
+        .map(|tx| tx.to_json_vec().unwrap().hash256())
+        .collect::<Vec<_>>();
+
+    let outcome = suite.make_block(txs);
+    let height = suite.block.height;
+
+    // The block should have been recorded.
+    let block = indexer.query_block(height).unwrap().unwrap();
+    assert_eq!(block.info, suite.block);
+    assert_eq!(block.app_hash, outcome.app_hash);
+    assert_eq!(block.num_txs, 2);
+
+    // Blocks that were made before the indexer was attached are not recorded.
+    assert!(indexer.query_block(height - 1).unwrap().is_none());
+
+    // Query transactions by hash.
+    let tx = indexer.query_tx(hashes[0]).unwrap().unwrap();
+    assert_eq!(tx.height, height);
+    assert_eq!(tx.index, 0);
+    assert_eq!(tx.outcome, outcome.tx_outcomes[0]);
+    tx.outcome.should_succeed();
+
+    let tx = indexer.query_tx(hashes[1]).unwrap().unwrap();
+    assert_eq!(tx.index, 1);
+    tx.outcome.should_fail();
+
+    // Query transactions by sender.
+    let txs = indexer.query_txs_by_sender(alice).unwrap();
+    assert_eq!(
+        txs.iter().map(|tx| tx.hash).collect::<Vec<_>>(),
+        hashes.clone()
+    );
+    assert!(indexer.query_txs_by_sender(bob).unwrap().is_empty());
+
+    // Query transactions by contract. Both transactions target Bob's account,
+    // but only the successful one has called the bank contract, since the
+    // state changes (including events) of the failed one are reverted.
+    let txs = indexer.query_txs_by_contract(bob).unwrap();
+    assert_eq!(
+        txs.iter().map(|tx| tx.hash).collect::<Vec<_>>(),
+        hashes.clone()
+    );
+
+    let txs = indexer.query_txs_by_contract(bank).unwrap();
+    assert_eq!(txs.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![
+        hashes[0]
+    ]);
+
+    // Query events. Only the successful transfer reaches Bob's account.
+    let events = indexer.query_events_by_contract(bob).unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event.ty, "guest");
+    assert_eq!(events[0].event.data["method"], "receive");
+    assert_eq!(events[0].tx_hash, Some(hashes[0]));
+
+    let events = indexer
+        .query_events_by_type(EventCategory::Host, "transfer")
+        .unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event.data["coins"]["uatom"], "30");
+
+    // Contracts can't emit events that pass as host events.
+    assert!(indexer
+        .query_events_by_type(EventCategory::Contract, "transfer")
+        .unwrap()
+        .is_empty());
+
+    let events = indexer
+        .query_events_by_attribute("recipient", &bob.to_string())
+        .unwrap();
+    assert_eq!(
+        events,
+        indexer
+            .query_events_by_type(EventCategory::Host, "transfer")
+            .unwrap()
+    );
+}
+
+#[test]
+fn recovering_blocks_after_crash() {
+    let (suite, mut accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new())
+        .add_account("alice", Coins::one("uatom", 100).unwrap())
+        .add_account("bob", Coins::new())
+        .set_owner("owner")
+        .build();
+
+    let indexer = SqlIndexer::open_in_memory().unwrap();
+    let mut suite = suite.with_indexer(indexer.clone());
+
+    let bob = accounts["bob"].address;
+
+    let msg = Message::transfer(bob, Coins::one("uatom", 30).unwrap()).unwrap();
+    let tx =
+        suite.sign_transaction_with_gas(accounts.get_mut("alice").unwrap(), 1_000_000, vec![msg]);
+    let hash = tx.to_json_vec().unwrap().hash256();
+
+    let outcome = suite.make_block(vec![tx.clone()]);
+    let height = suite.block.height;
+
+    // Hand the same block to another indexer backed by a file, as if the node
+    // had crashed after finalizing the block and committing the state, but
+    // before the indexer could commit.
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("index.sqlite");
+
+    let crashed = SqlIndexer::open(&path).unwrap();
+    crashed
+        .index_block(&suite.block, &[(tx, hash)], &outcome)
+        .unwrap();
+    drop(crashed);
+
+    // After restarting, the block isn't visible until the indexer is committed.
+    let recovered = SqlIndexer::open(&path).unwrap();
+    assert!(recovered.query_block(height).unwrap().is_none());
+
+    recovered.commit(height).unwrap();
+    assert_eq!(
+        recovered.query_block(height).unwrap(),
+        indexer.query_block(height).unwrap()
+    );
+    assert_eq!(
+        recovered.query_tx(hash).unwrap(),
+        indexer.query_tx(hash).unwrap()
+    );
+
+    // Committing again doesn't duplicate anything.
+    recovered.commit(height).unwrap();
+    assert_eq!(
+        recovered.query_events_by_contract(bob).unwrap(),
+        indexer.query_events_by_contract(bob).unwrap()
+    );
+    assert_eq!(recovered.query_txs_by_contract(bob).unwrap().len(), 1);
+}
@@ -4,7 +4,7 @@ use {
         ProofNode,
     },
     grug_storage::{Map, PrefixBound, Set},
-    grug_types::{Batch, Hash256, HashExt, Op, Order, StdResult, Storage},
+    grug_types::{Batch, BorshDeExt, Hash256, HashExt, Op, Order, Record, StdResult, Storage},
 };
 
 // Default storage namespaces
@@ -27,6 +27,15 @@ enum Outcome {
     Deleted,
 }
 
+/// Number of nodes in a tree, as counted by [`MerkleTree::verify`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TreeSize {
+    /// Number of nodes, including both internal nodes and leaves.
+    pub nodes: usize,
+    /// Number of leaves, i.e. key-value pairs.
+    pub leaves: usize,
+}
+
 /// Jellyfish Merkle tree (JMT).
 ///
 /// Adapted from Diem's work:
@@ -578,6 +587,96 @@ impl<'a> MerkleTree<'a> {
         Ok(())
     }
 
+    /// Return all nodes that are part of the tree at the given version, as raw
+    /// storage records, in depth-first order starting from the root.
+    ///
+    /// This is used for exporting state snapshots. Importing a snapshot is as
+    /// simple as writing the records to the storage.
+    pub fn export(&self, storage: &dyn Storage, version: u64) -> StdResult<Vec<Record>> {
+        let mut records = Vec::new();
+
+        if !self.nodes.has(storage, (version, &ROOT_BITS)) {
+            return Ok(records);
+        }
+
+        let mut stack = vec![(version, ROOT_BITS)];
+
+        while let Some((version, bits)) = stack.pop() {
+            let path = self.nodes.path((version, &bits));
+            let node_raw = path.as_path().load_raw(storage)?;
+
+            if let Node::Internal(InternalNode {
+                left_child,
+                right_child,
+            }) = node_raw.deserialize_borsh()?
+            {
+                // Push the right child first, so that the left child is popped
+                // (and thus exported) first.
+                if let Some(child) = right_child {
+                    stack.push((child.version, bits.extend_one_bit(false)));
+                }
+
+                if let Some(child) = left_child {
+                    stack.push((child.version, bits.extend_one_bit(true)));
+                }
+            }
+
+            records.push((path.as_path().storage_key().to_vec(), node_raw));
+        }
+
+        Ok(records)
+    }
+
+    /// Walk the tree at the given version, checking that each node hashes to
+    /// the hash committed to by its parent, and that the root node hashes to
+    /// `root_hash`. Return the number of nodes and leaves in the tree, or `None`
+    /// if any node is missing or doesn't hash to the expected value.
+    ///
+    /// This is used for importing snapshots. If the check passes, the nodes in
+    /// the storage are exactly those of the tree committed to by `root_hash`,
+    /// without having to trust whoever exported them.
+    pub fn verify(
+        &self,
+        storage: &dyn Storage,
+        version: u64,
+        root_hash: Hash256,
+    ) -> StdResult<Option<TreeSize>> {
+        let mut size = TreeSize::default();
+        let mut stack = vec![(version, ROOT_BITS, root_hash)];
+
+        while let Some((version, bits, hash)) = stack.pop() {
+            let Some(node) = self.nodes.may_load(storage, (version, &bits))? else {
+                return Ok(None);
+            };
+
+            if node.hash() != hash {
+                return Ok(None);
+            }
+
+            size.nodes += 1;
+
+            match node {
+                Node::Internal(InternalNode {
+                    left_child,
+                    right_child,
+                }) => {
+                    if let Some(child) = right_child {
+                        stack.push((child.version, bits.extend_one_bit(false), child.hash));
+                    }
+
+                    if let Some(child) = left_child {
+                        stack.push((child.version, bits.extend_one_bit(true), child.hash));
+                    }
+                },
+                Node::Leaf(_) => {
+                    size.leaves += 1;
+                },
+            }
+        }
+
+        Ok(Some(size))
+    }
+
     #[inline]
     fn save_node(
         &self,
@@ -758,7 +857,7 @@ fn into_child(version: u64, outcome: Outcome) -> Option<Child> {
 mod tests {
     use {
         super::*,
-        grug_types::{MockStorage, ResultExt, StdError},
+        grug_types::{BorshSerExt, MockStorage, ResultExt, StdError},
         hex_literal::hex,
         test_case::test_case,
     };
@@ -1038,6 +1137,70 @@ mod tests {
             ));
     }
 
+    #[test]
+    fn exporting() {
+        let (mut storage, _) = build_test_case().unwrap();
+
+        // v1: delete a leaf, such that the tree at v1 consists of nodes of
+        // both v0 and v1.
+        let root_hash = TREE
+            .apply_raw(
+                &mut storage,
+                0,
+                1,
+                &Batch::from([(b"r".to_vec(), Op::Delete)]),
+            )
+            .unwrap();
+
+        // Export the tree at v1, and import it into a fresh storage.
+        let records = TREE.export(&storage, 1).unwrap();
+        let mut imported = MockStorage::new();
+        for (key, value) in &records {
+            imported.write(key, value);
+        }
+
+        // The imported tree should have the same root hash and proofs.
+        assert_eq!(TREE.root_hash(&imported, 1).unwrap(), root_hash);
+        for key in ["m", "L", "a", "r"] {
+            let key_hash = key.as_bytes().hash256();
+            assert_eq!(
+                TREE.prove(&imported, key_hash, 1).unwrap(),
+                TREE.prove(&storage, key_hash, 1).unwrap()
+            );
+        }
+
+        // Nodes that aren't part of the tree at v1 shouldn't be exported.
+        // The tree now has: root, 0, 01, 011, 0110, 0111, 1.
+        assert_eq!(records.len(), 7);
+
+        // The imported tree passes verification...
+        assert_eq!(
+            TREE.verify(&imported, 1, root_hash.unwrap()).unwrap(),
+            Some(TreeSize {
+                nodes: 7,
+                leaves: 3,
+            })
+        );
+
+        // ...but not against another root hash...
+        assert!(TREE.verify(&imported, 1, HASH_ROOT).unwrap().is_none());
+
+        // ...nor if any node is tampered with.
+        let (key, _) = records.last().unwrap();
+        let fake_leaf = Node::Leaf(LeafNode {
+            key_hash: Hash256::ZERO,
+            value_hash: Hash256::ZERO,
+        });
+        imported.write(key, &fake_leaf.to_borsh_vec().unwrap());
+        assert!(TREE
+            .verify(&imported, 1, root_hash.unwrap())
+            .unwrap()
+            .is_none());
+
+        // Exporting an empty tree yields nothing.
+        assert!(TREE.export(&MockStorage::new(), 0).unwrap().is_empty());
+    }
+
     #[test]
     fn pruning() {
         let (mut storage, _) = build_test_case().unwrap();
@@ -30,8 +30,14 @@ impl TestAccount {
     /// The address is predicted with the given code hash and salt, assuming the
     /// account is to be instantiated during genesis.
     pub fn new_random(code_hash: Hash256, salt: &[u8]) -> Self {
+        Self::new_with_private_key(code_hash, salt, SigningKey::random(&mut OsRng))
+    }
+
+    /// Create a new test account with the given Secp256k1 private key.
+    ///
+    /// The address is predicted the same way as in [`TestAccount::new_random`].
+    pub fn new_with_private_key(code_hash: Hash256, salt: &[u8], sk: SigningKey) -> Self {
         let address = Addr::derive(GENESIS_SENDER, code_hash, salt);
-        let sk = SigningKey::random(&mut OsRng);
         let pk = sk
             .verifying_key()
             .to_encoded_point(true)
@@ -1,14 +1,16 @@
 use {
     crate::{tracing::setup_tracing_subscriber, TestAccount, TestAccounts, TestSuite, TestVm},
-    grug_app::{AppError, NaiveProposalPreparer, ProposalPreparer},
+    grug_app::{AppError, Db, NaiveProposalPreparer, ProposalPreparer},
     grug_db_memory::MemDb,
     grug_math::Udec128,
     grug_types::{
         Addr, Binary, BlockInfo, Coins, Config, Defined, Denom, Duration, GenesisState, HashExt,
-        Json, JsonSerExt, MaybeDefined, Message, Permission, Permissions, Timestamp, Undefined,
-        GENESIS_BLOCK_HASH, GENESIS_BLOCK_HEIGHT, GENESIS_SENDER,
+        Json, JsonSerExt, MaybeDefined, Message, Permission, Permissions, StateDepositParams,
+        Timestamp, Undefined, GENESIS_BLOCK_HASH, GENESIS_BLOCK_HEIGHT, GENESIS_SENDER,
     },
     grug_vm_rust::RustVm,
+    k256::ecdsa::SigningKey,
+    rand::rngs::OsRng,
     serde::Serialize,
     std::{
         collections::BTreeMap,
@@ -55,6 +57,7 @@ pub struct TestBuilder<
     genesis_time: Option<Timestamp>,
     block_time: Option<Duration>,
     default_gas_limit: Option<u64>,
+    parallelism: Option<usize>,
     // App configs
     app_configs: BTreeMap<String, Json>,
     // Owner
@@ -70,6 +73,8 @@ pub struct TestBuilder<
     fee_denom: Option<Denom>,
     fee_rate: Option<Udec128>,
     max_orphan_age: Option<Duration>,
+    max_block_gas: Option<u64>,
+    state_deposit: Option<StateDepositParams>,
 }
 
 // Clippy incorrectly thinks we can derive `Default` here, which we can't.
@@ -129,6 +134,7 @@ where
             genesis_time: None,
             block_time: None,
             default_gas_limit: None,
+            parallelism: None,
             app_configs: BTreeMap::new(),
             owner: Undefined::new(),
             accounts: Undefined::new(),
@@ -136,6 +142,8 @@ where
             fee_denom: None,
             fee_rate: None,
             max_orphan_age: None,
+            max_block_gas: None,
+            state_deposit: None,
         }
     }
 }
@@ -179,6 +187,13 @@ where
         self
     }
 
+    /// Execute transactions in `FinalizeBlock` in parallel using the given
+    /// number of threads.
+    pub fn set_parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = Some(parallelism);
+        self
+    }
+
     pub fn set_fee_denom<D>(mut self, fee_denom: D) -> Self
     where
         D: TryInto<Denom>,
@@ -193,6 +208,16 @@ where
         self
     }
 
+    pub fn set_max_block_gas(mut self, max_block_gas: u64) -> Self {
+        self.max_block_gas = Some(max_block_gas);
+        self
+    }
+
+    pub fn set_state_deposit(mut self, state_deposit: StateDepositParams) -> Self {
+        self.state_deposit = Some(state_deposit);
+        self
+    }
+
     pub fn set_fee_rate(mut self, fee_rate: Udec128) -> Self {
         self.fee_rate = Some(fee_rate);
         self
@@ -262,6 +287,7 @@ where
             genesis_time: self.genesis_time,
             block_time: self.block_time,
             default_gas_limit: self.default_gas_limit,
+            parallelism: self.parallelism,
             app_configs: self.app_configs,
             owner: self.owner,
             account_opt: self.account_opt,
@@ -275,6 +301,8 @@ where
             fee_denom: self.fee_denom,
             fee_rate: self.fee_rate,
             max_orphan_age: self.max_orphan_age,
+            max_block_gas: self.max_block_gas,
+            state_deposit: self.state_deposit,
         }
     }
 
@@ -326,6 +354,7 @@ where
             genesis_time: self.genesis_time,
             block_time: self.block_time,
             default_gas_limit: self.default_gas_limit,
+            parallelism: self.parallelism,
             app_configs: self.app_configs,
             owner: self.owner,
             account_opt: self.account_opt,
@@ -339,12 +368,30 @@ where
             fee_denom: self.fee_denom,
             fee_rate: self.fee_rate,
             max_orphan_age: self.max_orphan_age,
+            max_block_gas: self.max_block_gas,
+            state_deposit: self.state_deposit,
         }
     }
 
     pub fn add_account<C>(
+        self,
+        name: &'static str,
+        balances: C,
+    ) -> TestBuilder<VM, PP, M1, M2, M3, OW, Defined<TestAccounts>>
+    where
+        C: TryInto<Coins>,
+        C::Error: Debug,
+    {
+        self.add_account_with_private_key(name, SigningKey::random(&mut OsRng), balances)
+    }
+
+    /// Add an account with the given private key, instead of a random one.
+    ///
+    /// Useful for tests that need multiple suites to end up in the same state.
+    pub fn add_account_with_private_key<C>(
         mut self,
         name: &'static str,
+        sk: SigningKey,
         balances: C,
     ) -> TestBuilder<VM, PP, M1, M2, M3, OW, Defined<TestAccounts>>
     where
@@ -357,8 +404,8 @@ where
             "account with name {name} already exists"
         );
 
-        // Generate a random new account
-        let account = TestAccount::new_random(self.account_opt.code.hash256(), name.as_bytes());
+        let account =
+            TestAccount::new_with_private_key(self.account_opt.code.hash256(), name.as_bytes(), sk);
 
         // Save account and balances
         let balances = balances.try_into().unwrap();
@@ -375,6 +422,7 @@ where
             genesis_time: self.genesis_time,
             block_time: self.block_time,
             default_gas_limit: self.default_gas_limit,
+            parallelism: self.parallelism,
             app_configs: self.app_configs,
             owner: self.owner,
             account_opt: self.account_opt,
@@ -385,6 +433,8 @@ where
             fee_denom: self.fee_denom,
             fee_rate: self.fee_rate,
             max_orphan_age: self.max_orphan_age,
+            max_block_gas: self.max_block_gas,
+            state_deposit: self.state_deposit,
         }
     }
 }
@@ -446,6 +496,7 @@ where
             genesis_time: self.genesis_time,
             block_time: self.block_time,
             default_gas_limit: self.default_gas_limit,
+            parallelism: self.parallelism,
             app_configs: self.app_configs,
             owner: self.owner,
             account_opt: CodeOption {
@@ -459,6 +510,8 @@ where
             fee_denom: self.fee_denom,
             fee_rate: self.fee_rate,
             max_orphan_age: self.max_orphan_age,
+            max_block_gas: self.max_block_gas,
+            state_deposit: self.state_deposit,
         }
     }
 }
@@ -482,6 +535,7 @@ impl<VM, PP, M1, M2, M3> TestBuilder<VM, PP, M1, M2, M3, Undefined<Addr>, Define
             genesis_time: self.genesis_time,
             block_time: self.block_time,
             default_gas_limit: self.default_gas_limit,
+            parallelism: self.parallelism,
             app_configs: self.app_configs,
             owner: Defined::new(owner.address),
             account_opt: self.account_opt,
@@ -492,6 +546,8 @@ impl<VM, PP, M1, M2, M3> TestBuilder<VM, PP, M1, M2, M3, Undefined<Addr>, Define
             fee_denom: self.fee_denom,
             fee_rate: self.fee_rate,
             max_orphan_age: self.max_orphan_age,
+            max_block_gas: self.max_block_gas,
+            state_deposit: self.state_deposit,
         }
     }
 }
@@ -507,6 +563,15 @@ where
     AppError: From<VM::Error> + From<PP::Error>,
 {
     pub fn build(self) -> (TestSuite<MemDb, VM, PP>, TestAccounts) {
+        self.build_with_db(MemDb::new())
+    }
+
+    /// Build the test suite on top of the given DB, instead of a new `MemDb`.
+    pub fn build_with_db<DB>(self, db: DB) -> (TestSuite<DB, VM, PP>, TestAccounts)
+    where
+        DB: Db,
+        AppError: From<DB::Error>,
+    {
         if let Some(tracing_level) = self.tracing_level {
             setup_tracing_subscriber(tracing_level);
         }
@@ -614,10 +679,12 @@ where
             config,
             msgs,
             app_configs: self.app_configs,
+            max_block_gas: self.max_block_gas,
+            state_deposit: self.state_deposit,
         };
 
-        let suite = TestSuite::new_with_db_vm_and_pp(
-            MemDb::new(),
+        let mut suite = TestSuite::new_with_db_vm_and_pp(
+            db,
             self.vm,
             self.pp,
             chain_id,
@@ -627,6 +694,10 @@ where
             genesis_state,
         );
 
+        if let Some(parallelism) = self.parallelism {
+            suite.app = suite.app.with_parallelism(parallelism);
+        }
+
         (suite, self.accounts.into_inner())
     }
 }
@@ -1,13 +1,16 @@
 use {
-    grug_app::{App, AppError, AppResult, Db, NaiveProposalPreparer, ProposalPreparer, Vm},
+    grug_app::{
+        App, AppError, AppResult, Db, Indexer, NaiveProposalPreparer, NullIndexer,
+        ProposalPreparer, Vm, CHAIN_ID, LAST_FINALIZED_BLOCK,
+    },
     grug_crypto::sha2_256,
     grug_db_memory::MemDb,
     grug_math::Uint128,
     grug_types::{
         Addr, Addressable, Binary, BlockInfo, BlockOutcome, Code, Coins, Config, ConfigUpdates,
-        ContractInfo, Denom, Duration, GenesisState, Hash256, Json, JsonDeExt, JsonSerExt, Message,
-        Op, Outcome, Query, QueryRequest, ResultExt, Signer, StdError, Tx, TxError, TxOutcome,
-        TxSuccess, UnsignedTx,
+        ContractInfo, Denom, Duration, GasCosts, GenesisState, Hash256, HashExt, Json, JsonDeExt,
+        JsonSerExt, Message, Op, Outcome, Query, QueryRequest, ResultExt, Signer, StateDeposit,
+        StateDepositParams, StdError, Tx, TxError, TxOutcome, TxSuccess, UnsignedTx,
     },
     grug_vm_rust::RustVm,
     serde::{de::DeserializeOwned, ser::Serialize},
@@ -106,13 +109,14 @@ impl ResultExt for UploadAndInstantiateOutcome {
 
 // --------------------------------- TestSuite ---------------------------------
 
-pub struct TestSuite<DB = MemDb, VM = RustVm, PP = NaiveProposalPreparer>
+pub struct TestSuite<DB = MemDb, VM = RustVm, PP = NaiveProposalPreparer, ID = NullIndexer>
 where
     DB: Db,
     VM: Vm,
     PP: ProposalPreparer,
+    ID: Indexer,
 {
-    pub app: App<DB, VM, PP>,
+    pub app: App<DB, VM, PP, ID>,
     /// The chain ID can be queries from the `app`, but we internally track it in
     /// the test suite, so we don't need to query it every time we need it.
     pub chain_id: String,
@@ -239,6 +243,66 @@ where
         }
     }
 
+    /// Create a new test suite with the given DB, VM, and proposal preparer,
+    /// where the DB already contains a chain's state, e.g. a `ForkDb` forked
+    /// from mainnet.
+    ///
+    /// Genesis isn't run. Instead, the chain ID and the last finalized block
+    /// are loaded from the DB.
+    pub fn new_from_state(
+        db: DB,
+        vm: VM,
+        pp: PP,
+        block_time: Duration,
+        default_gas_limit: u64,
+    ) -> Self {
+        let storage = db.state_storage(None).unwrap_or_else(|err| {
+            panic!("fatal error while loading state: {}", err.to_string());
+        });
+
+        let chain_id = CHAIN_ID.load(&storage).unwrap_or_else(|err| {
+            panic!("fatal error while loading chain ID: {err}");
+        });
+
+        let block = LAST_FINALIZED_BLOCK.load(&storage).unwrap_or_else(|err| {
+            panic!("fatal error while loading last finalized block: {err}");
+        });
+
+        // Use `u64::MAX` as query gas limit so that there's practically no limit.
+        let app = App::new(db, vm, pp, u64::MAX);
+
+        Self {
+            app,
+            chain_id,
+            block,
+            block_time,
+            default_gas_limit,
+        }
+    }
+
+    /// Hand the blocks made from now on to the given indexer.
+    pub fn with_indexer<ID>(self, indexer: ID) -> TestSuite<DB, VM, PP, ID>
+    where
+        ID: Indexer,
+    {
+        TestSuite {
+            app: self.app.with_indexer(indexer),
+            chain_id: self.chain_id,
+            block: self.block,
+            block_time: self.block_time,
+            default_gas_limit: self.default_gas_limit,
+        }
+    }
+}
+
+impl<DB, VM, PP, ID> TestSuite<DB, VM, PP, ID>
+where
+    DB: Db,
+    VM: Vm + Clone,
+    PP: ProposalPreparer,
+    ID: Indexer,
+    AppError: From<DB::Error> + From<VM::Error> + From<PP::Error> + From<ID::Error>,
+{
     /// Simulate the gas cost and event outputs of an unsigned transaction.
     pub fn simulate_tx(&self, unsigned_tx: UnsignedTx) -> TxOutcome {
         self.app
@@ -248,6 +312,16 @@ where
             })
     }
 
+    /// Simulate an unsigned transaction, same as `simulate_tx`, but also record
+    /// its call tree in the outcome's `trace` field.
+    pub fn simulate_tx_with_trace(&self, unsigned_tx: UnsignedTx) -> TxOutcome {
+        self.app
+            .do_simulate_with_trace(unsigned_tx, 0, false)
+            .unwrap_or_else(|err| {
+                panic!("fatal error while simulating tx: {err}");
+            })
+    }
+
     /// Perform ABCI `CheckTx` call of a transaction.
     pub fn check_tx(&self, tx: Tx) -> Outcome {
         self.app
@@ -278,7 +352,7 @@ where
                 panic!("fatal error while preparing proposal: {err}");
             })
             .into_iter()
-            .map(|raw_tx| raw_tx.deserialize_json().unwrap())
+            .map(|raw_tx| (raw_tx.deserialize_json().unwrap(), raw_tx.hash256()))
             .collect();
 
         // Call ABCI `FinalizeBlock` method
@@ -624,12 +698,45 @@ where
         )
     }
 
+    /// Transfer a contract's admin privilege to another account.
+    pub fn update_admin(
+        &mut self,
+        signer: &mut dyn Signer,
+        contract: Addr,
+        new_admin: Addr,
+    ) -> TxOutcome {
+        self.send_message(signer, Message::update_admin(contract, new_admin))
+    }
+
+    /// Remove a contract's admin.
+    pub fn clear_admin(&mut self, signer: &mut dyn Signer, contract: Addr) -> TxOutcome {
+        self.send_message(signer, Message::clear_admin(contract))
+    }
+
     pub fn query_config(&self) -> AppResult<Config> {
         self.app
             .do_query_app(Query::config(), 0, false)
             .map(|val| val.as_config())
     }
 
+    pub fn query_gas_costs(&self) -> AppResult<GasCosts> {
+        self.app
+            .do_query_app(Query::gas_costs(), 0, false)
+            .map(|res| res.as_gas_costs())
+    }
+
+    pub fn query_max_block_gas(&self) -> AppResult<u64> {
+        self.app
+            .do_query_app(Query::max_block_gas(), 0, false)
+            .map(|res| res.as_max_block_gas())
+    }
+
+    pub fn query_state_deposit_params(&self) -> AppResult<Option<StateDepositParams>> {
+        self.app
+            .do_query_app(Query::state_deposit_params(), 0, false)
+            .map(|res| res.as_state_deposit_params())
+    }
+
     pub fn query_app_config(&self, key: &str) -> AppResult<Json> {
         self.app
             .do_query_app(Query::app_config(key), 0, false)
@@ -706,6 +813,12 @@ where
             .map(|res| res.as_contracts())
     }
 
+    pub fn query_state_deposit(&self, contract: &dyn Addressable) -> AppResult<StateDeposit> {
+        self.app
+            .do_query_app(Query::state_deposit(contract.address()), 0, false)
+            .map(|res| res.as_state_deposit())
+    }
+
     pub fn query_wasm_raw<B>(&self, contract: Addr, key: B) -> AppResult<Option<Binary>>
     where
         B: Into<Binary>,
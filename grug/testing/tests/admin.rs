@@ -0,0 +1,92 @@
+use {
+    grug_testing::TestBuilder,
+    grug_types::{Coins, Empty, Event, EvtClearAdmin, EvtUpdateAdmin, ResultExt},
+    grug_vm_rust::ContractBuilder,
+};
+
+mod tester {
+    use grug_types::{Empty, MutableCtx, Response, StdResult};
+
+    pub fn instantiate(_ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+}
+
+#[test]
+fn updating_and_clearing_admin() {
+    let (mut suite, mut accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new())
+        .add_account("larry", Coins::new())
+        .add_account("jake", Coins::new())
+        .set_owner("owner")
+        .build();
+
+    let tester_code = ContractBuilder::new(Box::new(tester::instantiate)).build();
+
+    let owner = accounts["owner"].address;
+    let larry = accounts["larry"].address;
+    let jake = accounts["jake"].address;
+
+    let tester = suite
+        .upload_and_instantiate(
+            &mut accounts["owner"],
+            tester_code,
+            &Empty {},
+            "tester",
+            Some("tester"),
+            Some(owner),
+            Coins::new(),
+        )
+        .should_succeed()
+        .address;
+
+    // Only the admin can update the admin.
+    suite
+        .update_admin(&mut accounts["larry"], tester, larry)
+        .should_fail_with_error(format!(
+            "sender is not the admin! sender: {larry}, admin: {owner}"
+        ));
+
+    // Hand over the contract to Larry.
+    let events = suite
+        .update_admin(&mut accounts["owner"], tester, larry)
+        .should_succeed()
+        .events;
+
+    assert!(events.contains(&Event::UpdateAdmin(EvtUpdateAdmin {
+        sender: owner,
+        contract: tester,
+        new_admin: larry,
+    })));
+
+    suite
+        .query_contract(&tester)
+        .should_succeed_and(|info| info.admin == Some(larry));
+
+    // The previous admin no longer has the privilege.
+    suite
+        .clear_admin(&mut accounts["owner"], tester)
+        .should_fail_with_error(format!(
+            "sender is not the admin! sender: {owner}, admin: {larry}"
+        ));
+
+    // Larry clears the admin.
+    let events = suite
+        .clear_admin(&mut accounts["larry"], tester)
+        .should_succeed()
+        .events;
+
+    assert!(events.contains(&Event::ClearAdmin(EvtClearAdmin {
+        sender: larry,
+        contract: tester,
+    })));
+
+    suite
+        .query_contract(&tester)
+        .should_succeed_and(|info| info.admin.is_none());
+
+    // Now that the admin is cleared, nobody can update it.
+    suite
+        .update_admin(&mut accounts["larry"], tester, jake)
+        .should_fail_with_error("admin account is not set");
+}
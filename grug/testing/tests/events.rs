@@ -0,0 +1,241 @@
+use {
+    grug_testing::TestBuilder,
+    grug_types::{Coins, Empty, Event, EventCategory, ResultExt},
+    grug_vm_rust::ContractBuilder,
+    pinger::{ExecuteMsg, Ping, Pong},
+};
+
+mod impersonator {
+    use {
+        grug_types::{Addr, Empty, EventName, MutableCtx, Response, StdResult},
+        serde::{Deserialize, Serialize},
+    };
+
+    /// An event that claims to be emitted by another contract.
+    #[derive(Serialize, Deserialize)]
+    pub struct Transfer {
+        pub contract: Addr,
+    }
+
+    impl EventName for Transfer {
+        const EVENT_NAME: &'static str = "transfer";
+    }
+
+    pub fn instantiate(_ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    pub fn execute(ctx: MutableCtx, contract: Addr) -> StdResult<Response> {
+        Response::new().add_event(&Transfer { contract })
+    }
+}
+
+mod pinger {
+    use {
+        grug_types::{
+            Empty, Event, EventName, Message, MutableCtx, Response, StdError, StdResult,
+            SubMessage, SubMsgResult, SudoCtx,
+        },
+        serde::{Deserialize, Serialize},
+    };
+
+    #[derive(Serialize, Deserialize)]
+    pub enum ExecuteMsg {
+        /// Emit a `Ping` event of the given value, then call self to do the
+        /// same with the value minus one, until it reaches zero.
+        Ping { value: u32 },
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+    pub struct Ping {
+        pub value: u32,
+    }
+
+    impl EventName for Ping {
+        const EVENT_NAME: &'static str = "ping";
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+    pub struct Pong {
+        pub value: u32,
+    }
+
+    impl EventName for Pong {
+        const EVENT_NAME: &'static str = "pong";
+    }
+
+    pub fn instantiate(_ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> StdResult<Response> {
+        let ExecuteMsg::Ping { value } = msg;
+
+        let submsg = if value > 0 {
+            let msg = Message::execute(
+                ctx.contract,
+                &ExecuteMsg::Ping { value: value - 1 },
+                ctx.funds,
+            )?;
+
+            Some(SubMessage::reply_on_success(msg, &Empty {})?)
+        } else {
+            None
+        };
+
+        Ok(Response::new()
+            .add_event(&Ping { value })?
+            .may_add_submessage(submsg))
+    }
+
+    /// Respond to the `Ping` event emitted by the submessage with a `Pong`
+    /// event of the same value.
+    pub fn reply(_ctx: SudoCtx, _msg: Empty, res: SubMsgResult) -> StdResult<Response> {
+        let Ok(Event::Execute(event)) = res else {
+            return Err(StdError::host(
+                "expecting a successful execute event".to_string(),
+            ));
+        };
+
+        let ping = event.guest.contract_events[0]
+            .decode::<Ping>()?
+            .ok_or_else(|| StdError::host("expecting a ping event".to_string()))?;
+
+        Response::new().add_event(&Pong { value: ping.value })
+    }
+}
+
+#[test]
+fn emitting_typed_events() {
+    let (mut suite, mut accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new())
+        .set_owner("owner")
+        .build();
+
+    let pinger_code = ContractBuilder::new(Box::new(pinger::instantiate))
+        .with_execute(Box::new(pinger::execute))
+        .with_reply(Box::new(pinger::reply))
+        .build();
+
+    let pinger = suite
+        .upload_and_instantiate(
+            &mut accounts["owner"],
+            pinger_code,
+            &Empty {},
+            "pinger",
+            Some("pinger"),
+            None,
+            Coins::new(),
+        )
+        .should_succeed()
+        .address;
+
+    let success = suite
+        .execute(
+            &mut accounts["owner"],
+            pinger,
+            &ExecuteMsg::Ping { value: 1 },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    // The events are `withhold_fee`, `authenticate`, the message, and
+    // `finalize_fee`.
+    assert_eq!(success.events.len(), 4);
+
+    let Event::Execute(execute) = &success.events[2] else {
+        panic!("expecting an execute event, got: {:?}", success.events[2]);
+    };
+
+    assert_eq!(execute.sender, accounts["owner"].address);
+    assert_eq!(execute.contract, pinger);
+    assert!(execute.transfer.is_none());
+    assert_eq!(execute.guest.method, "execute");
+    assert_eq!(
+        execute.guest.contract_events[0].decode::<Ping>().unwrap(),
+        Some(Ping { value: 1 })
+    );
+    assert_eq!(
+        execute.guest.contract_events[0].decode::<Pong>().unwrap(),
+        None
+    );
+
+    // The events of the submessage, and of the reply to it, are nested under
+    // the call that emitted the submessage.
+    let [Event::Execute(sub_execute), Event::Reply(reply)] = execute.guest.sub_events.as_slice()
+    else {
+        panic!(
+            "expecting an execute and a reply event, got: {:?}",
+            execute.guest.sub_events
+        );
+    };
+
+    assert_eq!(sub_execute.sender, pinger);
+    assert_eq!(
+        sub_execute.guest.contract_events[0]
+            .decode::<Ping>()
+            .unwrap(),
+        Some(Ping { value: 0 })
+    );
+    assert!(sub_execute.guest.sub_events.is_empty());
+
+    assert!(reply.ok);
+    assert_eq!(reply.guest.method, "reply");
+    assert_eq!(
+        reply.guest.contract_events[0].decode::<Pong>().unwrap(),
+        Some(Pong { value: 0 })
+    );
+
+    // Flattened, contract events follow the call that emitted them, and are
+    // kept apart from host events.
+    assert_eq!(
+        success.events[2]
+            .flatten()
+            .into_iter()
+            .map(|event| (event.category, event.ty))
+            .collect::<Vec<_>>(),
+        [
+            (EventCategory::Host, "execute"),
+            (EventCategory::Host, "guest"),
+            (EventCategory::Contract, "ping"),
+            (EventCategory::Host, "execute"),
+            (EventCategory::Host, "guest"),
+            (EventCategory::Contract, "ping"),
+            (EventCategory::Host, "reply"),
+            (EventCategory::Host, "guest"),
+            (EventCategory::Contract, "pong"),
+        ]
+        .map(|(category, ty)| (category, ty.to_string()))
+    );
+}
+
+#[test]
+fn emitting_events_with_reserved_keys() {
+    let (mut suite, mut accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new())
+        .set_owner("owner")
+        .build();
+
+    let impersonator_code = ContractBuilder::new(Box::new(impersonator::instantiate))
+        .with_execute(Box::new(impersonator::execute))
+        .build();
+
+    let impersonator = suite
+        .upload_and_instantiate(
+            &mut accounts["owner"],
+            impersonator_code,
+            &Empty {},
+            "impersonator",
+            Some("impersonator"),
+            None,
+            Coins::new(),
+        )
+        .should_succeed()
+        .address;
+
+    let bank = suite.query_config().unwrap().bank;
+
+    suite
+        .execute(&mut accounts["owner"], impersonator, &bank, Coins::new())
+        .should_fail_with_error("contract emitted an event with a reserved key");
+}
@@ -0,0 +1,68 @@
+use {
+    grug_app::{Db, NaiveProposalPreparer},
+    grug_db_fork::ForkDb,
+    grug_db_memory::MemDb,
+    grug_math::Uint128,
+    grug_testing::{TestBuilder, TestSuite},
+    grug_types::{Coins, ResultExt},
+    grug_vm_rust::RustVm,
+};
+
+#[test]
+fn forking_from_state_works() {
+    let remote = MemDb::new();
+
+    let (mut suite, mut accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new())
+        .add_account("alice", Coins::one("uatom", 100).unwrap())
+        .add_account("bob", Coins::new())
+        .set_owner("owner")
+        .build_with_db(remote.clone());
+
+    let bob = accounts["bob"].address;
+
+    suite
+        .transfer(
+            &mut accounts["alice"],
+            bob,
+            Coins::one("uatom", 30).unwrap(),
+        )
+        .should_succeed();
+
+    // Fork from the remote chain's latest state.
+    let fork_height = remote.latest_version().unwrap();
+    let db = ForkDb::new(remote, fork_height).unwrap();
+
+    let mut fork = TestSuite::new_from_state(
+        db,
+        RustVm::new(),
+        NaiveProposalPreparer,
+        suite.block_time,
+        suite.default_gas_limit,
+    );
+
+    // The chain ID and the last finalized block are loaded from the state.
+    assert_eq!(fork.chain_id, suite.chain_id);
+    assert_eq!(fork.block, suite.block);
+
+    fork.query_balance(&accounts["bob"], "uatom")
+        .should_succeed_and_equal(Uint128::new(30));
+
+    // Make blocks on top of the fork.
+    fork.transfer(
+        &mut accounts["alice"],
+        bob,
+        Coins::one("uatom", 20).unwrap(),
+    )
+    .should_succeed();
+
+    fork.query_balance(&accounts["alice"], "uatom")
+        .should_succeed_and_equal(Uint128::new(50));
+    fork.query_balance(&accounts["bob"], "uatom")
+        .should_succeed_and_equal(Uint128::new(50));
+
+    // The remote chain isn't affected.
+    suite
+        .query_balance(&accounts["bob"], "uatom")
+        .should_succeed_and_equal(Uint128::new(30));
+}
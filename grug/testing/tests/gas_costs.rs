@@ -0,0 +1,154 @@
+use {
+    grug_testing::TestBuilder,
+    grug_types::{
+        Coins, ConfigUpdates, GasCosts, Json, LinearGasCost, Message, ResultExt, UnsignedTx,
+    },
+    std::collections::BTreeMap,
+};
+
+#[test]
+fn updating_gas_costs() {
+    let (mut suite, mut accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new())
+        .add_account("larry", Coins::new())
+        .set_owner("owner")
+        .build();
+
+    // Gas costs should have been set to the default values during genesis.
+    suite
+        .query_gas_costs()
+        .should_succeed_and_equal(GasCosts::default());
+
+    // Upload a code under the default gas costs.
+    let gas_used_before = suite
+        .upload(&mut accounts["larry"], b"hello".to_vec())
+        .should_succeed()
+        .outcome
+        .gas_used;
+
+    // Make writing to storage more expensive.
+    let new_gas_costs = GasCosts {
+        db_write: LinearGasCost::new(11_176, 18),
+        ..GasCosts::default()
+    };
+
+    // Only the owner can update gas costs.
+    suite
+        .configure(
+            &mut accounts["larry"],
+            ConfigUpdates {
+                gas_costs: Some(new_gas_costs.clone()),
+                ..Default::default()
+            },
+            BTreeMap::new(),
+        )
+        .should_fail();
+
+    suite
+        .configure(
+            &mut accounts["owner"],
+            ConfigUpdates {
+                gas_costs: Some(new_gas_costs.clone()),
+                ..Default::default()
+            },
+            BTreeMap::new(),
+        )
+        .should_succeed();
+
+    suite
+        .query_gas_costs()
+        .should_succeed_and_equal(new_gas_costs);
+
+    // Upload another code of the same size. Gas used should increase by
+    // exactly the difference in the flat cost of `db_write`.
+    let gas_used_after = suite
+        .upload(&mut accounts["larry"], b"world".to_vec())
+        .should_succeed()
+        .outcome
+        .gas_used;
+
+    assert_eq!(gas_used_after, gas_used_before + 10_000);
+}
+
+#[test]
+fn validating_code_costs_gas() {
+    let (mut suite, mut accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new())
+        .add_account("larry", Coins::new())
+        .set_owner("owner")
+        .build();
+
+    let gas_used_before = suite
+        .upload(&mut accounts["larry"], b"hello".to_vec())
+        .should_succeed()
+        .outcome
+        .gas_used;
+
+    // Make validating code more expensive per byte.
+    suite
+        .configure(
+            &mut accounts["owner"],
+            ConfigUpdates {
+                gas_costs: Some(GasCosts {
+                    validate_code: LinearGasCost::new(0, 1_010),
+                    ..GasCosts::default()
+                }),
+                ..Default::default()
+            },
+            BTreeMap::new(),
+        )
+        .should_succeed();
+
+    // Upload another code of the same size. Gas used should increase by the
+    // difference in the per-byte cost, times the size of the code.
+    let gas_used_after = suite
+        .upload(&mut accounts["larry"], b"world".to_vec())
+        .should_succeed()
+        .outcome
+        .gas_used;
+
+    assert_eq!(gas_used_after, gas_used_before + 5 * 1_000);
+}
+
+#[test]
+fn profiling_gas_consumption() {
+    let (mut suite, mut accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new())
+        .add_account("larry", Coins::one("uatom", 100).unwrap())
+        .set_owner("owner")
+        .build();
+
+    let cfg = suite.query_config().unwrap();
+    let msg =
+        Message::transfer(accounts["owner"].address, Coins::one("uatom", 10).unwrap()).unwrap();
+
+    // Simulate a transfer. The outcome should come with a gas profile.
+    let outcome = suite.simulate_tx(UnsignedTx {
+        sender: accounts["larry"].address,
+        msgs: vec![msg.clone()],
+        data: Json::Null,
+    });
+    let gas_used = outcome.gas_used;
+    let profile = outcome.gas_profile.clone().unwrap();
+    outcome.should_succeed();
+
+    // All gas consumed is attributed to some category. The transfer reads and
+    // writes balances in the bank.
+    assert_eq!(profile.by_category.values().sum::<u64>(), gas_used);
+    assert!(profile.by_category["db_read"] > 0);
+    assert!(profile.by_category["db_write"] > 0);
+
+    // Gas is attributed to each contract involved: the sender's account, which
+    // authenticates the transaction; the bank, which makes the transfer; and
+    // the taxman, which handles the fee. Gas consumed by the app outside of
+    // contract calls isn't attributed to any contract.
+    for contract in [accounts["larry"].address, cfg.bank, cfg.taxman] {
+        assert!(profile.by_contract[&contract] > 0);
+    }
+    assert!(profile.by_contract.values().sum::<u64>() <= gas_used);
+
+    // Outside of simulation, there is no gas profile.
+    let outcome = suite.send_message(&mut accounts["larry"], msg);
+    assert!(outcome.gas_profile.is_none());
+    outcome.should_succeed();
+}
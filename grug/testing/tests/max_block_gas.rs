@@ -0,0 +1,115 @@
+use {
+    grug_math::Uint128,
+    grug_testing::{TestAccounts, TestBuilder, TestSuite},
+    grug_types::{Coins, ConfigUpdates, Message, ResultExt},
+    std::collections::BTreeMap,
+};
+
+const MAX_BLOCK_GAS: u64 = 1_000_000;
+
+fn setup_suite(parallelism: Option<usize>) -> (TestSuite, TestAccounts) {
+    let mut builder = TestBuilder::new()
+        .add_account("owner", Coins::new())
+        .add_account("alice", Coins::one("uatom", 100).unwrap())
+        .add_account("bob", Coins::one("uatom", 100).unwrap())
+        .add_account("carol", Coins::new())
+        .set_owner("owner")
+        .set_max_block_gas(MAX_BLOCK_GAS);
+
+    if let Some(parallelism) = parallelism {
+        builder = builder.set_parallelism(parallelism);
+    }
+
+    builder.build()
+}
+
+#[test]
+fn rejecting_tx_exceeding_max_block_gas() {
+    let (suite, mut accounts) = setup_suite(None);
+
+    let msg =
+        Message::transfer(accounts["carol"].address, Coins::one("uatom", 1).unwrap()).unwrap();
+    let tx = suite.sign_transaction_with_gas(
+        accounts.get_mut("alice").unwrap(),
+        MAX_BLOCK_GAS + 1,
+        vec![msg],
+    );
+
+    suite
+        .check_tx(tx)
+        .should_fail_with_error("transaction gas limit exceeds block gas limit");
+}
+
+#[test]
+fn skipping_txs_exceeding_remaining_block_gas() {
+    for parallelism in [None, Some(4)] {
+        let (mut suite, mut accounts) = setup_suite(parallelism);
+
+        let txs = [("alice", 600_000), ("alice", 600_000), ("bob", 400_000)]
+            .into_iter()
+            .map(|(sender, gas_limit)| {
+                let msg =
+                    Message::transfer(accounts["carol"].address, Coins::one("uatom", 1).unwrap())
+                        .unwrap();
+
+                suite.sign_transaction_with_gas(accounts.get_mut(sender).unwrap(), gas_limit, vec![
+                    msg,
+                ])
+            })
+            .collect();
+
+        let mut outcomes = suite.make_block(txs).tx_outcomes.into_iter();
+
+        outcomes.next().unwrap().should_succeed();
+
+        // The 2nd tx doesn't fit in the remaining gas, so it's skipped without
+        // being executed.
+        let outcome = outcomes.next().unwrap();
+        assert_eq!(outcome.gas_used, 0);
+        outcome.should_fail_with_error(
+            "transaction gas limit exceeds remaining block gas! gas limit: 600000, remaining: 400000",
+        );
+
+        // The 3rd tx fits in the remaining gas.
+        outcomes.next().unwrap().should_succeed();
+
+        suite
+            .query_balance(&accounts["carol"], "uatom")
+            .should_succeed_and_equal(Uint128::new(2));
+    }
+}
+
+#[test]
+fn updating_max_block_gas() {
+    let (mut suite, mut accounts) = setup_suite(None);
+
+    suite
+        .query_max_block_gas()
+        .should_succeed_and_equal(MAX_BLOCK_GAS);
+
+    suite
+        .configure(
+            &mut accounts["owner"],
+            ConfigUpdates {
+                max_block_gas: Some(MAX_BLOCK_GAS * 2),
+                ..Default::default()
+            },
+            BTreeMap::new(),
+        )
+        .should_succeed();
+
+    suite
+        .query_max_block_gas()
+        .should_succeed_and_equal(MAX_BLOCK_GAS * 2);
+
+    // A tx that was previously too big can now enter the mempool.
+    let msg =
+        Message::transfer(accounts["carol"].address, Coins::one("uatom", 1).unwrap()).unwrap();
+    let tx = suite.sign_transaction_with_gas(
+        accounts.get_mut("alice").unwrap(),
+        MAX_BLOCK_GAS + 1,
+        vec![msg],
+    );
+
+    suite.check_tx(tx).should_succeed();
+}
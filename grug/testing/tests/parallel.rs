@@ -0,0 +1,85 @@
+use {
+    grug_math::Uint128,
+    grug_testing::{TestAccounts, TestBuilder, TestSuite},
+    grug_types::{Coins, Message, ResultExt, Timestamp, Tx},
+    k256::ecdsa::SigningKey,
+};
+
+/// Use fixed private keys and genesis time, so that both suites start from the
+/// same state, and sign the same transactions.
+fn setup_suite(parallelism: Option<usize>) -> (TestSuite, TestAccounts) {
+    let sk = |byte| SigningKey::from_slice(&[byte; 32]).unwrap();
+
+    let mut builder = TestBuilder::new()
+        .add_account_with_private_key("owner", sk(1), Coins::new())
+        .add_account_with_private_key("alice", sk(2), Coins::one("uatom", 100).unwrap())
+        .add_account_with_private_key("bob", sk(3), Coins::one("uatom", 100).unwrap())
+        .add_account_with_private_key("carol", sk(4), Coins::new())
+        .add_account_with_private_key("dave", sk(5), Coins::one("uatom", 50).unwrap())
+        .set_owner("owner")
+        .set_genesis_time(Timestamp::from_seconds(1_700_000_000));
+
+    if let Some(parallelism) = parallelism {
+        builder = builder.set_parallelism(parallelism);
+    }
+
+    builder.build()
+}
+
+fn make_txs(suite: &TestSuite, accounts: &mut TestAccounts) -> Vec<Tx> {
+    [
+        // Independent of each other.
+        ("alice", "carol", 30),
+        ("bob", "dave", 10),
+        // Depends on the 1st tx: same sender.
+        ("alice", "carol", 30),
+        // Depends on the 1st and 3rd txs: Carol has no balance before the block,
+        // so this only succeeds if it sees the transfers she received.
+        ("carol", "bob", 40),
+        // Alice only has 40 uatom left at this point, so this should fail.
+        ("alice", "bob", 50),
+        // Depends on the 2nd tx.
+        ("dave", "carol", 5),
+    ]
+    .into_iter()
+    .map(|(from, to, amount)| {
+        let msg =
+            Message::transfer(accounts[to].address, Coins::one("uatom", amount).unwrap()).unwrap();
+
+        suite.sign_transaction(accounts.get_mut(from).unwrap(), vec![msg])
+    })
+    .collect()
+}
+
+#[test]
+fn parallel_execution_matches_sequential() {
+    let (mut sequential, mut sequential_accounts) = setup_suite(None);
+    let (mut parallel, mut parallel_accounts) = setup_suite(Some(4));
+
+    let txs = make_txs(&sequential, &mut sequential_accounts);
+    let sequential_outcome = sequential.make_block(txs);
+
+    let txs = make_txs(&parallel, &mut parallel_accounts);
+    let parallel_outcome = parallel.make_block(txs);
+
+    // The outcomes, including the events and the app hash, must be identical.
+    assert_eq!(sequential_outcome, parallel_outcome);
+
+    // Check the outcomes are those of executing the txs one by one.
+    for (idx, outcome) in parallel_outcome.tx_outcomes.into_iter().enumerate() {
+        if idx == 4 {
+            outcome.should_fail();
+        } else {
+            outcome.should_succeed();
+        }
+    }
+
+    for (name, balance) in [("alice", 40), ("bob", 130), ("carol", 25), ("dave", 55)] {
+        sequential
+            .query_balance(&sequential_accounts[name], "uatom")
+            .should_succeed_and_equal(Uint128::new(balance));
+        parallel
+            .query_balance(&parallel_accounts[name], "uatom")
+            .should_succeed_and_equal(Uint128::new(balance));
+    }
+}
@@ -1,9 +1,10 @@
 use {
-    grug_app::{AppError, NaiveProposalPreparer, ProposalPreparer},
+    grug_app::{AppError, NaiveProposalPreparer, PriorityProposalPreparer, ProposalPreparer},
+    grug_math::Udec128,
     grug_testing::TestBuilder,
     grug_types::{
-        btree_map, Addr, Coins, ConfigUpdates, Empty, Json, JsonDeExt, JsonSerExt, Message, Op,
-        QuerierWrapper, ResultExt, StdError, Tx,
+        btree_map, json, Addr, Coins, ConfigUpdates, Empty, Json, JsonDeExt, JsonSerExt, Message,
+        Op, QuerierWrapper, Query, ResultExt, StdError, Tx,
     },
     grug_vm_rust::ContractBuilder,
     prost::bytes::Bytes,
@@ -214,3 +215,67 @@ fn prepare_proposal_works() {
                 .eq(["bitcoin", "ethereum", "harrypotterobamasonic10in"])
         });
 }
+
+#[test]
+fn prioritizing_transactions_works() {
+    // The mock taxman doesn't have a fee market, so it can't be queried for the
+    // base fee in the default way. Use its fixed fee rate instead.
+    let pp = PriorityProposalPreparer::new(1_000, 2).with_base_fee_query(|querier, taxman| {
+        querier
+            .query(Query::wasm_smart(
+                taxman,
+                &grug_mock_taxman::QueryMsg::Config {},
+            )?)?
+            .as_wasm_smart()
+            .deserialize_json::<grug_mock_taxman::Config>()
+            .map(|cfg| cfg.fee_rate)
+    });
+
+    let (suite, _) = TestBuilder::new_with_pp(pp)
+        .add_account("owner", Coins::new())
+        .set_owner("owner")
+        .set_fee_rate(Udec128::new_percent(10))
+        .build();
+
+    let tx = |sender: u8, gas_limit: u64, gas_price: Option<&str>| -> Bytes {
+        Tx {
+            sender: Addr::mock(sender),
+            gas_limit,
+            msgs: vec![],
+            data: gas_price.map_or(Json::Null, |gas_price| json!({ "gas_price": gas_price })),
+            credential: Json::Null,
+        }
+        .to_json_vec()
+        .unwrap()
+        .into()
+    };
+
+    let txs = vec![
+        // Pays the fee rate.
+        tx(1, 400, None),
+        tx(2, 300, Some("0.5")),
+        // Pays the most, but from the same sender as the 1st tx, so it can't
+        // go before it. Doesn't fit in the block at that point.
+        tx(1, 300, Some("0.9")),
+        // Exceeds the limit of two transactions per sender.
+        tx(1, 100, Some("1")),
+        // Pays less than the fee rate.
+        tx(3, 300, Some("0.05")),
+        // Can't be decoded.
+        b"larry".to_vec().into(),
+        // Requests more than the block gas limit.
+        tx(4, 2_000, Some("1")),
+        tx(3, 300, Some("0.2")),
+    ];
+
+    let selected = suite
+        .app
+        .do_prepare_proposal(txs.clone(), usize::MAX)
+        .unwrap();
+
+    assert_eq!(selected, vec![
+        txs[1].clone(),
+        txs[7].clone(),
+        txs[0].clone()
+    ]);
+}
@@ -1,6 +1,9 @@
 use {
     grug_testing::{TestAccounts, TestBuilder, TestSuite},
-    grug_types::{Addr, Coins, Empty, ReplyOn, ResultExt},
+    grug_types::{
+        Addr, AuthCtx, AuthMode, AuthResponse, Coins, Empty, Json, JsonSerExt, Message, ReplyOn,
+        ResultExt, StdError, StdResult, Tx, UnsignedTx,
+    },
     grug_vm_rust::ContractBuilder,
     replier::{ExecuteMsg, QueryDataRequest, ReplyMsg},
     test_case::test_case,
@@ -445,3 +448,103 @@ fn reply<const S: usize>(msg: ExecuteMsg, mut data: [&str; S], should_tx_fail: b
         .query_wasm_smart(replier_addr, QueryDataRequest {})
         .should_succeed_and_equal(data);
 }
+
+/// An account that skips authentication when simulating, so that transactions
+/// can be simulated without a credential.
+fn authenticate_unless_simulating(ctx: AuthCtx, tx: Tx) -> StdResult<AuthResponse> {
+    if ctx.mode == AuthMode::Simulate {
+        return Ok(AuthResponse::new());
+    }
+
+    grug_mock_account::authenticate(ctx, tx).map_err(|err| StdError::host(err.to_string()))
+}
+
+#[test]
+fn tracing_call_tree() {
+    let account_code = ContractBuilder::new(Box::new(grug_mock_account::instantiate))
+        .with_receive(Box::new(grug_mock_account::receive))
+        .with_authenticate(Box::new(authenticate_unless_simulating))
+        .build();
+
+    let replier_code = ContractBuilder::new(Box::new(replier::instantiate))
+        .with_execute(Box::new(replier::execute))
+        .with_reply(Box::new(replier::reply))
+        .build();
+
+    let (mut suite, mut accounts) = TestBuilder::new()
+        .set_account_code(account_code, |public_key| {
+            grug_mock_account::InstantiateMsg { public_key }
+        })
+        .add_account("owner", Coins::new())
+        .set_owner("owner")
+        .build();
+
+    let replier_addr = suite
+        .upload_and_instantiate(
+            &mut accounts["owner"],
+            replier_code,
+            &Empty {},
+            "salt",
+            Some("label"),
+            None,
+            Coins::default(),
+        )
+        .should_succeed()
+        .address;
+
+    let msg = ExecuteMsg::perform(
+        "1",
+        ExecuteMsg::fail("execute deep 2 fail"),
+        ReplyOn::always(&ReplyMsg::Fail(ExecuteMsg::ok("1.1"))).unwrap(),
+    );
+
+    let unsigned_tx = UnsignedTx {
+        sender: accounts["owner"].address,
+        msgs: vec![Message::execute(replier_addr, &msg, Coins::new()).unwrap()],
+        data: Json::Null,
+    };
+
+    // Without tracing, no call tree is returned.
+    let outcome = suite.simulate_tx(unsigned_tx.clone());
+    assert!(outcome.trace.is_none());
+    outcome.should_succeed();
+
+    let outcome = suite.simulate_tx_with_trace(unsigned_tx);
+    let trace = outcome.trace.clone().unwrap();
+    outcome.should_succeed();
+
+    assert_eq!(
+        trace
+            .iter()
+            .map(|call| call.entry_point.as_str())
+            .collect::<Vec<_>>(),
+        ["withhold_fee", "authenticate", "execute", "finalize_fee"]
+    );
+
+    // The contract executes itself, which fails, and is then given a reply.
+    let execute = &trace[2];
+    assert_eq!(execute.contract, replier_addr);
+    assert_eq!(execute.sender, Some(accounts["owner"].address));
+    assert_eq!(execute.msg, msg.to_json_value().unwrap());
+    assert!(execute.events.is_empty());
+    assert!(execute.error.is_none());
+    assert_eq!(execute.calls.len(), 2);
+
+    let submsg = &execute.calls[0];
+    assert_eq!(submsg.entry_point, "execute");
+    assert_eq!(submsg.sender, Some(replier_addr));
+    assert!(submsg.events.is_empty());
+    assert!(submsg
+        .error
+        .as_ref()
+        .is_some_and(|err| err.contains("execute deep 2 fail")));
+
+    let reply = &execute.calls[1];
+    assert_eq!(reply.entry_point, "reply");
+    assert_eq!(reply.contract, replier_addr);
+    assert!(reply.error.is_none());
+    assert!(reply.calls.is_empty());
+
+    // The gas used by a call includes that of its children.
+    assert!(execute.gas_used.total() >= submsg.gas_used.total() + reply.gas_used.total());
+}
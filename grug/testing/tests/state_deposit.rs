@@ -0,0 +1,254 @@
+use {
+    grug_math::Uint128,
+    grug_testing::TestBuilder,
+    grug_types::{
+        Coin, Coins, ConfigUpdates, Denom, Empty, Event, ResultExt, StateDeposit,
+        StateDepositParams,
+    },
+    grug_vm_rust::ContractBuilder,
+    std::{collections::BTreeMap, str::FromStr},
+    storer::ExecuteMsg,
+};
+
+mod storer {
+    use {
+        grug_types::{Empty, MutableCtx, Response, StdResult},
+        serde::{Deserialize, Serialize},
+    };
+
+    #[derive(Serialize, Deserialize)]
+    pub enum ExecuteMsg {
+        Write { key: String, value: String },
+        Delete { key: String },
+    }
+
+    pub fn instantiate(_ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::Write { key, value } => {
+                ctx.storage.write(key.as_bytes(), value.as_bytes());
+            },
+            ExecuteMsg::Delete { key } => {
+                ctx.storage.remove(key.as_bytes());
+            },
+        }
+
+        Ok(Response::new())
+    }
+}
+
+const AMOUNT_PER_BYTE: u128 = 10;
+
+#[test]
+fn state_deposit_works() {
+    let denom = Denom::from_str("uusdc").unwrap();
+
+    let (mut suite, mut accounts) = TestBuilder::new()
+        .add_account("owner", Coins::one(denom.clone(), 1_000_000).unwrap())
+        .set_owner("owner")
+        .build();
+
+    // State deposit is disabled by default.
+    suite
+        .query_state_deposit_params()
+        .should_succeed_and_equal(None);
+
+    // Enable state deposit.
+    let params = StateDepositParams {
+        denom: denom.clone(),
+        amount_per_byte: Uint128::new(AMOUNT_PER_BYTE),
+    };
+
+    suite
+        .configure(
+            &mut accounts["owner"],
+            ConfigUpdates {
+                state_deposit: Some(Some(params.clone())),
+                ..Default::default()
+            },
+            BTreeMap::new(),
+        )
+        .should_succeed();
+
+    suite
+        .query_state_deposit_params()
+        .should_succeed_and_equal(Some(params));
+
+    let storer_code = ContractBuilder::new(Box::new(storer::instantiate))
+        .with_execute(Box::new(storer::execute))
+        .build();
+
+    let storer = suite
+        .upload_and_instantiate(
+            &mut accounts["owner"],
+            storer_code,
+            &Empty {},
+            "storer",
+            Some("storer"),
+            None,
+            Coins::one(denom.clone(), 100).unwrap(),
+        )
+        .should_succeed()
+        .address;
+
+    // Nothing is stored yet, so no deposit is held.
+    suite
+        .query_state_deposit(&storer)
+        .should_succeed_and_equal(StateDeposit {
+            bytes: 0,
+            deposit: None,
+        });
+
+    // Write 3 + 3 = 6 bytes. A deposit of 60 is charged.
+    let events = suite
+        .execute(
+            &mut accounts["owner"],
+            storer,
+            &ExecuteMsg::Write {
+                key: "foo".to_string(),
+                value: "bar".to_string(),
+            },
+            Coins::new(),
+        )
+        .should_succeed()
+        .events;
+
+    suite
+        .query_state_deposit(&storer)
+        .should_succeed_and_equal(StateDeposit {
+            bytes: 6,
+            deposit: Some(Coin::new(denom.clone(), 60).unwrap()),
+        });
+    suite
+        .query_balance(&storer, denom.clone())
+        .should_succeed_and_equal(Uint128::new(40));
+
+    // The charge is nested under the call that grew the storage.
+    let Event::Execute(execute) = &events[2] else {
+        panic!("expecting an execute event, got: {:?}", events[2]);
+    };
+    let [Event::Transfer(transfer)] = execute.guest.sub_events.as_slice() else {
+        panic!(
+            "expecting a transfer event, got: {:?}",
+            execute.guest.sub_events
+        );
+    };
+    assert_eq!(transfer.sender, storer);
+    assert_eq!(transfer.coins, Coins::one(denom.clone(), 60).unwrap());
+
+    // Overwrite the value with a longer one: 3 + 6 = 9 bytes. Another 30 is
+    // charged.
+    suite
+        .execute(
+            &mut accounts["owner"],
+            storer,
+            &ExecuteMsg::Write {
+                key: "foo".to_string(),
+                value: "barbar".to_string(),
+            },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    suite
+        .query_state_deposit(&storer)
+        .should_succeed_and_equal(StateDeposit {
+            bytes: 9,
+            deposit: Some(Coin::new(denom.clone(), 90).unwrap()),
+        });
+    suite
+        .query_balance(&storer, denom.clone())
+        .should_succeed_and_equal(Uint128::new(10));
+
+    // Attempt to write 3 + 3 = 6 more bytes. The contract can't afford the
+    // deposit of 60, so the call fails, and nothing is written.
+    suite
+        .execute(
+            &mut accounts["owner"],
+            storer,
+            &ExecuteMsg::Write {
+                key: "fuz".to_string(),
+                value: "baz".to_string(),
+            },
+            Coins::new(),
+        )
+        .should_fail();
+
+    suite
+        .query_state_deposit(&storer)
+        .should_succeed_and_equal(StateDeposit {
+            bytes: 9,
+            deposit: Some(Coin::new(denom.clone(), 90).unwrap()),
+        });
+
+    // Delete the data. The entire deposit is refunded.
+    suite
+        .execute(
+            &mut accounts["owner"],
+            storer,
+            &ExecuteMsg::Delete {
+                key: "foo".to_string(),
+            },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    suite
+        .query_state_deposit(&storer)
+        .should_succeed_and_equal(StateDeposit {
+            bytes: 0,
+            deposit: None,
+        });
+    suite
+        .query_balance(&storer, denom)
+        .should_succeed_and_equal(Uint128::new(100));
+}
+
+#[test]
+fn genesis_contracts_are_exempt_from_state_deposit() {
+    let denom = Denom::from_str("uusdc").unwrap();
+    let params = StateDepositParams {
+        denom: denom.clone(),
+        amount_per_byte: Uint128::new(AMOUNT_PER_BYTE),
+    };
+
+    let (mut suite, mut accounts) = TestBuilder::new()
+        .add_account("owner", Coins::one(denom.clone(), 1_000_000).unwrap())
+        .add_account("larry", Coins::new())
+        .set_owner("owner")
+        .set_state_deposit(params.clone())
+        .build();
+
+    suite
+        .query_state_deposit_params()
+        .should_succeed_and_equal(Some(params));
+
+    // The taxman has written its config during genesis, but holds no fund.
+    // The data written during genesis isn't counted, so no deposit is needed.
+    let taxman = suite.query_config().unwrap().taxman;
+
+    suite
+        .query_state_deposit(&taxman)
+        .should_succeed_and_equal(StateDeposit {
+            bytes: 0,
+            deposit: None,
+        });
+
+    // Transactions, which call the taxman, go through.
+    let larry = accounts["larry"].address;
+
+    suite
+        .transfer(
+            &mut accounts["owner"],
+            larry,
+            Coins::one(denom.clone(), 100).unwrap(),
+        )
+        .should_succeed();
+
+    suite
+        .query_balance(&larry, denom)
+        .should_succeed_and_equal(Uint128::new(100));
+}
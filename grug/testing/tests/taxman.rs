@@ -1,7 +1,7 @@
 use {
     grug_math::{NumberConst, Uint128},
     grug_testing::TestBuilder,
-    grug_types::{Coins, Empty, Message, ResultExt},
+    grug_types::{Coins, Empty, Message, ResultExt, StateDeposit, StateDepositParams},
     grug_vm_rust::ContractBuilder,
     test_case::test_case,
 };
@@ -93,6 +93,14 @@ mod taxman {
             .may_add_message(refund_msg))
     }
 
+    /// An alternative version of the `finalize_fee` function that also writes
+    /// to the taxman's storage. Used to test state deposit in `finalize_fee`.
+    pub fn recording_finalize_fee(ctx: AuthCtx, tx: Tx, outcome: TxOutcome) -> StdResult<Response> {
+        ctx.storage.write(b"last_sender", tx.sender.as_ref());
+
+        finalize_fee(ctx, tx, outcome)
+    }
+
     /// An alternative version of the `finalize_fee` function that errors on
     /// purpose. Used to test whether the `App` can correctly handle the case
     /// where `finalize_fee` errors.
@@ -260,3 +268,59 @@ fn finalizing_fee_erroring() {
         .query_balance(&accounts["sender"], taxman::FEE_DENOM.clone())
         .should_succeed_and_equal(Uint128::new(30_000));
 }
+
+// In this test, the taxman writes to its storage in `finalize_fee`, but doesn't
+// hold enough fund to pay the state deposit for it.
+//
+// Failing to settle the deposit must not fail `finalize_fee`, which would revert
+// the entire transaction, fee payment included. The settlement is skipped
+// instead.
+#[test]
+fn finalizing_fee_without_state_deposit() {
+    let recording_taxman_code = ContractBuilder::new(Box::new(taxman::instantiate))
+        .with_withhold_fee(Box::new(taxman::withhold_fee))
+        .with_finalize_fee(Box::new(taxman::recording_finalize_fee))
+        .build();
+
+    let (mut suite, mut accounts) = TestBuilder::new()
+        .set_taxman_code(recording_taxman_code, |_fee_denom, _fee_rate| Empty {})
+        .add_account("owner", Coins::new())
+        .add_account(
+            "sender",
+            Coins::one(taxman::FEE_DENOM.clone(), 30_000).unwrap(),
+        )
+        .set_owner("owner")
+        .set_state_deposit(StateDepositParams {
+            denom: taxman::FEE_DENOM.clone(),
+            amount_per_byte: Uint128::new(1_000),
+        })
+        .build();
+
+    let to = accounts["sender"].address;
+
+    suite
+        .send_message_with_gas(
+            &mut accounts["sender"],
+            100_000,
+            Message::transfer(to, Coins::new()).unwrap(),
+        )
+        .should_succeed();
+
+    // The fee is charged as usual.
+    suite
+        .query_balance(&accounts["owner"], taxman::FEE_DENOM.clone())
+        .should_succeed_and_equal(Uint128::new(6250));
+    suite
+        .query_balance(&accounts["sender"], taxman::FEE_DENOM.clone())
+        .should_succeed_and_equal(Uint128::new(23_750));
+
+    // The taxman's storage has grown, but no deposit is held.
+    let taxman = suite.query_config().unwrap().taxman;
+
+    suite
+        .query_state_deposit(&taxman)
+        .should_succeed_and_equal(StateDeposit {
+            bytes: 31, // = len("last_sender") + 20
+            deposit: None,
+        });
+}
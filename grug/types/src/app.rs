@@ -1,6 +1,10 @@
 use {
-    crate::{Addr, Duration, Event, GenericResult, Hash256, Json, Message, Timestamp},
+    crate::{
+        Addr, CallTrace, Coin, Denom, Duration, Event, GasCosts, GasProfile, GenericResult,
+        Hash256, Json, Message, Timestamp,
+    },
     borsh::{BorshDeserialize, BorshSerialize},
+    grug_math::Uint128,
     hex_literal::hex,
     serde::{Deserialize, Serialize},
     serde_with::skip_serializing_none,
@@ -48,6 +52,19 @@ pub struct GenesisState {
     pub app_configs: BTreeMap<String, Json>,
     /// Messages to be executed in order during genesis.
     pub msgs: Vec<Message>,
+    /// Maximum sum of the gas limits of all transactions in a block.
+    /// `None` means there is no limit.
+    ///
+    /// Not part of `Config`, so that the Borsh encoding of `Config`, which
+    /// deployed contracts decode, doesn't change.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_block_gas: Option<u64>,
+    /// If set, contracts must hold a refundable deposit proportional to the
+    /// number of bytes they store. `None` if state deposit is disabled.
+    ///
+    /// Not part of `Config`, for the same reason as `max_block_gas`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_deposit: Option<StateDepositParams>,
 }
 
 /// Chain-level configurations. Not to be confused with contract-level configs.
@@ -86,6 +103,25 @@ pub struct ConfigUpdates {
     pub taxman: Option<Addr>,
     pub cronjobs: Option<BTreeMap<Addr, Duration>>,
     pub permissions: Option<Permissions>,
+    pub gas_costs: Option<GasCosts>,
+    pub max_block_gas: Option<u64>,
+    /// `Some(None)` means to disable state deposit.
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    pub state_deposit: Option<Option<StateDepositParams>>,
+}
+
+/// Parameters of the state deposit.
+///
+/// After each call that returns a `Response`, the host compares the deposit
+/// required for the number of bytes the contract stores with the deposit it
+/// holds. The difference is transferred from the contract to the bank contract
+/// if the contract's storage has grown, or from the bank contract back to the
+/// contract if it has shrunk.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct StateDepositParams {
+    pub denom: Denom,
+    pub amount_per_byte: Uint128,
 }
 
 #[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
@@ -126,6 +162,20 @@ pub struct ContractInfo {
     pub admin: Option<Addr>,
 }
 
+/// The storage usage of a contract, and the state deposit it holds for it.
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct StateDeposit {
+    /// Total number of bytes of the keys and values in the contract's storage.
+    ///
+    /// Only data written since the contract was instantiated is counted. Data
+    /// written during genesis isn't counted either.
+    pub bytes: u64,
+    /// The deposit currently held. `None` if no deposit is held.
+    pub deposit: Option<Coin>,
+}
+
 /// Outcome of performing an operation that is not a full tx. These include:
 ///
 /// - processing a message;
@@ -165,6 +215,20 @@ pub struct TxOutcome {
     pub gas_used: u64,
     pub events: Vec<Event>,
     pub result: GenericResult<()>,
+    /// The call tree of the transaction. Only available when simulating with
+    /// tracing enabled.
+    ///
+    /// Not included in the Borsh encoding, so that contracts receiving the
+    /// outcome (e.g. the taxman in `finalize_fee`) aren't affected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[borsh(skip)]
+    pub trace: Option<Vec<CallTrace>>,
+    /// Breakdown of the gas consumption. Only available when simulating.
+    ///
+    /// Not included in the Borsh encoding, for the same reason as `trace`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[borsh(skip)]
+    pub gas_profile: Option<GasProfile>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -190,7 +254,7 @@ impl Display for TxError {
     }
 }
 
-#[derive(Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 /// Outcome of executing a block.
 pub struct BlockOutcome {
     /// The Merkle root hash after executing this block.
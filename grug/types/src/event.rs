@@ -1,62 +1,426 @@
 use {
+    crate::{Addr, Coins, Hash256, Json, JsonDeExt, JsonSerExt, StdResult},
     borsh::{BorshDeserialize, BorshSerialize},
-    serde::{Deserialize, Serialize},
+    serde::{de::DeserializeOwned, Deserialize, Serialize},
+    serde_json::json,
+    serde_with::skip_serializing_none,
+    std::fmt::{self, Display},
 };
 
-#[derive(
-    Serialize, Deserialize, BorshSerialize, BorshDeserialize, Default, Debug, Clone, PartialEq, Eq,
-)]
-pub struct Event {
-    pub r#type: String,
-    pub attributes: Vec<Attribute>,
+// ----------------------------- contract events ------------------------------
+
+/// A data type that contracts can emit as an event, in the `Response` of an
+/// entry point call.
+///
+/// The event is serialized as JSON, and identified by its name, so that
+/// indexers can decode it back to the same type without parsing strings.
+pub trait EventName {
+    const EVENT_NAME: &'static str;
 }
 
-impl Event {
-    pub fn new<T>(ty: T) -> Self
+/// An event emitted by a contract.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ContractEvent {
+    pub ty: String,
+    pub data: Json,
+}
+
+impl ContractEvent {
+    pub fn new<E>(event: &E) -> StdResult<Self>
     where
-        T: ToString,
+        E: EventName + Serialize,
     {
-        Self {
-            r#type: ty.to_string(),
-            attributes: vec![],
-        }
+        Ok(Self {
+            ty: E::EVENT_NAME.to_string(),
+            data: event.to_json_value()?,
+        })
     }
 
-    pub fn add_attribute<K, V>(mut self, key: K, value: V) -> Self
+    /// Decode the event as the given type. Return `None` if the event is of a
+    /// different type.
+    pub fn decode<E>(&self) -> StdResult<Option<E>>
     where
-        K: ToString,
-        V: ToString,
+        E: EventName + DeserializeOwned,
     {
-        self.attributes.push(Attribute::new(key, value));
-        self
+        if self.ty != E::EVENT_NAME {
+            return Ok(None);
+        }
+
+        self.data.clone().deserialize_json().map(Some)
     }
+}
 
-    pub fn add_attributes<A>(mut self, attrs: A) -> Self
-    where
-        A: IntoIterator<Item = Attribute>,
-    {
-        self.attributes.extend(attrs);
-        self
+// ------------------------------- host events --------------------------------
+
+/// An event emitted by the host while processing a message, a submessage, a
+/// cronjob, or one of the transaction processing steps.
+///
+/// Events that are triggered by others are nested within them. For example,
+/// the transfer made along with a contract execution, and the events emitted
+/// by the submessages of that execution, are both contained in the `Execute`
+/// event.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Event {
+    Configure(EvtConfigure),
+    Upload(EvtUpload),
+    Transfer(EvtTransfer),
+    Instantiate(EvtInstantiate),
+    Execute(EvtExecute),
+    Migrate(EvtMigrate),
+    UpdateAdmin(EvtUpdateAdmin),
+    ClearAdmin(EvtClearAdmin),
+    Reply(EvtReply),
+    Cron(EvtCron),
+    /// A call to a contract that isn't triggered by a message, e.g. the
+    /// `authenticate`, `backrun`, `withhold_fee`, and `finalize_fee` calls
+    /// made during the processing of a transaction.
+    Guest(EvtGuest),
+}
+
+impl Event {
+    /// Name of the event type, e.g. `execute`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Event::Configure(_) => "configure",
+            Event::Upload(_) => "upload",
+            Event::Transfer(_) => "transfer",
+            Event::Instantiate(_) => "instantiate",
+            Event::Execute(_) => "execute",
+            Event::Migrate(_) => "migrate",
+            Event::UpdateAdmin(_) => "update_admin",
+            Event::ClearAdmin(_) => "clear_admin",
+            Event::Reply(_) => "reply",
+            Event::Cron(_) => "cron",
+            Event::Guest(_) => "guest",
+        }
+    }
+
+    /// Flatten the event tree into a list of events, in depth-first order.
+    ///
+    /// This is used by consumers that can't handle nested events, such as
+    /// CometBFT and SQL indexers. Each host event and each contract call is
+    /// turned into a flat event, with the data it carries minus the events
+    /// nested within it. Contract events follow the call that emitted them.
+    pub fn flatten(&self) -> Vec<FlatEvent> {
+        let mut events = vec![];
+        self.flatten_into(&mut events);
+        events
+    }
+
+    fn flatten_into(&self, events: &mut Vec<FlatEvent>) {
+        match self {
+            Event::Configure(evt) => {
+                events.push(FlatEvent::host(
+                    "configure",
+                    None,
+                    json!({
+                        "sender": evt.sender,
+                    }),
+                ));
+            },
+            Event::Upload(evt) => {
+                events.push(FlatEvent::host(
+                    "upload",
+                    None,
+                    json!({
+                        "sender": evt.sender,
+                        "code_hash": evt.code_hash,
+                    }),
+                ));
+            },
+            Event::Transfer(evt) => {
+                evt.flatten_into(events);
+            },
+            Event::Instantiate(evt) => {
+                events.push(FlatEvent::host(
+                    "instantiate",
+                    Some(evt.contract),
+                    json!({
+                        "sender": evt.sender,
+                        "code_hash": evt.code_hash,
+                        "label": evt.label,
+                        "admin": evt.admin,
+                        "funds": evt.funds,
+                    }),
+                ));
+
+                if let Some(transfer) = &evt.transfer {
+                    transfer.flatten_into(events);
+                }
+
+                evt.guest.flatten_into(events);
+            },
+            Event::Execute(evt) => {
+                events.push(FlatEvent::host(
+                    "execute",
+                    Some(evt.contract),
+                    json!({
+                        "sender": evt.sender,
+                        "funds": evt.funds,
+                    }),
+                ));
+
+                if let Some(transfer) = &evt.transfer {
+                    transfer.flatten_into(events);
+                }
+
+                evt.guest.flatten_into(events);
+            },
+            Event::Migrate(evt) => {
+                events.push(FlatEvent::host(
+                    "migrate",
+                    Some(evt.contract),
+                    json!({
+                        "sender": evt.sender,
+                        "old_code_hash": evt.old_code_hash,
+                        "new_code_hash": evt.new_code_hash,
+                    }),
+                ));
+
+                evt.guest.flatten_into(events);
+            },
+            Event::UpdateAdmin(evt) => {
+                events.push(FlatEvent::host(
+                    "update_admin",
+                    Some(evt.contract),
+                    json!({
+                        "sender": evt.sender,
+                        "new_admin": evt.new_admin,
+                    }),
+                ));
+            },
+            Event::ClearAdmin(evt) => {
+                events.push(FlatEvent::host(
+                    "clear_admin",
+                    Some(evt.contract),
+                    json!({
+                        "sender": evt.sender,
+                    }),
+                ));
+            },
+            Event::Reply(evt) => {
+                events.push(FlatEvent::host(
+                    "reply",
+                    Some(evt.contract),
+                    json!({
+                        "ok": evt.ok,
+                    }),
+                ));
+
+                evt.guest.flatten_into(events);
+            },
+            Event::Cron(evt) => {
+                events.push(FlatEvent::host("cron", Some(evt.contract), json!({})));
+
+                evt.guest.flatten_into(events);
+            },
+            Event::Guest(evt) => {
+                evt.flatten_into(events);
+            },
+        }
     }
 }
 
-#[derive(
-    Serialize, Deserialize, BorshSerialize, BorshDeserialize, Default, Debug, Clone, PartialEq, Eq,
-)]
-pub struct Attribute {
-    pub key: String,
-    pub value: String,
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EvtConfigure {
+    pub sender: Addr,
 }
 
-impl Attribute {
-    pub fn new<K, V>(key: K, value: V) -> Self
-    where
-        K: ToString,
-        V: ToString,
-    {
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EvtUpload {
+    pub sender: Addr,
+    pub code_hash: Hash256,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EvtTransfer {
+    pub sender: Addr,
+    pub recipient: Addr,
+    pub coins: Coins,
+    /// The bank contract's `bank_execute` call.
+    pub bank_guest: EvtGuest,
+    /// The recipient's `receive` call. `None` if the transfer was made along
+    /// with an instantiation or execution, in which case the recipient isn't
+    /// informed via `receive`.
+    pub receive_guest: Option<EvtGuest>,
+}
+
+impl EvtTransfer {
+    fn flatten_into(&self, events: &mut Vec<FlatEvent>) {
+        events.push(FlatEvent::host(
+            "transfer",
+            None,
+            json!({
+                "sender": self.sender,
+                "recipient": self.recipient,
+                "coins": self.coins,
+            }),
+        ));
+
+        self.bank_guest.flatten_into(events);
+
+        if let Some(receive_guest) = &self.receive_guest {
+            receive_guest.flatten_into(events);
+        }
+    }
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EvtInstantiate {
+    pub sender: Addr,
+    pub code_hash: Hash256,
+    pub contract: Addr,
+    pub label: Option<String>,
+    pub admin: Option<Addr>,
+    pub funds: Coins,
+    /// The transfer of the funds. `None` if no fund is sent.
+    pub transfer: Option<EvtTransfer>,
+    /// The contract's `instantiate` call.
+    pub guest: EvtGuest,
+}
+
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EvtExecute {
+    pub sender: Addr,
+    pub contract: Addr,
+    pub funds: Coins,
+    /// The transfer of the funds. `None` if no fund is sent.
+    pub transfer: Option<EvtTransfer>,
+    /// The contract's `execute` call.
+    pub guest: EvtGuest,
+}
+
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EvtMigrate {
+    pub sender: Addr,
+    pub contract: Addr,
+    pub old_code_hash: Hash256,
+    pub new_code_hash: Hash256,
+    /// The contract's `migrate` call.
+    pub guest: EvtGuest,
+}
+
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EvtUpdateAdmin {
+    pub sender: Addr,
+    pub contract: Addr,
+    pub new_admin: Addr,
+}
+
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EvtClearAdmin {
+    pub sender: Addr,
+    pub contract: Addr,
+}
+
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EvtReply {
+    pub contract: Addr,
+    /// Whether the submessage being replied to succeeded.
+    pub ok: bool,
+    /// The contract's `reply` call.
+    pub guest: EvtGuest,
+}
+
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EvtCron {
+    pub contract: Addr,
+    /// The contract's `cron_execute` call.
+    pub guest: EvtGuest,
+}
+
+/// A call to a contract entry point that returned a `Response`.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EvtGuest {
+    pub contract: Addr,
+    /// Name of the entry point being called, e.g. `execute` or `receive`.
+    pub method: String,
+    /// Events emitted by the contract in its response.
+    pub contract_events: Vec<ContractEvent>,
+    /// Events of the transfers settling the contract's state deposit, followed
+    /// by those of the submessages emitted by the contract and of the replies
+    /// to them, in the order they were executed.
+    pub sub_events: Vec<Event>,
+}
+
+impl EvtGuest {
+    fn flatten_into(&self, events: &mut Vec<FlatEvent>) {
+        events.push(FlatEvent::host(
+            "guest",
+            Some(self.contract),
+            json!({
+                "method": self.method,
+            }),
+        ));
+
+        for contract_event in &self.contract_events {
+            events.push(FlatEvent {
+                category: EventCategory::Contract,
+                ty: contract_event.ty.clone(),
+                contract: Some(self.contract),
+                data: contract_event.data.clone(),
+            });
+        }
+
+        for sub_event in &self.sub_events {
+            sub_event.flatten_into(events);
+        }
+    }
+}
+
+// ------------------------------- flat events --------------------------------
+
+/// Whether a flat event is emitted by the host or a contract.
+///
+/// Indexers must tell them apart, so that a malicious contract can't emit an
+/// event that impersonates a host event.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventCategory {
+    Host,
+    Contract,
+}
+
+impl Display for EventCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventCategory::Host => write!(f, "host"),
+            EventCategory::Contract => write!(f, "contract"),
+        }
+    }
+}
+
+/// Keys used for the category and the contract when a flat event is turned
+/// into a flat list of key-value pairs, such as for CometBFT, with the event's
+/// data fields alongside them.
+///
+/// Contracts can't emit events with fields under these keys, which would
+/// otherwise impersonate host events or events of other contracts.
+pub const RESERVED_EVENT_KEYS: [&str; 2] = ["category", "contract"];
+
+/// An event with the events nested within it removed. See [`Event::flatten`].
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FlatEvent {
+    pub category: EventCategory,
+    pub ty: String,
+    /// The contract the event is about, or the contract that emitted it.
+    pub contract: Option<Addr>,
+    /// The event's data. Always a JSON object for host events.
+    pub data: Json,
+}
+
+impl FlatEvent {
+    fn host(ty: &str, contract: Option<Addr>, data: Json) -> Self {
         Self {
-            key: key.to_string(),
-            value: value.to_string(),
+            category: EventCategory::Host,
+            ty: ty.to_string(),
+            contract,
+            data,
         }
     }
 }
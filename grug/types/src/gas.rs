@@ -0,0 +1,179 @@
+use {
+    crate::Addr,
+    borsh::{BorshDeserialize, BorshSerialize},
+    serde::{Deserialize, Serialize},
+    std::collections::BTreeMap,
+};
+
+/// Gas costs of storage access and cryptography operations performed by the
+/// host on behalf of contracts.
+///
+/// This is an on-chain parameter, stored in the chain state and updatable by
+/// the chain owner via `Message::Configure`.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct GasCosts {
+    // Storage
+    pub db_read: LinearGasCost,
+    pub db_scan: u64,
+    pub db_next: u64,
+    pub db_write: LinearGasCost,
+    pub db_remove: u64,
+    // Code
+    pub validate_code: LinearGasCost,
+    // Signature verifiers
+    pub secp256r1_verify: u64,
+    pub secp256k1_verify: u64,
+    pub secp256k1_pubkey_recover: u64,
+    pub ed25519_verify: u64,
+    pub ed25519_batch_verify: LinearGasCost,
+    // Hashers
+    pub sha2_256: LinearGasCost,
+    pub sha2_512: LinearGasCost,
+    pub sha2_512_truncated: LinearGasCost,
+    pub sha3_256: LinearGasCost,
+    pub sha3_512: LinearGasCost,
+    pub sha3_512_truncated: LinearGasCost,
+    pub keccak256: LinearGasCost,
+    pub blake2s_256: LinearGasCost,
+    pub blake2b_512: LinearGasCost,
+    pub blake3: LinearGasCost,
+}
+
+impl Default for GasCosts {
+    /// The gas costs used at genesis, and for chains that haven't set them.
+    fn default() -> Self {
+        Self {
+            // Storage.
+            //
+            // For storage, we take the values from Cosmos SDK:
+            // https://github.com/cosmos/cosmos-sdk/blob/v0.50.7/store/types/gas.go#L232-L242
+            //
+            // Following the conversion:
+            // - 1 Cosmos SDK gas = 100 CosmWasm gas
+            // - 170 CosmWasm gas = 1 Wasmer point
+            // - 1 Wasmer point = 1 Grug gas
+            // This means: 1 Cosmos SDK gas = 0.588 Grug gas
+            db_read: LinearGasCost::new(588, 2),
+            db_scan: 588,
+            db_next: 18,
+            db_write: LinearGasCost::new(1176, 18),
+            db_remove: 588,
+            // Code.
+            //
+            // Validating a Wasm module involves compiling it, which takes time
+            // roughly linear in its size. `per_item` means per byte.
+            validate_code: LinearGasCost::new(0, 10),
+            // Verifiers
+            //
+            // For batch verification, there's a flat setup cost, and a cost per signature.
+            secp256r1_verify: 1_880_000,
+            secp256k1_verify: 770_000,
+            secp256k1_pubkey_recover: 1_580_000,
+            ed25519_verify: 410_000,
+            ed25519_batch_verify: LinearGasCost::new(1_340_000, 188_000),
+            // Hashers.
+            //
+            // For hashers, `per_item` means per byte.
+            // The truncated versions have the same cost as the untruncated counterparts.
+            sha2_256: LinearGasCost::new(0, 27),
+            sha2_512: LinearGasCost::new(0, 16),
+            sha2_512_truncated: LinearGasCost::new(0, 16),
+            sha3_256: LinearGasCost::new(0, 15),
+            sha3_512: LinearGasCost::new(0, 28),
+            sha3_512_truncated: LinearGasCost::new(0, 28),
+            keccak256: LinearGasCost::new(0, 15),
+            blake2s_256: LinearGasCost::new(0, 15),
+            blake2b_512: LinearGasCost::new(0, 9),
+            blake3: LinearGasCost::new(0, 5),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct LinearGasCost {
+    /// The flat part of the cost, charged once per batch.
+    base: u64,
+    /// The cost per item, on top of the flat part.
+    per_item: u64,
+}
+
+impl LinearGasCost {
+    pub const fn new(base: u64, per_item: u64) -> Self {
+        Self { base, per_item }
+    }
+
+    /// Saturates instead of overflowing, as the costs are set by the chain
+    /// owner and may be arbitrarily large.
+    pub fn cost(&self, items: usize) -> u64 {
+        self.base
+            .saturating_add(self.per_item.saturating_mul(items as u64))
+    }
+}
+
+/// Amount of gas consumed, broken down by the kind of operation that
+/// consumed it.
+///
+/// If a transaction runs out of gas, the gas that was remaining is attributed
+/// to the operation that ran out, even though it's not counted as used. The
+/// breakdown may thus add up to more than the gas used.
+#[derive(
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+)]
+#[serde(deny_unknown_fields)]
+pub struct GasBreakdown {
+    /// Gas consumed by reading from and writing to the storage.
+    pub storage: u64,
+    /// Gas consumed by hashing and signature verification.
+    pub crypto: u64,
+    /// Gas consumed by executing Wasm instructions.
+    pub wasm: u64,
+}
+
+impl GasBreakdown {
+    /// Return the total amount of gas consumed.
+    pub fn total(&self) -> u64 {
+        self.storage + self.crypto + self.wasm
+    }
+
+    /// Return the amount of gas consumed since the `earlier` breakdown was
+    /// taken.
+    ///
+    /// Gas consumption only ever increases, so `earlier` shouldn't be greater
+    /// than `self` in any category. If it is anyway, that category is zero.
+    pub fn since(&self, earlier: &Self) -> Self {
+        Self {
+            storage: self.storage.saturating_sub(earlier.storage),
+            crypto: self.crypto.saturating_sub(earlier.crypto),
+            wasm: self.wasm.saturating_sub(earlier.wasm),
+        }
+    }
+}
+
+/// Detailed gas consumption of a transaction, for figuring out where the gas
+/// goes.
+#[derive(
+    Serialize, Deserialize, BorshSerialize, BorshDeserialize, Default, Debug, Clone, PartialEq, Eq,
+)]
+#[serde(deny_unknown_fields)]
+pub struct GasProfile {
+    /// Gas consumed by each category of operation, e.g. `db_read`,
+    /// `sha2_256`, or `wasm_instructions`.
+    pub by_category: BTreeMap<String, u64>,
+    /// Gas consumed while executing each contract, including the host
+    /// functions it called.
+    ///
+    /// Gas consumed by the app outside of contract calls, e.g. loading a
+    /// contract's info before calling it, isn't attributed to any contract.
+    pub by_contract: BTreeMap<Addr, u64>,
+}
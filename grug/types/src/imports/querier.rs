@@ -1,7 +1,7 @@
 use {
     crate::{
-        Addr, Binary, Code, Coins, Config, ContractInfo, Denom, Hash256, Json, JsonDeExt, Query,
-        QueryRequest, QueryResponse, StdResult,
+        Addr, Binary, Code, Coins, Config, ContractInfo, Denom, GasCosts, Hash256, Json, JsonDeExt,
+        Query, QueryRequest, QueryResponse, StateDeposit, StateDepositParams, StdResult,
     },
     grug_math::Uint128,
     serde::{de::DeserializeOwned, ser::Serialize},
@@ -41,6 +41,24 @@ impl<'a> QuerierWrapper<'a> {
             .map(|res| res.as_config())
     }
 
+    pub fn query_gas_costs(&self) -> StdResult<GasCosts> {
+        self.inner
+            .query_chain(Query::gas_costs())
+            .map(|res| res.as_gas_costs())
+    }
+
+    pub fn query_max_block_gas(&self) -> StdResult<u64> {
+        self.inner
+            .query_chain(Query::max_block_gas())
+            .map(|res| res.as_max_block_gas())
+    }
+
+    pub fn query_state_deposit_params(&self) -> StdResult<Option<StateDepositParams>> {
+        self.inner
+            .query_chain(Query::state_deposit_params())
+            .map(|res| res.as_state_deposit_params())
+    }
+
     pub fn query_app_config<K, T>(&self, key: K) -> StdResult<T>
     where
         K: Into<String>,
@@ -126,6 +144,12 @@ impl<'a> QuerierWrapper<'a> {
             .map(|res| res.as_contracts())
     }
 
+    pub fn query_state_deposit(&self, contract: Addr) -> StdResult<StateDeposit> {
+        self.inner
+            .query_chain(Query::state_deposit(contract))
+            .map(|res| res.as_state_deposit())
+    }
+
     pub fn query_wasm_raw<B>(&self, contract: Addr, key: B) -> StdResult<Option<Binary>>
     where
         B: Into<Binary>,
@@ -19,6 +19,7 @@ mod encoders;
 mod error;
 mod event;
 mod ffi;
+mod gas;
 mod hash;
 mod hashers;
 mod imports;
@@ -32,6 +33,7 @@ mod result;
 mod serializers;
 mod signer;
 mod time;
+mod trace;
 mod tx;
 mod unique_vec;
 mod utils;
@@ -39,9 +41,9 @@ mod utils;
 pub use {
     address::*, app::*, bank::*, binary::*, bound::*, builder::*, bytes::*, changeset::*, code::*,
     coin::*, coin_pair::*, coins::*, context::*, db::*, denom::*, empty::*, encoded_bytes::*,
-    encoders::*, error::*, event::*, ffi::*, hash::*, hashers::*, imports::*, length_bounded::*,
-    lengthy::*, non_zero::*, query::*, response::*, result::*, serializers::*, signer::*, time::*,
-    tx::*, unique_vec::*, utils::*,
+    encoders::*, error::*, event::*, ffi::*, gas::*, hash::*, hashers::*, imports::*,
+    length_bounded::*, lengthy::*, non_zero::*, query::*, response::*, result::*, serializers::*,
+    signer::*, time::*, trace::*, tx::*, unique_vec::*, utils::*,
 };
 
 // ---------------------------------- testing ----------------------------------
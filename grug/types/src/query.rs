@@ -1,7 +1,7 @@
 use {
     crate::{
         extend_one_byte, Addr, Binary, Bound, Code, Coin, Coins, Config, ContractInfo, Denom,
-        Hash256, Json, JsonSerExt, StdResult,
+        GasCosts, Hash256, Json, JsonSerExt, StateDeposit, StateDepositParams, StdResult,
     },
     borsh::{BorshDeserialize, BorshSerialize},
     grug_math::Inner,
@@ -27,6 +27,9 @@ pub trait QueryRequest: Sized {
 
 // ---------------------------------- request ----------------------------------
 
+// Note: contracts send queries to the host Borsh-encoded, which identifies the
+// variants by their indexes. New variants must be added to the end, so that
+// already deployed contracts continue to work.
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -61,6 +64,14 @@ pub enum Query {
     WasmSmart(QueryWasmSmartRequest),
     /// Perform multiple queries at once.
     Multi(Vec<Query>),
+    /// Query the gas costs of host operations.
+    GasCosts(QueryGasCostsRequest),
+    /// Query a contract's storage usage and the state deposit it holds.
+    StateDeposit(QueryStateDepositRequest),
+    /// Query the maximum sum of the gas limits of all transactions in a block.
+    MaxBlockGas(QueryMaxBlockGasRequest),
+    /// Query the parameters of the state deposit.
+    StateDepositParams(QueryStateDepositParamsRequest),
 }
 
 impl Query {
@@ -68,6 +79,18 @@ impl Query {
         QueryConfigRequest {}.into()
     }
 
+    pub fn gas_costs() -> Self {
+        QueryGasCostsRequest {}.into()
+    }
+
+    pub fn max_block_gas() -> Self {
+        QueryMaxBlockGasRequest {}.into()
+    }
+
+    pub fn state_deposit_params() -> Self {
+        QueryStateDepositParamsRequest {}.into()
+    }
+
     pub fn app_config<T>(key: T) -> Self
     where
         T: Into<String>,
@@ -116,6 +139,10 @@ impl Query {
         QueryContractsRequest { start_after, limit }.into()
     }
 
+    pub fn state_deposit(contract: Addr) -> Self {
+        QueryStateDepositRequest { contract }.into()
+    }
+
     pub fn wasm_raw<B>(contract: Addr, key: B) -> Self
     where
         B: Into<Binary>,
@@ -175,6 +202,15 @@ impl Query {
 #[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
 pub struct QueryConfigRequest {}
 
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct QueryGasCostsRequest {}
+
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct QueryMaxBlockGasRequest {}
+
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct QueryStateDepositParamsRequest {}
+
 #[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
 pub struct QueryAppConfigRequest {
     pub key: String,
@@ -236,6 +272,11 @@ pub struct QueryContractsRequest {
     pub limit: Option<u32>,
 }
 
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct QueryStateDepositRequest {
+    pub contract: Addr,
+}
+
 #[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
 pub struct QueryWasmRawRequest {
     pub contract: Addr,
@@ -273,25 +314,30 @@ macro_rules! impl_into_query {
 }
 
 impl_into_query! {
-    Config     => QueryConfigRequest     => Config,
-    AppConfig  => QueryAppConfigRequest  => Json,
-    AppConfigs => QueryAppConfigsRequest => BTreeMap<String, Json>,
-    Balance    => QueryBalanceRequest    => Coin,
-    Balances   => QueryBalancesRequest   => Coins,
-    Supply     => QuerySupplyRequest     => Coin,
-    Supplies   => QuerySuppliesRequest   => Coins,
-    Code       => QueryCodeRequest       => Code,
-    Codes      => QueryCodesRequest      => BTreeMap<Hash256, Code>,
-    Contract   => QueryContractRequest   => ContractInfo,
-    Contracts  => QueryContractsRequest  => BTreeMap<Addr, ContractInfo>,
-    WasmRaw    => QueryWasmRawRequest    => Option<Binary>,
-    WasmScan   => QueryWasmScanRequest   => BTreeMap<Binary, Binary>,
-    WasmSmart  => QueryWasmSmartRequest  => Json,
-    Multi      => Vec<Query>             => Vec<QueryResponse>,
+    Config       => QueryConfigRequest       => Config,
+    AppConfig    => QueryAppConfigRequest    => Json,
+    AppConfigs   => QueryAppConfigsRequest   => BTreeMap<String, Json>,
+    Balance      => QueryBalanceRequest      => Coin,
+    Balances     => QueryBalancesRequest     => Coins,
+    Supply       => QuerySupplyRequest       => Coin,
+    Supplies     => QuerySuppliesRequest     => Coins,
+    Code         => QueryCodeRequest         => Code,
+    Codes        => QueryCodesRequest        => BTreeMap<Hash256, Code>,
+    Contract     => QueryContractRequest     => ContractInfo,
+    Contracts    => QueryContractsRequest    => BTreeMap<Addr, ContractInfo>,
+    WasmRaw      => QueryWasmRawRequest      => Option<Binary>,
+    WasmScan     => QueryWasmScanRequest     => BTreeMap<Binary, Binary>,
+    WasmSmart    => QueryWasmSmartRequest    => Json,
+    Multi        => Vec<Query>               => Vec<QueryResponse>,
+    GasCosts     => QueryGasCostsRequest     => GasCosts,
+    StateDeposit => QueryStateDepositRequest => StateDeposit,
+    MaxBlockGas  => QueryMaxBlockGasRequest  => u64,
+    StateDepositParams => QueryStateDepositParamsRequest => Option<StateDepositParams>,
 }
 
 // --------------------------------- response ----------------------------------
 
+// Same as with `Query`, new variants must be added to the end.
 #[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryResponse {
@@ -310,6 +356,10 @@ pub enum QueryResponse {
     WasmScan(BTreeMap<Binary, Binary>),
     WasmSmart(Json),
     Multi(Vec<QueryResponse>),
+    GasCosts(GasCosts),
+    StateDeposit(StateDeposit),
+    MaxBlockGas(u64),
+    StateDepositParams(Option<StateDepositParams>),
 }
 
 macro_rules! generate_downcast {
@@ -332,20 +382,24 @@ macro_rules! generate_downcast {
 
 impl QueryResponse {
     generate_downcast! {
-        Config     => Config,
-        AppConfig  => Json,
-        AppConfigs => BTreeMap<String, Json>,
-        Balance    => Coin,
-        Balances   => Coins,
-        Supply     => Coin,
-        Supplies   => Coins,
-        Code       => Code,
-        Codes      => BTreeMap<Hash256, Code>,
-        Contract   => ContractInfo,
-        Contracts  => BTreeMap<Addr, ContractInfo>,
-        WasmRaw    => Option<Binary>,
-        WasmScan   => BTreeMap<Binary, Binary>,
-        WasmSmart  => Json,
-        Multi      => Vec<QueryResponse>,
+        Config       => Config,
+        AppConfig    => Json,
+        AppConfigs   => BTreeMap<String, Json>,
+        Balance      => Coin,
+        Balances     => Coins,
+        Supply       => Coin,
+        Supplies     => Coins,
+        Code         => Code,
+        Codes        => BTreeMap<Hash256, Code>,
+        Contract     => ContractInfo,
+        Contracts    => BTreeMap<Addr, ContractInfo>,
+        WasmRaw      => Option<Binary>,
+        WasmScan     => BTreeMap<Binary, Binary>,
+        WasmSmart    => Json,
+        Multi        => Vec<QueryResponse>,
+        GasCosts     => GasCosts,
+        StateDeposit => StateDeposit,
+        MaxBlockGas  => u64,
+        StateDepositParams => Option<StateDepositParams>,
     }
 }
@@ -1,5 +1,5 @@
 use {
-    crate::{Attribute, Json, JsonSerExt, Message, StdResult},
+    crate::{ContractEvent, EventName, Json, JsonSerExt, Message, StdResult},
     borsh::{BorshDeserialize, BorshSerialize},
     serde::{Deserialize, Serialize},
 };
@@ -9,7 +9,7 @@ use {
 )]
 pub struct Response {
     pub submsgs: Vec<SubMessage>,
-    pub attributes: Vec<Attribute>,
+    pub events: Vec<ContractEvent>,
 }
 
 impl Response {
@@ -65,13 +65,12 @@ impl Response {
         self
     }
 
-    pub fn add_attribute<K, V>(mut self, key: K, value: V) -> Self
+    pub fn add_event<E>(mut self, event: &E) -> StdResult<Self>
     where
-        K: ToString,
-        V: ToString,
+        E: EventName + Serialize,
     {
-        self.attributes.push(Attribute::new(key, value));
-        self
+        self.events.push(ContractEvent::new(event)?);
+        Ok(self)
     }
 }
 
@@ -133,13 +132,12 @@ impl AuthResponse {
         self
     }
 
-    pub fn add_attribute<K, V>(mut self, key: K, value: V) -> Self
+    pub fn add_event<E>(mut self, event: &E) -> StdResult<Self>
     where
-        K: ToString,
-        V: ToString,
+        E: EventName + Serialize,
     {
-        self.response = self.response.add_attribute(key, value);
-        self
+        self.response = self.response.add_event(event)?;
+        Ok(self)
     }
 }
 
@@ -1,8 +1,8 @@
 use {
     crate::{
-        Addr, Binary, Code, CodeStatus, Coin, Config, ContractInfo, Denom, GenericResult, Hash256,
-        HashExt, Json, JsonSerExt, MockStorage, Order, Querier, Query, QueryResponse, StdError,
-        StdResult, Storage,
+        Addr, Binary, Code, CodeStatus, Coin, Config, ContractInfo, Denom, GasCosts, GenericResult,
+        Hash256, HashExt, Json, JsonSerExt, MockStorage, Order, Querier, Query, QueryResponse,
+        StateDeposit, StateDepositParams, StdError, StdResult, Storage,
     },
     grug_math::{NumberConst, Uint128},
     serde::Serialize,
@@ -19,11 +19,15 @@ type SmartQueryHandler = Box<dyn Fn(Addr, Json) -> GenericResult<Json>>;
 #[derive(Default)]
 pub struct MockQuerier {
     config: Option<Config>,
+    gas_costs: GasCosts,
+    max_block_gas: Option<u64>,
+    state_deposit_params: Option<StateDepositParams>,
     app_configs: BTreeMap<String, Json>,
     balances: BTreeMap<Addr, BTreeMap<Denom, Uint128>>,
     supplies: BTreeMap<Denom, Uint128>,
     codes: BTreeMap<Hash256, Code>,
     contracts: BTreeMap<Addr, ContractInfo>,
+    state_deposits: BTreeMap<Addr, StateDeposit>,
     raw_query_handler: MockRawQueryHandler,
     smart_query_handler: Option<SmartQueryHandler>,
 }
@@ -38,6 +42,21 @@ impl MockQuerier {
         self
     }
 
+    pub fn with_gas_costs(mut self, gas_costs: GasCosts) -> Self {
+        self.gas_costs = gas_costs;
+        self
+    }
+
+    pub fn with_max_block_gas(mut self, max_block_gas: u64) -> Self {
+        self.max_block_gas = Some(max_block_gas);
+        self
+    }
+
+    pub fn with_state_deposit_params(mut self, params: StateDepositParams) -> Self {
+        self.state_deposit_params = Some(params);
+        self
+    }
+
     pub fn with_app_config<K, V>(mut self, key: K, value: V) -> StdResult<Self>
     where
         K: Into<String>,
@@ -89,6 +108,11 @@ impl MockQuerier {
         self
     }
 
+    pub fn with_state_deposit(mut self, address: Addr, state_deposit: StateDeposit) -> Self {
+        self.state_deposits.insert(address, state_deposit);
+        self
+    }
+
     pub fn with_raw_contract_storage<F>(mut self, address: Addr, callback: F) -> Self
     where
         F: FnOnce(&mut dyn Storage),
@@ -123,6 +147,13 @@ impl Querier for MockQuerier {
                     .expect("[MockQuerier]: config is not set");
                 Ok(QueryResponse::Config(cfg))
             },
+            Query::GasCosts(_req) => Ok(QueryResponse::GasCosts(self.gas_costs.clone())),
+            Query::MaxBlockGas(_req) => Ok(QueryResponse::MaxBlockGas(
+                self.max_block_gas.unwrap_or(u64::MAX),
+            )),
+            Query::StateDepositParams(_req) => Ok(QueryResponse::StateDepositParams(
+                self.state_deposit_params.clone(),
+            )),
             Query::AppConfig(req) => {
                 let value = self
                     .app_configs
@@ -248,6 +279,17 @@ impl Querier for MockQuerier {
                     .collect();
                 Ok(QueryResponse::Contracts(contracts))
             },
+            Query::StateDeposit(req) => {
+                let state_deposit =
+                    self.state_deposits
+                        .get(&req.contract)
+                        .cloned()
+                        .unwrap_or(StateDeposit {
+                            bytes: 0,
+                            deposit: None,
+                        });
+                Ok(QueryResponse::StateDeposit(state_deposit))
+            },
             Query::WasmRaw(req) => {
                 let maybe_value = self
                     .raw_query_handler
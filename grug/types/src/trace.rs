@@ -0,0 +1,38 @@
+use {
+    crate::{Addr, Coins, ContractEvent, GasBreakdown, Json},
+    serde::{Deserialize, Serialize},
+    serde_with::skip_serializing_none,
+};
+
+/// A call to a contract entry point made during the processing of a
+/// transaction, together with the calls it triggered in turn.
+///
+/// A transaction's call tree is made of the top level calls (`withhold_fee`,
+/// `authenticate`, the messages, `backrun`, and `finalize_fee`). Each call's
+/// children include the submessages it emitted, and the replies to them.
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct CallTrace {
+    /// The contract being called.
+    pub contract: Addr,
+    /// Name of the entry point being called, e.g. `execute` or `reply`.
+    pub entry_point: String,
+    /// The account that made the call, if any.
+    pub sender: Option<Addr>,
+    /// The funds sent along with the call, if any.
+    pub funds: Option<Coins>,
+    /// The input to the call. `null` if the entry point takes no input, or an
+    /// array if it takes more than one.
+    pub msg: Json,
+    /// Gas consumed by this call, including that of its children.
+    pub gas_used: GasBreakdown,
+    /// Events emitted by the contract in this call, not including those of
+    /// its children.
+    /// Empty if the call failed, as its events are discarded in that case.
+    pub events: Vec<ContractEvent>,
+    /// The error, if the call failed.
+    pub error: Option<String>,
+    /// Calls triggered by this call, in the order they were made.
+    pub calls: Vec<CallTrace>,
+}
@@ -58,6 +58,10 @@ pub enum Message {
     Execute(MsgExecute),
     /// Update the code hash associated with a contract.
     Migrate(MsgMigrate),
+    /// Transfer a contract's admin privilege to another account.
+    UpdateAdmin(MsgUpdateAdmin),
+    /// Remove a contract's admin, making it no longer migratable.
+    ClearAdmin(MsgClearAdmin),
 }
 
 impl Message {
@@ -139,6 +143,18 @@ impl Message {
         }
         .into())
     }
+
+    pub fn update_admin(contract: Addr, new_admin: Addr) -> Self {
+        MsgUpdateAdmin {
+            contract,
+            new_admin,
+        }
+        .into()
+    }
+
+    pub fn clear_admin(contract: Addr) -> Self {
+        MsgClearAdmin { contract }.into()
+    }
 }
 
 #[skip_serializing_none]
@@ -188,6 +204,17 @@ pub struct MsgMigrate {
     pub msg: Json,
 }
 
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MsgUpdateAdmin {
+    pub contract: Addr,
+    pub new_admin: Addr,
+}
+
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MsgClearAdmin {
+    pub contract: Addr,
+}
+
 macro_rules! impl_into_message {
     ($variant:ident, $msg:ty) => {
         impl From<$msg> for Message {
@@ -211,4 +238,6 @@ impl_into_message! {
     Instantiate => MsgInstantiate,
     Execute     => MsgExecute,
     Migrate     => MsgMigrate,
+    UpdateAdmin => MsgUpdateAdmin,
+    ClearAdmin  => MsgClearAdmin,
 }
@@ -7,7 +7,7 @@ use {
         Addr, BlockInfo, BorshSerExt, Context, GenericResult, Hash, JsonSerExt, MockStorage,
         Timestamp,
     },
-    grug_vm_wasm::WasmVm,
+    grug_vm_wasm::{WasmVm, WasmVmConfig},
     std::time::Duration,
 };
 
@@ -26,7 +26,7 @@ static BENCHMARKER_CODE: &[u8] = include_bytes!("../testdata/grug_tester.wasm");
 fn looping(c: &mut Criterion) {
     // Share one `WasmVm` across all benches, which caches the module, so we
     // don't need to rebuild it every time.
-    let mut vm = WasmVm::new(100);
+    let mut vm = WasmVm::new(100, WasmVmConfig::default()).unwrap();
 
     for iterations in [200_000, 400_000, 600_000, 800_000, 1_000_000] {
         // The `criterion` library only benchmarks the time consumption, however
@@ -1,9 +1,12 @@
 use {
-    crate::VmResult,
+    crate::{FileSystemCache, VmResult},
     clru::CLruCache,
     grug_app::Shared,
     grug_types::Hash256,
-    std::num::NonZeroUsize,
+    std::{
+        collections::{HashMap, HashSet},
+        num::NonZeroUsize,
+    },
     wasmer::{Engine, Module},
 };
 
@@ -17,13 +20,26 @@ type Data = (Module, Engine);
 /// Statistics about the usage of the cache instance.
 #[derive(Default, Debug, Clone, Copy)]
 pub struct Metrics {
+    /// Number of times a module was found in memory, either pinned or in the
+    /// LRU cache.
     pub hits: usize,
+    /// Number of times a module was not found in memory.
     pub misses: usize,
+    /// Number of in-memory misses that were served by the file system cache.
+    pub fs_hits: usize,
+    /// Number of in-memory misses that were not found in the file system
+    /// cache either, so the module had to be compiled.
+    pub fs_misses: usize,
 }
 
 impl Metrics {
     pub fn new() -> Self {
-        Self { hits: 0, misses: 0 }
+        Self {
+            hits: 0,
+            misses: 0,
+            fs_hits: 0,
+            fs_misses: 0,
+        }
     }
 
     pub fn increment_hits(&mut self) {
@@ -36,10 +52,25 @@ impl Metrics {
         // Same as above, use saturating add to avoid panicking on overflow.
         self.misses = self.misses.saturating_add(1);
     }
+
+    pub fn increment_fs_hits(&mut self) {
+        self.fs_hits = self.fs_hits.saturating_add(1);
+    }
+
+    pub fn increment_fs_misses(&mut self) {
+        self.fs_misses = self.fs_misses.saturating_add(1);
+    }
 }
 
 /// An in-memory cache for wasm modules, so that they don't need to be re-built
 /// every time the same contract is called.
+///
+/// Optionally, a [`FileSystemCache`](crate::FileSystemCache) can be attached
+/// as a second tier, so that modules survive node restarts.
+///
+/// Modules of pinned code hashes are kept in memory separately from the LRU
+/// cache, and are never evicted. This is intended for contracts that are
+/// called in almost every transaction, such as the bank and taxman.
 #[derive(Clone)]
 pub struct Cache {
     inner: Shared<CacheInner>,
@@ -47,6 +78,9 @@ pub struct Cache {
 
 struct CacheInner {
     lru_cache: CLruCache<Hash256, Data>,
+    pinned_hashes: HashSet<Hash256>,
+    pinned_cache: HashMap<Hash256, Data>,
+    fs_cache: Option<FileSystemCache>,
     metrics: Metrics,
 }
 
@@ -56,37 +90,119 @@ impl Cache {
         Self {
             inner: Shared::new(CacheInner {
                 lru_cache: CLruCache::new(capacity),
+                pinned_hashes: HashSet::new(),
+                pinned_cache: HashMap::new(),
+                fs_cache: None,
                 metrics: Metrics::new(),
             }),
         }
     }
 
-    /// Attempt to get a cached module by hash. If not found, build the module
-    /// using the given method, insert the built module into the cache, and
-    /// return the module.
-    pub fn get_or_build_with<B>(&self, code_hash: Hash256, builder: B) -> VmResult<Data>
+    /// Use the given file system cache as the second tier behind the
+    /// in-memory cache.
+    pub fn with_file_system_cache(self, fs_cache: FileSystemCache) -> Self {
+        self.inner.write_with(|mut inner| {
+            inner.fs_cache = Some(fs_cache);
+        });
+
+        self
+    }
+
+    /// Return the current usage statistics.
+    pub fn metrics(&self) -> Metrics {
+        self.inner.read_with(|inner| inner.metrics)
+    }
+
+    /// Pin the module of the given code hash in memory, so that it's never
+    /// evicted.
+    ///
+    /// If the module is already in the LRU cache, it's moved to the pinned
+    /// cache; otherwise, it will be pinned the next time it's built.
+    pub fn pin(&self, code_hash: Hash256) {
+        self.inner.write_with(|mut inner| {
+            inner.pinned_hashes.insert(code_hash);
+
+            if let Some(data) = inner.lru_cache.pop(&code_hash) {
+                inner.pinned_cache.insert(code_hash, data);
+            }
+        });
+    }
+
+    /// Unpin the module of the given code hash. If the module was built, it's
+    /// moved back to the LRU cache.
+    pub fn unpin(&self, code_hash: Hash256) {
+        self.inner.write_with(|mut inner| {
+            inner.pinned_hashes.remove(&code_hash);
+
+            if let Some(data) = inner.pinned_cache.remove(&code_hash) {
+                inner.lru_cache.put(code_hash, data);
+            }
+        });
+    }
+
+    /// Attempt to get a cached module by hash. If not found in memory, attempt
+    /// to load it from the file system cache using an engine created by
+    /// `engine_builder`. If still not found, compile the module using the
+    /// given method. The module is inserted into the caches, and returned.
+    pub fn get_or_build_with<E, B>(
+        &self,
+        code_hash: Hash256,
+        engine_builder: E,
+        module_builder: B,
+    ) -> VmResult<Data>
     where
-        B: FnOnce() -> VmResult<Data>,
+        E: FnOnce() -> Engine,
+        B: FnOnce(&Engine) -> VmResult<Module>,
     {
         self.inner.write_with(|mut inner| {
-            match inner.lru_cache.get(&code_hash).cloned() {
-                // Cache hit - simply clone the cached data and return.
-                Some(data) => {
-                    inner.metrics.increment_hits();
+            // Cache hit - simply clone the cached data and return.
+            if let Some(data) = inner.pinned_cache.get(&code_hash).cloned() {
+                inner.metrics.increment_hits();
 
-                    Ok(data)
-                },
-                // Cache miss - build the module using the given builder method;
-                // insert both the module and engine to the cache.
-                None => {
-                    let data = builder()?;
+                return Ok(data);
+            }
+
+            if let Some(data) = inner.lru_cache.get(&code_hash).cloned() {
+                inner.metrics.increment_hits();
+
+                return Ok(data);
+            }
+
+            inner.metrics.increment_misses();
 
-                    inner.lru_cache.put(code_hash, data.clone());
-                    inner.metrics.increment_misses();
+            // Cache miss - attempt to load the module from the file system
+            // cache. If not found, build the module using the given builder
+            // method, and save it to the file system cache.
+            let engine = engine_builder();
+            let module = match inner.fs_cache.clone() {
+                Some(fs_cache) => match fs_cache.load(code_hash, &engine) {
+                    Some(module) => {
+                        inner.metrics.increment_fs_hits();
 
-                    Ok(data)
+                        module
+                    },
+                    None => {
+                        let module = module_builder(&engine)?;
+
+                        fs_cache.store(code_hash, &module);
+                        inner.metrics.increment_fs_misses();
+
+                        module
+                    },
                 },
+                None => module_builder(&engine)?,
+            };
+
+            // Insert both the module and engine to the in-memory cache.
+            let data = (module, engine);
+
+            if inner.pinned_hashes.contains(&code_hash) {
+                inner.pinned_cache.insert(code_hash, data.clone());
+            } else {
+                inner.lru_cache.put(code_hash, data.clone());
             }
+
+            Ok(data)
         })
     }
 }
@@ -96,7 +212,7 @@ impl Cache {
 #[cfg(test)]
 mod tests {
     use {
-        crate::{Cache, VmResult},
+        crate::{Cache, FileSystemCache, VmResult},
         grug_types::HashExt,
         std::num::NonZeroUsize,
         wasmer::{Engine, Module, Singlepass},
@@ -104,10 +220,12 @@ mod tests {
 
     const CONTRACT: &[u8] = br#"(module)"#;
 
-    fn builder() -> VmResult<(Module, Engine)> {
-        let engine = Engine::from(Singlepass::new());
-        let module = Module::new(&engine, CONTRACT)?;
-        Ok((module, engine))
+    fn engine_builder() -> Engine {
+        Engine::from(Singlepass::new())
+    }
+
+    fn module_builder(engine: &Engine) -> VmResult<Module> {
+        Ok(Module::new(engine, CONTRACT)?)
     }
 
     #[test]
@@ -117,13 +235,17 @@ mod tests {
         // Build the 1st contract. Should be a cache miss, and the data is
         // inserted into the cache.
         let hash1 = CONTRACT.hash256();
-        cache.get_or_build_with(hash1, builder).unwrap();
+        cache
+            .get_or_build_with(hash1, engine_builder, module_builder)
+            .unwrap();
 
         // Build the 2nd contract. Should also be a cache miss, and the data is
         // inserted. Data of the previous build should have been removed,
         // because the cache only has a capacity of 1.
         let hash2 = b"jake".hash256();
-        cache.get_or_build_with(hash2, builder).unwrap();
+        cache
+            .get_or_build_with(hash2, engine_builder, module_builder)
+            .unwrap();
 
         // Cache should have had 2 misses, with hash2 cached but hash1 not.
         cache.inner.read_with(|inner| {
@@ -142,8 +264,12 @@ mod tests {
         // Build the same contract twice. 1st time should be a cache miss, 2nd
         // time should be a cache hit.
         let hash = CONTRACT.hash256();
-        cache.get_or_build_with(hash, builder).unwrap();
-        cache.get_or_build_with(hash, builder).unwrap();
+        cache
+            .get_or_build_with(hash, engine_builder, module_builder)
+            .unwrap();
+        cache
+            .get_or_build_with(hash, engine_builder, module_builder)
+            .unwrap();
 
         cache.inner.read_with(|inner| {
             assert!(inner.lru_cache.contains(&hash));
@@ -152,4 +278,82 @@ mod tests {
             assert_eq!(inner.metrics.misses, 1);
         });
     }
+
+    #[test]
+    fn pinned_not_evicted() {
+        let cache = Cache::new(NonZeroUsize::new(1).unwrap());
+
+        // Pin hash1 before it's built. Once built, it should go to the pinned
+        // cache instead of the LRU cache.
+        let hash1 = CONTRACT.hash256();
+        cache.pin(hash1);
+        cache
+            .get_or_build_with(hash1, engine_builder, module_builder)
+            .unwrap();
+
+        // Building hash2 and hash3 fills up the LRU cache, but shouldn't evict
+        // the pinned hash1.
+        let hash2 = b"jake".hash256();
+        let hash3 = b"larry".hash256();
+        cache
+            .get_or_build_with(hash2, engine_builder, module_builder)
+            .unwrap();
+        cache
+            .get_or_build_with(hash3, engine_builder, module_builder)
+            .unwrap();
+        cache
+            .get_or_build_with(hash1, engine_builder, module_builder)
+            .unwrap();
+
+        cache.inner.read_with(|inner| {
+            assert!(inner.pinned_cache.contains_key(&hash1));
+            assert!(!inner.lru_cache.contains(&hash1));
+            assert!(inner.lru_cache.contains(&hash3));
+            assert_eq!(inner.metrics.hits, 1);
+            assert_eq!(inner.metrics.misses, 3);
+        });
+
+        // Unpinning moves the module back to the LRU cache, evicting hash3.
+        cache.unpin(hash1);
+
+        cache.inner.read_with(|inner| {
+            assert!(inner.pinned_cache.is_empty());
+            assert!(inner.lru_cache.contains(&hash1));
+            assert!(!inner.lru_cache.contains(&hash3));
+        });
+    }
+
+    #[test]
+    fn file_system_tier() {
+        let dir = tempfile::tempdir().unwrap();
+        let hash = CONTRACT.hash256();
+
+        // Build the contract with a fresh cache. Should miss both tiers, and
+        // save the module to disk.
+        let cache = Cache::new(NonZeroUsize::new(1).unwrap())
+            .with_file_system_cache(FileSystemCache::open(dir.path()).unwrap());
+        cache
+            .get_or_build_with(hash, engine_builder, module_builder)
+            .unwrap();
+
+        let metrics = cache.metrics();
+        assert_eq!(metrics.misses, 1);
+        assert_eq!(metrics.fs_hits, 0);
+        assert_eq!(metrics.fs_misses, 1);
+
+        // Simulate a restart by creating a new cache over the same directory.
+        // The module should be loaded from disk without being compiled.
+        let cache = Cache::new(NonZeroUsize::new(1).unwrap())
+            .with_file_system_cache(FileSystemCache::open(dir.path()).unwrap());
+        cache
+            .get_or_build_with(hash, engine_builder, |_| {
+                panic!("module should be loaded from disk")
+            })
+            .unwrap();
+
+        let metrics = cache.metrics();
+        assert_eq!(metrics.misses, 1);
+        assert_eq!(metrics.fs_hits, 1);
+        assert_eq!(metrics.fs_misses, 0);
+    }
 }
@@ -0,0 +1,214 @@
+use {
+    grug_crypto::sha2_256,
+    grug_types::Hash256,
+    wasmer::{wasmparser::Operator, WASM_PAGE_SIZE},
+};
+
+/// Default gas cost per Wasmer operation.
+pub const GAS_PER_OPERATION: u64 = 1;
+
+/// Default gas cost of a division or remainder operation, which are several
+/// times slower than other arithmetic operations on most CPUs.
+pub const GAS_PER_DIVISION: u64 = 8;
+
+/// Default gas cost of a `memory.grow` operation, which allocates and zeroes
+/// at least one 64 KiB page of memory.
+///
+/// This is charged once per operation, regardless of the number of pages
+/// requested, since the metering middleware only sees the operator, not its
+/// operand. The total memory that can be grown is bounded by
+/// [`MAX_MEMORY_PAGES`] instead.
+pub const GAS_PER_MEMORY_GROW: u64 = 100;
+
+/// Default maximum number of chained queries.
+///
+/// E.g. contract A queries contract B; when handling this query, contract B
+/// calls contract C; so on.
+///
+/// Without a limit, this can leads to stack overflow which halts the chain.
+pub const MAX_QUERY_DEPTH: usize = 3;
+
+/// Default Wasm memory size limit in MiB.
+///
+/// ## Note
+///
+/// Do not confuse MiB (mebibyte) with MB (megabyte):
+///
+/// - 1 MiB = 1,024 KiB = 1,024 * 1,024 bytes
+/// - 1 MB  = 1,000 KB  = 1,000 * 1,000 bytes
+pub const MAX_MEMORY_MEBI: usize = 32;
+
+/// Default Wasm memory size limit in pages.
+///
+/// In WebAssembly, a memory page is 64 KiB. There can be a maximum of 65,536
+/// pages.
+///
+/// In Grug, we limit each contract instance's memory to 32 MiB or 512 pages,
+/// consistent with [CosmWasm](https://github.com/CosmWasm/wasmd/blob/v0.53.0/x/wasm/keeper/keeper.go#L38-L40).
+pub const MAX_MEMORY_PAGES: u32 = (MAX_MEMORY_MEBI * 1024 * 1024 / WASM_PAGE_SIZE) as u32;
+
+// Assert at compile time that our instance memory is always smaller than the
+// max memory that Wasmer can support.
+const _: () = assert!(MAX_MEMORY_PAGES < wasmer::WASM_MAX_PAGES);
+
+/// Default maximum size of a Wasm module in bytes.
+pub const MAX_MODULE_SIZE: usize = 3 * 1024 * 1024;
+
+// ---------------------------------- config -----------------------------------
+
+/// Limits that the Wasm VM enforces on contracts.
+///
+/// All nodes in a network must use the same config, otherwise they may
+/// disagree on whether a transaction succeeds or how much gas it consumes.
+/// The app saves the config's [`hash`](Self::hash) at genesis, and refuses to
+/// start if it changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WasmVmConfig {
+    /// Maximum memory of a contract instance, in Wasm pages of 64 KiB each.
+    /// Must be smaller than 65,536.
+    pub max_memory_pages: u32,
+    /// Maximum number of chained queries.
+    pub max_query_depth: usize,
+    /// Maximum size of a Wasm module that can be uploaded, in bytes.
+    pub max_module_size: usize,
+    /// Gas cost of each Wasm operator.
+    pub gas_costs: OperatorGasCosts,
+}
+
+impl WasmVmConfig {
+    /// Return the SHA-256 hash of the config.
+    pub fn hash(&self) -> Hash256 {
+        let mut bytes = Vec::with_capacity(44);
+        bytes.extend_from_slice(&self.max_memory_pages.to_be_bytes());
+        bytes.extend_from_slice(&(self.max_query_depth as u64).to_be_bytes());
+        bytes.extend_from_slice(&(self.max_module_size as u64).to_be_bytes());
+        bytes.extend_from_slice(&self.gas_costs.default.to_be_bytes());
+        bytes.extend_from_slice(&self.gas_costs.division.to_be_bytes());
+        bytes.extend_from_slice(&self.gas_costs.memory_grow.to_be_bytes());
+
+        Hash256::from_inner(sha2_256(&bytes))
+    }
+}
+
+impl Default for WasmVmConfig {
+    fn default() -> Self {
+        Self {
+            max_memory_pages: MAX_MEMORY_PAGES,
+            max_query_depth: MAX_QUERY_DEPTH,
+            max_module_size: MAX_MODULE_SIZE,
+            gas_costs: OperatorGasCosts::default(),
+        }
+    }
+}
+
+/// Gas cost of Wasm operators, charged by the metering middleware.
+///
+/// Operators not covered by a specific field cost `default`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperatorGasCosts {
+    /// Cost of operators not covered by the other fields.
+    pub default: u64,
+    /// Cost of integer and floating point division and remainder operators.
+    pub division: u64,
+    /// Cost of the `memory.grow` operator. Charged once per operation, not
+    /// per page.
+    pub memory_grow: u64,
+}
+
+impl Default for OperatorGasCosts {
+    fn default() -> Self {
+        Self {
+            default: GAS_PER_OPERATION,
+            division: GAS_PER_DIVISION,
+            memory_grow: GAS_PER_MEMORY_GROW,
+        }
+    }
+}
+
+impl OperatorGasCosts {
+    /// Return the gas cost of the given operator.
+    pub fn cost(&self, operator: &Operator) -> u64 {
+        match operator {
+            Operator::I32DivS
+            | Operator::I32DivU
+            | Operator::I32RemS
+            | Operator::I32RemU
+            | Operator::I64DivS
+            | Operator::I64DivU
+            | Operator::I64RemS
+            | Operator::I64RemU
+            | Operator::F32Div
+            | Operator::F64Div => self.division,
+            Operator::MemoryGrow { .. } => self.memory_grow,
+            _ => self.default,
+        }
+    }
+
+    /// Return a short string that uniquely identifies these costs.
+    ///
+    /// Since gas costs are compiled into the module by the metering middleware,
+    /// this is used to separate modules compiled with different costs in the
+    /// file system cache.
+    pub fn fingerprint(&self) -> String {
+        let mut bytes = Vec::with_capacity(24);
+        bytes.extend_from_slice(&self.default.to_be_bytes());
+        bytes.extend_from_slice(&self.division.to_be_bytes());
+        bytes.extend_from_slice(&self.memory_grow.to_be_bytes());
+
+        data_encoding::HEXLOWER.encode(&sha2_256(&bytes)[..8])
+    }
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operator_costs() {
+        let costs = OperatorGasCosts {
+            default: 1,
+            division: 5,
+            memory_grow: 100,
+        };
+
+        assert_eq!(costs.cost(&Operator::I32Add), 1);
+        assert_eq!(costs.cost(&Operator::I64DivU), 5);
+        assert_eq!(costs.cost(&Operator::F64Div), 5);
+        assert_eq!(
+            costs.cost(&Operator::MemoryGrow {
+                mem: 0,
+                mem_byte: 0
+            }),
+            100
+        );
+    }
+
+    #[test]
+    fn config_hash_differs() {
+        let config = WasmVmConfig::default();
+        let other = WasmVmConfig {
+            max_memory_pages: config.max_memory_pages - 1,
+            ..config
+        };
+
+        assert_eq!(config.hash(), WasmVmConfig::default().hash());
+        assert_ne!(config.hash(), other.hash());
+    }
+
+    #[test]
+    fn fingerprint_differs() {
+        let costs = OperatorGasCosts::default();
+        let other = OperatorGasCosts {
+            memory_grow: costs.memory_grow + 1,
+            ..costs
+        };
+
+        assert_eq!(
+            costs.fingerprint(),
+            OperatorGasCosts::default().fingerprint()
+        );
+        assert_ne!(costs.fingerprint(), other.fingerprint());
+    }
+}
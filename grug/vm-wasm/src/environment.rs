@@ -254,10 +254,13 @@ impl Environment {
         let instance = self.get_wasmer_instance()?;
         match get_remaining_points(store, instance) {
             MeteringPoints::Remaining(remaining) => {
-                // gas_checkpoint can't be less than remaining
-                // compute consumed equals to the gas consumed since the last update + external gas
-                let consumed = self.gas_checkpoint - remaining + external;
-                self.gas_tracker.consume(consumed, comment)?;
+                // gas_checkpoint can't be less than remaining.
+                // Record the gas consumed by Wasm instructions since the last
+                // update separately from the external gas, so that the two
+                // are attributed to the right categories.
+                let consumed = self.gas_checkpoint - remaining;
+                self.gas_tracker.consume(consumed, "wasm_instructions")?;
+                self.gas_tracker.consume(external, comment)?;
 
                 // If there is a limit on gas_tracker, update the remaining points in the store
                 if let Some(remaining) = self.gas_tracker.remaining() {
@@ -279,7 +282,9 @@ impl Environment {
 #[cfg(test)]
 mod test {
     use {
-        crate::{Environment, Iterator, VmError, VmResult, WasmVm, GAS_PER_OPERATION},
+        crate::{
+            Environment, Iterator, VmError, VmResult, WasmVm, WasmVmConfig, GAS_PER_OPERATION,
+        },
         grug_app::{AppCtx, GasTracker, QuerierProvider, Shared, StorageProvider},
         grug_types::{BlockInfo, Hash256, MockStorage, Order, StdError, Storage, Timestamp},
         std::sync::Arc,
@@ -334,7 +339,7 @@ mod test {
             let storage_provider = StorageProvider::new(Box::new(storage.clone()), &[b"prefix"]);
 
             let querier_provider = QuerierProvider::new(AppCtx::new(
-                WasmVm::new(0),
+                WasmVm::new(0, WasmVmConfig::default()).unwrap(),
                 Box::new(storage),
                 gas_tracker.clone(),
                 "dev-1",
@@ -75,6 +75,34 @@ pub enum VmError {
 
     #[error("max query depth exceeded")]
     ExceedMaxQueryDepth,
+
+    #[error("Wasm module is too large! size: {size}, max: {max}")]
+    ModuleTooLarge { size: usize, max: usize },
+
+    #[error("max memory pages must be smaller than {max}! got: {pages}")]
+    MaxMemoryPagesTooLarge { pages: u32, max: u32 },
+
+    #[error("Wasm module defines too many functions! count: {count}, max: {max}")]
+    TooManyFunctions { count: u32, max: u32 },
+
+    #[error("Wasm module does not export `{name}`")]
+    MissingExport { name: String },
+
+    #[error("Wasm module exports unrecognized function `{name}`")]
+    UnknownExport { name: String },
+
+    #[error("Wasm module does not export any entry point")]
+    NoEntryPoint,
+
+    #[error("Wasm module imports unknown host function `{module}::{name}`")]
+    UnknownImport { module: String, name: String },
+
+    #[error("incorrect signature for function `{name}`! expect: {expect}, actual: {actual}")]
+    IncorrectSignature {
+        name: String,
+        expect: String,
+        actual: String,
+    },
 }
 
 impl From<CompileError> for VmError {
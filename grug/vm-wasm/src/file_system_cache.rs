@@ -0,0 +1,213 @@
+use {
+    grug_crypto::sha2_256,
+    grug_types::Hash256,
+    std::{
+        fs, io,
+        path::{Path, PathBuf},
+    },
+    wasmer::{Engine, Module, Target},
+};
+
+/// Version of the on-disk module format.
+///
+/// Bump this whenever a change is made to how modules are compiled (e.g. a
+/// middleware is added or changed), such that modules built by the old code
+/// are no longer loaded.
+pub const MODULE_CACHE_VERSION: &str = "v1";
+
+/// Length of the checksum prepended to each serialized module.
+const CHECKSUM_LEN: usize = 32;
+
+/// A file system cache for compiled Wasmer modules, so that contracts don't
+/// need to be re-compiled after the node restarts.
+///
+/// Modules are stored at `{base_dir}/{version_tag}/{code_hash}.module`. The
+/// version tag consists of [`MODULE_CACHE_VERSION`], the Wasmer version, and
+/// the target triple, because serialized modules are only compatible with the
+/// exact same compiler and CPU architecture.
+///
+/// Each file contains the SHA-256 checksum of the serialized module, followed
+/// by the module itself. The checksum is verified on load; a file that fails
+/// verification is deleted and treated as a cache miss.
+#[derive(Debug, Clone)]
+pub struct FileSystemCache {
+    dir: PathBuf,
+}
+
+impl FileSystemCache {
+    /// Open a file system cache under the given base directory, creating the
+    /// directory if it doesn't exist.
+    pub fn open<P>(base_dir: P) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let dir = base_dir.as_ref().join(version_tag());
+
+        fs::create_dir_all(&dir)?;
+
+        Ok(Self { dir })
+    }
+
+    /// Attempt to load a module by code hash.
+    ///
+    /// The engine must have been configured identically to the one used to
+    /// compile the module, otherwise the module may behave incorrectly.
+    pub fn load(&self, code_hash: Hash256, engine: &Engine) -> Option<Module> {
+        let path = self.module_path(code_hash);
+
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return None,
+            Err(err) => {
+                tracing::warn!(
+                    code_hash = code_hash.to_string(),
+                    err = err.to_string(),
+                    "Failed to read cached module"
+                );
+
+                return None;
+            },
+        };
+
+        match verify_and_deserialize(&bytes, engine) {
+            Ok(module) => Some(module),
+            Err(reason) => {
+                tracing::warn!(
+                    code_hash = code_hash.to_string(),
+                    reason,
+                    "Cached module is invalid; removing it"
+                );
+
+                fs::remove_file(&path).ok();
+
+                None
+            },
+        }
+    }
+
+    /// Save a module under the given code hash.
+    ///
+    /// Failing to save is not fatal, since the module can always be recompiled
+    /// from the contract code. Errors are logged and otherwise ignored.
+    pub fn store(&self, code_hash: Hash256, module: &Module) {
+        if let Err(err) = self.try_store(code_hash, module) {
+            tracing::warn!(
+                code_hash = code_hash.to_string(),
+                err,
+                "Failed to save module to file system cache"
+            );
+        }
+    }
+
+    fn try_store(&self, code_hash: Hash256, module: &Module) -> Result<(), String> {
+        let serialized = module.serialize().map_err(|err| err.to_string())?;
+
+        let mut bytes = Vec::with_capacity(CHECKSUM_LEN + serialized.len());
+        bytes.extend_from_slice(&sha2_256(&serialized));
+        bytes.extend_from_slice(&serialized);
+
+        // Write to a temporary file first, then rename it. This way, another
+        // process reading the cache never sees a partially written module.
+        let path = self.module_path(code_hash);
+        let tmp_path = path.with_extension("tmp");
+
+        fs::write(&tmp_path, bytes).map_err(|err| err.to_string())?;
+        fs::rename(&tmp_path, &path).map_err(|err| err.to_string())
+    }
+
+    fn module_path(&self, code_hash: Hash256) -> PathBuf {
+        self.dir.join(format!("{code_hash}.module"))
+    }
+}
+
+fn version_tag() -> String {
+    format!(
+        "{MODULE_CACHE_VERSION}-wasmer{}-{}",
+        wasmer::VERSION,
+        Target::default().triple()
+    )
+}
+
+fn verify_and_deserialize(bytes: &[u8], engine: &Engine) -> Result<Module, String> {
+    if bytes.len() < CHECKSUM_LEN {
+        return Err("file is shorter than checksum".to_string());
+    }
+
+    let (checksum, serialized) = bytes.split_at(CHECKSUM_LEN);
+
+    if sha2_256(serialized) != checksum {
+        return Err("checksum mismatch".to_string());
+    }
+
+    // Safety: the serialized module was produced by `Module::serialize` from
+    // this same cache directory, which is versioned by the Wasmer version and
+    // target, and its integrity has been verified with the checksum above.
+    // `deserialize_checked` additionally validates the artifact's structure.
+    unsafe { Module::deserialize_checked(engine, serialized) }.map_err(|err| err.to_string())
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        grug_types::HashExt,
+        wasmer::{Singlepass, Store},
+    };
+
+    const CONTRACT: &[u8] = br#"(module (func (export "foo")))"#;
+
+    fn engine() -> Engine {
+        Engine::from(Singlepass::new())
+    }
+
+    #[test]
+    fn store_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = FileSystemCache::open(dir.path()).unwrap();
+
+        let engine = engine();
+        let module = Module::new(&engine, CONTRACT).unwrap();
+        let code_hash = CONTRACT.hash256();
+
+        // Nothing has been stored yet.
+        assert!(cache.load(code_hash, &engine).is_none());
+
+        cache.store(code_hash, &module);
+
+        // A new cache instance opened at the same directory should find the
+        // module, as it would after a restart.
+        let cache = FileSystemCache::open(dir.path()).unwrap();
+        let engine = self::engine();
+        let loaded = cache.load(code_hash, &engine).unwrap();
+
+        // The loaded module should be usable.
+        let mut store = Store::new(engine);
+        let instance = wasmer::Instance::new(&mut store, &loaded, &wasmer::imports! {}).unwrap();
+        assert!(instance.exports.get_function("foo").is_ok());
+    }
+
+    #[test]
+    fn corrupted_module_is_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = FileSystemCache::open(dir.path()).unwrap();
+
+        let engine = engine();
+        let module = Module::new(&engine, CONTRACT).unwrap();
+        let code_hash = CONTRACT.hash256();
+
+        cache.store(code_hash, &module);
+
+        // Flip a byte in the serialized module.
+        let path = cache.module_path(code_hash);
+        let mut bytes = fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        fs::write(&path, bytes).unwrap();
+
+        // Checksum verification fails; the file should be removed.
+        assert!(cache.load(code_hash, &engine).is_none());
+        assert!(!path.exists());
+    }
+}
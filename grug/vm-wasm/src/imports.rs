@@ -1,6 +1,5 @@
 use {
     crate::{read_from_memory, write_to_memory, Environment, Iterator, VmError, VmResult},
-    grug_app::GAS_COSTS,
     grug_types::{decode_sections, Addr, BorshDeExt, BorshSerExt, Query, Record, Storage},
     tracing::info,
     wasmer::FunctionEnvMut,
@@ -15,13 +14,17 @@ pub fn db_read(mut fe: FunctionEnvMut<Environment>, key_ptr: u32) -> VmResult<u3
         Some(value) => {
             env.consume_external_gas(
                 &mut store,
-                GAS_COSTS.db_read.cost(value.len()),
+                env.gas_tracker.costs().db_read.cost(value.len()),
                 "db_read/found",
             )?;
             write_to_memory(env, &mut store, &value)
         },
         None => {
-            env.consume_external_gas(&mut store, GAS_COSTS.db_read.cost(0), "db_read/not_found")?;
+            env.consume_external_gas(
+                &mut store,
+                env.gas_tracker.costs().db_read.cost(0),
+                "db_read/not_found",
+            )?;
             // If the record doesn't exist, return a zero pointer.
             Ok(0)
         },
@@ -50,7 +53,7 @@ pub fn db_scan(
     let order = order.try_into()?;
     let iterator = Iterator::new(min, max, order);
 
-    env.consume_external_gas(&mut store, GAS_COSTS.db_scan, "db_scan")?;
+    env.consume_external_gas(&mut store, env.gas_tracker.costs().db_scan, "db_scan")?;
 
     Ok(env.add_iterator(iterator))
 }
@@ -60,16 +63,21 @@ pub fn db_next(mut fe: FunctionEnvMut<Environment>, iterator_id: i32) -> VmResul
 
     match env.advance_iterator(iterator_id)? {
         Some((key, value)) => {
-            env.consume_external_gas(
-                &mut store,
-                GAS_COSTS.db_next + GAS_COSTS.db_read.cost(key.len() + value.len()),
-                "db_next/found",
-            )?;
+            let costs = env.gas_tracker.costs();
+            let cost = costs
+                .db_next
+                .saturating_add(costs.db_read.cost(key.len() + value.len()));
+
+            env.consume_external_gas(&mut store, cost, "db_next/found")?;
 
             write_to_memory(env, &mut store, &encode_record((key, value)))
         },
         None => {
-            env.consume_external_gas(&mut store, GAS_COSTS.db_next, "db_next/not_found")?;
+            env.consume_external_gas(
+                &mut store,
+                env.gas_tracker.costs().db_next,
+                "db_next/not_found",
+            )?;
 
             Ok(0)
         },
@@ -81,16 +89,19 @@ pub fn db_next_key(mut fe: FunctionEnvMut<Environment>, iterator_id: i32) -> VmR
 
     match env.advance_iterator(iterator_id)? {
         Some((key, _)) => {
-            env.consume_external_gas(
-                &mut store,
-                GAS_COSTS.db_next + GAS_COSTS.db_read.cost(key.len()),
-                "db_next_key/found",
-            )?;
+            let costs = env.gas_tracker.costs();
+            let cost = costs.db_next.saturating_add(costs.db_read.cost(key.len()));
+
+            env.consume_external_gas(&mut store, cost, "db_next_key/found")?;
 
             write_to_memory(env, &mut store, &key)
         },
         None => {
-            env.consume_external_gas(&mut store, GAS_COSTS.db_next, "db_next_key/not_found")?;
+            env.consume_external_gas(
+                &mut store,
+                env.gas_tracker.costs().db_next,
+                "db_next_key/not_found",
+            )?;
 
             Ok(0)
         },
@@ -102,16 +113,21 @@ pub fn db_next_value(mut fe: FunctionEnvMut<Environment>, iterator_id: i32) -> V
 
     match env.advance_iterator(iterator_id)? {
         Some((_, value)) => {
-            env.consume_external_gas(
-                &mut store,
-                GAS_COSTS.db_next + GAS_COSTS.db_read.cost(value.len()),
-                "db_next_value/found",
-            )?;
+            let costs = env.gas_tracker.costs();
+            let cost = costs
+                .db_next
+                .saturating_add(costs.db_read.cost(value.len()));
+
+            env.consume_external_gas(&mut store, cost, "db_next_value/found")?;
 
             write_to_memory(env, &mut store, &value)
         },
         None => {
-            env.consume_external_gas(&mut store, GAS_COSTS.db_next, "db_next_value/not_found")?;
+            env.consume_external_gas(
+                &mut store,
+                env.gas_tracker.costs().db_next,
+                "db_next_value/not_found",
+            )?;
 
             Ok(0)
         },
@@ -133,7 +149,9 @@ pub fn db_write(mut fe: FunctionEnvMut<Environment>, key_ptr: u32, value_ptr: u3
     let key = read_from_memory(env, &store, key_ptr)?;
     let value = read_from_memory(env, &store, value_ptr)?;
 
-    let gas_cost = GAS_COSTS
+    let gas_cost = env
+        .gas_tracker
+        .costs()
         .db_write
         .cost(env.storage.namespace().len() + key.len() + value.len());
 
@@ -141,6 +159,9 @@ pub fn db_write(mut fe: FunctionEnvMut<Environment>, key_ptr: u32, value_ptr: u3
 
     env.storage.write(&key, &value);
 
+    let usage_gas = env.storage.take_usage_gas();
+    env.consume_external_gas(&mut store, usage_gas, "storage_usage")?;
+
     // Delete all existing iterators. This is necessary if the storage is to be
     // mutated.
     //
@@ -177,7 +198,15 @@ pub fn db_remove(mut fe: FunctionEnvMut<Environment>, key_ptr: u32) -> VmResult<
 
     env.storage.remove(&key);
     env.clear_iterators();
-    env.consume_external_gas(&mut store, GAS_COSTS.db_remove, "storage_remove")
+
+    let usage_gas = env.storage.take_usage_gas();
+    env.consume_external_gas(&mut store, usage_gas, "storage_usage")?;
+
+    env.consume_external_gas(
+        &mut store,
+        env.gas_tracker.costs().db_remove,
+        "storage_remove",
+    )
 }
 
 pub fn db_remove_range(
@@ -204,7 +233,15 @@ pub fn db_remove_range(
 
     env.storage.remove_range(min.as_deref(), max.as_deref());
     env.clear_iterators();
-    env.consume_external_gas(&mut store, GAS_COSTS.db_remove, "storage_remove_range")
+
+    let usage_gas = env.storage.take_usage_gas();
+    env.consume_external_gas(&mut store, usage_gas, "storage_usage")?;
+
+    env.consume_external_gas(
+        &mut store,
+        env.gas_tracker.costs().db_remove,
+        "storage_remove_range",
+    )
 }
 
 pub fn debug(mut fe: FunctionEnvMut<Environment>, addr_ptr: u32, msg_ptr: u32) -> VmResult<()> {
@@ -250,7 +287,11 @@ pub fn secp256k1_verify(
     let sig = read_from_memory(env, &store, sig_ptr)?;
     let pk = read_from_memory(env, &store, pk_ptr)?;
 
-    env.consume_external_gas(&mut store, GAS_COSTS.secp256k1_verify, "secp256k1_verify")?;
+    env.consume_external_gas(
+        &mut store,
+        env.gas_tracker.costs().secp256k1_verify,
+        "secp256k1_verify",
+    )?;
 
     match grug_crypto::secp256k1_verify(&msg_hash, &sig, &pk) {
         Ok(()) => Ok(0),
@@ -270,7 +311,11 @@ pub fn secp256r1_verify(
     let sig = read_from_memory(env, &store, sig_ptr)?;
     let pk = read_from_memory(env, &store, pk_ptr)?;
 
-    env.consume_external_gas(&mut store, GAS_COSTS.secp256k1_verify, "secp256r1_verify")?;
+    env.consume_external_gas(
+        &mut store,
+        env.gas_tracker.costs().secp256k1_verify,
+        "secp256r1_verify",
+    )?;
 
     match grug_crypto::secp256r1_verify(&msg_hash, &sig, &pk) {
         Ok(()) => Ok(0),
@@ -298,7 +343,7 @@ pub fn secp256k1_pubkey_recover(
 
     env.consume_external_gas(
         &mut store,
-        GAS_COSTS.secp256k1_pubkey_recover,
+        env.gas_tracker.costs().secp256k1_pubkey_recover,
         "secp256k1_pubkey_recover",
     )?;
 
@@ -328,7 +373,11 @@ pub fn ed25519_verify(
     let sig = read_from_memory(env, &store, sig_ptr)?;
     let pk = read_from_memory(env, &store, pk_ptr)?;
 
-    env.consume_external_gas(&mut store, GAS_COSTS.ed25519_verify, "ed25519_verify")?;
+    env.consume_external_gas(
+        &mut store,
+        env.gas_tracker.costs().ed25519_verify,
+        "ed25519_verify",
+    )?;
 
     match grug_crypto::ed25519_verify(&msg_hash, &sig, &pk) {
         Ok(()) => Ok(0),
@@ -354,7 +403,10 @@ pub fn ed25519_batch_verify(
 
     env.consume_external_gas(
         &mut store,
-        GAS_COSTS.ed25519_batch_verify.cost(prehash_msgs.len()),
+        env.gas_tracker
+            .costs()
+            .ed25519_batch_verify
+            .cost(prehash_msgs.len()),
         "ed25519_batch_verify",
     )?;
 
@@ -372,7 +424,9 @@ macro_rules! impl_hash_method {
             let data = read_from_memory(env, &store, data_ptr)?;
             let hash = grug_crypto::$hasher(&data);
 
-            env.consume_external_gas(&mut store, GAS_COSTS.$hasher.cost(data.len()), $name)?;
+            let cost = env.gas_tracker.costs().$hasher.cost(data.len());
+
+            env.consume_external_gas(&mut store, cost, $name)?;
 
             write_to_memory(env, &mut store, &hash)
         }
@@ -412,15 +466,13 @@ mod tests {
     use {
         crate::{
             db_read, db_remove, db_remove_range, db_scan, db_write, debug, read_from_memory,
-            write_to_memory, Environment, VmResult, WasmVm, GAS_PER_OPERATION,
-        },
-        grug_app::{
-            AppCtx, GasTracker, QuerierProvider, Shared, StorageProvider, APP_CONFIGS, GAS_COSTS,
+            write_to_memory, Environment, VmResult, WasmVm, WasmVmConfig, GAS_PER_OPERATION,
         },
+        grug_app::{AppCtx, GasTracker, QuerierProvider, Shared, StorageProvider, APP_CONFIGS},
         grug_crypto::{Identity256, Identity512},
         grug_types::{
-            encode_sections, json, Addr, BlockInfo, BorshDeExt, BorshSerExt, GenericResult,
-            Hash256, MockStorage, Order, Query, QueryResponse, Storage, Timestamp,
+            encode_sections, json, Addr, BlockInfo, BorshDeExt, BorshSerExt, GasCosts,
+            GenericResult, Hash256, MockStorage, Order, Query, QueryResponse, Storage, Timestamp,
         },
         rand::rngs::OsRng,
         std::{fmt::Debug, sync::Arc},
@@ -536,7 +588,7 @@ mod tests {
                 StorageProvider::new(Box::new(storage.clone()), &[NAMESPACE_CONTRACT]);
 
             let querier_provider = QuerierProvider::new(AppCtx::new(
-                WasmVm::new(0),
+                WasmVm::new(0, WasmVmConfig::default()).unwrap(),
                 Box::new(storage.clone()),
                 gas_tracker.clone(),
                 "dev-1",
@@ -757,7 +809,7 @@ mod tests {
 
         let gas_consumed = suite.env_mut().gas_tracker.used() - gas_pre;
 
-        let cost = GAS_COSTS
+        let cost = GasCosts::default()
             .db_write
             .cost(NAMESPACE_CONTRACT.len() + k.len() + v.len());
 
@@ -788,7 +840,7 @@ mod tests {
 
         let gas_consumed = suite.env_mut().gas_tracker.used() - gas_pre;
 
-        assert_eq!(gas_consumed, GAS_COSTS.db_remove);
+        assert_eq!(gas_consumed, GasCosts::default().db_remove);
     }
 
     // ---------------------------- db_remove_range ----------------------------
@@ -850,7 +902,7 @@ mod tests {
 
         let gas_consumed = suite.env_mut().gas_tracker.used() - gas_pre;
 
-        assert_eq!(gas_consumed, GAS_COSTS.db_remove);
+        assert_eq!(gas_consumed, GasCosts::default().db_remove);
     }
 
     // -------------------------------- debug ----------------------------------
@@ -1,6 +1,8 @@
 mod cache;
+mod config;
 mod environment;
 mod error;
+mod file_system_cache;
 mod gatekeeper;
 mod imports;
 mod iterator;
@@ -9,9 +11,10 @@ mod region;
 #[cfg(feature = "testing")]
 mod testing;
 mod tunables;
+mod validator;
 mod vm;
 
 pub use {
-    cache::*, environment::*, error::*, gatekeeper::*, imports::*, iterator::*, memory::*,
-    region::*, tunables::*, vm::*,
+    cache::*, config::*, environment::*, error::*, file_system_cache::*, gatekeeper::*, imports::*,
+    iterator::*, memory::*, region::*, tunables::*, validator::*, vm::*,
 };
@@ -0,0 +1,386 @@
+use {
+    crate::{build_engine, VmError, VmResult, WasmVmConfig},
+    wasmer::{
+        wasmparser::{Parser, Payload},
+        ExternType, FunctionType, Module,
+        Type::{I32, I64},
+    },
+};
+
+/// Maximum number of functions defined in a Wasm module, not including the
+/// imported ones.
+pub const MAX_FUNCTIONS: u32 = 20_000;
+
+/// Functions that every contract must export, used by the host to pass data
+/// into the contract's memory. See [`Region`](crate::Region).
+const REQUIRED_EXPORTS: &[(&str, &[wasmer::Type], &[wasmer::Type])] =
+    &[("allocate", &[I32], &[I32]), ("deallocate", &[I32], &[])];
+
+/// Entry points that the host may call, corresponding to those in
+/// `grug_ffi::exports`. A contract doesn't need to export all of them.
+///
+/// Each entry point takes a pointer to the context, followed by pointers to
+/// zero or more parameters, and returns a pointer to the response.
+const ENTRY_POINTS: &[(&str, &[wasmer::Type], &[wasmer::Type])] = &[
+    ("instantiate", &[I32, I32], &[I32]),
+    ("execute", &[I32, I32], &[I32]),
+    ("query", &[I32, I32], &[I32]),
+    ("migrate", &[I32, I32], &[I32]),
+    ("reply", &[I32, I32, I32], &[I32]),
+    ("receive", &[I32], &[I32]),
+    ("cron_execute", &[I32], &[I32]),
+    ("authenticate", &[I32, I32], &[I32]),
+    ("backrun", &[I32, I32], &[I32]),
+    ("bank_execute", &[I32, I32], &[I32]),
+    ("bank_query", &[I32, I32], &[I32]),
+    ("withhold_fee", &[I32, I32], &[I32]),
+    ("finalize_fee", &[I32, I32, I32], &[I32]),
+];
+
+/// Host functions that a contract may import, corresponding to those in
+/// `grug_ffi::imports`. All of them are under the `env` namespace.
+const HOST_FUNCTIONS: &[(&str, &[wasmer::Type], &[wasmer::Type])] = &[
+    ("db_read", &[I32], &[I32]),
+    ("db_scan", &[I32, I32, I32], &[I32]),
+    ("db_next", &[I32], &[I32]),
+    ("db_next_key", &[I32], &[I32]),
+    ("db_next_value", &[I32], &[I32]),
+    ("db_write", &[I32, I32], &[]),
+    ("db_remove", &[I32], &[]),
+    ("db_remove_range", &[I32, I32], &[]),
+    ("secp256k1_verify", &[I32, I32, I32], &[I32]),
+    ("secp256r1_verify", &[I32, I32, I32], &[I32]),
+    ("secp256k1_pubkey_recover", &[I32, I32, I32, I32], &[I64]),
+    ("ed25519_verify", &[I32, I32, I32], &[I32]),
+    ("ed25519_batch_verify", &[I32, I32, I32], &[I32]),
+    ("sha2_256", &[I32], &[I32]),
+    ("sha2_512", &[I32], &[I32]),
+    ("sha2_512_truncated", &[I32], &[I32]),
+    ("sha3_256", &[I32], &[I32]),
+    ("sha3_512", &[I32], &[I32]),
+    ("sha3_512_truncated", &[I32], &[I32]),
+    ("keccak256", &[I32], &[I32]),
+    ("blake2s_256", &[I32], &[I32]),
+    ("blake2b_512", &[I32], &[I32]),
+    ("blake3", &[I32], &[I32]),
+    ("debug", &[I32, I32], &[]),
+    ("query_chain", &[I32], &[I32]),
+];
+
+/// Namespace of the host functions.
+const HOST_MODULE: &str = "env";
+
+/// Name of the linear memory that the contract must export.
+const MEMORY_EXPORT: &str = "memory";
+
+/// Statically check that the given bytes are a valid Grug contract, without
+/// instantiating it.
+///
+/// This is done when the code is uploaded, so that an invalid contract is
+/// rejected immediately, instead of when it's first instantiated.
+pub fn validate_wasm(code: &[u8], config: &WasmVmConfig) -> VmResult<()> {
+    validate_wasm_code(code, config)?;
+
+    // Compile the module using the same engine used for execution. This parses
+    // the module and runs it through the `Gatekeeper`, rejecting disallowed
+    // features and operators.
+    let module = Module::new(&build_engine(config), code)?;
+
+    validate_wasm_module(&module)
+}
+
+/// The checks of [`validate_wasm`] that are done before compiling, which are
+/// cheap compared to compiling.
+pub(crate) fn validate_wasm_code(code: &[u8], config: &WasmVmConfig) -> VmResult<()> {
+    if code.len() > config.max_module_size {
+        return Err(VmError::ModuleTooLarge {
+            size: code.len(),
+            max: config.max_module_size,
+        });
+    }
+
+    check_function_count(code)
+}
+
+/// The checks of [`validate_wasm`] that are done on the compiled module.
+pub(crate) fn validate_wasm_module(module: &Module) -> VmResult<()> {
+    check_exports(module)?;
+    check_imports(module)
+}
+
+fn check_function_count(code: &[u8]) -> VmResult<()> {
+    for payload in Parser::new(0).parse_all(code) {
+        let payload = payload.map_err(|err| VmError::Instantiation(err.to_string()))?;
+
+        if let Payload::FunctionSection(reader) = payload {
+            if reader.count() > MAX_FUNCTIONS {
+                return Err(VmError::TooManyFunctions {
+                    count: reader.count(),
+                    max: MAX_FUNCTIONS,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn check_exports(module: &Module) -> VmResult<()> {
+    if !module.exports().any(|export| {
+        export.name() == MEMORY_EXPORT && matches!(export.ty(), ExternType::Memory(_))
+    }) {
+        return Err(VmError::MissingExport {
+            name: MEMORY_EXPORT.to_string(),
+        });
+    }
+
+    for (name, params, results) in REQUIRED_EXPORTS {
+        let Some(export) = module.exports().find(|export| export.name() == *name) else {
+            return Err(VmError::MissingExport {
+                name: name.to_string(),
+            });
+        };
+
+        check_signature(name, export.ty(), params, results)?;
+    }
+
+    let mut has_entry_point = false;
+
+    for export in module.exports() {
+        // Only exported functions can be called by the host. Other exports,
+        // such as the `__data_end` and `__heap_base` globals emitted by the
+        // Rust compiler, are ignored.
+        if !matches!(export.ty(), ExternType::Function(_)) {
+            continue;
+        }
+
+        if REQUIRED_EXPORTS
+            .iter()
+            .any(|(name, ..)| *name == export.name())
+        {
+            continue;
+        }
+
+        let Some((name, params, results)) = ENTRY_POINTS
+            .iter()
+            .find(|(name, ..)| *name == export.name())
+        else {
+            return Err(VmError::UnknownExport {
+                name: export.name().to_string(),
+            });
+        };
+
+        check_signature(name, export.ty(), params, results)?;
+
+        has_entry_point = true;
+    }
+
+    if !has_entry_point {
+        return Err(VmError::NoEntryPoint);
+    }
+
+    Ok(())
+}
+
+fn check_imports(module: &Module) -> VmResult<()> {
+    for import in module.imports() {
+        let Some((name, params, results)) = HOST_FUNCTIONS
+            .iter()
+            .find(|(name, ..)| import.module() == HOST_MODULE && *name == import.name())
+        else {
+            return Err(VmError::UnknownImport {
+                module: import.module().to_string(),
+                name: import.name().to_string(),
+            });
+        };
+
+        check_signature(name, import.ty(), params, results)?;
+    }
+
+    Ok(())
+}
+
+fn check_signature(
+    name: &str,
+    ty: &ExternType,
+    params: &[wasmer::Type],
+    results: &[wasmer::Type],
+) -> VmResult<()> {
+    let expect = FunctionType::new(params, results);
+
+    match ty {
+        ExternType::Function(actual) if *actual == expect => Ok(()),
+        ExternType::Function(actual) => Err(VmError::IncorrectSignature {
+            name: name.to_string(),
+            expect: expect.to_string(),
+            actual: actual.to_string(),
+        }),
+        _ => Err(VmError::IncorrectSignature {
+            name: name.to_string(),
+            expect: expect.to_string(),
+            actual: "non-function".to_string(),
+        }),
+    }
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {super::*, test_case::test_case, wasmer::wat2wasm};
+
+    const MOCK_BANK: &[u8] = include_bytes!("../testdata/grug_mock_bank.wasm");
+
+    const MOCK_TAXMAN: &[u8] = include_bytes!("../testdata/grug_mock_taxman.wasm");
+
+    /// Memory and functions that every contract must export.
+    const REQUIRED: &str = r#"
+        (memory (export "memory") 1)
+        (func (export "allocate") (param i32) (result i32) (i32.const 0))
+        (func (export "deallocate") (param i32))
+    "#;
+
+    /// A `query` entry point with the correct signature.
+    const QUERY: &str = r#"(func (export "query") (param i32 i32) (result i32) (i32.const 0))"#;
+
+    fn validate_wat(wat: &str) -> VmResult<()> {
+        validate_wasm(&wat2wasm(wat.as_bytes()).unwrap(), &WasmVmConfig::default())
+    }
+
+    #[test_case(MOCK_BANK; "mock bank")]
+    #[test_case(MOCK_TAXMAN; "mock taxman")]
+    fn valid_contracts(code: &[u8]) {
+        assert!(validate_wasm(code, &WasmVmConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn minimal_contract_is_valid() {
+        assert!(validate_wat(&format!("(module {REQUIRED} {QUERY})")).is_ok());
+    }
+
+    #[test]
+    fn invalid_bytes() {
+        // The text format isn't accepted; contracts must be uploaded as binary.
+        assert!(matches!(
+            validate_wasm(
+                format!("(module {REQUIRED} {QUERY})").as_bytes(),
+                &WasmVmConfig::default()
+            ),
+            Err(VmError::Instantiation(_))
+        ));
+    }
+
+    #[test]
+    fn too_large() {
+        let config = WasmVmConfig {
+            max_module_size: MOCK_BANK.len() - 1,
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            validate_wasm(MOCK_BANK, &config),
+            Err(VmError::ModuleTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn missing_memory() {
+        let wat = format!(
+            r#"(module
+                (func (export "allocate") (param i32) (result i32) (i32.const 0))
+                (func (export "deallocate") (param i32))
+                {QUERY}
+            )"#
+        );
+
+        assert!(matches!(
+            validate_wat(&wat),
+            Err(VmError::MissingExport { name }) if name == "memory"
+        ));
+    }
+
+    #[test]
+    fn missing_allocate() {
+        let wat = format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (func (export "deallocate") (param i32))
+                {QUERY}
+            )"#
+        );
+
+        assert!(matches!(
+            validate_wat(&wat),
+            Err(VmError::MissingExport { name }) if name == "allocate"
+        ));
+    }
+
+    #[test]
+    fn no_entry_point() {
+        assert!(matches!(
+            validate_wat(&format!("(module {REQUIRED})")),
+            Err(VmError::NoEntryPoint)
+        ));
+    }
+
+    #[test]
+    fn unknown_export() {
+        let wat = format!(r#"(module {REQUIRED} {QUERY} (func (export "foo")))"#);
+
+        assert!(matches!(
+            validate_wat(&wat),
+            Err(VmError::UnknownExport { name }) if name == "foo"
+        ));
+    }
+
+    #[test]
+    fn incorrect_entry_point_signature() {
+        let wat = format!(
+            r#"(module {REQUIRED} (func (export "execute") (param i32) (result i32) (i32.const 0)))"#
+        );
+
+        assert!(matches!(
+            validate_wat(&wat),
+            Err(VmError::IncorrectSignature { name, .. }) if name == "execute"
+        ));
+    }
+
+    #[test_case(r#"(import "env" "foo" (func (param i32)))"#; "unknown host function")]
+    #[test_case(r#"(import "wasi" "db_read" (func (param i32) (result i32)))"#; "unknown module")]
+    fn unknown_import(import: &str) {
+        // Imports must come before other definitions in the text format.
+        let wat = format!("(module {import} {REQUIRED} {QUERY})");
+
+        assert!(matches!(
+            validate_wat(&wat),
+            Err(VmError::UnknownImport { .. })
+        ));
+    }
+
+    #[test]
+    fn incorrect_import_signature() {
+        let import = r#"(import "env" "db_read" (func (param i64) (result i32)))"#;
+        let wat = format!("(module {import} {REQUIRED} {QUERY})");
+
+        assert!(matches!(
+            validate_wat(&wat),
+            Err(VmError::IncorrectSignature { name, .. }) if name == "db_read"
+        ));
+    }
+
+    #[test]
+    fn disallowed_feature() {
+        // `memory.fill` is part of the bulk memory operations feature, which the
+        // `Gatekeeper` rejects.
+        let wat = format!(
+            r#"(module {REQUIRED}
+                (func (export "execute") (param i32 i32) (result i32)
+                    (memory.fill (i32.const 0) (i32.const 0) (i32.const 0))
+                    (i32.const 0)
+                )
+            )"#
+        );
+
+        assert!(matches!(validate_wat(&wat), Err(VmError::Instantiation(_))));
+    }
+}
@@ -4,65 +4,86 @@ use {
         db_remove_range, db_scan, db_write, debug, ed25519_batch_verify, ed25519_verify, keccak256,
         query_chain, read_then_wipe, secp256k1_pubkey_recover, secp256k1_verify, secp256r1_verify,
         sha2_256, sha2_512, sha2_512_truncated, sha3_256, sha3_512, sha3_512_truncated,
-        write_to_memory, Cache, Environment, Gatekeeper, LimitingTunables, VmError, VmResult,
+        validate_wasm, validate_wasm_code, validate_wasm_module, write_to_memory, Cache,
+        Environment, FileSystemCache, Gatekeeper, LimitingTunables, Metrics, VmError, VmResult,
+        WasmVmConfig,
     },
     grug_app::{GasTracker, Instance, QuerierProvider, StorageProvider, Vm},
     grug_types::{BorshSerExt, Context, Hash256},
-    std::{num::NonZeroUsize, sync::Arc},
+    std::{io, num::NonZeroUsize, path::Path, sync::Arc},
     wasmer::{
         imports, sys::BaseTunables, CompilerConfig, Engine, Function, FunctionEnv, Module,
-        NativeEngineExt, Singlepass, Store, StoreMut, Target, WASM_PAGE_SIZE,
+        NativeEngineExt, Singlepass, Store, StoreMut, Target, WASM_MAX_PAGES,
     },
     wasmer_middlewares::{metering::set_remaining_points, Metering},
 };
 
-/// Gas cost per Wasmer operation.
-pub const GAS_PER_OPERATION: u64 = 1;
-
-/// Maximum number of chained queries.
-///
-/// E.g. contract A queries contract B; when handling this query, contract B
-/// calls contract C; so on.
-///
-/// Without a limit, this can leads to stack overflow which halts the chain.
-pub const MAX_QUERY_DEPTH: usize = 3;
-
-/// Wasm memory size limit in MiB.
-///
-/// ## Note
-///
-/// Do not confuse MiB (mebibyte) with MB (megabyte):
-///
-/// - 1 MiB = 1,024 KiB = 1,024 * 1,024 bytes
-/// - 1 MB  = 1,000 KB  = 1,000 * 1,000 bytes
-pub const MAX_MEMORY_MEBI: usize = 32;
-
-/// Wasm memory size limit in pages.
-///
-/// In WebAssembly, a memory page is 64 KiB. There can be a maximum of 65,536
-/// pages.
-///
-/// In Grug, we limit each contract instance's memory to 32 MiB or 512 pages,
-/// consistent with [CosmWasm](https://github.com/CosmWasm/wasmd/blob/v0.53.0/x/wasm/keeper/keeper.go#L38-L40).
-pub const MAX_MEMORY_PAGES: u32 = (MAX_MEMORY_MEBI * 1024 * 1024 / WASM_PAGE_SIZE) as u32;
-
-// Assert at compile time that our instance memory is always smaller than the
-// max memory that Wasmer can support.
-const _: () = assert!(MAX_MEMORY_PAGES < wasmer::WASM_MAX_PAGES);
-
 // ------------------------------------ vm -------------------------------------
 
 #[derive(Clone)]
 pub struct WasmVm {
     cache: Option<Cache>,
+    config: WasmVmConfig,
 }
 
 impl WasmVm {
-    pub fn new(cache_capacity: usize) -> Self {
-        Self {
+    pub fn new(cache_capacity: usize, config: WasmVmConfig) -> VmResult<Self> {
+        // Wasmer can't support more than 65,536 pages of memory.
+        if config.max_memory_pages >= WASM_MAX_PAGES {
+            return Err(VmError::MaxMemoryPagesTooLarge {
+                pages: config.max_memory_pages,
+                max: WASM_MAX_PAGES,
+            });
+        }
+
+        Ok(Self {
             cache: NonZeroUsize::new(cache_capacity).map(Cache::new),
+            config,
+        })
+    }
+
+    /// Persist compiled modules in the given directory, so that they don't
+    /// need to be re-compiled after a restart.
+    ///
+    /// The file system cache is a second tier behind the in-memory cache, so
+    /// this has no effect if the in-memory cache capacity is zero.
+    ///
+    /// Modules compiled with different gas costs are kept in separate
+    /// subdirectories, since the costs are compiled into the modules.
+    pub fn with_file_system_cache<P>(mut self, dir: P) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        if let Some(cache) = self.cache.take() {
+            let dir = dir.as_ref().join(self.config.gas_costs.fingerprint());
+            let fs_cache = FileSystemCache::open(dir)?;
+            self.cache = Some(cache.with_file_system_cache(fs_cache));
+        }
+
+        Ok(self)
+    }
+
+    /// Keep the module of the given code hash in memory permanently.
+    ///
+    /// Has no effect if the in-memory cache capacity is zero.
+    pub fn pin(&self, code_hash: Hash256) {
+        if let Some(cache) = &self.cache {
+            cache.pin(code_hash);
         }
     }
+
+    /// Undo a previous [`pin`](Self::pin).
+    pub fn unpin(&self, code_hash: Hash256) {
+        if let Some(cache) = &self.cache {
+            cache.unpin(code_hash);
+        }
+    }
+
+    /// Return usage statistics of the module cache, or `None` if the cache is
+    /// disabled.
+    pub fn cache_metrics(&self) -> Option<Metrics> {
+        self.cache.as_ref().map(Cache::metrics)
+    }
 }
 
 impl Vm for WasmVm {
@@ -79,16 +100,22 @@ impl Vm for WasmVm {
         query_depth: usize,
         gas_tracker: GasTracker,
     ) -> VmResult<WasmInstance> {
-        if query_depth > MAX_QUERY_DEPTH {
+        if query_depth > self.config.max_query_depth {
             return Err(VmError::ExceedMaxQueryDepth);
         }
 
         let (module, engine) = if let Some(cache) = &self.cache {
             // Attempt to fetch a pre-built Wasmer module from the cache.
             // If not found, build it and insert it into the cache.
-            cache.get_or_build_with(code_hash, || compile_wasmer(code))?
+            cache.get_or_build_with(
+                code_hash,
+                || build_engine(&self.config),
+                |engine| compile_wasmer(engine, code),
+            )?
         } else {
-            compile_wasmer(code)?
+            let engine = build_engine(&self.config);
+            let module = compile_wasmer(&engine, code)?;
+            (module, engine)
         };
 
         // Compute the amount of gas left for this call. This will be used as
@@ -166,9 +193,36 @@ impl Vm for WasmVm {
             fe,
         })
     }
+
+    fn validate_code(&mut self, code: &[u8], code_hash: Hash256) -> VmResult<()> {
+        let Some(cache) = &self.cache else {
+            return validate_wasm(code, &self.config);
+        };
+
+        validate_wasm_code(code, &self.config)?;
+
+        // Compile the module into the cache, so that it isn't compiled again
+        // when the contract is first called. The module is checked before it's
+        // returned to the cache, such that an invalid module isn't cached.
+        cache.get_or_build_with(
+            code_hash,
+            || build_engine(&self.config),
+            |engine| {
+                let module = compile_wasmer(engine, code)?;
+                validate_wasm_module(&module)?;
+                Ok(module)
+            },
+        )?;
+
+        Ok(())
+    }
+
+    fn config_hash(&self) -> Option<Hash256> {
+        Some(self.config.hash())
+    }
 }
 
-fn compile_wasmer(code: &[u8]) -> VmResult<(Module, Engine)> {
+pub(crate) fn build_engine(config: &WasmVmConfig) -> Engine {
     let mut compiler = Singlepass::new();
 
     // Set up the gas metering middleware.
@@ -182,7 +236,8 @@ fn compile_wasmer(code: &[u8]) -> VmResult<(Module, Engine)> {
     //
     // Also, compiling the module doesn't cost gas, so setting the limit
     // to zero won't raise out of gas errors.
-    let metering = Metering::new(0, |_| GAS_PER_OPERATION);
+    let gas_costs = config.gas_costs;
+    let metering = Metering::new(0, move |operator| gas_costs.cost(operator));
     compiler.push_middleware(Arc::new(metering));
 
     // Set up the `Gatekeeper`. This rejects certain Wasm operators that
@@ -196,12 +251,14 @@ fn compile_wasmer(code: &[u8]) -> VmResult<(Module, Engine)> {
 
     // Set memory limit for Wasm instances.
     let base = BaseTunables::for_target(&Target::default());
-    let tunables = LimitingTunables::new(base, MAX_MEMORY_PAGES);
+    let tunables = LimitingTunables::new(base, config.max_memory_pages);
     engine.set_tunables(tunables);
 
-    let module = Module::new(&engine, code)?;
+    engine
+}
 
-    Ok((module, engine))
+fn compile_wasmer(engine: &Engine, code: &[u8]) -> VmResult<Module> {
+    Ok(Module::new(engine, code)?)
 }
 
 // --------------------------------- instance ----------------------------------
@@ -292,3 +349,61 @@ impl Instance for WasmInstance {
         })
     }
 }
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {super::*, grug_types::HashExt};
+
+    const MOCK_BANK: &[u8] = include_bytes!("../testdata/grug_mock_bank.wasm");
+
+    #[test]
+    fn max_memory_pages_is_validated() {
+        assert!(WasmVm::new(0, WasmVmConfig::default()).is_ok());
+
+        let config = WasmVmConfig {
+            max_memory_pages: WASM_MAX_PAGES,
+            ..Default::default()
+        };
+        assert!(matches!(
+            WasmVm::new(0, config),
+            Err(VmError::MaxMemoryPagesTooLarge { pages, .. }) if pages == WASM_MAX_PAGES
+        ));
+
+        let config = WasmVmConfig {
+            max_memory_pages: WASM_MAX_PAGES - 1,
+            ..Default::default()
+        };
+        assert!(WasmVm::new(0, config).is_ok());
+    }
+
+    #[test]
+    fn validated_modules_are_cached() {
+        let mut vm = WasmVm::new(1, WasmVmConfig::default()).unwrap();
+        let code_hash = MOCK_BANK.hash256();
+
+        vm.validate_code(MOCK_BANK, code_hash).unwrap();
+
+        // The module compiled during validation should be in the cache.
+        let cache = vm.cache.as_ref().unwrap();
+        assert_eq!(cache.metrics().misses, 1);
+
+        cache
+            .get_or_build_with(
+                code_hash,
+                || build_engine(&vm.config),
+                |_| panic!("module should have been cached"),
+            )
+            .unwrap();
+        assert_eq!(cache.metrics().hits, 1);
+
+        // Invalid modules aren't cached.
+        let code = wasmer::wat2wasm(br#"(module (memory (export "memory") 1))"#).unwrap();
+        let code_hash = code.hash256();
+
+        assert!(vm.validate_code(&code, code_hash).is_err());
+        assert!(vm.validate_code(&code, code_hash).is_err());
+        assert_eq!(vm.cache.as_ref().unwrap().metrics().misses, 3);
+    }
+}
@@ -12,7 +12,7 @@ use {
         Addr, Binary, Coins, Denom, GenericResult, Message, QueryRequest, ResultExt,
         VerificationError,
     },
-    grug_vm_wasm::{VmError, WasmVm},
+    grug_vm_wasm::{VmError, WasmVm, WasmVmConfig},
     rand::rngs::OsRng,
     serde::{de::DeserializeOwned, Serialize},
     std::{fmt::Debug, fs, str::FromStr, sync::LazyLock, vec},
@@ -31,7 +31,9 @@ fn read_wasm_file(filename: &str) -> Binary {
 }
 
 fn setup_test() -> (TestSuite<MemDb, WasmVm>, TestAccounts, Addr) {
-    let (mut suite, mut accounts) = TestBuilder::new_with_vm(WasmVm::new(WASM_CACHE_CAPACITY))
+    let vm = WasmVm::new(WASM_CACHE_CAPACITY, WasmVmConfig::default()).unwrap();
+
+    let (mut suite, mut accounts) = TestBuilder::new_with_vm(vm)
         .add_account("owner", Coins::new())
         .add_account("sender", Coins::one(DENOM.clone(), 32_100_000).unwrap())
         .set_owner("owner")
@@ -146,6 +148,29 @@ fn message_stack_overflow() {
         .should_fail_with_error(AppError::ExceedMaxMessageDepth);
 }
 
+#[test]
+fn upload_invalid_code() {
+    let (mut suite, mut accounts, _) = setup_test();
+
+    // Not a Wasm module at all.
+    suite
+        .upload(&mut accounts["sender"], b"larry".to_vec())
+        .should_fail_with_error("is invalid");
+
+    // A valid Wasm module, but not a Grug contract, since it doesn't export
+    // the `allocate` and `deallocate` functions.
+    suite
+        .upload(
+            &mut accounts["sender"],
+            wasmer::wat2wasm(br#"(module (memory (export "memory") 1))"#)
+                .unwrap()
+                .to_vec(),
+        )
+        .should_fail_with_error(VmError::MissingExport {
+            name: "allocate".to_string(),
+        });
+}
+
 // ------------------------------- crypto tests --------------------------------
 
 const MSG: &[u8] = b"finger but hole";
@@ -2,7 +2,7 @@ use {
     grug_math::{MultiplyFraction, NumberConst, Udec128, Uint128},
     grug_testing::TestBuilder,
     grug_types::{Coins, Denom, Message, ResultExt},
-    grug_vm_wasm::WasmVm,
+    grug_vm_wasm::{WasmVm, WasmVmConfig},
     std::{collections::BTreeMap, str::FromStr, sync::LazyLock, vec},
 };
 
@@ -14,7 +14,9 @@ static DENOM: LazyLock<Denom> = LazyLock::new(|| Denom::from_str("ugrug").unwrap
 
 #[test]
 fn transfers() {
-    let (mut suite, mut accounts) = TestBuilder::new_with_vm(WasmVm::new(WASM_CACHE_CAPACITY))
+    let vm = WasmVm::new(WASM_CACHE_CAPACITY, WasmVmConfig::default()).unwrap();
+
+    let (mut suite, mut accounts) = TestBuilder::new_with_vm(vm)
         .add_account("owner", Coins::new())
         .add_account("sender", Coins::one(DENOM.clone(), 300_000).unwrap())
         .add_account("receiver", Coins::new())
@@ -77,7 +79,9 @@ fn transfers() {
 
 #[test]
 fn transfers_with_insufficient_gas_limit() {
-    let (mut suite, mut accounts) = TestBuilder::new_with_vm(WasmVm::new(WASM_CACHE_CAPACITY))
+    let vm = WasmVm::new(WASM_CACHE_CAPACITY, WasmVmConfig::default()).unwrap();
+
+    let (mut suite, mut accounts) = TestBuilder::new_with_vm(vm)
         .add_account("owner", Coins::new())
         .add_account("sender", Coins::one(DENOM.clone(), 200_000).unwrap())
         .add_account("receiver", Coins::new())